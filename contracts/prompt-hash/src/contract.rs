@@ -1,19 +1,33 @@
 use super::events::Events;
+use super::oracle::{OracleAsset, PriceOracleClient};
 use super::storage::{InstanceStorage, Storage};
 use super::types::{
-    AccessPass, AssetLiability, AssetSolvency, Bundle, CatalogPassPurchase, DataKey,
-    DisputeReason, DisputeStatus, Error, ListingConfig, ListingRevisionRecord, Prompt,
-    PromptHashTrait, PromptSaleStatus, PurchaseDispute, PurchaseEscrow, SettlementStatus,
-    SignedDiscountAuthorization, Split,
+    AccessPass, AcquisitionKind, Arbitration, AssetLiability, AssetSolvency, Auction, AuctionKind,
+    AuctionStatus, Bundle, BuyerDisputeStats, BuyerEncryptionKey, BuyerWrappedKey, CatalogFilter,
+    CatalogPassPurchase, CatalogSort, ContentAccessPolicy, DataKey, DisputeEvidence, DisputeReason,
+    DisputeStatus, DisputeWindowConfig, Error, GovernanceAction, GovernanceProposal, Lease,
+    LeaseTerms, LicenseToken, LicenseTokenApproval, LicensedContent, ListingConfig,
+    ListingRevisionRecord, OracleConversion, PassSubscription, PriceConversion, PriceOracleConfig,
+    PricingConfig, Prompt, PromptHashTrait, PromptIndex, PromptSaleStatus, Purchase,
+    PurchaseDispute, PurchaseEscrow, QuoteCommitment, ResaleOrder, ResaleOrderStatus,
+    SettlementRecord, SettlementStatus, SignedDiscountAuthorization, Split, SubscriptionEndReason,
+};
+use soroban_sdk::{
+    contract, contractimpl, token, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, String, Val,
+    Vec,
 };
-use soroban_sdk::{contract, contractimpl, token, Address, Bytes, BytesN, Env, String, Vec};
 use stellar_access::ownable::{self as ownable, Ownable};
 use stellar_macros::only_owner;
 
 const DEFAULT_FEE_BPS: u32 = 500;
-const ROYALTY_BPS: u32 = 500;
+/// Upper bound on the resale royalty a creator may set on a listing.
+const MAX_RESALE_ROYALTY_BPS: u32 = 2_500;
 const MAX_BPS: u32 = 10_000;
 const MAX_PLATFORM_FEE: u32 = 1_000;
+/// Minimum raise over the current high bid in an English auction.
+const MIN_BID_INCREMENT_BPS: u32 = 500;
+const LICENSE_TOKEN_NAME: &str = "PromptHash License";
+const LICENSE_TOKEN_SYMBOL: &str = "PHL";
 const MAX_TITLE_LEN: u32 = 120;
 const MAX_CATEGORY_LEN: u32 = 40;
 const MAX_PREVIEW_LEN: u32 = 280;
@@ -27,6 +41,8 @@ const MAX_SPLITS: u32 = 10;
 const MAX_TAGS: u32 = 8;
 const MAX_TAG_LEN: u32 = 32;
 const MAX_BUNDLE_PROMPTS: u32 = 20;
+/// Extra assets a listing may accept besides its primary one.
+const MAX_PRICING_ASSETS: u32 = 4;
 const MAX_PASS_DURATION_SECS: u64 = 31_536_000;
 // Bounds buy_prompts_bulk's per-call resource footprint (CPU/memory/read-write
 // budget) so a caller cannot force an unbounded-cost simulation or transaction
@@ -34,8 +50,42 @@ const MAX_PASS_DURATION_SECS: u64 = 31_536_000;
 const MAX_BULK_PURCHASE_SIZE: u32 = 20;
 // Window (from purchase) within which a buyer may open a dispute against a
 // pending escrow. After it elapses with no open dispute, settlement becomes
-// permissionless (#541).
+// permissionless (#541). This is the default until governance sets one.
 const DISPUTE_WINDOW_SECS: u64 = 3 * 24 * 60 * 60;
+/// Default bounds on a creator-selected listing dispute window.
+const DEFAULT_MIN_DISPUTE_WINDOW_SECS: u64 = 60 * 60;
+const DEFAULT_MAX_DISPUTE_WINDOW_SECS: u64 = 14 * 24 * 60 * 60;
+/// Upper bound on any dispute window.
+const MAX_DISPUTE_WINDOW_SECS: u64 = 30 * 24 * 60 * 60;
+/// Reference prices are USD with this many decimals (cents).
+const REFERENCE_PRICE_DECIMALS: u32 = 2;
+/// Upper bound on the oracle price age a purchase may be configured to accept.
+const MAX_ORACLE_PRICE_AGE_SECS: u64 = 24 * 60 * 60;
+/// How long a creator has to deliver a buyer's wrapped key after purchase.
+const KEY_DELIVERY_WINDOW_SECS: u64 = 24 * 60 * 60;
+/// How long before a pass period ends that a subscription may renew it.
+/// Passes shorter than this use their own duration as the window.
+const PASS_RENEWAL_WINDOW_SECS: u64 = 24 * 60 * 60;
+
+// Dispute arbitration: a first panel of three, and a single appeal to a
+// fresh panel of up to five, sized down to the arbiters still eligible.
+// Each panel decides by simple majority.
+const MAX_ARBITERS: u32 = 100;
+const ARBITRATION_PANEL_SIZE: u32 = 3;
+const APPEAL_PANEL_SIZE: u32 = 5;
+const ARBITRATION_VOTING_SECS: u64 = 7 * 24 * 60 * 60;
+const APPEAL_WINDOW_SECS: u64 = 2 * 24 * 60 * 60;
+const MAX_DISPUTE_EVIDENCE: u32 = 20;
+
+/// Default ledgers a governance proposal must wait before it can execute
+/// (#569), roughly one hour. Adjustable within the bounds below through
+/// `GovernanceAction::SetGovernanceDelay`, which is itself delayed.
+const DEFAULT_GOVERNANCE_DELAY_LEDGERS: u32 = 720;
+const MIN_GOVERNANCE_DELAY_LEDGERS: u32 = 120;
+const MAX_GOVERNANCE_DELAY_LEDGERS: u32 = 30 * super::storage::DAY_IN_LEDGERS;
+/// Ledgers after `executable_at_ledger` during which a proposal may still
+/// execute before it lapses.
+const GOVERNANCE_EXECUTION_WINDOW_LEDGERS: u32 = 7 * super::storage::DAY_IN_LEDGERS;
 
 #[contract]
 pub struct PromptHashContract;
@@ -100,6 +150,12 @@ impl PromptHashTrait for PromptHashContract {
         validate_no_duplicate_recipients(&listing.splits)?;
         ensure(listing.splits.len() <= MAX_SPLITS, Error::TooManySplits)?;
         validate_tags(&listing.tags)?;
+        ensure(
+            listing.royalty_bps <= MAX_RESALE_ROYALTY_BPS,
+            Error::InvalidRoyaltyBps,
+        )?;
+        validate_royalty_splits(&listing.royalty_splits)?;
+        validate_pricing(&env, &listing.asset, &listing.pricing)?;
 
         let prompt_id = InstanceStorage::get_prompt_counter(&env);
         InstanceStorage::save_prompt_counter(&env, prompt_id + 1);
@@ -123,6 +179,10 @@ impl PromptHashTrait for PromptHashContract {
             splits: listing.splits,
             revision: 0,
             tags: listing.tags,
+            royalty_bps: listing.royalty_bps,
+            royalty_splits: listing.royalty_splits,
+            resale_allowed: listing.resale_allowed,
+            pricing: listing.pricing,
         };
 
         Storage::save_prompt(&env, &prompt)?;
@@ -148,8 +208,9 @@ impl PromptHashTrait for PromptHashContract {
         )?;
         ensure(prompt.status != status, Error::InvalidStatusTransition)?;
 
+        let previous = prompt.clone();
         prompt.status = status.clone();
-        Storage::update_prompt(&env, &prompt);
+        Storage::update_listing(&env, &previous, &prompt);
         Events::emit_prompt_sale_status_updated(&env, prompt_id, status);
         Ok(())
     }
@@ -172,8 +233,9 @@ impl PromptHashTrait for PromptHashContract {
         )?;
         ensure(prompt.status != status, Error::InvalidStatusTransition)?;
 
+        let previous = prompt.clone();
         prompt.status = status.clone();
-        Storage::update_prompt(&env, &prompt);
+        Storage::update_listing(&env, &previous, &prompt);
         Events::emit_prompt_admin_moderated(&env, prompt_id, admin, status);
         Ok(())
     }
@@ -208,7 +270,7 @@ impl PromptHashTrait for PromptHashContract {
     ) -> Result<(), Error> {
         creator.require_auth();
         ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
-        ensure(price_stroops > 0, Error::InvalidPrice)?;
+        ensure(price_stroops >= 0, Error::InvalidPrice)?;
 
         let mut prompt = Storage::require_prompt(&env, prompt_id)?;
         ensure(prompt.creator == creator, Error::Unauthorized)?;
@@ -226,6 +288,115 @@ impl PromptHashTrait for PromptHashContract {
         referrer: Option<Address>,
         payment_amount_stroops: i128,
         voucher: Option<Bytes>,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        execute_buy(
+            &env,
+            &buyer,
+            prompt_id,
+            &prompt.asset,
+            &referrer,
+            payment_amount_stroops,
+            voucher,
+            AcquisitionKind::DirectPurchase,
+        )
+    }
+
+    fn claim_free_prompt(env: Env, buyer: Address, prompt_id: u64) -> Result<(), Error> {
+        buyer.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let mut prompt = Storage::require_prompt(&env, prompt_id)?;
+        check_direct_purchase(&env, &buyer, &prompt)?;
+        ensure(
+            listing_price(&env, &prompt, &prompt.asset)?.0 == 0,
+            Error::NotFreeListing,
+        )?;
+        ensure(
+            !Storage::has_open_auction(&env, prompt_id),
+            Error::ListingInAuction,
+        )?;
+
+        // Nothing is paid, so no liability moves; the claim is still
+        // recorded as a settled zero-value acquisition backing the license.
+        prompt.sales_count = reserve_supply(prompt.sales_count, prompt.max_supply)?;
+        Storage::update_prompt(&env, &prompt);
+        grant_license(&env, &prompt, &buyer, 0, MAX_ACCESS_EXPIRY)?;
+        let now = env.ledger().timestamp();
+        let fee_wallet = InstanceStorage::get_fee_wallet(&env).ok_or(Error::FeeWalletNotSet)?;
+        let escrow = PurchaseEscrow {
+            prompt_id,
+            buyer: buyer.clone(),
+            amount: 0,
+            refunded_amount: 0,
+            asset: prompt.asset.clone(),
+            referrer: None,
+            status: SettlementStatus::Settled,
+            created_at: now,
+            settled_at: now,
+            dispute_deadline: now,
+            creator_amount: 0,
+            fee_amount: 0,
+            referral_amount: 0,
+            payout_plan: super::types::PayoutPlan {
+                creator: prompt.creator.clone(),
+                fee_wallet,
+                fee_amount: 0,
+                referrer: None,
+                referral_amount: 0,
+                splits: Vec::new(&env),
+                creator_amount: 0,
+                conversion: PriceConversion::Listed,
+            },
+        };
+        record_acquisition(
+            &env,
+            AcquisitionKind::FreeClaim,
+            prompt_id as u128,
+            &escrow,
+            &Vec::from_array(&env, [prompt_id]),
+        )?;
+        Events::emit_prompt_claimed(&env, prompt_id, buyer);
+        Ok(())
+    }
+
+    fn set_pay_what_you_want(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        minimum: Option<i128>,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+        if let Some(minimum) = minimum {
+            // An open price and an oracle-converted one cannot both apply.
+            ensure(
+                minimum >= 0 && Storage::get_reference_price(&env, prompt_id).is_none(),
+                Error::InvalidPrice,
+            )?;
+        }
+
+        Storage::set_pay_what_you_want(&env, prompt_id, minimum);
+        Storage::sync_price_index(&env, &prompt);
+        Events::emit_pay_what_you_want_updated(&env, prompt_id, minimum);
+        Ok(())
+    }
+
+    fn get_pay_what_you_want(env: Env, prompt_id: u64) -> Option<i128> {
+        Storage::get_pay_what_you_want(&env, prompt_id)
+    }
+
+    fn buy_prompt_in_asset(
+        env: Env,
+        buyer: Address,
+        prompt_id: u64,
+        asset: Address,
+        referrer: Option<Address>,
+        payment_amount_stroops: i128,
+        voucher: Option<Bytes>,
     ) -> Result<(), Error> {
         buyer.require_auth();
         ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
@@ -233,12 +404,37 @@ impl PromptHashTrait for PromptHashContract {
             &env,
             &buyer,
             prompt_id,
+            &asset,
             &referrer,
             payment_amount_stroops,
             voucher,
+            AcquisitionKind::DirectPurchase,
         )
     }
 
+    fn set_listing_pricing(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        pricing: Vec<PricingConfig>,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let mut prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+        validate_pricing(&env, &prompt.asset, &pricing)?;
+
+        prompt.pricing = pricing.clone();
+        Storage::update_prompt(&env, &prompt);
+        Events::emit_listing_pricing_updated(&env, prompt_id, pricing);
+        Ok(())
+    }
+
+    fn get_listing_price(env: Env, prompt_id: u64, asset: Address) -> Result<i128, Error> {
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        Ok(listing_price(&env, &prompt, &asset)?.0)
+    }
+
     fn buy_prompt_with_auth(
         env: Env,
         buyer: Address,
@@ -307,13 +503,12 @@ impl PromptHashTrait for PromptHashContract {
         let _ = creator_sig;
 
         // 8. Execute buy with discount
-        let discount_amount = prompt
-            .price_stroops
+        let (price, conversion) = listing_price(&env, &prompt, &prompt.asset)?;
+        let discount_amount = price
             .checked_mul(authorization.discount_bps as i128)
             .ok_or(Error::ArithmeticOverflow)?
             / MAX_BPS as i128;
-        let required_price = prompt
-            .price_stroops
+        let required_price = price
             .checked_sub(discount_amount)
             .ok_or(Error::ArithmeticOverflow)?;
 
@@ -327,9 +522,12 @@ impl PromptHashTrait for PromptHashContract {
             &env,
             &buyer,
             prompt_id,
+            &prompt.asset,
             &referrer,
             payment_amount_stroops,
             required_price,
+            conversion,
+            AcquisitionKind::DirectPurchase,
         )?;
 
         Events::emit_discount_applied(&env, prompt_id, buyer, authorization.discount_bps);
@@ -344,97 +542,208 @@ impl PromptHashTrait for PromptHashContract {
     ) -> Result<(), Error> {
         buyer.require_auth();
         ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
-        let mut prompt = Storage::require_prompt(&env, prompt_id)?;
+        execute_lease(&env, &buyer, prompt_id, lease_duration_secs)
+    }
+
+    fn set_lease_terms(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        terms: Option<LeaseTerms>,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+        if let Some(ref terms) = terms {
+            ensure(
+                terms.price_per_period > 0
+                    && terms.period_secs > 0
+                    && terms.min_duration_secs > 0
+                    && terms.min_duration_secs <= terms.max_duration_secs,
+                Error::InvalidLeaseTerms,
+            )?;
+        }
+        Storage::set_lease_terms(&env, prompt_id, &terms);
+        Events::emit_lease_terms_updated(
+            &env,
+            prompt_id,
+            terms.map(|terms| terms.enabled).unwrap_or(true),
+        );
+        Ok(())
+    }
+
+    fn get_lease_terms(env: Env, prompt_id: u64) -> Option<LeaseTerms> {
+        Storage::get_lease_terms(&env, prompt_id)
+    }
+
+    fn get_lease_price(env: Env, prompt_id: u64, lease_duration_secs: u64) -> Result<i128, Error> {
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        lease_price_for(&env, &prompt, lease_duration_secs)
+    }
+
+    fn get_lease(env: Env, prompt_id: u64, lessee: Address) -> Result<Lease, Error> {
+        Storage::get_lease(&env, prompt_id, &lessee).ok_or(Error::LeaseNotFound)
+    }
+
+    fn renew_lease(
+        env: Env,
+        lessee: Address,
+        prompt_id: u64,
+        lease_duration_secs: u64,
+    ) -> Result<(), Error> {
+        lessee.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        let lease = Storage::get_lease(&env, prompt_id, &lessee).ok_or(Error::LeaseNotFound)?;
+        let purchase =
+            Storage::get_purchase(&env, prompt_id, &lessee).ok_or(Error::LeaseNotFound)?;
+        // The license may have been converted or moved on since the lease
+        // was recorded; only the lease it still describes can be renewed.
+        ensure(
+            purchase.expires_at == lease.expires_at,
+            Error::LeaseNotFound,
+        )?;
+        check_lease_listing(&env, &prompt)?;
+
         let now = env.ledger().timestamp();
+        let lease_price = lease_price_for(&env, &prompt, lease_duration_secs)?;
+        // Like `buy_access_pass`, an active lease extends from its current
+        // expiry rather than from now.
+        let active = lease.expires_at > now;
+        let starts_at = if active { lease.expires_at } else { now };
+        let expires_at = starts_at
+            .checked_add(lease_duration_secs)
+            .ok_or(Error::ArithmeticOverflow)?;
+
+        InstanceStorage::set_reentrancy_guard(&env)?;
+        let settlement_id = collect_lease_payment(&env, &lessee, &prompt, lease_price, expires_at)?;
+        // The renewal reserves no supply of its own; a refund of it rolls
+        // the lease back to where it stood before.
+        Storage::save_lease_renewal(&env, settlement_id, expires_at - lease.expires_at);
+        Storage::save_lease(
+            &env,
+            &Lease {
+                prompt_id,
+                lessee: lessee.clone(),
+                paid_total: if active {
+                    lease
+                        .paid_total
+                        .checked_add(lease_price)
+                        .ok_or(Error::ArithmeticOverflow)?
+                } else {
+                    lease_price
+                },
+                started_at: if active { lease.started_at } else { now },
+                expires_at,
+            },
+        );
+        InstanceStorage::clear_reentrancy_guard(&env);
+
+        Events::emit_lease_renewed(&env, prompt_id, lessee, lease_price, expires_at);
+        Ok(())
+    }
+
+    fn convert_lease(
+        env: Env,
+        lessee: Address,
+        prompt_id: u64,
+        max_charge: i128,
+    ) -> Result<(), Error> {
+        lessee.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
 
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
         ensure(
             prompt.status == PromptSaleStatus::Active,
             Error::PromptInactive,
         )?;
-        ensure(prompt.creator != buyer, Error::CreatorCannotBuy)?;
-        ensure(lease_duration_secs > 0, Error::InvalidPrice)?;
-        ensure(
-            !Storage::has_active_purchase(&env, prompt_id, &buyer, now),
-            Error::AlreadyPurchased,
-        )?;
-
         if prompt.expires_at != 0 {
-            ensure(prompt.expires_at >= now, Error::ListingExpired)?;
+            ensure(
+                prompt.expires_at >= env.ledger().timestamp(),
+                Error::ListingExpired,
+            )?;
         }
+        ensure(
+            !Storage::has_open_auction(&env, prompt_id),
+            Error::ListingInAuction,
+        )?;
 
-        // Leases consume the same supply pool as direct sales (#538).
-        let reserved_sales_count = reserve_supply(prompt.sales_count, prompt.max_supply)?;
+        let (price, conversion) = listing_price(&env, &prompt, &prompt.asset)?;
+        let credit = lease_conversion_credit(&env, &lessee, prompt_id, price)?;
+        let charge = price.checked_sub(credit).ok_or(Error::ArithmeticOverflow)?;
+        ensure(charge <= max_charge, Error::InvalidPaymentAmount)?;
 
+        // The lease already holds a supply unit, so none is reserved here.
         InstanceStorage::set_reentrancy_guard(&env)?;
+        if charge > 0 {
+            let this_contract = env.current_contract_address();
+            token::StellarAssetClient::new(&env, &prompt.asset).transfer_from(
+                &this_contract,
+                &lessee,
+                &this_contract,
+                &charge,
+            );
+        }
+        let escrow = open_sale_escrow(
+            &env,
+            &lessee,
+            &prompt,
+            &prompt.asset,
+            &None,
+            charge,
+            conversion,
+            AcquisitionKind::DirectPurchase,
+        )?;
+        Storage::add_pending_liability(&env, &escrow.asset, escrow.amount)?;
+        Storage::remove_lease(&env, prompt_id, &lessee);
+        InstanceStorage::clear_reentrancy_guard(&env);
 
-        let fee_wallet = InstanceStorage::get_fee_wallet(&env).ok_or(Error::FeeWalletNotSet)?;
-        let this_contract = env.current_contract_address();
-        let fee_percentage = InstanceStorage::get_fee_percentage(&env);
-        ensure(fee_percentage <= MAX_BPS, Error::InvalidFeePercentage)?;
-
-        let lease_price = prompt
-            .price_stroops
-            .checked_mul(LEASE_PRICE_BPS as i128)
-            .ok_or(Error::ArithmeticOverflow)?
-            / MAX_BPS as i128;
-        ensure(lease_price > 0, Error::InvalidPrice)?;
+        Events::emit_lease_converted(&env, prompt_id, lessee.clone(), credit, charge);
+        Events::emit_prompt_purchased(&env, prompt_id, lessee, prompt.creator, charge, None);
+        Ok(())
+    }
 
-        let fee_amount = lease_price
-            .checked_mul(fee_percentage as i128)
-            .ok_or(Error::ArithmeticOverflow)?
-            / MAX_BPS as i128;
-        let seller_amount = lease_price
-            .checked_sub(fee_amount)
-            .ok_or(Error::ArithmeticOverflow)?;
+    fn get_lease_conversion_credit(
+        env: Env,
+        prompt_id: u64,
+        lessee: Address,
+    ) -> Result<i128, Error> {
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        let (price, _) = listing_price(&env, &prompt, &prompt.asset)?;
+        lease_conversion_credit(&env, &lessee, prompt_id, price)
+    }
 
-        // Route the full lease payment through the contract so it holds
-        // escrow for dispute refunds (#564). The buyer must have
-        // approved the contract for at least `lease_price`.
-        let asset_client = token::StellarAssetClient::new(&env, &prompt.asset);
-        asset_client.transfer_from(&this_contract, &buyer, &this_contract, &lease_price);
+    fn update_tags(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        tags: Vec<String>,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let mut prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+        validate_tags(&tags)?;
 
-        // Distribute from the contract's held balance
-        if seller_amount > 0 {
-            asset_client.transfer(&this_contract, &prompt.creator, &seller_amount);
+        let mut added = Vec::new(&env);
+        for tag in tags.iter() {
+            if !prompt.tags.contains(&tag) {
+                added.push_back(tag);
+            }
         }
-        if fee_amount > 0 {
-            asset_client.transfer(&this_contract, &fee_wallet, &fee_amount);
+        let mut removed = Vec::new(&env);
+        for tag in prompt.tags.iter() {
+            if !tags.contains(&tag) {
+                removed.push_back(tag);
+            }
         }
 
-        prompt.sales_count = reserved_sales_count;
-        let expires_at = now
-            .checked_add(lease_duration_secs)
-            .ok_or(Error::ArithmeticOverflow)?;
-        Storage::update_prompt(&env, &prompt);
-        Storage::grant_purchase(&env, &prompt, &buyer, lease_price, expires_at);
-        let payout_plan = super::types::PayoutPlan {
-            creator: prompt.creator.clone(),
-            fee_wallet: fee_wallet.clone(),
-            fee_amount,
-            referrer: None,
-            referral_amount: 0,
-            splits: Vec::new(&env),
-            creator_amount: seller_amount,
-        };
-        let escrow = PurchaseEscrow {
-            prompt_id,
-            buyer: buyer.clone(),
-            amount: lease_price,
-            asset: prompt.asset.clone(),
-            referrer: None,
-            status: SettlementStatus::Settled,
-            created_at: now,
-            settled_at: now,
-            // Lease funds settle immediately — there is no pending window.
-            dispute_deadline: now,
-            creator_amount: seller_amount,
-            fee_amount,
-            referral_amount: 0,
-            payout_plan,
-        };
-        Storage::save_purchase_escrow(&env, &escrow);
-        InstanceStorage::clear_reentrancy_guard(&env);
-        Events::emit_prompt_purchased(&env, prompt_id, buyer, prompt.creator, lease_price, None);
+        let previous = prompt.clone();
+        prompt.tags = tags;
+        Storage::update_listing(&env, &previous, &prompt);
+        Events::emit_tags_updated(&env, prompt_id, added, removed);
         Ok(())
     }
 
@@ -452,8 +761,9 @@ impl PromptHashTrait for PromptHashContract {
         let now = env.ledger().timestamp();
         ensure(new_expires_at > now, Error::InvalidPrice)?;
 
+        let previous = prompt.clone();
         prompt.expires_at = new_expires_at;
-        Storage::update_prompt(&env, &prompt);
+        Storage::update_listing(&env, &previous, &prompt);
         Events::emit_listing_extended(&env, prompt_id, new_expires_at);
         Ok(())
     }
@@ -480,244 +790,340 @@ impl PromptHashTrait for PromptHashContract {
         for i in 0..prompt_ids.len() {
             let prompt_id = prompt_ids.get(i).unwrap();
             let payment_amount = payment_amounts.get(i).unwrap();
-            execute_buy(&env, &buyer, prompt_id, &referrer, payment_amount, None)?;
+            let prompt = Storage::require_prompt(&env, prompt_id)?;
+            execute_buy(
+                &env,
+                &buyer,
+                prompt_id,
+                &prompt.asset,
+                &referrer,
+                payment_amount,
+                None,
+                AcquisitionKind::BulkCheckout,
+            )?;
         }
         Ok(())
     }
 
-    fn create_bundle(
+    fn buy_with_quote(
         env: Env,
-        creator: Address,
-        title: String,
-        prompt_ids: Vec<u64>,
-        price_stroops: i128,
-        asset: Address,
-        expires_at: u64,
-    ) -> Result<u128, Error> {
-        creator.require_auth();
+        quote: QuoteCommitment,
+        referrer: Option<Address>,
+    ) -> Result<(), Error> {
+        quote.buyer.require_auth();
         ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
-        validate_len(&title, MAX_TITLE_LEN, Error::InvalidTitleLength)?;
-        ensure(price_stroops > 0, Error::InvalidPrice)?;
-        ensure(!prompt_ids.is_empty(), Error::InvalidBundle)?;
-        ensure(prompt_ids.len() <= MAX_BUNDLE_PROMPTS, Error::InvalidBundle)?;
-        token::Client::new(&env, &asset).decimals();
 
-        let now = env.ledger().timestamp();
-        if expires_at != 0 {
-            ensure(expires_at > now, Error::ListingExpired)?;
-        }
+        let prompt_id = quote_prompt_id(quote.acquisition_id)?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
 
-        for index in 0..prompt_ids.len() {
-            let prompt = Storage::require_prompt(&env, prompt_ids.get(index).unwrap())?;
-            ensure(prompt.creator == creator, Error::Unauthorized)?;
-            ensure(
-                prompt.status == PromptSaleStatus::Active,
-                Error::PromptInactive,
-            )?;
-            ensure(prompt.asset == asset, Error::InvalidAsset)?;
-            if prompt.expires_at != 0 {
-                ensure(prompt.expires_at >= now, Error::ListingExpired)?;
-            }
-            for duplicate_index in (index + 1)..prompt_ids.len() {
-                ensure(
-                    prompt_ids.get(index).unwrap() != prompt_ids.get(duplicate_index).unwrap(),
-                    Error::InvalidBundle,
-                )?;
+        // The quote may name any asset the listing accepts.
+        let (price, conversion) = listing_price(&env, &prompt, &quote.asset).map_err(|e| {
+            if e == Error::InvalidAsset {
+                Error::QuoteAssetMismatch
+            } else {
+                e
             }
-        }
+        })?;
+        let terms_hash =
+            quote_terms_hash(&env, AcquisitionKind::DirectPurchase, quote.acquisition_id)?;
+        verify_quote(
+            &env,
+            &quote,
+            AcquisitionKind::DirectPurchase,
+            &quote.asset,
+            &terms_hash,
+            price,
+        )?;
+        check_direct_purchase(&env, &quote.buyer, &prompt)?;
+        check_referrer(&quote.buyer, &prompt, &referrer)?;
 
-        let bundle_id = Storage::get_bundle_counter(&env);
-        let bundle = Bundle {
-            id: bundle_id,
-            creator: creator.clone(),
-            title,
-            prompt_ids,
-            price_stroops,
-            asset,
-            active: true,
-            sales_count: 0,
-            expires_at,
-        };
+        let payment = price
+            .checked_add(quote.tip_amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        execute_buy_with_required_price(
+            &env,
+            &quote.buyer,
+            prompt_id,
+            &quote.asset,
+            &referrer,
+            payment,
+            price,
+            conversion,
+            AcquisitionKind::DirectPurchase,
+        )?;
 
-        Storage::save_bundle(&env, &bundle)?;
-        Storage::add_bundle_to_creator(&env, &creator, bundle_id);
-        Events::emit_bundle_created(&env, bundle_id, creator, price_stroops);
-        Ok(bundle_id)
+        Events::emit_quote_redeemed(
+            &env,
+            quote.buyer,
+            quote.kind,
+            quote.acquisition_id,
+            price,
+            quote.asset,
+            quote.tip_amount,
+            quote.nonce,
+        );
+        Ok(())
     }
 
-    fn buy_bundle(
+    fn lease_with_quote(
         env: Env,
-        buyer: Address,
-        bundle_id: u128,
-        payment_amount_stroops: i128,
+        quote: QuoteCommitment,
+        lease_duration_secs: u64,
     ) -> Result<(), Error> {
-        buyer.require_auth();
+        quote.buyer.require_auth();
         ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
-        let now = env.ledger().timestamp();
-        let mut bundle = Storage::require_bundle(&env, bundle_id)?;
 
-        ensure(bundle.active, Error::PromptInactive)?;
-        ensure(bundle.creator != buyer, Error::CreatorCannotBuy)?;
+        let prompt_id = quote_prompt_id(quote.acquisition_id)?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        let lease_price = lease_price_for(&env, &prompt, lease_duration_secs)?;
+
+        let terms_hash = lease_quote_terms_hash(&env, prompt_id, lease_duration_secs)?;
+        verify_quote(
+            &env,
+            &quote,
+            AcquisitionKind::Lease,
+            &prompt.asset,
+            &terms_hash,
+            lease_price,
+        )?;
+        execute_lease(&env, &quote.buyer, prompt_id, lease_duration_secs)?;
+
+        Events::emit_quote_redeemed(
+            &env,
+            quote.buyer,
+            quote.kind,
+            quote.acquisition_id,
+            lease_price,
+            quote.asset,
+            0,
+            quote.nonce,
+        );
+        Ok(())
+    }
+
+    fn buy_bundle_with_quote(env: Env, quote: QuoteCommitment) -> Result<(), Error> {
+        quote.buyer.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+
+        let bundle = Storage::require_bundle(&env, quote.acquisition_id)?;
+        let terms_hash = quote_terms_hash(&env, AcquisitionKind::Bundle, quote.acquisition_id)?;
+        verify_quote(
+            &env,
+            &quote,
+            AcquisitionKind::Bundle,
+            &bundle.asset,
+            &terms_hash,
+            bundle.price_stroops,
+        )?;
+        execute_buy_bundle(
+            &env,
+            &quote.buyer,
+            quote.acquisition_id,
+            bundle.price_stroops,
+        )?;
+
+        Events::emit_quote_redeemed(
+            &env,
+            quote.buyer,
+            quote.kind,
+            quote.acquisition_id,
+            bundle.price_stroops,
+            quote.asset,
+            0,
+            quote.nonce,
+        );
+        Ok(())
+    }
+
+    fn buy_pass_with_quote(env: Env, quote: QuoteCommitment) -> Result<(), Error> {
+        quote.buyer.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+
+        let access_pass = Storage::require_access_pass(&env, quote.acquisition_id)?;
+        let terms_hash = quote_terms_hash(&env, AcquisitionKind::AccessPass, quote.acquisition_id)?;
+        verify_quote(
+            &env,
+            &quote,
+            AcquisitionKind::AccessPass,
+            &access_pass.asset,
+            &terms_hash,
+            access_pass.price_stroops,
+        )?;
+        execute_buy_access_pass(
+            &env,
+            &quote.buyer,
+            quote.acquisition_id,
+            access_pass.price_stroops,
+        )?;
+
+        Events::emit_quote_redeemed(
+            &env,
+            quote.buyer,
+            quote.kind,
+            quote.acquisition_id,
+            access_pass.price_stroops,
+            quote.asset,
+            0,
+            quote.nonce,
+        );
+        Ok(())
+    }
+
+    fn buy_bulk_with_quote(
+        env: Env,
+        quote: QuoteCommitment,
+        prompt_ids: Vec<u64>,
+        referrer: Option<Address>,
+    ) -> Result<(), Error> {
+        quote.buyer.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
         ensure(
-            payment_amount_stroops >= bundle.price_stroops,
-            Error::InvalidPaymentAmount,
+            !prompt_ids.is_empty() && prompt_ids.len() <= MAX_BULK_PURCHASE_SIZE,
+            Error::BulkPurchaseTooLarge,
         )?;
-        if bundle.expires_at != 0 {
-            ensure(bundle.expires_at >= now, Error::ListingExpired)?;
+        validate_no_duplicate_prompt_ids(&prompt_ids)?;
+
+        // Every item must accept the quoted asset so `max_charge` caps a
+        // single, comparable total.
+        let mut total: i128 = 0;
+        for i in 0..prompt_ids.len() {
+            let prompt = Storage::require_prompt(&env, prompt_ids.get(i).unwrap())?;
+            let (price, _) = listing_price(&env, &prompt, &quote.asset).map_err(|e| {
+                if e == Error::InvalidAsset {
+                    Error::QuoteAssetMismatch
+                } else {
+                    e
+                }
+            })?;
+            total = total.checked_add(price).ok_or(Error::ArithmeticOverflow)?;
         }
 
-        let mut prompts = Vec::new(&env);
-        let mut needs_access = false;
-        for index in 0..bundle.prompt_ids.len() {
-            let mut prompt = Storage::require_prompt(&env, bundle.prompt_ids.get(index).unwrap())?;
-            ensure(prompt.creator == bundle.creator, Error::Unauthorized)?;
-            ensure(
-                prompt.status == PromptSaleStatus::Active,
-                Error::PromptInactive,
+        let terms_hash = bulk_quote_terms_hash(&env, &prompt_ids)?;
+        verify_quote(
+            &env,
+            &quote,
+            AcquisitionKind::BulkCheckout,
+            &quote.asset,
+            &terms_hash,
+            total,
+        )?;
+
+        for i in 0..prompt_ids.len() {
+            let prompt = Storage::require_prompt(&env, prompt_ids.get(i).unwrap())?;
+            execute_buy(
+                &env,
+                &quote.buyer,
+                prompt.id,
+                &quote.asset,
+                &referrer,
+                listing_price(&env, &prompt, &quote.asset)?.0,
+                None,
+                AcquisitionKind::BulkCheckout,
             )?;
-            ensure(prompt.asset == bundle.asset, Error::InvalidAsset)?;
-            if prompt.expires_at != 0 {
-                ensure(prompt.expires_at >= now, Error::ListingExpired)?;
-            }
-            if !Storage::has_active_purchase(&env, prompt.id, &buyer, now) {
-                needs_access = true;
-                prompt.sales_count = reserve_supply(prompt.sales_count, prompt.max_supply)?;
-            }
-            prompts.push_back(prompt);
         }
-        ensure(needs_access, Error::AlreadyPurchased)?;
-
-        InstanceStorage::set_reentrancy_guard(&env)?;
 
-        // Route the full payment through the contract so it holds
-        // escrow for dispute refunds (#454, #563). The buyer must have
-        // approved the contract for at least `payment_amount_stroops`.
-        let this_contract = env.current_contract_address();
-        let asset_client = token::StellarAssetClient::new(&env, &bundle.asset);
-        asset_client.transfer_from(
-            &this_contract,
-            &buyer,
-            &this_contract,
-            &payment_amount_stroops,
+        Events::emit_quote_redeemed(
+            &env,
+            quote.buyer,
+            quote.kind,
+            quote.acquisition_id,
+            total,
+            quote.asset,
+            0,
+            quote.nonce,
         );
+        Ok(())
+    }
 
-        // Calculate all allocations from the single payment amount.
-        let fee_percentage = InstanceStorage::get_fee_percentage(&env);
-        ensure(fee_percentage <= MAX_BPS, Error::InvalidFeePercentage)?;
+    fn get_quote_terms_hash(
+        env: Env,
+        kind: AcquisitionKind,
+        acquisition_id: u128,
+    ) -> Result<BytesN<32>, Error> {
+        quote_terms_hash(&env, kind, acquisition_id)
+    }
 
-        let fee_amount = payment_amount_stroops
-            .checked_mul(fee_percentage as i128)
-            .ok_or(Error::ArithmeticOverflow)?
-            / MAX_BPS as i128;
+    fn get_bulk_quote_terms_hash(env: Env, prompt_ids: Vec<u64>) -> Result<BytesN<32>, Error> {
+        bulk_quote_terms_hash(&env, &prompt_ids)
+    }
 
-        let fee_wallet = InstanceStorage::get_fee_wallet(&env).ok_or(Error::FeeWalletNotSet)?;
+    fn get_lease_quote_terms_hash(
+        env: Env,
+        prompt_id: u64,
+        lease_duration_secs: u64,
+    ) -> Result<BytesN<32>, Error> {
+        lease_quote_terms_hash(&env, prompt_id, lease_duration_secs)
+    }
 
-        // Distribute fee to the fee wallet from the contract's held balance
-        if fee_amount > 0 {
-            asset_client.transfer(&this_contract, &fee_wallet, &fee_amount);
-        }
-
-        // Distribute collaborator splits from the contract's held balance
-        let mut split_total: i128 = 0;
-        let mut payout_splits: Vec<super::types::PayoutSplit> = Vec::new(&env);
-        for index in 0..prompts.len() {
-            let prompt = prompts.get(index).unwrap();
-            if !Storage::has_active_purchase(&env, prompt.id, &buyer, now) {
-                for split_idx in 0..prompt.splits.len() {
-                    let split = prompt.splits.get(split_idx).unwrap();
-                    let split_amount = payment_amount_stroops
-                        .checked_mul(split.bps as i128)
-                        .ok_or(Error::ArithmeticOverflow)?
-                        / MAX_BPS as i128;
-                    split_total = split_total
-                        .checked_add(split_amount)
-                        .ok_or(Error::ArithmeticOverflow)?;
-                    if split_amount > 0 {
-                        asset_client.transfer(&this_contract, &split.recipient, &split_amount);
-                        payout_splits.push_back(super::types::PayoutSplit {
-                            recipient: split.recipient.clone(),
-                            amount: split_amount,
-                        });
-                    }
-                }
-            }
+    fn create_bundle(
+        env: Env,
+        creator: Address,
+        title: String,
+        prompt_ids: Vec<u64>,
+        price_stroops: i128,
+        asset: Address,
+        expires_at: u64,
+    ) -> Result<u128, Error> {
+        creator.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        validate_len(&title, MAX_TITLE_LEN, Error::InvalidTitleLength)?;
+        ensure(price_stroops > 0, Error::InvalidPrice)?;
+        ensure(!prompt_ids.is_empty(), Error::InvalidBundle)?;
+        ensure(prompt_ids.len() <= MAX_BUNDLE_PROMPTS, Error::InvalidBundle)?;
+        token::Client::new(&env, &asset).decimals();
+
+        let now = env.ledger().timestamp();
+        if expires_at != 0 {
+            ensure(expires_at > now, Error::ListingExpired)?;
         }
 
-        // Creator receives the remainder after all deductions
-        let creator_amount = payment_amount_stroops
-            .checked_sub(fee_amount)
-            .ok_or(Error::ArithmeticOverflow)?
-            .checked_sub(split_total)
-            .ok_or(Error::ArithmeticOverflow)?;
-        ensure(creator_amount >= 0, Error::InvalidSplits)?;
-
-        if creator_amount > 0 {
-            asset_client.transfer(&this_contract, &bundle.creator, &creator_amount);
-        }
-
-        // Update prompt sales counts and grant access for newly purchased prompts
-        for index in 0..prompts.len() {
-            let prompt = prompts.get(index).unwrap();
-            if !Storage::has_active_purchase(&env, prompt.id, &buyer, now) {
-                Storage::update_prompt(&env, &prompt);
-                Storage::grant_purchase(
-                    &env,
-                    &prompt,
-                    &buyer,
-                    payment_amount_stroops
-                        .checked_div(prompts.len() as i128)
-                        .ok_or(Error::InvalidPaymentAmount)?,
-                    MAX_ACCESS_EXPIRY,
-                );
+        for index in 0..prompt_ids.len() {
+            let prompt = Storage::require_prompt(&env, prompt_ids.get(index).unwrap())?;
+            ensure(prompt.creator == creator, Error::Unauthorized)?;
+            ensure(
+                prompt.status == PromptSaleStatus::Active,
+                Error::PromptInactive,
+            )?;
+            price_in(&prompt, &asset)?;
+            if prompt.expires_at != 0 {
+                ensure(prompt.expires_at >= now, Error::ListingExpired)?;
+            }
+            for duplicate_index in (index + 1)..prompt_ids.len() {
+                ensure(
+                    prompt_ids.get(index).unwrap() != prompt_ids.get(duplicate_index).unwrap(),
+                    Error::InvalidBundle,
+                )?;
             }
         }
 
-        bundle.sales_count = bundle
-            .sales_count
-            .checked_add(1)
-            .ok_or(Error::ArithmeticOverflow)?;
-        Storage::update_bundle(&env, &bundle);
-
-        // Create escrow with payout plan for unified dispute/settlement (#564)
-        let fee_wallet = InstanceStorage::get_fee_wallet(&env).ok_or(Error::FeeWalletNotSet)?;
-        let payout_plan = super::types::PayoutPlan {
-            creator: bundle.creator.clone(),
-            fee_wallet: fee_wallet.clone(),
-            fee_amount,
-            referrer: None,
-            referral_amount: 0,
-            splits: payout_splits,
-            creator_amount,
-        };
-        let escrow = PurchaseEscrow {
-            prompt_id: 0, // Bundles don't have a single prompt ID
-            buyer: buyer.clone(),
-            amount: payment_amount_stroops,
-            asset: bundle.asset.clone(),
-            referrer: None,
-            status: SettlementStatus::Settled,
-            created_at: now,
-            settled_at: now,
-            // Bundle funds settle immediately — there is no pending window (#541).
-            dispute_deadline: now,
-            creator_amount,
-            fee_amount,
-            referral_amount: 0,
-            payout_plan,
+        let bundle_id = Storage::get_bundle_counter(&env);
+        let bundle = Bundle {
+            id: bundle_id,
+            creator: creator.clone(),
+            title,
+            prompt_ids,
+            price_stroops,
+            asset,
+            active: true,
+            sales_count: 0,
+            expires_at,
         };
-        Storage::save_purchase_escrow(&env, &escrow);
 
-        InstanceStorage::clear_reentrancy_guard(&env);
+        Storage::save_bundle(&env, &bundle)?;
+        Storage::add_bundle_to_creator(&env, &creator, bundle_id);
+        Events::emit_bundle_created(&env, bundle_id, creator, price_stroops);
+        Ok(bundle_id)
+    }
 
-        Events::emit_bundle_purchased(
-            &env,
-            bundle_id,
-            buyer,
-            bundle.creator,
-            payment_amount_stroops,
-            bundle.prompt_ids,
-        );
-        Ok(())
+    fn buy_bundle(
+        env: Env,
+        buyer: Address,
+        bundle_id: u128,
+        payment_amount_stroops: i128,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        execute_buy_bundle(&env, &buyer, bundle_id, payment_amount_stroops)
     }
 
     fn get_bundle(env: Env, bundle_id: u128) -> Result<Bundle, Error> {
@@ -814,126 +1220,136 @@ impl PromptHashTrait for PromptHashContract {
     ) -> Result<(), Error> {
         buyer.require_auth();
         ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
-        let mut access_pass = Storage::require_access_pass(&env, pass_id)?;
-        let now = env.ledger().timestamp();
+        execute_buy_access_pass(&env, &buyer, pass_id, payment_amount_stroops)
+    }
 
+    fn get_access_pass(env: Env, pass_id: u128) -> Result<AccessPass, Error> {
+        Storage::require_access_pass(&env, pass_id)
+    }
+
+    fn get_access_passes_by_creator(env: Env, creator: Address) -> Result<Vec<AccessPass>, Error> {
+        Ok(Storage::get_access_passes_by_creator(&env, &creator))
+    }
+
+    fn subscribe_pass(
+        env: Env,
+        buyer: Address,
+        pass_id: u128,
+        max_price: i128,
+        max_periods: u32,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let access_pass = Storage::require_access_pass(&env, pass_id)?;
         ensure(
             access_pass.status == PromptSaleStatus::Active,
             Error::PromptInactive,
         )?;
         ensure(access_pass.creator != buyer, Error::CreatorCannotBuy)?;
-        ensure(
-            payment_amount_stroops >= access_pass.price_stroops,
-            Error::InvalidPaymentAmount,
-        )?;
-
-        // Each purchase reserves one unit of this pass's own supply, on top
-        // of whatever other passes this creator has sold (#538).
-        access_pass.sales_count =
-            reserve_pass_supply(access_pass.sales_count, access_pass.max_supply)?;
+        ensure(max_price > 0 && max_periods > 0, Error::InvalidSubscription)?;
 
-        InstanceStorage::set_reentrancy_guard(&env)?;
+        Storage::save_pass_subscription(
+            &env,
+            &PassSubscription {
+                buyer: buyer.clone(),
+                pass_id,
+                max_price,
+                periods_remaining: max_periods,
+                renewals: 0,
+            },
+        );
+        Events::emit_pass_subscribed(&env, pass_id, buyer, max_price, max_periods);
+        Ok(())
+    }
 
-        // Route the full payment through the contract so it holds
-        // escrow for dispute refunds (#564). The buyer must have
-        // approved the contract for at least `payment_amount_stroops`.
-        let this_contract = env.current_contract_address();
-        let asset_client = token::StellarAssetClient::new(&env, &access_pass.asset);
-        asset_client.transfer_from(
-            &this_contract,
+    fn cancel_pass_subscription(env: Env, buyer: Address, pass_id: u128) -> Result<(), Error> {
+        buyer.require_auth();
+        Storage::require_pass_subscription(&env, &buyer, pass_id)?;
+        end_pass_subscription(
+            &env,
             &buyer,
-            &this_contract,
-            &payment_amount_stroops,
+            pass_id,
+            SubscriptionEndReason::CancelledByBuyer,
         );
+        Ok(())
+    }
 
-        // Calculate allocations from the single payment
-        let fee_percentage = InstanceStorage::get_fee_percentage(&env);
-        ensure(fee_percentage <= MAX_BPS, Error::InvalidFeePercentage)?;
-
-        let fee_amount = payment_amount_stroops
-            .checked_mul(fee_percentage as i128)
-            .ok_or(Error::ArithmeticOverflow)?
-            / MAX_BPS as i128;
-        let creator_amount = payment_amount_stroops
-            .checked_sub(fee_amount)
-            .ok_or(Error::ArithmeticOverflow)?;
-
-        let fee_wallet = InstanceStorage::get_fee_wallet(&env).ok_or(Error::FeeWalletNotSet)?;
+    fn renew_pass(env: Env, buyer: Address, pass_id: u128) -> Result<bool, Error> {
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let mut subscription = Storage::require_pass_subscription(&env, &buyer, pass_id)?;
+        let access_pass = Storage::require_access_pass(&env, pass_id)?;
 
-        // Distribute from the contract's held balance
-        if fee_amount > 0 {
-            asset_client.transfer(&this_contract, &fee_wallet, &fee_amount);
+        // A renewal extends the period by a full duration, so a window no
+        // longer than the duration (and strictly before its end) admits at
+        // most one renewal per period.
+        let now = env.ledger().timestamp();
+        if let Some(current) =
+            Storage::get_catalog_pass_purchase(&env, &access_pass.creator, &buyer)
+        {
+            let window = PASS_RENEWAL_WINDOW_SECS.min(access_pass.duration_secs);
+            ensure(
+                current.expires_at < now.saturating_add(window),
+                Error::RenewalWindowNotOpen,
+            )?;
         }
-        if creator_amount > 0 {
-            asset_client.transfer(&this_contract, &access_pass.creator, &creator_amount);
+
+        // Any condition the buyer did not consent to, or that would make the
+        // charge fail, ends the mandate instead of reverting, so a stale
+        // subscription cannot be retried forever.
+        let price = access_pass.price_stroops;
+        let end_reason = if access_pass.status != PromptSaleStatus::Active
+            || reserve_pass_supply(access_pass.sales_count, access_pass.max_supply).is_err()
+        {
+            Some(SubscriptionEndReason::PassUnavailable)
+        } else if price > subscription.max_price {
+            Some(SubscriptionEndReason::PriceAboveCeiling)
+        } else {
+            let asset = token::TokenClient::new(&env, &access_pass.asset);
+            let this_contract = env.current_contract_address();
+            if asset.allowance(&buyer, &this_contract) < price || asset.balance(&buyer) < price {
+                Some(SubscriptionEndReason::InsufficientAllowance)
+            } else {
+                None
+            }
+        };
+        if let Some(reason) = end_reason {
+            end_pass_subscription(&env, &buyer, pass_id, reason);
+            return Ok(false);
         }
 
-        // Renewing before the current grant expires extends it forward from
-        // the existing expiry rather than from `now`, so the buyer never
-        // loses paid-for time or is double-charged for an overlapping
-        // period (#539).
-        let existing = Storage::get_catalog_pass_purchase(&env, &access_pass.creator, &buyer);
-        let base = existing
-            .map(|p| {
-                if p.expires_at > now {
-                    p.expires_at
-                } else {
-                    now
-                }
-            })
-            .unwrap_or(now);
-        let expires_at = base
-            .checked_add(access_pass.duration_secs)
+        execute_buy_access_pass(&env, &buyer, pass_id, price)?;
+
+        subscription.periods_remaining -= 1;
+        subscription.renewals = subscription
+            .renewals
+            .checked_add(1)
             .ok_or(Error::ArithmeticOverflow)?;
-        let catalog_pass = CatalogPassPurchase {
-            creator: access_pass.creator.clone(),
-            buyer: buyer.clone(),
+        Events::emit_pass_renewed(
+            &env,
             pass_id,
-            expires_at,
-        };
-        Storage::save_catalog_pass_purchase(&env, &catalog_pass);
-
-        // Create escrow with payout plan for unified dispute/settlement (#564).
-        // sales_count was already advanced by reserve_pass_supply above (#538)
-        // — do not increment it again here.
-        let payout_plan = super::types::PayoutPlan {
-            creator: access_pass.creator.clone(),
-            fee_wallet: fee_wallet.clone(),
-            fee_amount,
-            referrer: None,
-            referral_amount: 0,
-            splits: Vec::new(&env),
-            creator_amount,
-        };
-        let escrow = PurchaseEscrow {
-            prompt_id: 0, // Access passes don't have prompt IDs
-            buyer: buyer.clone(),
-            amount: payment_amount_stroops,
-            asset: access_pass.asset.clone(),
-            referrer: None,
-            status: SettlementStatus::Settled,
-            created_at: now,
-            settled_at: now,
-            // Access-pass funds settle immediately — there is no pending window (#541).
-            dispute_deadline: now,
-            creator_amount,
-            fee_amount,
-            referral_amount: 0,
-            payout_plan,
-        };
-        Storage::save_purchase_escrow(&env, &escrow);
-        Storage::update_access_pass(&env, &access_pass);
-        InstanceStorage::clear_reentrancy_guard(&env);
-        Events::emit_access_pass_purchased(&env, pass_id, buyer, access_pass.creator, expires_at);
-        Ok(())
-    }
-
-    fn get_access_pass(env: Env, pass_id: u128) -> Result<AccessPass, Error> {
-        Storage::require_access_pass(&env, pass_id)
+            buyer.clone(),
+            price,
+            subscription.periods_remaining,
+        );
+        if subscription.periods_remaining == 0 {
+            end_pass_subscription(
+                &env,
+                &buyer,
+                pass_id,
+                SubscriptionEndReason::PeriodsExhausted,
+            );
+        } else {
+            Storage::save_pass_subscription(&env, &subscription);
+        }
+        Ok(true)
     }
 
-    fn get_access_passes_by_creator(env: Env, creator: Address) -> Result<Vec<AccessPass>, Error> {
-        Ok(Storage::get_access_passes_by_creator(&env, &creator))
+    fn get_pass_subscription(
+        env: Env,
+        buyer: Address,
+        pass_id: u128,
+    ) -> Result<PassSubscription, Error> {
+        Storage::require_pass_subscription(&env, &buyer, pass_id)
     }
 
     fn transfer_license(
@@ -949,1101 +1365,4300 @@ impl PromptHashTrait for PromptHashContract {
         ensure(seller != new_buyer, Error::InvalidLicenseTransfer)?;
         new_buyer.require_auth();
 
-        let prompt = Storage::require_prompt(&env, prompt_id)?;
-        let now = env.ledger().timestamp();
-        let mut purchase = Storage::require_purchase(&env, prompt_id, &seller)?;
+        let purchase = Storage::require_purchase(&env, prompt_id, &seller)?;
         ensure(purchase.owner == seller, Error::Unauthorized)?;
-        ensure(purchase.expires_at >= now, Error::LicenseNotFound)?;
-        ensure(
-            !Storage::has_active_purchase(&env, prompt_id, &new_buyer, now),
-            Error::AlreadyPurchased,
-        )?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
 
-        InstanceStorage::set_reentrancy_guard(&env)?;
+        execute_license_resale(
+            &env,
+            &seller,
+            &new_buyer,
+            prompt_id,
+            resale_price,
+            prompt.royalty_bps,
+        )?;
+        Ok(())
+    }
 
-        let this_contract = env.current_contract_address();
-        let asset_client = token::StellarAssetClient::new(&env, &prompt.asset);
-        let royalty_amount = resale_price
-            .checked_mul(ROYALTY_BPS as i128)
-            .ok_or(Error::ArithmeticOverflow)?
-            / MAX_BPS as i128;
-        let seller_amount = resale_price
-            .checked_sub(royalty_amount)
-            .ok_or(Error::ArithmeticOverflow)?;
+    fn post_resale_order(env: Env, order: ResaleOrder) -> Result<BytesN<32>, Error> {
+        order.seller.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
 
-        if royalty_amount > 0 {
-            asset_client.transfer_from(
-                &this_contract,
-                &new_buyer,
-                &purchase.original_creator,
-                &royalty_amount,
-            );
-        }
-        if seller_amount > 0 {
-            asset_client.transfer_from(&this_contract, &new_buyer, &seller, &seller_amount);
+        ensure(
+            order.network_id == env.ledger().network_id(),
+            Error::ResaleOrderDomainMismatch,
+        )?;
+        ensure(
+            order.contract_id == current_contract_id_hash(&env),
+            Error::ResaleOrderDomainMismatch,
+        )?;
+        ensure(
+            order.status == ResaleOrderStatus::Open,
+            Error::ResaleOrderNotOpen,
+        )?;
+        ensure(
+            order.expiry_ledger >= env.ledger().sequence(),
+            Error::ResaleOrderExpired,
+        )?;
+        ensure(
+            !Storage::is_resale_nonce_consumed(&env, &order.seller, &order.nonce),
+            Error::ResaleOrderNonceConsumed,
+        )?;
+        ensure(order.price > 0, Error::InvalidPaymentAmount)?;
+        if let Some(ref buyer) = order.buyer {
+            ensure(*buyer != order.seller, Error::InvalidLicenseTransfer)?;
         }
 
-        Storage::remove_purchase(&env, prompt_id, &seller);
-        Storage::remove_prompt_from_buyer(&env, &seller, prompt_id);
-        purchase.owner = new_buyer.clone();
-        purchase.last_transfer_price = resale_price;
-        purchase.transfer_count = purchase
-            .transfer_count
-            .checked_add(1)
-            .ok_or(Error::ArithmeticOverflow)?;
-        purchase.last_transferred_at = now;
-        Storage::save_purchase(&env, &purchase);
-        Storage::add_prompt_to_buyer(&env, &new_buyer, prompt_id);
-        InstanceStorage::clear_reentrancy_guard(&env);
+        let prompt = Storage::require_prompt(&env, order.prompt_id)?;
+        ensure(order.asset == prompt.asset, Error::InvalidAsset)?;
+        ensure(prompt.resale_allowed, Error::ResaleNotAllowed)?;
+        // The creator's royalty is a floor; a seller may offer more but
+        // never less.
+        ensure(
+            order.royalty_bps >= prompt.royalty_bps && order.royalty_bps <= MAX_BPS,
+            Error::InvalidRoyaltyBps,
+        )?;
+        let (_, proceeds) = split_resale_price(order.price, order.royalty_bps)?;
+        ensure(
+            proceeds >= order.min_proceeds,
+            Error::ResaleProceedsBelowMinimum,
+        )?;
+        require_resale_ownership(&env, &order)?;
 
-        Events::emit_license_transferred(
+        let order_hash = order.hash(&env);
+        Storage::consume_resale_nonce(&env, &order.seller, &order.nonce);
+        Storage::save_resale_order(&env, &order_hash, &order);
+        Events::emit_resale_order_posted(
             &env,
-            prompt_id,
-            seller,
-            new_buyer,
-            purchase.original_creator,
-            resale_price,
-            royalty_amount,
+            order.seller,
+            order.prompt_id,
+            order_hash.clone(),
+            order.price,
+            order.buyer,
         );
+        Ok(order_hash)
+    }
+
+    fn cancel_resale_order(env: Env, seller: Address, order_hash: BytesN<32>) -> Result<(), Error> {
+        seller.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let mut order = Storage::require_resale_order(&env, &order_hash)?;
+        ensure(order.seller == seller, Error::Unauthorized)?;
+        require_open_resale_order(&order)?;
+
+        order.status = ResaleOrderStatus::Cancelled;
+        Storage::save_resale_order(&env, &order_hash, &order);
+        Events::emit_resale_order_cancelled(&env, seller, order_hash);
         Ok(())
     }
 
-    #[allow(clippy::too_many_arguments)]
-    fn revise_listing(
-        env: Env,
-        creator: Address,
-        prompt_id: u64,
-        title: String,
-        category: String,
-        preview_text: String,
-        image_url: String,
-        price_stroops: i128,
-    ) -> Result<u32, Error> {
-        creator.require_auth();
+    fn fill_resale_order(env: Env, buyer: Address, order: ResaleOrder) -> Result<(), Error> {
+        buyer.require_auth();
         ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
-        let mut prompt = Storage::require_prompt(&env, prompt_id)?;
-        ensure(prompt.creator == creator, Error::Unauthorized)?;
 
-        ensure(price_stroops > 0, Error::InvalidPrice)?;
-        validate_len(&image_url, MAX_IMAGE_URL_LEN, Error::InvalidImageUrlLength)?;
-        validate_len(&title, MAX_TITLE_LEN, Error::InvalidTitleLength)?;
-        validate_len(&category, MAX_CATEGORY_LEN, Error::InvalidCategoryLength)?;
-        validate_len(&preview_text, MAX_PREVIEW_LEN, Error::InvalidPreviewLength)?;
+        let order_hash = order.hash(&env);
+        let mut stored = Storage::require_resale_order(&env, &order_hash)?;
+        // Compare every term the buyer authorized against what the seller
+        // posted; only the lifecycle status may differ.
+        let mut expected = order.clone();
+        expected.status = stored.status.clone();
+        ensure(stored == expected, Error::InvalidResaleOrderSignature)?;
+        require_open_resale_order(&stored)?;
+        ensure(
+            env.ledger().sequence() <= stored.expiry_ledger,
+            Error::ResaleOrderExpired,
+        )?;
+        if let Some(ref restricted) = stored.buyer {
+            ensure(*restricted == buyer, Error::ResaleOrderBuyerMismatch)?;
+        }
+        ensure(stored.seller != buyer, Error::InvalidLicenseTransfer)?;
 
-        let snapshot = ListingRevisionRecord {
-            prompt_id,
-            revision: prompt.revision,
-            title: prompt.title.clone(),
-            category: prompt.category.clone(),
-            preview_text: prompt.preview_text.clone(),
-            image_url: prompt.image_url.clone(),
-            price_stroops: prompt.price_stroops,
-            revised_at: env.ledger().timestamp(),
-        };
-        Storage::save_listing_revision(&env, &snapshot);
+        // Ownership is revalidated at fill time: the seller must still hold
+        // the exact settlement the order was written against.
+        require_resale_ownership(&env, &stored)?;
+        let (_, proceeds) = split_resale_price(stored.price, stored.royalty_bps)?;
+        ensure(
+            proceeds >= stored.min_proceeds,
+            Error::ResaleProceedsBelowMinimum,
+        )?;
 
-        prompt.title = title;
-        prompt.category = category;
-        prompt.preview_text = preview_text;
-        prompt.image_url = image_url;
-        prompt.price_stroops = price_stroops;
-        prompt.revision = prompt
-            .revision
-            .checked_add(1)
-            .ok_or(Error::ArithmeticOverflow)?;
+        let settlement_id = execute_license_resale(
+            &env,
+            &stored.seller,
+            &buyer,
+            stored.prompt_id,
+            stored.price,
+            stored.royalty_bps,
+        )?;
 
-        Storage::update_prompt(&env, &prompt);
-        Events::emit_listing_revised(&env, prompt_id, prompt.revision);
-        Ok(prompt.revision)
+        stored.status = ResaleOrderStatus::Filled;
+        Storage::save_resale_order(&env, &order_hash, &stored);
+        Events::emit_resale_order_filled(
+            &env,
+            buyer,
+            stored.prompt_id,
+            order_hash,
+            stored.seller,
+            settlement_id,
+        );
+        Ok(())
     }
 
-    fn get_listing_revision(
-        env: Env,
-        prompt_id: u64,
-        revision: u32,
-    ) -> Result<ListingRevisionRecord, Error> {
-        Storage::require_prompt(&env, prompt_id)?;
-        Storage::get_listing_revision(&env, prompt_id, revision).ok_or(Error::PromptNotFound)
+    fn get_resale_order(env: Env, order_hash: BytesN<32>) -> Result<ResaleOrder, Error> {
+        Storage::require_resale_order(&env, &order_hash)
     }
 
-    fn update_splits(
+    fn set_license_token_mode(
         env: Env,
         creator: Address,
         prompt_id: u64,
-        new_splits: Vec<Split>,
+        enabled: bool,
     ) -> Result<(), Error> {
         creator.require_auth();
         ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
-        let mut prompt = Storage::require_prompt(&env, prompt_id)?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
         ensure(prompt.creator == creator, Error::Unauthorized)?;
+        // Switching modes after a sale would leave some licenses with tokens
+        // and some without.
+        ensure(prompt.sales_count == 0, Error::LicenseTokenModeLocked)?;
 
-        validate_splits(&env, &new_splits)?;
-        validate_no_duplicate_recipients(&new_splits)?;
-        ensure(new_splits.len() <= MAX_SPLITS, Error::TooManySplits)?;
-
-        prompt.splits = new_splits;
-        Storage::update_prompt(&env, &prompt);
-        Events::emit_splits_updated(&env, prompt_id);
+        Storage::set_license_token_mode(&env, prompt_id, enabled);
+        Events::emit_license_token_mode_updated(&env, prompt_id, enabled);
         Ok(())
     }
 
-    fn has_access(env: Env, user: Address, prompt_id: u64) -> Result<bool, Error> {
-        let prompt = Storage::require_prompt(&env, prompt_id)?;
-        let now = env.ledger().timestamp();
-        Ok(prompt.creator == user
-            || Storage::has_active_purchase(&env, prompt_id, &user, now)
-            || Storage::has_active_creator_pass(&env, &prompt.creator, &user, now))
+    fn is_license_token_mode(env: Env, prompt_id: u64) -> bool {
+        Storage::is_license_token_mode(&env, prompt_id)
     }
 
-    fn get_prompt(env: Env, prompt_id: u64) -> Result<Prompt, Error> {
-        Storage::require_prompt(&env, prompt_id)
+    fn get_license_token(env: Env, token_id: u32) -> Result<LicenseToken, Error> {
+        Storage::require_license_token(&env, token_id)
     }
 
-    fn get_all_prompts(env: Env) -> Result<Vec<Prompt>, Error> {
-        Ok(Storage::get_all_prompts(&env))
+    fn get_purchase_license_token(env: Env, prompt_id: u64, owner: Address) -> Option<u32> {
+        Storage::get_purchase_license_token(&env, prompt_id, &owner)
     }
 
-    fn get_prompts_by_category(env: Env, category: String) -> Result<Vec<Prompt>, Error> {
-        validate_len(&category, MAX_CATEGORY_LEN, Error::InvalidCategoryLength)?;
-        Ok(Storage::get_prompts_by_category(&env, &category))
+    fn balance(env: Env, account: Address) -> u32 {
+        Storage::get_license_token_balance(&env, &account)
     }
 
-    fn get_prompts_by_tag(env: Env, tag: String) -> Result<Vec<Prompt>, Error> {
-        validate_len(&tag, MAX_TAG_LEN, Error::InvalidCategoryLength)?;
-        Ok(Storage::get_prompts_by_tag(&env, &tag))
+    fn owner_of(env: Env, token_id: u32) -> Result<Address, Error> {
+        Ok(Storage::require_license_token(&env, token_id)?.owner)
     }
 
-    // Paginated catalog queries (bounded, respects resource limits)
-    fn get_all_prompts_paginated(
-        env: Env,
-        cursor: Option<String>,
-        limit: u64,
-    ) -> Result<(Vec<Prompt>, Option<String>), Error> {
-        use crate::pagination::{decode_cursor, encode_cursor, IndexType};
-
-        let cursor_id = if let Some(c) = cursor {
-            let parsed = decode_cursor(&env, &c)?;
-            Some(parsed.last_id)
-        } else {
-            None
-        };
-
-        let key = crate::types::DataKey::AllPrompts;
-        let prompts = Storage::get_prompts_paginated(&env, &key, cursor_id, limit);
-
-        let next_cursor = if !prompts.is_empty() {
-            let last_id = prompts.last().ok_or(Error::PromptNotFound)?.id;
-            Some(encode_cursor(&env, last_id, IndexType::All))
-        } else {
-            None
-        };
-
-        Ok((prompts, next_cursor))
+    fn transfer(env: Env, from: Address, to: Address, token_id: u32) -> Result<(), Error> {
+        from.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        reject_unpriced_token_transfer(&env, &from, &from, &to, token_id)
     }
 
-    fn get_prompts_by_category_page(
+    fn transfer_from(
         env: Env,
-        category: String,
-        cursor: Option<String>,
-        limit: u64,
-    ) -> Result<(Vec<Prompt>, Option<String>), Error> {
-        use crate::pagination::{decode_cursor, encode_cursor, IndexType};
-
-        validate_len(&category, MAX_CATEGORY_LEN, Error::InvalidCategoryLength)?;
-
-        let cursor_id = if let Some(c) = cursor {
-            let parsed = decode_cursor(&env, &c)?;
-            Some(parsed.last_id)
-        } else {
-            None
-        };
-
-        let key = crate::types::DataKey::CategoryPrompts(category);
-        let prompts = Storage::get_prompts_paginated(&env, &key, cursor_id, limit);
-
-        let next_cursor = if !prompts.is_empty() {
-            let last_id = prompts.last().ok_or(Error::PromptNotFound)?.id;
-            Some(encode_cursor(&env, last_id, IndexType::Category))
-        } else {
-            None
-        };
-
-        Ok((prompts, next_cursor))
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: u32,
+    ) -> Result<(), Error> {
+        spender.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        reject_unpriced_token_transfer(&env, &spender, &from, &to, token_id)
     }
 
-    fn get_prompts_by_tag_paginated(
+    fn approve(
         env: Env,
-        tag: String,
-        cursor: Option<String>,
-        limit: u64,
-    ) -> Result<(Vec<Prompt>, Option<String>), Error> {
-        use crate::pagination::{decode_cursor, encode_cursor, IndexType};
-
-        validate_len(&tag, MAX_TAG_LEN, Error::InvalidCategoryLength)?;
-
-        let cursor_id = if let Some(c) = cursor {
-            let parsed = decode_cursor(&env, &c)?;
-            Some(parsed.last_id)
-        } else {
-            None
-        };
-
-        let key = crate::types::DataKey::TagPrompts(tag);
-        let prompts = Storage::get_prompts_paginated(&env, &key, cursor_id, limit);
+        approver: Address,
+        approved: Address,
+        token_id: u32,
+        live_until_ledger: u32,
+    ) -> Result<(), Error> {
+        approver.require_auth();
+        let token = Storage::require_license_token(&env, token_id)?;
+        ensure(
+            token.owner == approver
+                || Storage::is_license_token_operator(&env, &token.owner, &approver),
+            Error::LicenseTokenNotApproved,
+        )?;
 
-        let next_cursor = if !prompts.is_empty() {
-            let last_id = prompts.last().ok_or(Error::PromptNotFound)?.id;
-            Some(encode_cursor(&env, last_id, IndexType::Tag))
+        if live_until_ledger == 0 {
+            Storage::set_license_token_approval(&env, token_id, None);
         } else {
-            None
-        };
-
-        Ok((prompts, next_cursor))
+            ensure(
+                live_until_ledger >= env.ledger().sequence(),
+                Error::InvalidLicenseApproval,
+            )?;
+            Storage::set_license_token_approval(
+                &env,
+                token_id,
+                Some(LicenseTokenApproval {
+                    approved: approved.clone(),
+                    live_until_ledger,
+                }),
+            );
+        }
+        Events::emit_license_token_approved(&env, approver, token_id, approved, live_until_ledger);
+        Ok(())
     }
 
-    fn get_active_prompts_paginated(
+    fn approve_for_all(
         env: Env,
-        cursor: Option<String>,
-        limit: u64,
-    ) -> Result<(Vec<Prompt>, Option<String>), Error> {
-        use crate::pagination::{decode_cursor, encode_cursor, IndexType};
+        owner: Address,
+        operator: Address,
+        live_until_ledger: u32,
+    ) -> Result<(), Error> {
+        owner.require_auth();
+        ensure(
+            live_until_ledger == 0 || live_until_ledger >= env.ledger().sequence(),
+            Error::InvalidLicenseApproval,
+        )?;
+        Storage::set_license_token_operator(&env, &owner, &operator, live_until_ledger);
+        Events::emit_license_token_approved_for_all(&env, owner, operator, live_until_ledger);
+        Ok(())
+    }
 
-        let cursor_id = if let Some(c) = cursor {
-            let parsed = decode_cursor(&env, &c)?;
-            Some(parsed.last_id)
-        } else {
-            None
-        };
+    fn get_approved(env: Env, token_id: u32) -> Option<Address> {
+        Storage::get_license_token_approval(&env, token_id).map(|approval| approval.approved)
+    }
 
-        let key = crate::types::DataKey::ActivePrompts;
-        let prompts = Storage::get_prompts_paginated(&env, &key, cursor_id, limit);
+    fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool {
+        Storage::is_license_token_operator(&env, &owner, &operator)
+    }
 
-        let next_cursor = if !prompts.is_empty() {
-            let last_id = prompts.last().ok_or(Error::PromptNotFound)?.id;
-            Some(encode_cursor(&env, last_id, IndexType::Active))
-        } else {
-            None
-        };
+    fn name(env: Env) -> String {
+        String::from_str(&env, LICENSE_TOKEN_NAME)
+    }
 
-        Ok((prompts, next_cursor))
+    fn symbol(env: Env) -> String {
+        String::from_str(&env, LICENSE_TOKEN_SYMBOL)
     }
 
-    // ====== TTL MAINTENANCE (OPERATOR UTILITIES) ======
+    fn token_uri(env: Env, token_id: u32) -> Result<String, Error> {
+        let token = Storage::require_license_token(&env, token_id)?;
+        Ok(Storage::require_prompt(&env, token.prompt_id)?.image_url)
+    }
 
-    fn renew_critical_keys(env: Env, cursor: Option<u64>) -> Result<(u32, Option<u64>), Error> {
-        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
-        Ok(Storage::renew_critical_keys(&env, cursor))
+    fn royalty_info(env: Env, token_id: u32, sale_price: i128) -> Result<(Address, i128), Error> {
+        ensure(sale_price >= 0, Error::InvalidPaymentAmount)?;
+        let token = Storage::require_license_token(&env, token_id)?;
+        let purchase = Storage::require_purchase(&env, token.prompt_id, &token.owner)?;
+        let prompt = Storage::require_prompt(&env, token.prompt_id)?;
+        let (royalty_amount, _) = split_resale_price(sale_price, prompt.royalty_bps)?;
+        Ok((purchase.original_creator, royalty_amount))
     }
 
-    fn get_expiry_risk_metrics(env: Env) -> Result<Vec<(String, String)>, Error> {
+    fn start_english_auction(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        reserve_price: i128,
+        ends_at: u64,
+    ) -> Result<(), Error> {
+        creator.require_auth();
         ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
-        Ok(Storage::compute_expiry_risks(&env))
+        check_auction_listing(&env, &creator, prompt_id)?;
+        ensure(
+            reserve_price > 0 && ends_at > env.ledger().timestamp(),
+            Error::InvalidAuctionConfig,
+        )?;
+
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        let now = env.ledger().sequence();
+        Storage::save_auction(
+            &env,
+            &Auction {
+                prompt_id,
+                creator,
+                kind: AuctionKind::English,
+                asset: prompt.asset,
+                reserve_price,
+                start_price: reserve_price,
+                start_ledger: now,
+                end_ledger: now,
+                ends_at,
+                highest_bidder: None,
+                highest_bid: 0,
+                status: AuctionStatus::Open,
+            },
+        );
+        Events::emit_auction_started(
+            &env,
+            prompt_id,
+            AuctionKind::English,
+            reserve_price,
+            reserve_price,
+        );
+        Ok(())
     }
 
-    fn open_dispute(
+    fn start_dutch_auction(
         env: Env,
-        buyer: Address,
+        creator: Address,
         prompt_id: u64,
-        reason: DisputeReason,
+        start_price: i128,
+        floor_price: i128,
+        duration_ledgers: u32,
     ) -> Result<(), Error> {
-        buyer.require_auth();
+        creator.require_auth();
         ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
-        let now = env.ledger().timestamp();
-        Storage::require_purchase(&env, prompt_id, &buyer)?;
-        // Purchases with a pending escrow (direct/bulk buys) may only be
-        // disputed within the purchase-relative window; once it closes the
-        // escrow is eligible for permissionless settlement (#541). Purchases
-        // without a pending escrow (leases, bundles, passes) are unaffected.
-        let mut escrow_liability_move: Option<(Address, i128)> = None;
-        if let Some(escrow) = Storage::get_purchase_escrow(&env, prompt_id, &buyer) {
-            if escrow.status == SettlementStatus::Pending {
-                ensure(now <= escrow.dispute_deadline, Error::DisputeWindowClosed)?;
-                escrow_liability_move = Some((escrow.asset, escrow.amount));
-            } else {
-                // Already settled or refunded — nothing left to dispute.
-                return Err(Error::DisputeWindowClosed);
-            }
-        }
-        if let Some(dispute) = Storage::get_dispute(&env, prompt_id, &buyer) {
-            ensure(
-                dispute.status != DisputeStatus::Open,
-                Error::DisputeAlreadyOpen,
-            )?;
-        }
-        // The escrowed amount is now at risk of refund rather than
-        // settlement — move it out of `pending` into `disputed` so per-asset
-        // liability reflects the dispute (#570). Applied only once every
-        // other guard above has passed, so a rejected duplicate-open attempt
-        // never touches the ledger.
-        if let Some((asset, amount)) = escrow_liability_move {
-            Storage::move_pending_to_disputed(&env, &asset, amount)?;
-        }
-        let dispute = PurchaseDispute {
+        check_auction_listing(&env, &creator, prompt_id)?;
+        ensure(
+            floor_price > 0 && start_price > floor_price && duration_ledgers > 0,
+            Error::InvalidAuctionConfig,
+        )?;
+
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        let now = env.ledger().sequence();
+        Storage::save_auction(
+            &env,
+            &Auction {
+                prompt_id,
+                creator,
+                kind: AuctionKind::Dutch,
+                asset: prompt.asset,
+                reserve_price: floor_price,
+                start_price,
+                start_ledger: now,
+                end_ledger: now
+                    .checked_add(duration_ledgers)
+                    .ok_or(Error::ArithmeticOverflow)?,
+                ends_at: 0,
+                highest_bidder: None,
+                highest_bid: 0,
+                status: AuctionStatus::Open,
+            },
+        );
+        Events::emit_auction_started(
+            &env,
             prompt_id,
-            buyer: buyer.clone(),
-            reason,
-            opened_at: now,
-            resolved_at: 0,
-            status: DisputeStatus::Open,
-        };
-        Storage::save_dispute(&env, &dispute);
-        Events::emit_dispute_opened(&env, prompt_id, buyer);
+            AuctionKind::Dutch,
+            start_price,
+            floor_price,
+        );
         Ok(())
     }
 
-    fn resolve_dispute(
-        env: Env,
-        admin: Address,
-        prompt_id: u64,
-        buyer: Address,
-        refund: bool,
-    ) -> Result<(), Error> {
-        admin.require_auth();
-        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
-        ensure(owner == admin, Error::Unauthorized)?;
-        // Refunds move customer funds — must respect the pause flag like
-        // every other mutating entry point, including an invariant-triggered
-        // pause from `check_asset_solvency` (#570).
+    fn place_bid(env: Env, bidder: Address, prompt_id: u64, amount: i128) -> Result<(), Error> {
+        bidder.require_auth();
         ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
-        let mut prompt = Storage::require_prompt(&env, prompt_id)?;
-        let purchase = Storage::require_purchase(&env, prompt_id, &buyer)?;
-        let mut dispute = Storage::require_dispute(&env, prompt_id, &buyer)?;
+        let mut auction = require_open_auction(&env, prompt_id, AuctionKind::English)?;
         ensure(
-            dispute.status == DisputeStatus::Open,
-            Error::DisputeResolved,
+            env.ledger().timestamp() <= auction.ends_at,
+            Error::AuctionEnded,
         )?;
-        dispute.resolved_at = env.ledger().timestamp();
-        if refund {
-            let asset_client = token::StellarAssetClient::new(&env, &prompt.asset);
-            // Refund from the contract's escrowed balance (#454).  The
-            // funds were routed to the contract during purchase so that
-            // a refund is always possible without additional auth.
-            asset_client.transfer(
-                &env.current_contract_address(),
-                &buyer,
-                &purchase.original_price,
-            );
-
-            // Mark the purchase as revoked so the buyer can no longer claim access.
-            Storage::remove_purchase(&env, prompt_id, &buyer);
-            Storage::remove_prompt_from_buyer(&env, &buyer, prompt_id);
-            dispute.status = DisputeStatus::Refunded;
+        check_auction_buyer(&env, &auction, &bidder)?;
+        ensure(amount >= min_next_bid(&auction)?, Error::BidTooLow)?;
 
-            // Release the reserved supply unit back to the pool so another
-            // buyer can acquire it (#538).
-            prompt.sales_count = prompt.sales_count.saturating_sub(1);
-            Storage::update_prompt(&env, &prompt);
-
-            // Update the escrow record so the settlement admin knows
-            // the funds were already returned to the buyer.
-            if let Some(mut escrow) = Storage::get_purchase_escrow(&env, prompt_id, &buyer) {
-                // The disputed amount was just paid out to the buyer — it's
-                // no longer anyone's liability (#570).
-                Storage::remove_disputed_liability(&env, &escrow.asset, escrow.amount)?;
-                escrow.status = SettlementStatus::Refunded;
-                escrow.settled_at = env.ledger().timestamp();
-                Storage::save_purchase_escrow(&env, &escrow);
-            }
-        } else {
-            dispute.status = DisputeStatus::Rejected;
-            // The escrow stays Pending, awaiting a future `settle_purchase`
-            // call — move its amount back out of `disputed` into `pending`
-            // (#570).
-            if let Some(escrow) = Storage::get_purchase_escrow(&env, prompt_id, &buyer) {
-                if escrow.status == SettlementStatus::Pending {
-                    Storage::move_disputed_to_pending(&env, &escrow.asset, escrow.amount)?;
-                }
-            }
+        InstanceStorage::set_reentrancy_guard(&env)?;
+        let this_contract = env.current_contract_address();
+        let asset_client = token::StellarAssetClient::new(&env, &auction.asset);
+        asset_client.transfer_from(&this_contract, &bidder, &this_contract, &amount);
+        // An escrowed bid is owed back to its bidder until it wins, so it
+        // is customer liability from the moment it is placed (#570).
+        Storage::add_pending_liability(&env, &auction.asset, amount)?;
+
+        // The outbid bid becomes a claimable balance rather than a push
+        // transfer, so a bidder whose trustline is missing or frozen cannot
+        // block later bids.
+        let refunded = auction.highest_bidder.clone();
+        if let Some(ref outbid) = refunded {
+            Storage::remove_pending_liability(&env, &auction.asset, auction.highest_bid)?;
+            Storage::credit_claimable(&env, outbid, &auction.asset, auction.highest_bid)?;
         }
-        Storage::save_dispute(&env, &dispute);
-        Events::emit_dispute_resolved(&env, prompt_id, buyer, refund);
+        auction.highest_bidder = Some(bidder.clone());
+        auction.highest_bid = amount;
+        Storage::save_auction(&env, &auction);
+        InstanceStorage::clear_reentrancy_guard(&env);
+
+        Events::emit_auction_bid_placed(&env, prompt_id, bidder, amount, refunded);
         Ok(())
     }
 
-    /// Release escrowed funds using the immutable payout plan snapshotted at
-    /// purchase time (#562), not the current listing state. The contract
-    /// owner (admin) or the prompt creator may settle at any time; any
-    /// other caller may settle only once the purchase-relative dispute
-    /// window has closed with no open dispute, guaranteeing every escrow
-    /// has a bounded path to Released even if the admin/creator never
-    /// act (#454/#541).
-    fn settle_purchase(
+    fn settle_auction(env: Env, prompt_id: u64) -> Result<(), Error> {
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let auction = require_open_auction(&env, prompt_id, AuctionKind::English)?;
+        ensure(
+            env.ledger().timestamp() > auction.ends_at,
+            Error::AuctionNotEnded,
+        )?;
+        execute_settle_auction(&env, auction)
+    }
+
+    fn buy_dutch_auction(
         env: Env,
-        caller: Address,
-        prompt_id: u64,
         buyer: Address,
+        prompt_id: u64,
+        max_price: i128,
     ) -> Result<(), Error> {
-        caller.require_auth();
-        // Settlement moves customer funds — must respect the pause flag like
-        // every other mutating entry point, including an invariant-triggered
-        // pause from `check_asset_solvency` (#570).
+        buyer.require_auth();
         ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
-        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
-        let prompt = Storage::require_prompt(&env, prompt_id)?;
-
-        let mut escrow = Storage::require_purchase_escrow(&env, prompt_id, &buyer)?;
+        let mut auction = require_open_auction(&env, prompt_id, AuctionKind::Dutch)?;
+        check_auction_buyer(&env, &auction, &buyer)?;
+        let mut prompt = Storage::require_prompt(&env, prompt_id)?;
         ensure(
-            escrow.status == SettlementStatus::Pending,
-            Error::DisputeResolved,
+            prompt.status == PromptSaleStatus::Active,
+            Error::PromptInactive,
         )?;
-
-        // An open dispute must be resolved via `resolve_dispute`, not
-        // settled — this holds even for the admin/creator fast path (#541).
-        if let Some(dispute) = Storage::get_dispute(&env, prompt_id, &buyer) {
+        if prompt.expires_at != 0 {
             ensure(
-                dispute.status != DisputeStatus::Open,
-                Error::DisputeAlreadyOpen,
+                prompt.expires_at >= env.ledger().timestamp(),
+                Error::ListingExpired,
             )?;
         }
+        let price = dutch_auction_price(&env, &auction)?;
+        ensure(price <= max_price, Error::AuctionPriceAboveMax)?;
+        let reserved_sales_count = reserve_supply(prompt.sales_count, prompt.max_supply)?;
 
-        let now = env.ledger().timestamp();
-        let is_privileged = caller == owner || caller == prompt.creator;
-        if !is_privileged {
-            // Permissionless fallback: once the dispute window has closed
-            // with no open dispute, anyone may finalize the escrow — this
-            // guarantees a bounded path to Released even if the admin or
-            // creator never act (#541).
-            ensure(
-                now > escrow.dispute_deadline,
-                Error::DisputeWindowNotElapsed,
-            )?;
+        InstanceStorage::set_reentrancy_guard(&env)?;
+        let this_contract = env.current_contract_address();
+        token::StellarAssetClient::new(&env, &auction.asset).transfer_from(
+            &this_contract,
+            &buyer,
+            &this_contract,
+            &price,
+        );
+        prompt.sales_count = reserved_sales_count;
+        Storage::update_prompt(&env, &prompt);
+        let escrow = open_sale_escrow(
+            &env,
+            &buyer,
+            &prompt,
+            &prompt.asset,
+            &None,
+            price,
+            None,
+            AcquisitionKind::Auction,
+        )?;
+        Storage::add_pending_liability(&env, &escrow.asset, escrow.amount)?;
+        // Each purchase takes one unit; the auction ends with the last one.
+        if prompt.sales_count >= prompt.max_supply {
+            auction.status = AuctionStatus::Sold;
+            Storage::save_auction(&env, &auction);
         }
+        InstanceStorage::clear_reentrancy_guard(&env);
 
-        let this_contract = env.current_contract_address();
-        let asset_client = token::StellarAssetClient::new(&env, &escrow.asset);
+        Events::emit_prompt_purchased(&env, prompt_id, buyer.clone(), prompt.creator, price, None);
+        Events::emit_auction_sale(&env, prompt_id, buyer, price);
+        Ok(())
+    }
 
-        // Use the snapshotted payout plan, not the current listing state (#562).
-        let plan = &escrow.payout_plan;
+    fn cancel_auction(env: Env, creator: Address, prompt_id: u64) -> Result<(), Error> {
+        creator.require_auth();
+        let mut auction = Storage::require_auction(&env, prompt_id)?;
+        ensure(auction.creator == creator, Error::Unauthorized)?;
+        ensure(auction.status == AuctionStatus::Open, Error::AuctionNotOpen)?;
+        // Bidders are owed a fair close once they have committed funds.
+        ensure(auction.highest_bidder.is_none(), Error::AuctionHasBids)?;
+
+        auction.status = AuctionStatus::Closed;
+        Storage::save_auction(&env, &auction);
+        Events::emit_auction_closed(&env, prompt_id);
+        Ok(())
+    }
 
-        // Distribute fee to the snapshotted fee wallet
-        if plan.fee_amount > 0 {
-            asset_client.transfer(&this_contract, &plan.fee_wallet, &plan.fee_amount);
-        }
+    fn get_auction(env: Env, prompt_id: u64) -> Result<Auction, Error> {
+        Storage::require_auction(&env, prompt_id)
+    }
 
-        // Distribute referral to the snapshotted referrer
-        if let Some(ref r) = plan.referrer {
-            if plan.referral_amount > 0 {
-                asset_client.transfer(&this_contract, r, &plan.referral_amount);
-            }
-        }
+    fn get_dutch_auction_price(env: Env, prompt_id: u64) -> Result<i128, Error> {
+        let auction = Storage::require_auction(&env, prompt_id)?;
+        ensure(
+            auction.kind == AuctionKind::Dutch,
+            Error::AuctionKindMismatch,
+        )?;
+        dutch_auction_price(&env, &auction)
+    }
 
-        // Distribute collaborator splits from the snapshotted amounts
-        for i in 0..plan.splits.len() {
-            let split = plan.splits.get(i).unwrap();
-            if split.amount > 0 {
-                asset_client.transfer(&this_contract, &split.recipient, &split.amount);
-            }
-        }
+    #[allow(clippy::too_many_arguments)]
+    fn revise_listing(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        title: String,
+        category: String,
+        preview_text: String,
+        image_url: String,
+        price_stroops: i128,
+    ) -> Result<u32, Error> {
+        creator.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let mut prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
 
-        // Transfer the creator's escrowed share to the snapshotted creator
-        if plan.creator_amount > 0 {
-            asset_client.transfer(&this_contract, &plan.creator, &plan.creator_amount);
-        }
+        ensure(price_stroops >= 0, Error::InvalidPrice)?;
+        validate_len(&image_url, MAX_IMAGE_URL_LEN, Error::InvalidImageUrlLength)?;
+        validate_len(&title, MAX_TITLE_LEN, Error::InvalidTitleLength)?;
+        validate_len(&category, MAX_CATEGORY_LEN, Error::InvalidCategoryLength)?;
+        validate_len(&preview_text, MAX_PREVIEW_LEN, Error::InvalidPreviewLength)?;
+
+        Storage::save_listing_revision(&env, &revision_snapshot(&env, &prompt));
+
+        let previous = prompt.clone();
+        prompt.title = title;
+        prompt.category = category;
+        prompt.preview_text = preview_text;
+        prompt.image_url = image_url;
+        prompt.price_stroops = price_stroops;
+        prompt.revision = prompt
+            .revision
+            .checked_add(1)
+            .ok_or(Error::ArithmeticOverflow)?;
+
+        Storage::update_listing(&env, &previous, &prompt);
+        Events::emit_listing_revised(&env, prompt_id, prompt.revision);
+        Ok(prompt.revision)
+    }
+
+    fn get_listing_revision(
+        env: Env,
+        prompt_id: u64,
+        revision: u32,
+    ) -> Result<ListingRevisionRecord, Error> {
+        Storage::require_prompt(&env, prompt_id)?;
+        Storage::get_listing_revision(&env, prompt_id, revision).ok_or(Error::PromptNotFound)
+    }
+
+    fn revise_content(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        encrypted_payload: String,
+        encryption_iv: String,
+        wrapped_key: String,
+        content_hash: BytesN<32>,
+    ) -> Result<u32, Error> {
+        creator.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let mut prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+
+        validate_len(
+            &encrypted_payload,
+            MAX_ENCRYPTED_PROMPT_LEN,
+            Error::InvalidEncryptedPromptLength,
+        )?;
+        validate_len(
+            &wrapped_key,
+            MAX_WRAPPED_KEY_LEN,
+            Error::InvalidWrappedKeyLength,
+        )?;
+        validate_len(&encryption_iv, MAX_IV_LEN, Error::InvalidIvLength)?;
+
+        Storage::save_listing_revision(&env, &revision_snapshot(&env, &prompt));
 
-        // The escrow guards above guarantee this amount was still in the
-        // pending bucket (Pending status, no open dispute) — release it
-        // from tracked liability now that it's been paid out (#570).
-        Storage::remove_pending_liability(&env, &escrow.asset, escrow.amount)?;
+        let previous_content_hash = prompt.content_hash.clone();
+        prompt.encrypted_payload = encrypted_payload;
+        prompt.encryption_iv = encryption_iv;
+        prompt.wrapped_key = wrapped_key;
+        prompt.content_hash = content_hash.clone();
+        prompt.revision = prompt
+            .revision
+            .checked_add(1)
+            .ok_or(Error::ArithmeticOverflow)?;
 
-        escrow.status = SettlementStatus::Settled;
-        escrow.settled_at = now;
-        Storage::save_purchase_escrow(&env, &escrow);
-        Events::emit_prompt_purchased(
+        Storage::update_prompt(&env, &prompt);
+        Events::emit_content_revised(
             &env,
             prompt_id,
-            buyer,
-            prompt.creator,
-            escrow.amount,
-            escrow.referrer,
+            prompt.revision,
+            previous_content_hash,
+            content_hash,
         );
-        Ok(())
+        Ok(prompt.revision)
     }
 
-    fn get_dispute(env: Env, prompt_id: u64, buyer: Address) -> Result<PurchaseDispute, Error> {
-        Storage::require_dispute(&env, prompt_id, &buyer)
+    fn set_content_access_policy(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        policy: ContentAccessPolicy,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+        Storage::set_content_access_policy(&env, prompt_id, &policy);
+        Ok(())
     }
 
-    fn get_purchase_escrow(env: Env, prompt_id: u64, buyer: Address) -> Option<PurchaseEscrow> {
-        Storage::get_purchase_escrow(&env, prompt_id, &buyer)
+    fn get_content_access_policy(env: Env, prompt_id: u64) -> Result<ContentAccessPolicy, Error> {
+        Storage::require_prompt(&env, prompt_id)?;
+        Ok(Storage::get_content_access_policy(&env, prompt_id))
     }
 
-    fn get_prompts_by_creator(env: Env, creator: Address) -> Result<Vec<Prompt>, Error> {
-        Ok(Storage::get_prompts_by_creator(&env, &creator))
-    }
+    fn get_licensed_content(
+        env: Env,
+        prompt_id: u64,
+        user: Address,
+    ) -> Result<LicensedContent, Error> {
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        let now = env.ledger().timestamp();
+        let latest = LicensedContent {
+            prompt_id,
+            revision: prompt.revision,
+            encrypted_payload: prompt.encrypted_payload.clone(),
+            encryption_iv: prompt.encryption_iv.clone(),
+            wrapped_key: prompt.wrapped_key.clone(),
+            content_hash: prompt.content_hash.clone(),
+        };
+        if prompt.creator == user {
+            return Ok(latest);
+        }
 
-    fn get_prompts_by_buyer(env: Env, buyer: Address) -> Result<Vec<Prompt>, Error> {
-        Ok(Storage::get_prompts_by_buyer(&env, &buyer))
-    }
+        if Storage::has_active_purchase(&env, prompt_id, &user, now) {
+            let purchase =
+                Storage::get_purchase(&env, prompt_id, &user).ok_or(Error::LicenseNotFound)?;
+            let revision = served_revision(&env, &prompt, &purchase);
+            let mut content = if revision < prompt.revision {
+                // The record keyed on a revision holds the listing as it was
+                // while that revision was live.
+                let record = Storage::get_listing_revision(&env, prompt_id, revision)
+                    .ok_or(Error::PromptNotFound)?;
+                LicensedContent {
+                    prompt_id,
+                    revision,
+                    encrypted_payload: record.encrypted_payload,
+                    encryption_iv: record.encryption_iv,
+                    wrapped_key: record.wrapped_key,
+                    content_hash: record.content_hash,
+                }
+            } else {
+                latest
+            };
+            if let Some(delivered) = current_wrapped_key(&env, prompt_id, &user, revision) {
+                content.wrapped_key = delivered.wrapped_key;
+            }
+            return Ok(content);
+        }
 
-    /// Canonical, bounded fee-configuration entrypoint (#566). Both this
-    /// function and the deprecated `update_platform_fee` alias route through
-    /// `set_platform_fee_internal`, so every caller is held to the same
-    /// `MAX_PLATFORM_FEE` ceiling and emits the same canonical event —
-    /// neither entrypoint can be used to bypass the other's policy.
-    #[only_owner]
-    fn set_fee_percentage(env: Env, new_fee_percentage: u32) -> Result<(), Error> {
-        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
-        set_platform_fee_internal(&env, owner, new_fee_percentage)
+        ensure(
+            Storage::has_active_creator_pass(&env, &prompt.creator, &user, now),
+            Error::LicenseNotFound,
+        )?;
+        Ok(latest)
     }
 
-    #[only_owner]
-    fn set_fee_wallet(env: Env, new_fee_wallet: Address) -> Result<(), Error> {
-        InstanceStorage::set_fee_wallet(&env, &new_fee_wallet);
-        Events::emit_fee_wallet_updated(&env, new_fee_wallet);
+    fn update_splits(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        new_splits: Vec<Split>,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let mut prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+
+        validate_splits(&env, &new_splits)?;
+        validate_no_duplicate_recipients(&new_splits)?;
+        ensure(new_splits.len() <= MAX_SPLITS, Error::TooManySplits)?;
+
+        prompt.splits = new_splits;
+        Storage::update_prompt(&env, &prompt);
+        Events::emit_splits_updated(&env, prompt_id);
         Ok(())
     }
 
-    fn get_fee_percentage(env: Env) -> u32 {
-        InstanceStorage::get_fee_percentage(&env)
+    fn has_access(env: Env, user: Address, prompt_id: u64) -> Result<bool, Error> {
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        let now = env.ledger().timestamp();
+        Ok(prompt.creator == user
+            || Storage::has_active_purchase(&env, prompt_id, &user, now)
+            || Storage::has_active_creator_pass(&env, &prompt.creator, &user, now))
     }
 
-    fn get_fee_wallet(env: Env) -> Option<Address> {
-        InstanceStorage::get_fee_wallet(&env)
+    fn get_prompt(env: Env, prompt_id: u64) -> Result<Prompt, Error> {
+        Storage::require_prompt(&env, prompt_id)
     }
 
-    /// Deprecated alias for `set_fee_percentage` (#566), kept because removing
-    /// a public entrypoint is a breaking ABI change. Delegates to the same
-    /// internal helper, so it enforces the identical bound/auth/event as the
-    /// canonical entrypoint rather than a looser or divergent policy.
-    #[only_owner]
-    fn update_platform_fee(env: Env, admin: Address, new_fee: u32) -> Result<(), Error> {
-        admin.require_auth();
-        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
-        ensure(owner == admin, Error::Unauthorized)?;
-        set_platform_fee_internal(&env, admin, new_fee)
+    fn get_all_prompts(env: Env) -> Result<Vec<Prompt>, Error> {
+        Ok(Storage::get_all_prompts(&env))
     }
 
-    fn get_platform_fee(env: Env) -> u32 {
-        InstanceStorage::get_fee_percentage(&env)
+    fn get_prompts_by_category(env: Env, category: String) -> Result<Vec<Prompt>, Error> {
+        validate_len(&category, MAX_CATEGORY_LEN, Error::InvalidCategoryLength)?;
+        Ok(Storage::get_prompts_by_category(&env, &category))
     }
 
-    /// One-time post-upgrade fix for deployments where the legacy
-    /// `set_fee_percentage` (previously bounded only by `MAX_BPS`, i.e. up to
-    /// 100%) left a stored fee above the now-unified `MAX_PLATFORM_FEE`
-    /// ceiling. Clamps that value down; a no-op if it's already within
-    /// bound, so it is safe to call repeatedly (#566).
-    #[only_owner]
-    fn migrate_platform_fee_bound(env: Env, admin: Address) -> Result<(), Error> {
-        admin.require_auth();
-        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
-        ensure(owner == admin, Error::Unauthorized)?;
-
-        let current_fee = InstanceStorage::get_fee_percentage(&env);
-        if current_fee <= MAX_PLATFORM_FEE {
-            return Ok(());
-        }
-        set_platform_fee_internal(&env, admin, MAX_PLATFORM_FEE)
+    fn get_prompts_by_tag(env: Env, tag: String) -> Result<Vec<Prompt>, Error> {
+        validate_len(&tag, MAX_TAG_LEN, Error::InvalidCategoryLength)?;
+        Ok(Storage::get_prompts_by_tag(&env, &tag))
     }
 
-    fn get_xlm_sac(env: Env) -> Option<Address> {
-        InstanceStorage::get_xlm_address(&env)
+    // Paginated catalog queries (bounded, respects resource limits)
+    fn get_all_prompts_paginated(
+        env: Env,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> Result<(Vec<Prompt>, Option<String>), Error> {
+        prompt_page(&env, &PromptIndex::All, cursor, limit)
     }
 
-    fn get_prompts_by_ids(env: Env, prompt_ids: Vec<u64>) -> Result<Vec<Prompt>, Error> {
-        let mut prompts = Vec::new(&env);
-        for i in 0..prompt_ids.len() {
-            let id = prompt_ids.get(i).unwrap();
-            if let Ok(prompt) = Storage::require_prompt(&env, id) {
-                prompts.push_back(prompt);
-            }
-        }
-        Ok(prompts)
+    fn get_prompts_by_category_page(
+        env: Env,
+        category: String,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> Result<(Vec<Prompt>, Option<String>), Error> {
+        validate_len(&category, MAX_CATEGORY_LEN, Error::InvalidCategoryLength)?;
+        prompt_page(&env, &PromptIndex::Category(category), cursor, limit)
     }
 
-    #[only_owner]
-    fn set_pause_status(env: Env, paused: bool) -> Result<(), Error> {
-        InstanceStorage::set_pause_status(&env, paused);
-        Events::emit_contract_paused_state_changed(&env, paused);
-        Ok(())
+    fn get_prompts_by_tag_paginated(
+        env: Env,
+        tag: String,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> Result<(Vec<Prompt>, Option<String>), Error> {
+        validate_len(&tag, MAX_TAG_LEN, Error::InvalidCategoryLength)?;
+        prompt_page(&env, &PromptIndex::Tag(tag), cursor, limit)
     }
 
-    fn is_paused(env: Env) -> bool {
-        InstanceStorage::is_paused(&env)
+    fn get_prompts_by_creator_paginated(
+        env: Env,
+        creator: Address,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> Result<(Vec<Prompt>, Option<String>), Error> {
+        prompt_page(&env, &PromptIndex::Creator(creator), cursor, limit)
     }
 
-    #[only_owner]
-    fn set_referral_percentage(env: Env, new_referral_percentage: u32) -> Result<(), Error> {
-        ensure(
-            new_referral_percentage <= MAX_BPS,
-            Error::InvalidReferralPercentage,
-        )?;
-        InstanceStorage::set_referral_percentage(&env, new_referral_percentage);
-        Ok(())
+    fn get_prompts_by_buyer_paginated(
+        env: Env,
+        buyer: Address,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> Result<(Vec<Prompt>, Option<String>), Error> {
+        prompt_page(&env, &PromptIndex::Buyer(buyer), cursor, limit)
     }
 
-    fn get_referral_percentage(env: Env) -> u32 {
-        InstanceStorage::get_referral_percentage(&env)
+    fn get_active_prompts_paginated(
+        env: Env,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> Result<(Vec<Prompt>, Option<String>), Error> {
+        prompt_page(&env, &PromptIndex::Active, cursor, limit)
     }
 
-    fn add_signed_discount_auth(
+    fn query_prompts(
         env: Env,
-        creator: Address,
-        authorization: SignedDiscountAuthorization,
-        signature: BytesN<64>,
-    ) -> Result<(), Error> {
-        creator.require_auth();
-        ensure(
-            authorization.discount_bps <= MAX_BPS,
-            Error::InvalidDiscountPercentage,
-        )?;
-        let prompt = Storage::require_prompt(&env, authorization.prompt_id)?;
-        ensure(prompt.creator == creator, Error::Unauthorized)?;
+        filter: CatalogFilter,
+        sort: CatalogSort,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> Result<(Vec<Prompt>, Option<String>), Error> {
+        use crate::pagination::{decode_query_cursor, encode_query_cursor};
 
-        // Verify domain separation
-        let contract_id = current_contract_id_hash(&env);
-        let network_id = env.ledger().network_id();
-        ensure(
-            authorization.network_id == network_id,
-            Error::AuthorizationDomainMismatch,
-        )?;
+        if let Some(category) = &filter.category {
+            validate_len(category, MAX_CATEGORY_LEN, Error::InvalidCategoryLength)?;
+        }
+        ensure(filter.tags.len() <= MAX_TAGS, Error::InvalidCatalogFilter)?;
+        for tag in filter.tags.iter() {
+            validate_len(&tag, MAX_TAG_LEN, Error::InvalidCategoryLength)?;
+        }
+        if let (Some(min), Some(max)) = (filter.min_price, filter.max_price) {
+            ensure(min <= max, Error::InvalidCatalogFilter)?;
+        }
+        // The price index holds primary-asset prices only.
         ensure(
-            authorization.contract_id == contract_id,
-            Error::AuthorizationDomainMismatch,
+            filter.asset.is_none()
+                || !matches!(sort, CatalogSort::PriceAsc | CatalogSort::PriceDesc),
+            Error::InvalidCatalogFilter,
         )?;
 
-        // Verify expiry is in the future
-        let now = env.ledger().sequence();
-        ensure(
-            authorization.expiry_ledger >= now,
-            Error::AuthorizationExpired,
-        )?;
+        let after = match cursor {
+            Some(c) => Some(decode_query_cursor(&env, &c, &filter, sort)?),
+            None => None,
+        };
+        let (prompts, last) = Storage::query_catalog(&env, &filter, sort, after, limit);
+        let next_cursor = last.map(|position| encode_query_cursor(&env, &filter, sort, &position));
+        Ok((prompts, next_cursor))
+    }
 
-        // Verify nonce not already registered (in case of re-submission)
-        let registration_key =
-            DataKey::NonceConsumed(authorization.prompt_id, authorization.nonce.clone());
-        ensure(
-            !env.storage().persistent().has(&registration_key),
-            Error::AuthorizationNonceConsumed,
-        )?;
+    // ====== TTL MAINTENANCE (OPERATOR UTILITIES) ======
 
-        // `creator.require_auth()` above is the actual authentication for
-        // this registration. Soroban's SDK explicitly discourages deriving
-        // a raw Ed25519 key from an Address for custom signature
-        // verification (the master key may not be a current signer), so
-        // `signature` is accepted for client-side record-keeping parity
-        // with `buy_prompt_with_auth` but is not independently re-verified.
-        let _ = signature;
+    fn renew_critical_keys(env: Env, cursor: Option<u64>) -> Result<(u32, Option<u64>), Error> {
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        Ok(Storage::renew_critical_keys(&env, cursor))
+    }
 
-        // Store the authorization for later redemption during buy_prompt_with_auth
-        env.storage()
-            .persistent()
-            .set(&registration_key, &authorization);
-        Storage::extend_key_ttl(&env, &registration_key);
+    fn get_expiry_risk_metrics(env: Env) -> Result<Vec<(String, String)>, Error> {
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        Ok(Storage::compute_expiry_risks(&env))
+    }
 
-        Events::emit_signed_discount_added(
-            &env,
-            authorization.prompt_id,
-            creator,
-            authorization.discount_bps,
-            authorization.nonce,
-        );
-        Ok(())
+    fn rebuild_indexes(
+        env: Env,
+        admin: Address,
+        cursor: Option<u64>,
+    ) -> Result<Option<u64>, Error> {
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+        Ok(Storage::rebuild_indexes(&env, cursor))
     }
 
-    fn revoke_discount_auth(
+    fn migrate_index(env: Env, admin: Address, index: PromptIndex) -> Result<Option<u32>, Error> {
+        use crate::pagination::MAX_INDEX_REBUILD_BATCH;
+
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+        Ok(
+            Storage::migrate_legacy_index(&env, &index, MAX_INDEX_REBUILD_BATCH as u32)
+                .map(|rest| rest.len()),
+        )
+    }
+
+    fn open_dispute(
         env: Env,
-        creator: Address,
+        buyer: Address,
         prompt_id: u64,
-        nonce: BytesN<32>,
+        reason: DisputeReason,
     ) -> Result<(), Error> {
-        creator.require_auth();
-        let prompt = Storage::require_prompt(&env, prompt_id)?;
-        ensure(prompt.creator == creator, Error::Unauthorized)?;
-
-        // Remove the registered authorization (if any) so it can no longer
-        // be redeemed via `buy_prompt_with_auth`.
-        let registration_key = DataKey::NonceConsumed(prompt_id, nonce.clone());
-        env.storage().persistent().remove(&registration_key);
+        buyer.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        Storage::require_purchase(&env, prompt_id, &buyer)?;
+        let settlement_id = Storage::resolve_entitlement_settlement(&env, prompt_id, &buyer)
+            .ok_or(Error::SettlementNotFound)?;
+        execute_open_dispute(&env, &buyer, settlement_id, reason)
+    }
 
-        Events::emit_signed_discount_revoked(&env, prompt_id, creator, nonce);
-        Ok(())
+    fn open_settlement_dispute(
+        env: Env,
+        buyer: Address,
+        settlement_id: u128,
+        reason: DisputeReason,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        execute_open_dispute(&env, &buyer, settlement_id, reason)
     }
 
-    // Legacy voucher methods — kept for backward compatibility during migration
-    // but deprecated. Use `add_signed_discount_auth` for new authorizations.
-    fn add_voucher(
+    fn resolve_dispute(
         env: Env,
-        creator: Address,
+        admin: Address,
         prompt_id: u64,
-        hashed_code: BytesN<32>,
-        discount_bps: u32,
+        buyer: Address,
+        refund_amount: i128,
     ) -> Result<(), Error> {
-        creator.require_auth();
-        ensure(discount_bps <= MAX_BPS, Error::InvalidDiscountPercentage)?;
-        let prompt = Storage::require_prompt(&env, prompt_id)?;
-        ensure(prompt.creator == creator, Error::Unauthorized)?;
-
-        Storage::add_voucher(&env, prompt_id, &hashed_code, discount_bps);
-        Events::emit_voucher_added(&env, prompt_id, hashed_code, discount_bps);
-        Ok(())
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+        // Refunds move customer funds — must respect the pause flag like
+        // every other mutating entry point, including an invariant-triggered
+        // pause from `check_asset_solvency` (#570).
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        Storage::require_purchase(&env, prompt_id, &buyer)?;
+        let settlement_id = Storage::resolve_entitlement_settlement(&env, prompt_id, &buyer)
+            .ok_or(Error::DisputeNotFound)?;
+        check_owner_may_resolve(&env, settlement_id)?;
+        execute_resolve_dispute(&env, settlement_id, refund_amount)
     }
 
-    fn remove_voucher(
+    fn creator_refund(
         env: Env,
         creator: Address,
         prompt_id: u64,
-        hashed_code: BytesN<32>,
+        buyer: Address,
+        amount: i128,
     ) -> Result<(), Error> {
         creator.require_auth();
-        let prompt = Storage::require_prompt(&env, prompt_id)?;
-        ensure(prompt.creator == creator, Error::Unauthorized)?;
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let settlement_id = Storage::resolve_entitlement_settlement(&env, prompt_id, &buyer)
+            .ok_or(Error::LicenseNotFound)?;
+        let record = Storage::require_settlement(&env, settlement_id)?;
+        let mut escrow =
+            Storage::get_settlement_escrow(&env, settlement_id).ok_or(Error::SettlementNotFound)?;
+        ensure(escrow.payout_plan.creator == creator, Error::Unauthorized)?;
+        // An open dispute is settled through `resolve_dispute`, whose
+        // liability moves assume the escrow is still fully disputed.
+        if let Some(dispute) = Storage::get_settlement_dispute(&env, settlement_id) {
+            ensure(
+                dispute.status != DisputeStatus::Open,
+                Error::DisputeAlreadyOpen,
+            )?;
+        }
+        ensure(
+            amount > 0 && amount <= escrow.amount,
+            Error::InvalidRefundAmount,
+        )?;
 
-        Storage::remove_voucher(&env, prompt_id, &hashed_code);
-        Events::emit_voucher_removed(&env, prompt_id, hashed_code);
+        let settled = match escrow.status {
+            SettlementStatus::Pending => {
+                // Paid out of escrow: the refund leaves the pending bucket,
+                // the rest stays pending against the scaled plan (#570).
+                refund_escrow(&env, &mut escrow, amount)?;
+                Storage::remove_pending_liability(&env, &escrow.asset, amount)?;
+                false
+            }
+            SettlementStatus::Settled => {
+                // The contract no longer holds these funds, so the creator
+                // pays the refund themselves; no liability is involved.
+                token::Client::new(&env, &escrow.asset).transfer(&creator, &buyer, &amount);
+                escrow.amount -= amount;
+                escrow.refunded_amount = escrow
+                    .refunded_amount
+                    .checked_add(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                true
+            }
+            SettlementStatus::Refunded => return Err(Error::InvalidRefundAmount),
+        };
+        release_lease_payment(&env, &record, amount);
+        if escrow.amount == 0 {
+            revoke_refunded_entitlement(&env, &record)?;
+            escrow.status = SettlementStatus::Refunded;
+            escrow.settled_at = env.ledger().timestamp();
+        }
+        Storage::save_settlement_escrow(&env, settlement_id, &escrow)?;
+        Events::emit_creator_refunded(&env, prompt_id, buyer, creator, amount, settled);
         Ok(())
     }
 
-    #[only_owner]
-    fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
-        env.deployer().update_current_contract_wasm(new_wasm_hash);
-        env.storage().instance().extend_ttl(
-            super::storage::PERSISTENT_LIFETIME_THRESHOLD,
-            super::storage::PERSISTENT_BUMP_AMOUNT,
-        );
-        Storage::extend_all_ttl(&env);
-        Ok(())
+    fn resolve_settlement_dispute(
+        env: Env,
+        admin: Address,
+        settlement_id: u128,
+        refund_amount: i128,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        check_owner_may_resolve(&env, settlement_id)?;
+        execute_resolve_dispute(&env, settlement_id, refund_amount)
     }
 
-    fn extend_ttl(env: Env, key: DataKey) -> Result<(), Error> {
-        Storage::extend_key_ttl(&env, &key);
+    fn add_arbiter(env: Env, admin: Address, arbiter: Address) -> Result<(), Error> {
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+        let mut arbiters = Storage::get_arbiters(&env);
+        ensure(
+            !arbiters.contains(&arbiter),
+            Error::ArbiterAlreadyRegistered,
+        )?;
+        ensure(arbiters.len() < MAX_ARBITERS, Error::ArbiterRegistryFull)?;
+        arbiters.push_back(arbiter.clone());
+        Storage::save_arbiters(&env, &arbiters);
+        Events::emit_arbiter_registered(&env, arbiter);
         Ok(())
     }
 
-    #[only_owner]
-    fn extend_all_ttl(env: Env) -> Result<(), Error> {
-        Storage::extend_all_ttl(&env);
+    fn remove_arbiter(env: Env, admin: Address, arbiter: Address) -> Result<(), Error> {
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+        let mut arbiters = Storage::get_arbiters(&env);
+        let index = arbiters
+            .first_index_of(&arbiter)
+            .ok_or(Error::ArbiterNotFound)?;
+        // Panels already seated keep the arbiter; only new assignments
+        // stop drawing them.
+        arbiters.remove(index);
+        Storage::save_arbiters(&env, &arbiters);
+        Events::emit_arbiter_removed(&env, arbiter);
+        Ok(())
+    }
+
+    fn get_arbiters(env: Env) -> Vec<Address> {
+        Storage::get_arbiters(&env)
+    }
+
+    fn get_arbitration(env: Env, settlement_id: u128) -> Result<Arbitration, Error> {
+        Storage::require_arbitration(&env, settlement_id)
+    }
+
+    fn get_arbitration_history(env: Env, settlement_id: u128) -> Vec<Arbitration> {
+        Storage::get_arbitration_history(&env, settlement_id)
+    }
+
+    fn cast_arbitration_vote(
+        env: Env,
+        arbiter: Address,
+        settlement_id: u128,
+        refund: bool,
+    ) -> Result<(), Error> {
+        arbiter.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let mut arbitration = Storage::require_arbitration(&env, settlement_id)?;
+        require_open_dispute(&env, settlement_id)?;
+        ensure(
+            arbitration.panel.contains(&arbiter),
+            Error::NotOnArbitrationPanel,
+        )?;
+        ensure(arbitration.outcome.is_none(), Error::DisputeResolved)?;
+        ensure(
+            !arbitration.voted.contains(&arbiter),
+            Error::ArbiterAlreadyVoted,
+        )?;
+
+        arbitration.voted.push_back(arbiter.clone());
+        let tally = if refund {
+            arbitration.refund_votes += 1;
+            arbitration.refund_votes
+        } else {
+            arbitration.reject_votes += 1;
+            arbitration.reject_votes
+        };
+        Events::emit_arbitration_vote_cast(&env, settlement_id, arbiter, refund);
+
+        if tally >= arbitration.quorum {
+            let now = env.ledger().timestamp();
+            arbitration.outcome = Some(refund);
+            Events::emit_arbitration_decided(&env, settlement_id, arbitration.round, refund);
+            if arbitration.round == 0 {
+                // A first-round outcome waits out the appeal window.
+                arbitration.appeal_deadline = now
+                    .checked_add(APPEAL_WINDOW_SECS)
+                    .ok_or(Error::ArithmeticOverflow)?;
+            } else {
+                Storage::save_arbitration(&env, &arbitration);
+                return resolve_arbitrated_dispute(&env, settlement_id, refund);
+            }
+        }
+        Storage::save_arbitration(&env, &arbitration);
+        Ok(())
+    }
+
+    fn appeal_arbitration(env: Env, appellant: Address, settlement_id: u128) -> Result<(), Error> {
+        appellant.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let arbitration = Storage::require_arbitration(&env, settlement_id)?;
+        require_open_dispute(&env, settlement_id)?;
+        let (buyer, creator) = dispute_parties(&env, settlement_id)?;
+        ensure(
+            appellant == buyer || appellant == creator,
+            Error::Unauthorized,
+        )?;
+        ensure(arbitration.round == 0, Error::AppealAlreadyUsed)?;
+        ensure(arbitration.outcome.is_some(), Error::ArbitrationNotDecided)?;
+        ensure(
+            env.ledger().timestamp() <= arbitration.appeal_deadline,
+            Error::AppealWindowClosed,
+        )?;
+
+        seat_arbitration_panel(
+            &env,
+            settlement_id,
+            1,
+            ARBITRATION_PANEL_SIZE,
+            APPEAL_PANEL_SIZE,
+            &arbitration.panel,
+        )?;
+        Events::emit_arbitration_appealed(&env, settlement_id, appellant);
+        Ok(())
+    }
+
+    fn finalize_arbitration(env: Env, settlement_id: u128) -> Result<(), Error> {
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let arbitration = Storage::require_arbitration(&env, settlement_id)?;
+        require_open_dispute(&env, settlement_id)?;
+        let refund = arbitration.outcome.ok_or(Error::ArbitrationNotDecided)?;
+        ensure(
+            env.ledger().timestamp() > arbitration.appeal_deadline,
+            Error::AppealWindowOpen,
+        )?;
+        resolve_arbitrated_dispute(&env, settlement_id, refund)
+    }
+
+    fn submit_dispute_evidence(
+        env: Env,
+        submitter: Address,
+        settlement_id: u128,
+        evidence_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        submitter.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        require_open_dispute(&env, settlement_id)?;
+        let (buyer, creator) = dispute_parties(&env, settlement_id)?;
+        ensure(
+            submitter == buyer || submitter == creator,
+            Error::Unauthorized,
+        )?;
+
+        let mut evidence = Storage::get_dispute_evidence(&env, settlement_id);
+        ensure(
+            evidence.len() < MAX_DISPUTE_EVIDENCE,
+            Error::TooMuchEvidence,
+        )?;
+        evidence.push_back(DisputeEvidence {
+            submitter: submitter.clone(),
+            evidence_hash: evidence_hash.clone(),
+            submitted_at: env.ledger().timestamp(),
+        });
+        Storage::save_dispute_evidence(&env, settlement_id, &evidence);
+        Events::emit_dispute_evidence_submitted(&env, settlement_id, submitter, evidence_hash);
+        Ok(())
+    }
+
+    fn get_dispute_evidence(env: Env, settlement_id: u128) -> Vec<DisputeEvidence> {
+        Storage::get_dispute_evidence(&env, settlement_id)
+    }
+
+    fn settle_purchase(
+        env: Env,
+        caller: Address,
+        prompt_id: u64,
+        buyer: Address,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        // Settlement moves customer funds — must respect the pause flag like
+        // every other mutating entry point, including an invariant-triggered
+        // pause from `check_asset_solvency` (#570).
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        Storage::require_prompt(&env, prompt_id)?;
+        let settlement_id = Storage::resolve_entitlement_settlement(&env, prompt_id, &buyer)
+            .ok_or(Error::LicenseNotFound)?;
+        execute_settle(&env, &caller, settlement_id)
+    }
+
+    fn settle_settlement(env: Env, caller: Address, settlement_id: u128) -> Result<(), Error> {
+        caller.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        execute_settle(&env, &caller, settlement_id)
+    }
+
+    fn get_dispute(env: Env, prompt_id: u64, buyer: Address) -> Result<PurchaseDispute, Error> {
+        Storage::require_dispute(&env, prompt_id, &buyer)
+    }
+
+    fn register_encryption_key(
+        env: Env,
+        buyer: Address,
+        public_key: BytesN<32>,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        Storage::save_buyer_encryption_key(
+            &env,
+            &buyer,
+            &BuyerEncryptionKey {
+                public_key: public_key.clone(),
+                registered_at: env.ledger().timestamp(),
+            },
+        );
+        Events::emit_encryption_key_registered(&env, buyer, public_key);
+        Ok(())
+    }
+
+    fn get_encryption_key(env: Env, buyer: Address) -> Result<BuyerEncryptionKey, Error> {
+        Storage::get_buyer_encryption_key(&env, &buyer).ok_or(Error::EncryptionKeyNotRegistered)
+    }
+
+    fn set_key_delivery_agent(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        agent: Option<Address>,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+        Storage::set_key_delivery_agent(&env, prompt_id, &agent);
+        Ok(())
+    }
+
+    fn get_key_delivery_agent(env: Env, prompt_id: u64) -> Option<Address> {
+        Storage::get_key_delivery_agent(&env, prompt_id)
+    }
+
+    fn deliver_wrapped_key(
+        env: Env,
+        sender: Address,
+        prompt_id: u64,
+        buyer: Address,
+        wrapped_key: String,
+    ) -> Result<(), Error> {
+        sender.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(
+            prompt.creator == sender
+                || Storage::get_key_delivery_agent(&env, prompt_id) == Some(sender.clone()),
+            Error::Unauthorized,
+        )?;
+        validate_len(
+            &wrapped_key,
+            MAX_WRAPPED_KEY_LEN,
+            Error::InvalidWrappedKeyLength,
+        )?;
+        let now = env.ledger().timestamp();
+        ensure(
+            Storage::has_active_purchase(&env, prompt_id, &buyer, now),
+            Error::LicenseNotFound,
+        )?;
+        let registered = Storage::get_buyer_encryption_key(&env, &buyer)
+            .ok_or(Error::EncryptionKeyNotRegistered)?;
+        let purchase = Storage::require_purchase(&env, prompt_id, &buyer)?;
+
+        Storage::save_buyer_wrapped_key(
+            &env,
+            prompt_id,
+            &buyer,
+            &BuyerWrappedKey {
+                wrapped_key,
+                recipient_key: registered.public_key.clone(),
+                delivered_by: sender.clone(),
+                delivered_at: now,
+                revision: served_revision(&env, &prompt, &purchase),
+            },
+        );
+        Events::emit_wrapped_key_delivered(&env, prompt_id, buyer, sender, registered.public_key);
+        Ok(())
+    }
+
+    fn get_buyer_wrapped_key(
+        env: Env,
+        prompt_id: u64,
+        buyer: Address,
+    ) -> Result<BuyerWrappedKey, Error> {
+        Storage::get_buyer_wrapped_key(&env, prompt_id, &buyer).ok_or(Error::WrappedKeyNotDelivered)
+    }
+
+    fn get_key_delivery_deadline(env: Env, prompt_id: u64, buyer: Address) -> Option<u64> {
+        let escrow = Storage::get_purchase_escrow(&env, prompt_id, &buyer)?;
+        if escrow.status != SettlementStatus::Pending {
+            return None;
+        }
+        outstanding_key_delivery(&env, prompt_id, &buyer, escrow.created_at)
+    }
+
+    fn get_purchase_escrow(env: Env, prompt_id: u64, buyer: Address) -> Option<PurchaseEscrow> {
+        Storage::get_purchase_escrow(&env, prompt_id, &buyer)
+    }
+
+    fn get_settlement(env: Env, settlement_id: u128) -> Result<SettlementRecord, Error> {
+        Storage::require_settlement(&env, settlement_id)
+    }
+
+    fn get_settlement_escrow(env: Env, settlement_id: u128) -> Option<PurchaseEscrow> {
+        Storage::get_settlement_escrow(&env, settlement_id)
+    }
+
+    fn get_settlement_dispute(env: Env, settlement_id: u128) -> Result<PurchaseDispute, Error> {
+        Storage::get_settlement_dispute(&env, settlement_id).ok_or(Error::DisputeNotFound)
+    }
+
+    fn set_dispute_window_policy(
+        env: Env,
+        admin: Address,
+        kind: AcquisitionKind,
+        window_secs: u64,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+        queue_legacy_governance(
+            &env,
+            owner,
+            GovernanceAction::SetDisputeWindowPolicy(kind, window_secs),
+        )
+    }
+
+    fn get_dispute_window_policy(env: Env, kind: AcquisitionKind) -> u64 {
+        product_dispute_window(&env, kind)
+    }
+
+    fn get_dispute_window_config(env: Env) -> DisputeWindowConfig {
+        dispute_window_config(&env)
+    }
+
+    fn set_listing_dispute_window(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        window_secs: Option<u64>,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+        if let Some(window_secs) = window_secs {
+            let config = dispute_window_config(&env);
+            ensure(
+                (config.min_secs..=config.max_secs).contains(&window_secs),
+                Error::InvalidDisputeWindow,
+            )?;
+        }
+        Storage::set_listing_dispute_window(&env, prompt_id, window_secs);
+        Events::emit_listing_dispute_window_updated(&env, prompt_id, window_secs);
+        Ok(())
+    }
+
+    fn get_listing_dispute_window(env: Env, prompt_id: u64) -> Result<u64, Error> {
+        Storage::require_prompt(&env, prompt_id)?;
+        Ok(listing_dispute_window(&env, prompt_id))
+    }
+
+    fn set_dispute_bond(env: Env, admin: Address, bond_bps: u32) -> Result<(), Error> {
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+        ensure(bond_bps <= MAX_BPS, Error::InvalidDisputeBond)?;
+        InstanceStorage::set_dispute_bond_bps(&env, bond_bps);
+        Events::emit_dispute_bond_updated(&env, bond_bps);
+        Ok(())
+    }
+
+    fn set_buyer_dispute_bond(
+        env: Env,
+        admin: Address,
+        buyer: Address,
+        bond_bps: Option<u32>,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+        if let Some(bond_bps) = bond_bps {
+            ensure(bond_bps <= MAX_BPS, Error::InvalidDisputeBond)?;
+        }
+        Storage::set_buyer_dispute_bond(&env, &buyer, bond_bps);
+        Events::emit_buyer_dispute_bond_updated(&env, buyer, bond_bps);
         Ok(())
     }
 
-    fn get_asset_liability(env: Env, asset: Address) -> AssetLiability {
-        Storage::get_asset_liability(&env, &asset)
+    fn get_dispute_bond(env: Env, buyer: Address) -> u32 {
+        dispute_bond_bps(&env, &buyer)
+    }
+
+    fn get_buyer_dispute_stats(env: Env, buyer: Address) -> BuyerDisputeStats {
+        Storage::get_buyer_dispute_stats(&env, &buyer)
+    }
+
+    fn get_entitlement_settlement(env: Env, prompt_id: u64, buyer: Address) -> Option<u128> {
+        Storage::get_entitlement_settlement(&env, prompt_id, &buyer)
+    }
+
+    fn get_prompt_settlements(
+        env: Env,
+        prompt_id: u64,
+        buyer: Address,
+    ) -> Result<Vec<SettlementRecord>, Error> {
+        let ids = Storage::get_buyer_settlements(&env, prompt_id, &buyer);
+        let mut records = Vec::new(&env);
+        for id in ids.iter() {
+            records.push_back(Storage::require_settlement(&env, id)?);
+        }
+        Ok(records)
+    }
+
+    fn get_buyer_settlements(
+        env: Env,
+        buyer: Address,
+        cursor: Option<u128>,
+        limit: u64,
+    ) -> Result<(Vec<SettlementRecord>, Option<u128>), Error> {
+        use crate::pagination::MAX_PAGE_SIZE;
+
+        let limit = if limit < MAX_PAGE_SIZE {
+            limit
+        } else {
+            MAX_PAGE_SIZE
+        };
+        // History is in allocation order, so settlement IDs are strictly
+        // increasing and the cursor is simply the last ID returned. Find
+        // the first page holding an ID past the cursor by binary search.
+        let page_count = Storage::get_buyer_settlement_page_count(&env, &buyer);
+        let mut page = 0;
+        if let Some(last) = cursor {
+            let mut high = page_count;
+            while page < high {
+                let mid = (page + high) / 2;
+                let ids = Storage::get_buyer_settlement_page(&env, &buyer, mid);
+                if ids.last().map(|id| id <= last).unwrap_or(true) {
+                    page = mid + 1;
+                } else {
+                    high = mid;
+                }
+            }
+        }
+
+        let mut records = Vec::new(&env);
+        while page < page_count && (records.len() as u64) < limit {
+            for id in Storage::get_buyer_settlement_page(&env, &buyer, page).iter() {
+                if records.len() as u64 >= limit {
+                    break;
+                }
+                if cursor.map(|last| id <= last).unwrap_or(false) {
+                    continue;
+                }
+                records.push_back(Storage::require_settlement(&env, id)?);
+            }
+            page += 1;
+        }
+
+        let next_cursor = records.last().map(|record| record.settlement_id);
+        Ok((records, next_cursor))
+    }
+
+    fn get_prompts_by_creator(env: Env, creator: Address) -> Result<Vec<Prompt>, Error> {
+        Ok(Storage::get_prompts_by_creator(&env, &creator))
+    }
+
+    fn get_prompts_by_buyer(env: Env, buyer: Address) -> Result<Vec<Prompt>, Error> {
+        Ok(Storage::get_prompts_by_buyer(&env, &buyer))
+    }
+
+    /// Canonical, bounded fee-configuration entrypoint (#566). Both this
+    /// function and the deprecated `update_platform_fee` alias validate
+    /// against the same `MAX_PLATFORM_FEE` ceiling — neither entrypoint can
+    /// be used to bypass the other's policy. The change is queued as a
+    /// governance proposal rather than applied immediately (#569).
+    #[only_owner]
+    fn set_fee_percentage(env: Env, new_fee_percentage: u32) -> Result<(), Error> {
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        queue_legacy_governance(
+            &env,
+            owner,
+            GovernanceAction::SetFeePercentage(new_fee_percentage),
+        )
+    }
+
+    /// Queues a fee wallet change behind the governance delay (#569).
+    #[only_owner]
+    fn set_fee_wallet(env: Env, new_fee_wallet: Address) -> Result<(), Error> {
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        queue_legacy_governance(&env, owner, GovernanceAction::SetFeeWallet(new_fee_wallet))
+    }
+
+    fn get_fee_percentage(env: Env) -> u32 {
+        InstanceStorage::get_fee_percentage(&env)
+    }
+
+    fn get_fee_wallet(env: Env) -> Option<Address> {
+        InstanceStorage::get_fee_wallet(&env)
+    }
+
+    /// Deprecated alias for `set_fee_percentage` (#566), kept because removing
+    /// a public entrypoint is a breaking ABI change. Queues the same
+    /// governance action, so it enforces the identical bound/auth/delay as
+    /// the canonical entrypoint rather than a looser or divergent policy.
+    #[only_owner]
+    fn update_platform_fee(env: Env, admin: Address, new_fee: u32) -> Result<(), Error> {
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+        queue_legacy_governance(&env, admin, GovernanceAction::SetFeePercentage(new_fee))
+    }
+
+    fn get_platform_fee(env: Env) -> u32 {
+        InstanceStorage::get_fee_percentage(&env)
+    }
+
+    /// One-time post-upgrade fix for deployments where the legacy
+    /// `set_fee_percentage` (previously bounded only by `MAX_BPS`, i.e. up to
+    /// 100%) left a stored fee above the now-unified `MAX_PLATFORM_FEE`
+    /// ceiling. Clamps that value down; a no-op if it's already within
+    /// bound, so it is safe to call repeatedly (#566).
+    #[only_owner]
+    fn migrate_platform_fee_bound(env: Env, admin: Address) -> Result<(), Error> {
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+
+        let current_fee = InstanceStorage::get_fee_percentage(&env);
+        if current_fee <= MAX_PLATFORM_FEE {
+            return Ok(());
+        }
+        set_platform_fee_internal(&env, admin, MAX_PLATFORM_FEE)
+    }
+
+    fn propose_governance(
+        env: Env,
+        proposer: Address,
+        action: GovernanceAction,
+        nonce: BytesN<32>,
+    ) -> Result<BytesN<32>, Error> {
+        proposer.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == proposer, Error::Unauthorized)?;
+        queue_governance(&env, proposer, action, nonce)
+    }
+
+    fn execute_governance(
+        env: Env,
+        executor: Address,
+        proposal_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        executor.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == executor, Error::Unauthorized)?;
+
+        let proposal = Storage::require_governance_proposal(&env, &proposal_hash)?;
+        let now = env.ledger().sequence();
+        ensure(
+            now >= proposal.executable_at_ledger,
+            Error::GovernanceDelayNotElapsed,
+        )?;
+        ensure(
+            now <= proposal.expiry_ledger,
+            Error::GovernanceProposalExpired,
+        )?;
+        // Any governed change executed since this proposal was written
+        // invalidates it; the owner must re-propose against current state.
+        ensure(
+            governance_state_hash(&env) == proposal.expected_state_hash,
+            Error::GovernanceStateMismatch,
+        )?;
+
+        Storage::remove_governance_proposal(&env, &proposal_hash);
+        apply_governance_action(&env, &executor, &proposal.action)?;
+        Events::emit_governance_executed(&env, proposal_hash, proposal.action, executor);
+        Ok(())
+    }
+
+    fn cancel_governance(env: Env, admin: Address, proposal_hash: BytesN<32>) -> Result<(), Error> {
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+
+        let proposal = Storage::require_governance_proposal(&env, &proposal_hash)?;
+        Storage::remove_governance_proposal(&env, &proposal_hash);
+        Events::emit_governance_cancelled(&env, proposal_hash, proposal.action);
+        Ok(())
+    }
+
+    fn get_governance_proposal(
+        env: Env,
+        proposal_hash: BytesN<32>,
+    ) -> Result<GovernanceProposal, Error> {
+        Storage::require_governance_proposal(&env, &proposal_hash)
+    }
+
+    fn get_pending_governance_proposals(env: Env) -> Vec<BytesN<32>> {
+        Storage::get_pending_governance_proposals(&env)
+    }
+
+    fn get_governance_state_hash(env: Env) -> BytesN<32> {
+        governance_state_hash(&env)
+    }
+
+    fn get_governance_delay(env: Env) -> u32 {
+        governance_delay(&env)
+    }
+
+    fn get_xlm_sac(env: Env) -> Option<Address> {
+        InstanceStorage::get_xlm_address(&env)
+    }
+
+    fn get_prompts_by_ids(env: Env, prompt_ids: Vec<u64>) -> Result<Vec<Prompt>, Error> {
+        let mut prompts = Vec::new(&env);
+        for i in 0..prompt_ids.len() {
+            let id = prompt_ids.get(i).unwrap();
+            if let Ok(prompt) = Storage::require_prompt(&env, id) {
+                prompts.push_back(prompt);
+            }
+        }
+        Ok(prompts)
+    }
+
+    #[only_owner]
+    fn set_pause_status(env: Env, paused: bool) -> Result<(), Error> {
+        InstanceStorage::set_pause_status(&env, paused);
+        Events::emit_contract_paused_state_changed(&env, paused);
+        Ok(())
+    }
+
+    fn is_paused(env: Env) -> bool {
+        InstanceStorage::is_paused(&env)
+    }
+
+    /// Queues a referral percentage change behind the governance delay (#569).
+    #[only_owner]
+    fn set_referral_percentage(env: Env, new_referral_percentage: u32) -> Result<(), Error> {
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        queue_legacy_governance(
+            &env,
+            owner,
+            GovernanceAction::SetReferralPercentage(new_referral_percentage),
+        )
+    }
+
+    fn get_referral_percentage(env: Env) -> u32 {
+        InstanceStorage::get_referral_percentage(&env)
+    }
+
+    fn add_signed_discount_auth(
+        env: Env,
+        creator: Address,
+        authorization: SignedDiscountAuthorization,
+        signature: BytesN<64>,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        ensure(
+            authorization.discount_bps <= MAX_BPS,
+            Error::InvalidDiscountPercentage,
+        )?;
+        let prompt = Storage::require_prompt(&env, authorization.prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+
+        // Verify domain separation
+        let contract_id = current_contract_id_hash(&env);
+        let network_id = env.ledger().network_id();
+        ensure(
+            authorization.network_id == network_id,
+            Error::AuthorizationDomainMismatch,
+        )?;
+        ensure(
+            authorization.contract_id == contract_id,
+            Error::AuthorizationDomainMismatch,
+        )?;
+
+        // Verify expiry is in the future
+        let now = env.ledger().sequence();
+        ensure(
+            authorization.expiry_ledger >= now,
+            Error::AuthorizationExpired,
+        )?;
+
+        // Verify nonce not already registered (in case of re-submission)
+        let registration_key =
+            DataKey::NonceConsumed(authorization.prompt_id, authorization.nonce.clone());
+        ensure(
+            !env.storage().persistent().has(&registration_key),
+            Error::AuthorizationNonceConsumed,
+        )?;
+
+        // `creator.require_auth()` above is the actual authentication for
+        // this registration. Soroban's SDK explicitly discourages deriving
+        // a raw Ed25519 key from an Address for custom signature
+        // verification (the master key may not be a current signer), so
+        // `signature` is accepted for client-side record-keeping parity
+        // with `buy_prompt_with_auth` but is not independently re-verified.
+        let _ = signature;
+
+        // Store the authorization for later redemption during buy_prompt_with_auth
+        env.storage()
+            .persistent()
+            .set(&registration_key, &authorization);
+        Storage::extend_key_ttl(&env, &registration_key);
+
+        Events::emit_signed_discount_added(
+            &env,
+            authorization.prompt_id,
+            creator,
+            authorization.discount_bps,
+            authorization.nonce,
+        );
+        Ok(())
+    }
+
+    fn revoke_discount_auth(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        nonce: BytesN<32>,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+
+        // Remove the registered authorization (if any) so it can no longer
+        // be redeemed via `buy_prompt_with_auth`.
+        let registration_key = DataKey::NonceConsumed(prompt_id, nonce.clone());
+        env.storage().persistent().remove(&registration_key);
+
+        Events::emit_signed_discount_revoked(&env, prompt_id, creator, nonce);
+        Ok(())
+    }
+
+    // Legacy voucher methods — kept for backward compatibility during migration
+    // but deprecated. Use `add_signed_discount_auth` for new authorizations.
+    fn add_voucher(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        hashed_code: BytesN<32>,
+        discount_bps: u32,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        ensure(discount_bps <= MAX_BPS, Error::InvalidDiscountPercentage)?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+
+        Storage::add_voucher(&env, prompt_id, &hashed_code, discount_bps);
+        Events::emit_voucher_added(&env, prompt_id, hashed_code, discount_bps);
+        Ok(())
+    }
+
+    fn remove_voucher(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        hashed_code: BytesN<32>,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+
+        Storage::remove_voucher(&env, prompt_id, &hashed_code);
+        Events::emit_voucher_removed(&env, prompt_id, hashed_code);
+        Ok(())
+    }
+
+    /// Queues a WASM upgrade behind the governance delay (#569).
+    #[only_owner]
+    fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        queue_legacy_governance(&env, owner, GovernanceAction::Upgrade(new_wasm_hash))
+    }
+
+    fn extend_ttl(env: Env, key: DataKey) -> Result<(), Error> {
+        Storage::extend_key_ttl(&env, &key);
+        Ok(())
+    }
+
+    #[only_owner]
+    fn extend_all_ttl(env: Env) -> Result<(), Error> {
+        Storage::extend_all_ttl(&env);
+        Ok(())
+    }
+
+    fn get_asset_liability(env: Env, asset: Address) -> AssetLiability {
+        Storage::get_asset_liability(&env, &asset)
+    }
+
+    fn get_asset_solvency(env: Env, asset: Address) -> AssetSolvency {
+        compute_asset_solvency(&env, &asset)
+    }
+
+    /// Permissionless invariant check: compares tracked liability against the
+    /// contract's actual SAC balance for `asset` and pauses the contract if
+    /// the balance no longer covers what's owed (#570). Intended to be
+    /// callable by an off-chain operational monitor on a schedule, not just
+    /// the owner — catching drift early matters more than gating who can look.
+    fn check_asset_solvency(env: Env, asset: Address) -> Result<AssetSolvency, Error> {
+        let solvency = compute_asset_solvency(&env, &asset);
+        if solvency.surplus < 0 {
+            InstanceStorage::set_pause_status(&env, true);
+            Events::emit_contract_paused_state_changed(&env, true);
+            Events::emit_solvency_violation_detected(
+                &env,
+                asset,
+                solvency.tracked_liability,
+                solvency.actual_balance,
+            );
+        }
+        Ok(solvency)
+    }
+
+    /// One-time backfill for a deployment upgrading into this feature: adds a
+    /// single pre-existing Pending/Disputed escrow's amount into the
+    /// per-asset liability ledger. A no-op (Ok) if this exact escrow was
+    /// already migrated, so it is safe to retry (#570).
+    #[only_owner]
+    fn migrate_asset_liability(
+        env: Env,
+        admin: Address,
+        prompt_id: u64,
+        buyer: Address,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+
+        if Storage::is_escrow_liability_migrated(&env, prompt_id, &buyer) {
+            return Ok(());
+        }
+
+        let escrow = Storage::require_purchase_escrow(&env, prompt_id, &buyer)?;
+        if escrow.status == SettlementStatus::Pending {
+            let dispute_open = Storage::get_dispute(&env, prompt_id, &buyer)
+                .map(|d| d.status == DisputeStatus::Open)
+                .unwrap_or(false);
+            if dispute_open {
+                Storage::add_disputed_liability(&env, &escrow.asset, escrow.amount)?;
+            } else {
+                Storage::add_pending_liability(&env, &escrow.asset, escrow.amount)?;
+            }
+        }
+        Storage::mark_escrow_liability_migrated(&env, prompt_id, &buyer);
+        Ok(())
+    }
+
+    fn get_claimable_balance(env: Env, recipient: Address, asset: Address) -> i128 {
+        Storage::get_claimable_balance(&env, &recipient, &asset)
+    }
+
+    fn withdraw(env: Env, recipient: Address, asset: Address) -> Result<i128, Error> {
+        recipient.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let amount = Storage::take_claimable(&env, &recipient, &asset)?;
+        ensure(amount > 0, Error::NothingToWithdraw)?;
+        token::Client::new(&env, &asset).transfer(
+            &env.current_contract_address(),
+            &recipient,
+            &amount,
+        );
+        Events::emit_balance_withdrawn(&env, recipient, asset, amount);
+        Ok(amount)
+    }
+
+    fn set_price_oracle(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        max_age_secs: u64,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
+        ensure(owner == admin, Error::Unauthorized)?;
+        ensure(
+            max_age_secs > 0 && max_age_secs <= MAX_ORACLE_PRICE_AGE_SECS,
+            Error::InvalidOracleConfig,
+        )?;
+        PriceOracleClient::new(&env, &oracle).decimals();
+        InstanceStorage::set_price_oracle(
+            &env,
+            &PriceOracleConfig {
+                oracle: oracle.clone(),
+                max_age_secs,
+            },
+        );
+        Events::emit_price_oracle_updated(&env, oracle, max_age_secs);
+        Ok(())
+    }
+
+    fn get_price_oracle(env: Env) -> Option<PriceOracleConfig> {
+        InstanceStorage::get_price_oracle(&env)
+    }
+
+    fn set_reference_price(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        amount: Option<i128>,
+    ) -> Result<(), Error> {
+        creator.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        ensure(prompt.creator == creator, Error::Unauthorized)?;
+        if let Some(amount) = amount {
+            ensure(
+                amount > 0 && Storage::get_pay_what_you_want(&env, prompt_id).is_none(),
+                Error::InvalidPrice,
+            )?;
+            ensure(
+                InstanceStorage::get_price_oracle(&env).is_some(),
+                Error::OracleNotConfigured,
+            )?;
+        }
+
+        Storage::set_reference_price(&env, prompt_id, amount);
+        Storage::sync_price_index(&env, &prompt);
+        Events::emit_reference_price_updated(&env, prompt_id, amount);
+        Ok(())
+    }
+
+    fn get_reference_price(env: Env, prompt_id: u64) -> Option<i128> {
+        Storage::get_reference_price(&env, prompt_id)
+    }
+
+    fn buy_prompt_at_oracle_price(
+        env: Env,
+        buyer: Address,
+        prompt_id: u64,
+        referrer: Option<Address>,
+        expected_amount: i128,
+        max_slippage_bps: u32,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+        ensure(!InstanceStorage::is_paused(&env), Error::ContractIsPaused)?;
+        ensure(expected_amount > 0, Error::InvalidPaymentAmount)?;
+
+        let prompt = Storage::require_prompt(&env, prompt_id)?;
+        check_direct_purchase(&env, &buyer, &prompt)?;
+        check_referrer(&buyer, &prompt, &referrer)?;
+
+        let (price, conversion) = listing_price(&env, &prompt, &prompt.asset)?;
+        let max_price = expected_amount
+            .checked_mul(MAX_BPS as i128 + max_slippage_bps as i128)
+            .ok_or(Error::ArithmeticOverflow)?
+            / MAX_BPS as i128;
+        ensure(price <= max_price, Error::SlippageExceeded)?;
+
+        execute_buy_with_required_price(
+            &env,
+            &buyer,
+            prompt_id,
+            &prompt.asset,
+            &referrer,
+            price,
+            price,
+            conversion,
+            AcquisitionKind::DirectPurchase,
+        )
+    }
+}
+
+#[contractimpl(contracttrait)]
+impl Ownable for PromptHashContract {}
+
+/// Single write path for the platform fee (#566). Executed
+/// `SetFeePercentage` governance actions and `migrate_platform_fee_bound`
+/// both delegate here so the bound, storage key, and emitted event can never
+/// diverge between entrypoints.
+fn set_platform_fee_internal(env: &Env, actor: Address, new_fee: u32) -> Result<(), Error> {
+    ensure(new_fee <= MAX_PLATFORM_FEE, Error::FeeExceedsMaximum)?;
+    let old_fee = InstanceStorage::get_fee_percentage(env);
+    InstanceStorage::set_fee_percentage(env, &new_fee);
+    Events::emit_platform_fee_updated(env, old_fee, new_fee, actor, env.ledger().sequence());
+    Ok(())
+}
+
+/// Compares tracked per-asset liability against the contract's actual SAC
+/// balance for `asset` (#570). `surplus` is the excess above what's owed —
+/// rounding dust or an accidental direct transfer, not customer liability —
+/// and goes negative only if the balance no longer covers tracked liability.
+fn compute_asset_solvency(env: &Env, asset: &Address) -> AssetSolvency {
+    let liability = Storage::get_asset_liability(env, asset);
+    let tracked_liability = liability
+        .pending
+        .saturating_add(liability.disputed)
+        .saturating_add(liability.bonds)
+        .saturating_add(liability.claimable);
+    let actual_balance = token::Client::new(env, asset).balance(&env.current_contract_address());
+    AssetSolvency {
+        tracked_liability,
+        actual_balance,
+        surplus: actual_balance.saturating_sub(tracked_liability),
+    }
+}
+
+fn execute_buy(
+    env: &Env,
+    buyer: &Address,
+    prompt_id: u64,
+    asset: &Address,
+    referrer: &Option<Address>,
+    payment_amount_stroops: i128,
+    voucher: Option<Bytes>,
+    kind: AcquisitionKind,
+) -> Result<(), Error> {
+    let prompt = Storage::require_prompt(env, prompt_id)?;
+    check_direct_purchase(env, buyer, &prompt)?;
+
+    let (mut required_price, conversion) = listing_price(env, &prompt, asset)?;
+    if let Some(code) = voucher {
+        let hashed_raw = env.crypto().sha256(&code);
+        let hashed = BytesN::from_array(env, &hashed_raw.to_array());
+        if let Some(discount_bps) = Storage::get_voucher(env, prompt_id, &hashed) {
+            let discount_amount = required_price
+                .checked_mul(discount_bps as i128)
+                .ok_or(Error::ArithmeticOverflow)?
+                / MAX_BPS as i128;
+            required_price = required_price
+                .checked_sub(discount_amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            Storage::remove_voucher(env, prompt_id, &hashed);
+        } else {
+            return Err(Error::InvalidVoucher);
+        }
+    }
+
+    ensure(
+        payment_amount_stroops >= required_price,
+        Error::InvalidPaymentAmount,
+    )?;
+
+    check_referrer(buyer, &prompt, referrer)?;
+
+    execute_buy_with_required_price(
+        env,
+        buyer,
+        prompt_id,
+        asset,
+        referrer,
+        payment_amount_stroops,
+        required_price,
+        conversion,
+        kind,
+    )
+}
+
+/// Eligibility checks shared by every direct-purchase path: voucher,
+/// signed-discount and quote-bound checkout.
+fn check_direct_purchase(env: &Env, buyer: &Address, prompt: &Prompt) -> Result<(), Error> {
+    let now = env.ledger().timestamp();
+
+    ensure(
+        prompt.status == PromptSaleStatus::Active,
+        Error::PromptInactive,
+    )?;
+    ensure(prompt.creator != *buyer, Error::CreatorCannotBuy)?;
+    ensure(
+        !Storage::has_active_purchase(env, prompt.id, buyer, now),
+        Error::AlreadyPurchased,
+    )?;
+
+    if prompt.expires_at != 0 {
+        ensure(prompt.expires_at >= now, Error::ListingExpired)?;
+    }
+
+    // Fail fast before spending gas on voucher validation; the actual
+    // reservation is (re)computed against a fresh fetch in
+    // `execute_buy_with_required_price`, which is also reachable directly
+    // from `buy_prompt_with_auth`.
+    reserve_supply(prompt.sales_count, prompt.max_supply)?;
+    Ok(())
+}
+
+fn check_referrer(
+    buyer: &Address,
+    prompt: &Prompt,
+    referrer: &Option<Address>,
+) -> Result<(), Error> {
+    if let Some(ref r) = referrer {
+        ensure(
+            r != buyer && r != &prompt.creator,
+            Error::ReferrerCannotBeBuyerOrCreator,
+        )?;
+    }
+    Ok(())
+}
+
+/// Buy execution after all price and voucher validation is done.
+/// Shared between `execute_buy` (legacy vouchers) and `buy_prompt_with_auth`
+/// (signed discount authorizations). `asset` is the accepted asset the
+/// buyer pays in; `conversion` is the oracle rate behind `required_price`,
+/// if any.
+fn execute_buy_with_required_price(
+    env: &Env,
+    buyer: &Address,
+    prompt_id: u64,
+    asset: &Address,
+    referrer: &Option<Address>,
+    payment_amount_stroops: i128,
+    required_price: i128,
+    conversion: Option<OracleConversion>,
+    kind: AcquisitionKind,
+) -> Result<(), Error> {
+    // Free listings are claimed, never bought for nothing.
+    ensure(payment_amount_stroops > 0, Error::InvalidPaymentAmount)?;
+    let mut prompt = Storage::require_prompt(env, prompt_id)?;
+    ensure(
+        !Storage::has_open_auction(env, prompt_id),
+        Error::ListingInAuction,
+    )?;
+    let reserved_sales_count = reserve_supply(prompt.sales_count, prompt.max_supply)?;
+    let pay_what_you_want =
+        *asset == prompt.asset && Storage::get_pay_what_you_want(env, prompt_id).is_some();
+
+    InstanceStorage::set_reentrancy_guard(env)?;
+
+    let this_contract = env.current_contract_address();
+    let asset_client = token::StellarAssetClient::new(env, asset);
+
+    // Route the full payment through the contract so it holds
+    // escrow for dispute refunds (#454).  The buyer must have
+    // approved the contract for at least `payment_amount_stroops`.
+    asset_client.transfer_from(
+        &this_contract,
+        buyer,
+        &this_contract,
+        &payment_amount_stroops,
+    );
+
+    // All funds are held in the contract.  No distribution occurs
+    // here — `settle_purchase` releases them to the creator, fee wallet,
+    // referrer, and split recipients, or a dispute refund returns them
+    // to the buyer.
+    prompt.sales_count = reserved_sales_count;
+    Storage::update_prompt(env, &prompt);
+    let escrow = open_sale_escrow(
+        env,
+        buyer,
+        &prompt,
+        asset,
+        referrer,
+        payment_amount_stroops,
+        conversion,
+        kind,
+    )?;
+    // Escrow was just created Pending — its full amount is now tracked
+    // liability for this asset until settled or refunded (#570).
+    Storage::add_pending_liability(env, &escrow.asset, escrow.amount)?;
+
+    InstanceStorage::clear_reentrancy_guard(env);
+
+    Events::emit_prompt_purchased(
+        env,
+        prompt_id,
+        buyer.clone(),
+        prompt.creator,
+        payment_amount_stroops,
+        referrer.clone(),
+    );
+
+    // Paying above a pay-what-you-want minimum is the price, not a tip.
+    if payment_amount_stroops > required_price && !pay_what_you_want {
+        Events::emit_prompt_tipped(
+            env,
+            prompt_id,
+            buyer.clone(),
+            payment_amount_stroops - required_price,
+        );
+    }
+
+    Ok(())
+}
+
+/// Grant `buyer` a license to `prompt` for `payment_amount_stroops` already
+/// held by the contract, snapshot its payout plan, and record the Pending
+/// escrow as a new settlement. Liability accounting is left to the caller,
+/// since an auction's winning bid is already tracked from the moment it was
+/// escrowed. `asset` is the one the payment was made in, and `conversion`
+/// the oracle rate it was priced at, if any.
+fn open_sale_escrow(
+    env: &Env,
+    buyer: &Address,
+    prompt: &Prompt,
+    asset: &Address,
+    referrer: &Option<Address>,
+    payment_amount_stroops: i128,
+    conversion: Option<OracleConversion>,
+    kind: AcquisitionKind,
+) -> Result<PurchaseEscrow, Error> {
+    let fee_percentage = InstanceStorage::get_fee_percentage(env);
+    ensure(fee_percentage <= MAX_BPS, Error::InvalidFeePercentage)?;
+
+    let fee_amount = payment_amount_stroops
+        .checked_mul(fee_percentage as i128)
+        .ok_or(Error::ArithmeticOverflow)?
+        / MAX_BPS as i128;
+
+    let referral_percentage = InstanceStorage::get_referral_percentage(env);
+    let referral_amount = if referrer.is_some() {
+        payment_amount_stroops
+            .checked_mul(referral_percentage as i128)
+            .ok_or(Error::ArithmeticOverflow)?
+            / MAX_BPS as i128
+    } else {
+        0
+    };
+
+    // Snapshot the complete payout plan at purchase time (#562).
+    let mut payout_splits: Vec<super::types::PayoutSplit> = Vec::new(env);
+    let mut split_total: i128 = 0;
+    for i in 0..prompt.splits.len() {
+        let split = prompt.splits.get(i).unwrap();
+        let split_amount = payment_amount_stroops
+            .checked_mul(split.bps as i128)
+            .ok_or(Error::ArithmeticOverflow)?
+            / MAX_BPS as i128;
+        split_total = split_total
+            .checked_add(split_amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        if split_amount > 0 {
+            payout_splits.push_back(super::types::PayoutSplit {
+                recipient: split.recipient.clone(),
+                amount: split_amount,
+            });
+        }
+    }
+
+    let total_deductions = fee_amount
+        .checked_add(referral_amount)
+        .and_then(|deductions| deductions.checked_add(split_total))
+        .ok_or(Error::ArithmeticOverflow)?;
+    let creator_amount = payment_amount_stroops
+        .checked_sub(total_deductions)
+        .ok_or(Error::ArithmeticOverflow)?;
+    ensure(creator_amount >= 0, Error::InvalidSplits)?;
+
+    grant_license(
+        env,
+        prompt,
+        buyer,
+        payment_amount_stroops,
+        MAX_ACCESS_EXPIRY,
+    )?;
+
+    // Escrow is created as Pending — the creator's share is held in
+    // the contract until `settle_purchase` is called (#454).
+    let now = env.ledger().timestamp();
+    let fee_wallet = InstanceStorage::get_fee_wallet(env).ok_or(Error::FeeWalletNotSet)?;
+    let payout_plan = super::types::PayoutPlan {
+        creator: prompt.creator.clone(),
+        fee_wallet,
+        fee_amount,
+        referrer: referrer.clone(),
+        referral_amount,
+        splits: payout_splits,
+        creator_amount,
+        conversion: conversion.map_or(PriceConversion::Listed, PriceConversion::Oracle),
+    };
+
+    let escrow = PurchaseEscrow {
+        refunded_amount: 0,
+        prompt_id: prompt.id,
+        buyer: buyer.clone(),
+        amount: payment_amount_stroops,
+        asset: asset.clone(),
+        referrer: referrer.clone(),
+        status: SettlementStatus::Pending,
+        created_at: now,
+        settled_at: 0,
+        dispute_deadline: now
+            .checked_add(listing_dispute_window(env, prompt.id))
+            .ok_or(Error::ArithmeticOverflow)?,
+        creator_amount,
+        fee_amount,
+        referral_amount,
+        payout_plan,
+    };
+    record_acquisition(
+        env,
+        kind,
+        prompt.id as u128,
+        &escrow,
+        &Vec::from_array(env, [prompt.id]),
+    )?;
+    Ok(escrow)
+}
+
+/// Dispute window for a product sale: direct sales get the governed
+/// default, while leases, bundles and passes follow the governed policy and
+/// default to none. A policy window is held to the current bounds in case
+/// governance narrowed them after it was set.
+fn product_dispute_window(env: &Env, kind: AcquisitionKind) -> u64 {
+    let config = dispute_window_config(env);
+    match kind {
+        AcquisitionKind::Lease | AcquisitionKind::Bundle | AcquisitionKind::AccessPass => {
+            InstanceStorage::get_dispute_window_policy(env, kind)
+                .map(|window_secs| window_secs.clamp(config.min_secs, config.max_secs))
+                .unwrap_or(0)
+        }
+        _ => config.default_secs,
+    }
+}
+
+fn dispute_window_config(env: &Env) -> DisputeWindowConfig {
+    InstanceStorage::get_dispute_window_config(env).unwrap_or(DisputeWindowConfig {
+        default_secs: DISPUTE_WINDOW_SECS,
+        min_secs: DEFAULT_MIN_DISPUTE_WINDOW_SECS,
+        max_secs: DEFAULT_MAX_DISPUTE_WINDOW_SECS,
+    })
+}
+
+/// The creator's window for a listing, held to the current bounds in case
+/// governance narrowed them after it was chosen, or else the default.
+fn listing_dispute_window(env: &Env, prompt_id: u64) -> u64 {
+    let config = dispute_window_config(env);
+    match Storage::get_listing_dispute_window(env, prompt_id) {
+        Some(window_secs) => window_secs.clamp(config.min_secs, config.max_secs),
+        None => config.default_secs,
+    }
+}
+
+/// Escrow for a lease, bundle or pass payment the contract already holds.
+/// With no dispute window under the product's policy the plan is paid out
+/// at once and the escrow is recorded Settled; otherwise the funds stay
+/// Pending until `settle_purchase`, exactly like a direct sale.
+fn open_product_escrow(
+    env: &Env,
+    kind: AcquisitionKind,
+    prompt_id: u64,
+    buyer: &Address,
+    amount: i128,
+    asset: &Address,
+    payout_plan: super::types::PayoutPlan,
+) -> Result<PurchaseEscrow, Error> {
+    let now = env.ledger().timestamp();
+    let window = product_dispute_window(env, kind);
+    let status = if window == 0 {
+        pay_out_plan(env, asset, &payout_plan)?;
+        SettlementStatus::Settled
+    } else {
+        Storage::add_pending_liability(env, asset, amount)?;
+        SettlementStatus::Pending
+    };
+    Ok(PurchaseEscrow {
+        prompt_id,
+        buyer: buyer.clone(),
+        amount,
+        refunded_amount: 0,
+        asset: asset.clone(),
+        referrer: None,
+        settled_at: if status == SettlementStatus::Settled {
+            now
+        } else {
+            0
+        },
+        status,
+        created_at: now,
+        dispute_deadline: now.checked_add(window).ok_or(Error::ArithmeticOverflow)?,
+        creator_amount: payout_plan.creator_amount,
+        fee_amount: payout_plan.fee_amount,
+        referral_amount: 0,
+        payout_plan,
+    })
+}
+
+fn execute_lease(
+    env: &Env,
+    buyer: &Address,
+    prompt_id: u64,
+    lease_duration_secs: u64,
+) -> Result<(), Error> {
+    let mut prompt = Storage::require_prompt(env, prompt_id)?;
+    let now = env.ledger().timestamp();
+
+    ensure(prompt.creator != *buyer, Error::CreatorCannotBuy)?;
+    ensure(
+        !Storage::has_active_purchase(env, prompt_id, buyer, now),
+        Error::AlreadyPurchased,
+    )?;
+    check_lease_listing(env, &prompt)?;
+    let lease_price = lease_price_for(env, &prompt, lease_duration_secs)?;
+
+    // Leases consume the same supply pool as direct sales (#538).
+    let reserved_sales_count = reserve_supply(prompt.sales_count, prompt.max_supply)?;
+
+    InstanceStorage::set_reentrancy_guard(env)?;
+
+    prompt.sales_count = reserved_sales_count;
+    let expires_at = now
+        .checked_add(lease_duration_secs)
+        .ok_or(Error::ArithmeticOverflow)?;
+    Storage::update_prompt(env, &prompt);
+    collect_lease_payment(env, buyer, &prompt, lease_price, expires_at)?;
+    Storage::save_lease(
+        env,
+        &Lease {
+            prompt_id,
+            lessee: buyer.clone(),
+            paid_total: lease_price,
+            started_at: now,
+            expires_at,
+        },
+    );
+    InstanceStorage::clear_reentrancy_guard(env);
+    Events::emit_prompt_purchased(
+        env,
+        prompt_id,
+        buyer.clone(),
+        prompt.creator,
+        lease_price,
+        None,
+    );
+    Ok(())
+}
+
+/// Listing-side checks shared by new leases and renewals.
+fn check_lease_listing(env: &Env, prompt: &Prompt) -> Result<(), Error> {
+    ensure(
+        prompt.status == PromptSaleStatus::Active,
+        Error::PromptInactive,
+    )?;
+    if prompt.expires_at != 0 {
+        ensure(
+            prompt.expires_at >= env.ledger().timestamp(),
+            Error::ListingExpired,
+        )?;
+    }
+    ensure(
+        !Storage::has_open_auction(env, prompt.id),
+        Error::ListingInAuction,
+    )
+}
+
+/// Take `lease_price` from `buyer` and (re)grant the license until
+/// `expires_at` under a Lease acquisition, held or paid out per the lease
+/// dispute window policy.
+fn collect_lease_payment(
+    env: &Env,
+    buyer: &Address,
+    prompt: &Prompt,
+    lease_price: i128,
+    expires_at: u64,
+) -> Result<u128, Error> {
+    let fee_wallet = InstanceStorage::get_fee_wallet(env).ok_or(Error::FeeWalletNotSet)?;
+    let this_contract = env.current_contract_address();
+    let fee_percentage = InstanceStorage::get_fee_percentage(env);
+    ensure(fee_percentage <= MAX_BPS, Error::InvalidFeePercentage)?;
+
+    let fee_amount = lease_price
+        .checked_mul(fee_percentage as i128)
+        .ok_or(Error::ArithmeticOverflow)?
+        / MAX_BPS as i128;
+    let seller_amount = lease_price
+        .checked_sub(fee_amount)
+        .ok_or(Error::ArithmeticOverflow)?;
+
+    // Route the full lease payment through the contract so it holds
+    // escrow for dispute refunds (#564). The buyer must have
+    // approved the contract for at least `lease_price`.
+    let asset_client = token::StellarAssetClient::new(env, &prompt.asset);
+    asset_client.transfer_from(&this_contract, buyer, &this_contract, &lease_price);
+
+    grant_license(env, prompt, buyer, lease_price, expires_at)?;
+    let payout_plan = super::types::PayoutPlan {
+        creator: prompt.creator.clone(),
+        fee_wallet: fee_wallet.clone(),
+        fee_amount,
+        referrer: None,
+        referral_amount: 0,
+        splits: Vec::new(env),
+        creator_amount: seller_amount,
+        conversion: PriceConversion::Listed,
+    };
+    let escrow = open_product_escrow(
+        env,
+        AcquisitionKind::Lease,
+        prompt.id,
+        buyer,
+        lease_price,
+        &prompt.asset,
+        payout_plan,
+    )?;
+    record_acquisition(
+        env,
+        AcquisitionKind::Lease,
+        prompt.id as u128,
+        &escrow,
+        &Vec::from_array(env, [prompt.id]),
+    )
+}
+
+/// A lease is priced by the listing's `LeaseTerms` when set, and otherwise
+/// at a fixed fraction of the purchase price for any duration.
+fn lease_price_for(env: &Env, prompt: &Prompt, lease_duration_secs: u64) -> Result<i128, Error> {
+    ensure(lease_duration_secs > 0, Error::InvalidPrice)?;
+    let lease_price = match Storage::get_lease_terms(env, prompt.id) {
+        Some(terms) => {
+            ensure(terms.enabled, Error::LeaseDisabled)?;
+            ensure(
+                lease_duration_secs >= terms.min_duration_secs
+                    && lease_duration_secs <= terms.max_duration_secs,
+                Error::InvalidLeaseDuration,
+            )?;
+            let periods = lease_duration_secs.div_ceil(terms.period_secs);
+            terms
+                .price_per_period
+                .checked_mul(periods as i128)
+                .ok_or(Error::ArithmeticOverflow)?
+        }
+        None => {
+            prompt
+                .price_stroops
+                .checked_mul(LEASE_PRICE_BPS as i128)
+                .ok_or(Error::ArithmeticOverflow)?
+                / MAX_BPS as i128
+        }
+    };
+    ensure(lease_price > 0, Error::InvalidPrice)?;
+    Ok(lease_price)
+}
+
+/// Unused share of `lessee`'s active lease payments, pro rata over the
+/// remaining lease time and capped at the purchase price.
+fn lease_conversion_credit(
+    env: &Env,
+    lessee: &Address,
+    prompt_id: u64,
+    price: i128,
+) -> Result<i128, Error> {
+    let now = env.ledger().timestamp();
+    let lease = Storage::get_lease(env, prompt_id, lessee).ok_or(Error::LeaseNotFound)?;
+    let purchase = Storage::get_purchase(env, prompt_id, lessee).ok_or(Error::LeaseNotFound)?;
+    ensure(
+        purchase.expires_at == lease.expires_at && lease.expires_at > now,
+        Error::LeaseNotFound,
+    )?;
+
+    let remaining = (lease.expires_at - now) as i128;
+    let term = (lease.expires_at - lease.started_at) as i128;
+    let credit = lease
+        .paid_total
+        .checked_mul(remaining)
+        .ok_or(Error::ArithmeticOverflow)?
+        / term;
+    Ok(credit.min(price))
+}
+
+fn execute_buy_bundle(
+    env: &Env,
+    buyer: &Address,
+    bundle_id: u128,
+    payment_amount_stroops: i128,
+) -> Result<(), Error> {
+    let now = env.ledger().timestamp();
+    let mut bundle = Storage::require_bundle(env, bundle_id)?;
+
+    ensure(bundle.active, Error::PromptInactive)?;
+    ensure(bundle.creator != *buyer, Error::CreatorCannotBuy)?;
+    ensure(
+        payment_amount_stroops >= bundle.price_stroops,
+        Error::InvalidPaymentAmount,
+    )?;
+    if bundle.expires_at != 0 {
+        ensure(bundle.expires_at >= now, Error::ListingExpired)?;
+    }
+
+    let mut prompts = Vec::new(env);
+    let mut needs_access = false;
+    for index in 0..bundle.prompt_ids.len() {
+        let mut prompt = Storage::require_prompt(env, bundle.prompt_ids.get(index).unwrap())?;
+        ensure(prompt.creator == bundle.creator, Error::Unauthorized)?;
+        ensure(
+            prompt.status == PromptSaleStatus::Active,
+            Error::PromptInactive,
+        )?;
+        price_in(&prompt, &bundle.asset)?;
+        if prompt.expires_at != 0 {
+            ensure(prompt.expires_at >= now, Error::ListingExpired)?;
+        }
+        if !Storage::has_active_purchase(env, prompt.id, buyer, now) {
+            ensure(
+                !Storage::has_open_auction(env, prompt.id),
+                Error::ListingInAuction,
+            )?;
+            needs_access = true;
+            prompt.sales_count = reserve_supply(prompt.sales_count, prompt.max_supply)?;
+        }
+        prompts.push_back(prompt);
+    }
+    ensure(needs_access, Error::AlreadyPurchased)?;
+
+    InstanceStorage::set_reentrancy_guard(env)?;
+
+    // Route the full payment through the contract so it holds
+    // escrow for dispute refunds (#454, #563). The buyer must have
+    // approved the contract for at least `payment_amount_stroops`.
+    let this_contract = env.current_contract_address();
+    let asset_client = token::StellarAssetClient::new(env, &bundle.asset);
+    asset_client.transfer_from(
+        &this_contract,
+        buyer,
+        &this_contract,
+        &payment_amount_stroops,
+    );
+
+    // Calculate all allocations from the single payment amount.
+    let fee_percentage = InstanceStorage::get_fee_percentage(env);
+    ensure(fee_percentage <= MAX_BPS, Error::InvalidFeePercentage)?;
+
+    let fee_amount = payment_amount_stroops
+        .checked_mul(fee_percentage as i128)
+        .ok_or(Error::ArithmeticOverflow)?
+        / MAX_BPS as i128;
+
+    // Snapshot collaborator splits for every newly granted prompt.
+    let mut split_total: i128 = 0;
+    let mut payout_splits: Vec<super::types::PayoutSplit> = Vec::new(env);
+    for index in 0..prompts.len() {
+        let prompt = prompts.get(index).unwrap();
+        if !Storage::has_active_purchase(env, prompt.id, buyer, now) {
+            for split_idx in 0..prompt.splits.len() {
+                let split = prompt.splits.get(split_idx).unwrap();
+                let split_amount = payment_amount_stroops
+                    .checked_mul(split.bps as i128)
+                    .ok_or(Error::ArithmeticOverflow)?
+                    / MAX_BPS as i128;
+                split_total = split_total
+                    .checked_add(split_amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                if split_amount > 0 {
+                    payout_splits.push_back(super::types::PayoutSplit {
+                        recipient: split.recipient.clone(),
+                        amount: split_amount,
+                    });
+                }
+            }
+        }
+    }
+
+    // Creator receives the remainder after all deductions
+    let creator_amount = payment_amount_stroops
+        .checked_sub(fee_amount)
+        .ok_or(Error::ArithmeticOverflow)?
+        .checked_sub(split_total)
+        .ok_or(Error::ArithmeticOverflow)?;
+    ensure(creator_amount >= 0, Error::InvalidSplits)?;
+
+    // Update prompt sales counts and grant access for newly purchased prompts
+    let mut granted = Vec::new(env);
+    for index in 0..prompts.len() {
+        let prompt = prompts.get(index).unwrap();
+        if !Storage::has_active_purchase(env, prompt.id, buyer, now) {
+            granted.push_back(prompt.id);
+            Storage::update_prompt(env, &prompt);
+            grant_license(
+                env,
+                &prompt,
+                buyer,
+                payment_amount_stroops
+                    .checked_div(prompts.len() as i128)
+                    .ok_or(Error::InvalidPaymentAmount)?,
+                MAX_ACCESS_EXPIRY,
+            )?;
+        }
+    }
+
+    bundle.sales_count = bundle
+        .sales_count
+        .checked_add(1)
+        .ok_or(Error::ArithmeticOverflow)?;
+    Storage::update_bundle(env, &bundle);
+
+    // Create escrow with payout plan for unified dispute/settlement (#564)
+    let fee_wallet = InstanceStorage::get_fee_wallet(env).ok_or(Error::FeeWalletNotSet)?;
+    let payout_plan = super::types::PayoutPlan {
+        creator: bundle.creator.clone(),
+        fee_wallet: fee_wallet.clone(),
+        fee_amount,
+        referrer: None,
+        referral_amount: 0,
+        splits: payout_splits,
+        creator_amount,
+        conversion: PriceConversion::Listed,
+    };
+    let escrow = open_product_escrow(
+        env,
+        AcquisitionKind::Bundle,
+        0, // Bundles don't have a single prompt ID
+        buyer,
+        payment_amount_stroops,
+        &bundle.asset,
+        payout_plan,
+    )?;
+    record_acquisition(env, AcquisitionKind::Bundle, bundle_id, &escrow, &granted)?;
+
+    InstanceStorage::clear_reentrancy_guard(env);
+
+    Events::emit_bundle_purchased(
+        env,
+        bundle_id,
+        buyer.clone(),
+        bundle.creator,
+        payment_amount_stroops,
+        bundle.prompt_ids,
+    );
+    Ok(())
+}
+
+fn end_pass_subscription(env: &Env, buyer: &Address, pass_id: u128, reason: SubscriptionEndReason) {
+    Storage::remove_pass_subscription(env, buyer, pass_id);
+    Events::emit_pass_subscription_ended(env, pass_id, buyer.clone(), reason);
+}
+
+fn execute_buy_access_pass(
+    env: &Env,
+    buyer: &Address,
+    pass_id: u128,
+    payment_amount_stroops: i128,
+) -> Result<(), Error> {
+    let mut access_pass = Storage::require_access_pass(env, pass_id)?;
+    let now = env.ledger().timestamp();
+
+    ensure(
+        access_pass.status == PromptSaleStatus::Active,
+        Error::PromptInactive,
+    )?;
+    ensure(access_pass.creator != *buyer, Error::CreatorCannotBuy)?;
+    ensure(
+        payment_amount_stroops >= access_pass.price_stroops,
+        Error::InvalidPaymentAmount,
+    )?;
+
+    // Each purchase reserves one unit of this pass's own supply, on top
+    // of whatever other passes this creator has sold (#538).
+    access_pass.sales_count = reserve_pass_supply(access_pass.sales_count, access_pass.max_supply)?;
+
+    InstanceStorage::set_reentrancy_guard(env)?;
+
+    // Route the full payment through the contract so it holds
+    // escrow for dispute refunds (#564). The buyer must have
+    // approved the contract for at least `payment_amount_stroops`.
+    let this_contract = env.current_contract_address();
+    let asset_client = token::StellarAssetClient::new(env, &access_pass.asset);
+    asset_client.transfer_from(
+        &this_contract,
+        buyer,
+        &this_contract,
+        &payment_amount_stroops,
+    );
+
+    // Calculate allocations from the single payment
+    let fee_percentage = InstanceStorage::get_fee_percentage(env);
+    ensure(fee_percentage <= MAX_BPS, Error::InvalidFeePercentage)?;
+
+    let fee_amount = payment_amount_stroops
+        .checked_mul(fee_percentage as i128)
+        .ok_or(Error::ArithmeticOverflow)?
+        / MAX_BPS as i128;
+    let creator_amount = payment_amount_stroops
+        .checked_sub(fee_amount)
+        .ok_or(Error::ArithmeticOverflow)?;
+
+    let fee_wallet = InstanceStorage::get_fee_wallet(env).ok_or(Error::FeeWalletNotSet)?;
+
+    // Renewing before the current grant expires extends it forward from
+    // the existing expiry rather than from `now`, so the buyer never
+    // loses paid-for time or is double-charged for an overlapping
+    // period (#539).
+    let existing = Storage::get_catalog_pass_purchase(env, &access_pass.creator, buyer);
+    let base = existing
+        .map(|p| {
+            if p.expires_at > now {
+                p.expires_at
+            } else {
+                now
+            }
+        })
+        .unwrap_or(now);
+    let expires_at = base
+        .checked_add(access_pass.duration_secs)
+        .ok_or(Error::ArithmeticOverflow)?;
+    let catalog_pass = CatalogPassPurchase {
+        creator: access_pass.creator.clone(),
+        buyer: buyer.clone(),
+        pass_id,
+        expires_at,
+    };
+    Storage::save_catalog_pass_purchase(env, &catalog_pass);
+
+    // Create escrow with payout plan for unified dispute/settlement (#564).
+    // sales_count was already advanced by reserve_pass_supply above (#538)
+    // — do not increment it again here.
+    let payout_plan = super::types::PayoutPlan {
+        creator: access_pass.creator.clone(),
+        fee_wallet: fee_wallet.clone(),
+        fee_amount,
+        referrer: None,
+        referral_amount: 0,
+        splits: Vec::new(env),
+        creator_amount,
+        conversion: PriceConversion::Listed,
+    };
+    let escrow = open_product_escrow(
+        env,
+        AcquisitionKind::AccessPass,
+        0, // Access passes don't have prompt IDs
+        buyer,
+        payment_amount_stroops,
+        &access_pass.asset,
+        payout_plan,
+    )?;
+    record_acquisition(
+        env,
+        AcquisitionKind::AccessPass,
+        pass_id,
+        &escrow,
+        &Vec::new(env),
+    )?;
+    Storage::update_access_pass(env, &access_pass);
+    InstanceStorage::clear_reentrancy_guard(env);
+    Events::emit_access_pass_purchased(
+        env,
+        pass_id,
+        buyer.clone(),
+        access_pass.creator,
+        expires_at,
+    );
+    Ok(())
+}
+
+// ─── Two-phase governance (#569) ───────────────────────────────────────────
+//
+// High-risk configuration changes are proposed, observable on-chain for at
+// least the governance delay, and only then executable within a bounded
+// window. Every proposal pins the governed configuration it was written
+// against, so executing one proposal invalidates any other written against
+// the prior state.
+
+fn governance_delay(env: &Env) -> u32 {
+    InstanceStorage::get_governance_delay(env).unwrap_or(DEFAULT_GOVERNANCE_DELAY_LEDGERS)
+}
+
+/// Hash over every setting a governance action can change (the WASM hash
+/// is not readable on-chain and is excluded).
+fn governance_state_hash(env: &Env) -> BytesN<32> {
+    let mut buf: Vec<Val> = Vec::new(env);
+    buf.push_back(InstanceStorage::get_fee_wallet(env).into_val(env));
+    buf.push_back((InstanceStorage::get_fee_percentage(env) as u128).into_val(env));
+    buf.push_back((InstanceStorage::get_referral_percentage(env) as u128).into_val(env));
+    buf.push_back((governance_delay(env) as u128).into_val(env));
+    buf.push_back(dispute_window_config(env).into_val(env));
+    for kind in [
+        AcquisitionKind::Lease,
+        AcquisitionKind::Bundle,
+        AcquisitionKind::AccessPass,
+    ] {
+        buf.push_back(InstanceStorage::get_dispute_window_policy(env, kind).into_val(env));
+    }
+    env.crypto().sha256(&buf.to_xdr(env)).to_bytes()
+}
+
+/// Reject actions that could never execute, at proposal time rather than
+/// after the observation window.
+fn validate_governance_action(env: &Env, action: &GovernanceAction) -> Result<(), Error> {
+    match action {
+        GovernanceAction::SetFeePercentage(fee) => {
+            ensure(*fee <= MAX_PLATFORM_FEE, Error::FeeExceedsMaximum)
+        }
+        GovernanceAction::SetReferralPercentage(percentage) => {
+            ensure(*percentage <= MAX_BPS, Error::InvalidReferralPercentage)
+        }
+        GovernanceAction::SetGovernanceDelay(delay) => ensure(
+            (MIN_GOVERNANCE_DELAY_LEDGERS..=MAX_GOVERNANCE_DELAY_LEDGERS).contains(delay),
+            Error::InvalidGovernanceDelay,
+        ),
+        GovernanceAction::SetDisputeWindow(config) => ensure(
+            config.min_secs > 0
+                && config.min_secs <= config.default_secs
+                && config.default_secs <= config.max_secs
+                && config.max_secs <= MAX_DISPUTE_WINDOW_SECS,
+            Error::InvalidDisputeWindow,
+        ),
+        GovernanceAction::SetDisputeWindowPolicy(kind, window_secs) => {
+            let config = dispute_window_config(env);
+            ensure(
+                matches!(
+                    kind,
+                    AcquisitionKind::Lease | AcquisitionKind::Bundle | AcquisitionKind::AccessPass
+                ) && (*window_secs == 0
+                    || (config.min_secs..=config.max_secs).contains(window_secs)),
+                Error::InvalidDisputeWindow,
+            )
+        }
+        GovernanceAction::Upgrade(_) | GovernanceAction::SetFeeWallet(_) => Ok(()),
+    }
+}
+
+fn queue_governance(
+    env: &Env,
+    proposer: Address,
+    action: GovernanceAction,
+    nonce: BytesN<32>,
+) -> Result<BytesN<32>, Error> {
+    validate_governance_action(env, &action)?;
+    ensure(
+        !Storage::is_governance_nonce_consumed(env, &nonce),
+        Error::GovernanceNonceConsumed,
+    )?;
+
+    let now = env.ledger().sequence();
+    let executable_at_ledger = now
+        .checked_add(governance_delay(env))
+        .ok_or(Error::ArithmeticOverflow)?;
+    let expiry_ledger = executable_at_ledger
+        .checked_add(GOVERNANCE_EXECUTION_WINDOW_LEDGERS)
+        .ok_or(Error::ArithmeticOverflow)?;
+    let proposal = GovernanceProposal {
+        action,
+        proposer,
+        proposed_at_ledger: now,
+        executable_at_ledger,
+        expiry_ledger,
+        expected_state_hash: governance_state_hash(env),
+        nonce,
+    };
+    let proposal_hash = proposal.hash(env);
+    ensure(
+        Storage::get_governance_proposal(env, &proposal_hash).is_none(),
+        Error::GovernanceProposalExists,
+    )?;
+
+    // Drop proposals whose execution window has already closed so the
+    // pending list stays bounded by live proposals.
+    let mut live = Storage::prune_expired_governance_proposals(env, now);
+    live.push_back(proposal_hash.clone());
+    Storage::save_pending_governance_proposals(env, &live);
+
+    Storage::consume_governance_nonce(env, &proposal.nonce);
+    Storage::save_governance_proposal(env, &proposal_hash, &proposal);
+    Events::emit_governance_proposed(
+        env,
+        proposal_hash.clone(),
+        proposal.action,
+        proposal.proposer,
+        executable_at_ledger,
+        expiry_ledger,
+    );
+    Ok(proposal_hash)
+}
+
+/// The legacy setters carry no nonce; derive one from the action and a
+/// monotonic counter so each call queues a distinct proposal, even when two
+/// calls land in the same ledger.
+fn queue_legacy_governance(
+    env: &Env,
+    proposer: Address,
+    action: GovernanceAction,
+) -> Result<(), Error> {
+    let mut buf: Vec<Val> = Vec::new(env);
+    buf.push_back(action.clone().into_val(env));
+    buf.push_back(InstanceStorage::next_legacy_governance_nonce(env)?.into_val(env));
+    let nonce = env.crypto().sha256(&buf.to_xdr(env)).to_bytes();
+    queue_governance(env, proposer, action, nonce)?;
+    Ok(())
+}
+
+fn apply_governance_action(
+    env: &Env,
+    executor: &Address,
+    action: &GovernanceAction,
+) -> Result<(), Error> {
+    match action {
+        GovernanceAction::Upgrade(new_wasm_hash) => {
+            env.deployer()
+                .update_current_contract_wasm(new_wasm_hash.clone());
+            env.storage().instance().extend_ttl(
+                super::storage::PERSISTENT_LIFETIME_THRESHOLD,
+                super::storage::PERSISTENT_BUMP_AMOUNT,
+            );
+            Storage::extend_all_ttl(env);
+        }
+        GovernanceAction::SetFeeWallet(new_fee_wallet) => {
+            InstanceStorage::set_fee_wallet(env, new_fee_wallet);
+            Events::emit_fee_wallet_updated(env, new_fee_wallet.clone());
+        }
+        GovernanceAction::SetFeePercentage(new_fee) => {
+            set_platform_fee_internal(env, executor.clone(), *new_fee)?;
+        }
+        GovernanceAction::SetReferralPercentage(percentage) => {
+            InstanceStorage::set_referral_percentage(env, *percentage);
+        }
+        GovernanceAction::SetGovernanceDelay(delay) => {
+            InstanceStorage::set_governance_delay(env, *delay);
+        }
+        GovernanceAction::SetDisputeWindow(config) => {
+            InstanceStorage::set_dispute_window_config(env, config);
+            Events::emit_dispute_window_config_updated(env, config.clone());
+        }
+        GovernanceAction::SetDisputeWindowPolicy(kind, window_secs) => {
+            InstanceStorage::set_dispute_window_policy(env, kind.clone(), *window_secs);
+            Events::emit_dispute_window_policy_updated(env, kind.clone(), *window_secs);
+        }
+    }
+    Ok(())
+}
+
+// ─── License resale (#568) ─────────────────────────────────────────────────
+//
+// Resale moves an already-reserved unit between owners rather than minting a
+// new one, so it never touches supply. Both the signed order flow and the
+// deprecated `transfer_license` alias settle through `execute_license_resale`.
+
+/// Split a resale price into `(royalty, seller_proceeds)`.
+fn split_resale_price(price: i128, royalty_bps: u32) -> Result<(i128, i128), Error> {
+    let royalty_amount = price
+        .checked_mul(royalty_bps as i128)
+        .ok_or(Error::ArithmeticOverflow)?
+        / MAX_BPS as i128;
+    let seller_amount = price
+        .checked_sub(royalty_amount)
+        .ok_or(Error::ArithmeticOverflow)?;
+    Ok((royalty_amount, seller_amount))
+}
+
+/// Divide a resale royalty between the listing's royalty split recipients,
+/// returning the creator's remainder and the non-zero collaborator shares.
+fn royalty_payouts(
+    env: &Env,
+    prompt: &Prompt,
+    royalty_amount: i128,
+) -> Result<(i128, Vec<super::types::PayoutSplit>), Error> {
+    let mut splits = Vec::new(env);
+    let mut creator_amount = royalty_amount;
+    for split in prompt.royalty_splits.iter() {
+        let amount = royalty_amount
+            .checked_mul(split.bps as i128)
+            .ok_or(Error::ArithmeticOverflow)?
+            / MAX_BPS as i128;
+        if amount > 0 {
+            creator_amount = creator_amount
+                .checked_sub(amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            splits.push_back(super::types::PayoutSplit {
+                recipient: split.recipient,
+                amount,
+            });
+        }
+    }
+    Ok((creator_amount, splits))
+}
+
+fn require_open_resale_order(order: &ResaleOrder) -> Result<(), Error> {
+    match order.status {
+        ResaleOrderStatus::Open => Ok(()),
+        ResaleOrderStatus::Cancelled => Err(Error::ResaleOrderCancelled),
+        ResaleOrderStatus::Filled => Err(Error::ResaleOrderFilled),
+    }
+}
+
+/// The seller must still hold an unexpired license backed by exactly the
+/// settlement named in the order.
+fn require_resale_ownership(env: &Env, order: &ResaleOrder) -> Result<(), Error> {
+    let now = env.ledger().timestamp();
+    let held = Storage::get_purchase(env, order.prompt_id, &order.seller)
+        .map(|purchase| purchase.owner == order.seller && purchase.expires_at >= now)
+        .unwrap_or(false);
+    ensure(held, Error::ResaleOrderOwnershipChanged)?;
+    ensure(
+        Storage::resolve_entitlement_settlement(env, order.prompt_id, &order.seller)
+            == Some(order.settlement_id),
+        Error::ResaleOrderOwnershipChanged,
+    )
+}
+
+/// Checks shared by every path that moves a license between owners: the
+/// listing must allow resale, the license must be a live non-lease one, the
+/// recipient must not already hold it, and a purchase under dispute cannot
+/// change hands until the dispute resolves.
+fn check_license_move(
+    env: &Env,
+    from: &Address,
+    to: &Address,
+    prompt_id: u64,
+) -> Result<(Prompt, Purchase), Error> {
+    let prompt = Storage::require_prompt(env, prompt_id)?;
+    ensure(prompt.resale_allowed, Error::ResaleNotAllowed)?;
+    let now = env.ledger().timestamp();
+    let purchase = Storage::require_purchase(env, prompt_id, from)?;
+    ensure(purchase.expires_at >= now, Error::LicenseNotTransferable)?;
+    let leased = Storage::get_lease(env, prompt_id, from)
+        .map(|lease| lease.expires_at >= now)
+        .unwrap_or(false);
+    ensure(!leased, Error::LicenseNotTransferable)?;
+    ensure(
+        !Storage::has_active_purchase(env, prompt_id, to, now),
+        Error::AlreadyPurchased,
+    )?;
+    if let Some(dispute) = Storage::get_dispute(env, prompt_id, from) {
+        ensure(
+            dispute.status != DisputeStatus::Open,
+            Error::DisputeAlreadyOpen,
+        )?;
+    }
+    // The settlement backing the license — a single purchase, or the
+    // bundle or pass that granted it — must have settled. While it is
+    // pending it can still be disputed and refunded, which would revoke a
+    // license the seller no longer holds.
+    if let Some(settlement_id) = Storage::resolve_entitlement_settlement(env, prompt_id, from) {
+        let escrow =
+            Storage::get_settlement_escrow(env, settlement_id).ok_or(Error::SettlementNotFound)?;
+        ensure(
+            escrow.status != SettlementStatus::Pending,
+            Error::LicenseNotTransferable,
+        )?;
+    }
+    Ok((prompt, purchase))
+}
+
+/// Move `purchase` (and its license token, if any) from `from` to `to` and
+/// record the move as an immediately settled acquisition for the new owner
+/// (#567). The previous owner's entitlement pointer is dropped, so their
+/// earlier settlement can no longer be disputed. Returns the new owner's
+/// settlement ID.
+#[allow(clippy::too_many_arguments)]
+fn move_license(
+    env: &Env,
+    kind: AcquisitionKind,
+    from: &Address,
+    to: &Address,
+    prompt: &Prompt,
+    mut purchase: Purchase,
+    price: i128,
+    royalty_amount: i128,
+    royalty_splits: Vec<super::types::PayoutSplit>,
+) -> Result<u128, Error> {
+    let now = env.ledger().timestamp();
+    let prompt_id = prompt.id;
+    let seller_amount = price
+        .checked_sub(royalty_amount)
+        .ok_or(Error::ArithmeticOverflow)?;
+    let mut creator_amount = royalty_amount;
+    for split in royalty_splits.iter() {
+        creator_amount = creator_amount
+            .checked_sub(split.amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+    }
+
+    // A stale token left behind by the recipient's own expired license
+    // would otherwise be orphaned by the move.
+    if let Some(token_id) = Storage::burn_license_token(env, prompt_id, to) {
+        Events::emit_license_token_burned(env, to.clone(), token_id);
+    }
+
+    Storage::remove_purchase(env, prompt_id, from);
+    Storage::remove_prompt_from_buyer(env, from, prompt_id);
+    purchase.owner = to.clone();
+    purchase.last_transfer_price = price;
+    purchase.transfer_count = purchase
+        .transfer_count
+        .checked_add(1)
+        .ok_or(Error::ArithmeticOverflow)?;
+    purchase.last_transferred_at = now;
+    Storage::save_purchase(env, &purchase);
+    Storage::add_prompt_to_buyer(env, to, prompt_id);
+    if let Some(token_id) = Storage::move_license_token(env, prompt_id, from, to) {
+        Events::emit_license_token_transferred(env, from.clone(), to.clone(), token_id);
+    }
+
+    let fee_wallet = InstanceStorage::get_fee_wallet(env).ok_or(Error::FeeWalletNotSet)?;
+    let mut payout_splits = royalty_splits;
+    if seller_amount > 0 {
+        payout_splits.push_back(super::types::PayoutSplit {
+            recipient: from.clone(),
+            amount: seller_amount,
+        });
+    }
+    let escrow = PurchaseEscrow {
+        refunded_amount: 0,
+        prompt_id,
+        buyer: to.clone(),
+        amount: price,
+        asset: prompt.asset.clone(),
+        referrer: None,
+        status: SettlementStatus::Settled,
+        created_at: now,
+        settled_at: now,
+        dispute_deadline: now,
+        creator_amount,
+        fee_amount: 0,
+        referral_amount: 0,
+        payout_plan: super::types::PayoutPlan {
+            creator: purchase.original_creator.clone(),
+            fee_wallet,
+            fee_amount: 0,
+            referrer: None,
+            referral_amount: 0,
+            splits: payout_splits,
+            creator_amount,
+            conversion: PriceConversion::Listed,
+        },
+    };
+    Storage::remove_entitlement_settlement(env, prompt_id, from);
+    record_acquisition(
+        env,
+        kind,
+        prompt_id as u128,
+        &escrow,
+        &Vec::from_array(env, [prompt_id]),
+    )
+}
+
+/// Collect `resale_price` from `new_buyer`, credit the royalty to the
+/// original creator and the remainder to `seller` as claimable balances,
+/// then move the license. Returns the new owner's settlement ID.
+fn execute_license_resale(
+    env: &Env,
+    seller: &Address,
+    new_buyer: &Address,
+    prompt_id: u64,
+    resale_price: i128,
+    royalty_bps: u32,
+) -> Result<u128, Error> {
+    let (prompt, purchase) = check_license_move(env, seller, new_buyer, prompt_id)?;
+
+    InstanceStorage::set_reentrancy_guard(env)?;
+
+    let this_contract = env.current_contract_address();
+    let asset_client = token::StellarAssetClient::new(env, &prompt.asset);
+    let (royalty_amount, seller_amount) = split_resale_price(resale_price, royalty_bps)?;
+    let (creator_royalty, royalty_splits) = royalty_payouts(env, &prompt, royalty_amount)?;
+
+    asset_client.transfer_from(&this_contract, new_buyer, &this_contract, &resale_price);
+    for split in royalty_splits.iter() {
+        Storage::credit_claimable(env, &split.recipient, &prompt.asset, split.amount)?;
+    }
+    if creator_royalty > 0 {
+        Storage::credit_claimable(
+            env,
+            &purchase.original_creator,
+            &prompt.asset,
+            creator_royalty,
+        )?;
+    }
+    if seller_amount > 0 {
+        Storage::credit_claimable(env, seller, &prompt.asset, seller_amount)?;
+    }
+
+    let original_creator = purchase.original_creator.clone();
+    let settlement_id = move_license(
+        env,
+        AcquisitionKind::ResaleFill,
+        seller,
+        new_buyer,
+        &prompt,
+        purchase,
+        resale_price,
+        royalty_amount,
+        royalty_splits,
+    )?;
+    InstanceStorage::clear_reentrancy_guard(env);
+
+    Events::emit_license_transferred(
+        env,
+        prompt_id,
+        seller.clone(),
+        new_buyer.clone(),
+        original_creator,
+        resale_price,
+        royalty_amount,
+    );
+    Ok(settlement_id)
+}
+
+/// Snapshot of `prompt` as it stands at its current revision, saved before
+/// either `revise_listing` or `revise_content` moves it on (#226).
+fn revision_snapshot(env: &Env, prompt: &Prompt) -> ListingRevisionRecord {
+    ListingRevisionRecord {
+        prompt_id: prompt.id,
+        revision: prompt.revision,
+        title: prompt.title.clone(),
+        category: prompt.category.clone(),
+        preview_text: prompt.preview_text.clone(),
+        image_url: prompt.image_url.clone(),
+        price_stroops: prompt.price_stroops,
+        encrypted_payload: prompt.encrypted_payload.clone(),
+        encryption_iv: prompt.encryption_iv.clone(),
+        wrapped_key: prompt.wrapped_key.clone(),
+        content_hash: prompt.content_hash.clone(),
+        revised_at: env.ledger().timestamp(),
+    }
+}
+
+// ─── Per-buyer key delivery ────────────────────────────────────────────────
+
+/// Delivery deadline for a purchase made at `purchased_at`, if the buyer is
+/// owed a wrapped key and has not yet received one wrapped to their current
+/// registered key. Only keys registered by the time of purchase count, so a
+/// buyer cannot retroactively impose a delivery obligation.
+fn outstanding_key_delivery(
+    env: &Env,
+    prompt_id: u64,
+    buyer: &Address,
+    purchased_at: u64,
+) -> Option<u64> {
+    let registered = Storage::get_buyer_encryption_key(env, buyer)?;
+    if registered.registered_at > purchased_at {
+        return None;
+    }
+    let revision = match (
+        Storage::get_prompt(env, prompt_id),
+        Storage::get_purchase(env, prompt_id, buyer),
+    ) {
+        (Some(prompt), Some(purchase)) => served_revision(env, &prompt, &purchase),
+        (Some(prompt), None) => prompt.revision,
+        _ => return None,
+    };
+    if current_wrapped_key(env, prompt_id, buyer, revision).is_some() {
+        return None;
+    }
+    Some(purchased_at.saturating_add(KEY_DELIVERY_WINDOW_SECS))
+}
+
+/// The key delivered to `buyer` if it is wrapped to their current registered
+/// key and unlocks `revision`. A key left over from a rotated encryption key
+/// or an earlier content revision counts as undelivered.
+fn current_wrapped_key(
+    env: &Env,
+    prompt_id: u64,
+    buyer: &Address,
+    revision: u32,
+) -> Option<BuyerWrappedKey> {
+    let registered = Storage::get_buyer_encryption_key(env, buyer)?;
+    Storage::get_buyer_wrapped_key(env, prompt_id, buyer).filter(|delivered| {
+        delivered.recipient_key == registered.public_key && delivered.revision == revision
+    })
+}
+
+/// The content revision a license holder reads: the purchased one when the
+/// listing pins buyers to it, otherwise the latest.
+fn served_revision(env: &Env, prompt: &Prompt, purchase: &Purchase) -> u32 {
+    if Storage::get_content_access_policy(env, prompt.id) == ContentAccessPolicy::PurchasedRevision
+        && purchase.revision < prompt.revision
+    {
+        purchase.revision
+    } else {
+        prompt.revision
+    }
+}
+
+// ─── Auctions ──────────────────────────────────────────────────────────────
+//
+// Limited-supply listings can be sold by English or Dutch auction instead of
+// at their fixed price. Either way the sale settles through
+// `open_sale_escrow`, so the buyer gets the same Purchase, PayoutPlan and
+// Pending escrow as a direct purchase and disputes work unchanged.
+
+/// Checks shared by both auction kinds before a listing enters auction.
+fn check_auction_listing(env: &Env, creator: &Address, prompt_id: u64) -> Result<(), Error> {
+    let prompt = Storage::require_prompt(env, prompt_id)?;
+    ensure(prompt.creator == *creator, Error::Unauthorized)?;
+    ensure(
+        prompt.status == PromptSaleStatus::Active,
+        Error::PromptInactive,
+    )?;
+    ensure(prompt.max_supply > 0, Error::AuctionRequiresLimitedSupply)?;
+    reserve_supply(prompt.sales_count, prompt.max_supply)?;
+    if prompt.expires_at != 0 {
+        ensure(
+            prompt.expires_at >= env.ledger().timestamp(),
+            Error::ListingExpired,
+        )?;
     }
+    ensure(
+        !Storage::has_open_auction(env, prompt_id),
+        Error::AuctionAlreadyOpen,
+    )
+}
 
-    fn get_asset_solvency(env: Env, asset: Address) -> AssetSolvency {
-        compute_asset_solvency(&env, &asset)
+fn require_open_auction(env: &Env, prompt_id: u64, kind: AuctionKind) -> Result<Auction, Error> {
+    let auction = Storage::require_auction(env, prompt_id)?;
+    ensure(auction.status == AuctionStatus::Open, Error::AuctionNotOpen)?;
+    ensure(auction.kind == kind, Error::AuctionKindMismatch)?;
+    Ok(auction)
+}
+
+/// Current Dutch auction price: linear from `start_price` at `start_ledger`
+/// down to `reserve_price` at `end_ledger`, flat at the floor afterwards.
+fn dutch_auction_price(env: &Env, auction: &Auction) -> Result<i128, Error> {
+    let now = env.ledger().sequence();
+    if now >= auction.end_ledger {
+        return Ok(auction.reserve_price);
     }
+    let elapsed = now.saturating_sub(auction.start_ledger) as i128;
+    let duration = (auction.end_ledger - auction.start_ledger) as i128;
+    let decay = auction
+        .start_price
+        .checked_sub(auction.reserve_price)
+        .and_then(|range| range.checked_mul(elapsed))
+        .ok_or(Error::ArithmeticOverflow)?
+        / duration;
+    auction
+        .start_price
+        .checked_sub(decay)
+        .ok_or(Error::ArithmeticOverflow)
+}
 
-    /// Permissionless invariant check: compares tracked liability against the
-    /// contract's actual SAC balance for `asset` and pauses the contract if
-    /// the balance no longer covers what's owed (#570). Intended to be
-    /// callable by an off-chain operational monitor on a schedule, not just
-    /// the owner — catching drift early matters more than gating who can look.
-    fn check_asset_solvency(env: Env, asset: Address) -> Result<AssetSolvency, Error> {
-        let solvency = compute_asset_solvency(&env, &asset);
-        if solvency.surplus < 0 {
-            InstanceStorage::set_pause_status(&env, true);
-            Events::emit_contract_paused_state_changed(&env, true);
-            Events::emit_solvency_violation_detected(
-                &env,
-                asset,
-                solvency.tracked_liability,
-                solvency.actual_balance,
-            );
-        }
-        Ok(solvency)
+/// Lowest bid an English auction will accept next.
+fn min_next_bid(auction: &Auction) -> Result<i128, Error> {
+    if auction.highest_bidder.is_none() {
+        return Ok(auction.reserve_price);
     }
+    let increment = auction
+        .highest_bid
+        .checked_mul(MIN_BID_INCREMENT_BPS as i128)
+        .ok_or(Error::ArithmeticOverflow)?
+        / MAX_BPS as i128;
+    auction
+        .highest_bid
+        .checked_add(increment.max(1))
+        .ok_or(Error::ArithmeticOverflow)
+}
 
-    /// One-time backfill for a deployment upgrading into this feature: adds a
-    /// single pre-existing Pending/Disputed escrow's amount into the
-    /// per-asset liability ledger. A no-op (Ok) if this exact escrow was
-    /// already migrated, so it is safe to retry (#570).
-    #[only_owner]
-    fn migrate_asset_liability(
-        env: Env,
-        admin: Address,
-        prompt_id: u64,
-        buyer: Address,
-    ) -> Result<(), Error> {
-        admin.require_auth();
-        let owner = ownable::get_owner(&env).ok_or(Error::Unauthorized)?;
-        ensure(owner == admin, Error::Unauthorized)?;
+fn check_auction_buyer(env: &Env, auction: &Auction, buyer: &Address) -> Result<(), Error> {
+    ensure(auction.creator != *buyer, Error::CreatorCannotBuy)?;
+    ensure(
+        !Storage::has_active_purchase(env, auction.prompt_id, buyer, env.ledger().timestamp()),
+        Error::AlreadyPurchased,
+    )
+}
 
-        if Storage::is_escrow_liability_migrated(&env, prompt_id, &buyer) {
-            return Ok(());
-        }
+/// Close an English auction after `ends_at`. The high bid is already
+/// escrowed and tracked as Pending liability; it either becomes the sale's
+/// escrow or, if the winner can no longer take a unit, is refunded.
+fn execute_settle_auction(env: &Env, mut auction: Auction) -> Result<(), Error> {
+    let Some(winner) = auction.highest_bidder.clone() else {
+        auction.status = AuctionStatus::Closed;
+        Storage::save_auction(env, &auction);
+        Events::emit_auction_closed(env, auction.prompt_id);
+        return Ok(());
+    };
 
-        let escrow = Storage::require_purchase_escrow(&env, prompt_id, &buyer)?;
-        if escrow.status == SettlementStatus::Pending {
-            let dispute_open = Storage::get_dispute(&env, prompt_id, &buyer)
-                .map(|d| d.status == DisputeStatus::Open)
-                .unwrap_or(false);
-            if dispute_open {
-                Storage::add_disputed_liability(&env, &escrow.asset, escrow.amount)?;
-            } else {
-                Storage::add_pending_liability(&env, &escrow.asset, escrow.amount)?;
-            }
+    let mut prompt = Storage::require_prompt(env, auction.prompt_id)?;
+    let now = env.ledger().timestamp();
+    // The winner may have acquired the listing another way (e.g. a resale
+    // or bundle) while the auction ran; a bid must never be stranded.
+    let reservation = if Storage::has_active_purchase(env, prompt.id, &winner, now) {
+        None
+    } else {
+        reserve_supply(prompt.sales_count, prompt.max_supply).ok()
+    };
+
+    InstanceStorage::set_reentrancy_guard(env)?;
+    match reservation {
+        Some(reserved_sales_count) => {
+            prompt.sales_count = reserved_sales_count;
+            Storage::update_prompt(env, &prompt);
+            open_sale_escrow(
+                env,
+                &winner,
+                &prompt,
+                &prompt.asset,
+                &None,
+                auction.highest_bid,
+                None,
+                AcquisitionKind::Auction,
+            )?;
+            auction.status = AuctionStatus::Sold;
+            Storage::save_auction(env, &auction);
+            Events::emit_prompt_purchased(
+                env,
+                prompt.id,
+                winner.clone(),
+                prompt.creator,
+                auction.highest_bid,
+                None,
+            );
+            Events::emit_auction_sale(env, auction.prompt_id, winner, auction.highest_bid);
+        }
+        None => {
+            Storage::remove_pending_liability(env, &auction.asset, auction.highest_bid)?;
+            Storage::credit_claimable(env, &winner, &auction.asset, auction.highest_bid)?;
+            auction.status = AuctionStatus::Closed;
+            Storage::save_auction(env, &auction);
+            Events::emit_auction_closed(env, auction.prompt_id);
         }
-        Storage::mark_escrow_liability_migrated(&env, prompt_id, &buyer);
-        Ok(())
     }
+    InstanceStorage::clear_reentrancy_guard(env);
+    Ok(())
 }
 
-#[contractimpl(contracttrait)]
-impl Ownable for PromptHashContract {}
+// ─── License tokens ────────────────────────────────────────────────────────
+//
+// Listings in token mode back each license with a non-fungible token. The
+// token never carries state of its own beyond its owner: it points at the
+// `Purchase` keyed by that owner, and `move_license` moves both together.
 
-/// Single write path for the platform fee (#566). `set_fee_percentage`,
-/// `update_platform_fee`, and `migrate_platform_fee_bound` all delegate here
-/// so the bound, storage key, and emitted event can never diverge between
-/// entrypoints.
-fn set_platform_fee_internal(env: &Env, actor: Address, new_fee: u32) -> Result<(), Error> {
-    ensure(new_fee <= MAX_PLATFORM_FEE, Error::FeeExceedsMaximum)?;
-    let old_fee = InstanceStorage::get_fee_percentage(env);
-    InstanceStorage::set_fee_percentage(env, &new_fee);
-    Events::emit_platform_fee_updated(env, old_fee, new_fee, actor, env.ledger().sequence());
+/// Grant a license through `Storage::grant_purchase`, announcing any token
+/// it mints.
+fn grant_license(
+    env: &Env,
+    prompt: &Prompt,
+    buyer: &Address,
+    paid_price: i128,
+    expires_at: u64,
+) -> Result<(), Error> {
+    if let Some(token_id) = Storage::grant_purchase(env, prompt, buyer, paid_price, expires_at)? {
+        Events::emit_license_token_minted(env, buyer.clone(), token_id);
+    }
     Ok(())
 }
 
-/// Compares tracked per-asset liability against the contract's actual SAC
-/// balance for `asset` (#570). `surplus` is the excess above what's owed —
-/// rounding dust or an accidental direct transfer, not customer liability —
-/// and goes negative only if the balance no longer covers tracked liability.
-fn compute_asset_solvency(env: &Env, asset: &Address) -> AssetSolvency {
-    let liability = Storage::get_asset_liability(env, asset);
-    let tracked_liability = liability.pending.saturating_add(liability.disputed);
-    let actual_balance = token::Client::new(env, asset).balance(&env.current_contract_address());
-    AssetSolvency {
-        tracked_liability,
-        actual_balance,
-        surplus: actual_balance.saturating_sub(tracked_liability),
+/// Revoke `owner`'s license to `prompt_id`, burning its token.
+fn revoke_license(env: &Env, prompt_id: u64, owner: &Address) {
+    Storage::remove_purchase(env, prompt_id, owner);
+    Storage::remove_prompt_from_buyer(env, owner, prompt_id);
+    if let Some(token_id) = Storage::burn_license_token(env, prompt_id, owner) {
+        Events::emit_license_token_burned(env, owner.clone(), token_id);
     }
 }
 
-fn execute_buy(
+/// SEP-50 `transfer`/`transfer_from` carry no sale price, so the creator's
+/// royalty could not be charged on them. License tokens only change hands
+/// through a priced resale (a filled resale order, or `transfer_license`),
+/// which moves the token with the license. After the usual ownership and
+/// approval checks, an unpriced transfer is refused.
+fn reject_unpriced_token_transfer(
+    env: &Env,
+    spender: &Address,
+    from: &Address,
+    to: &Address,
+    token_id: u32,
+) -> Result<(), Error> {
+    let token = Storage::require_license_token(env, token_id)?;
+    ensure(token.owner == *from, Error::Unauthorized)?;
+    ensure(from != to, Error::InvalidLicenseTransfer)?;
+    if spender != from {
+        let approved = Storage::get_license_token_approval(env, token_id)
+            .map(|approval| approval.approved == *spender)
+            .unwrap_or(false);
+        ensure(
+            approved || Storage::is_license_token_operator(env, from, spender),
+            Error::LicenseTokenNotApproved,
+        )?;
+    }
+    Err(Error::UnpricedLicenseTransfer)
+}
+
+// ─── Settlement ledger (#567) ──────────────────────────────────────────────
+//
+// Every acquisition path records its escrow under a freshly allocated
+// settlement ID and repoints the buyer's entitlement for each prompt it
+// grants. Disputes and settlement act on one settlement ID, so a lease
+// followed by a repurchase, or a bundle next to a pass, can never touch each
+// other's records.
+
+/// Record `escrow` as a new settlement and point the buyer's entitlement for
+/// every prompt in `granted` at it. `supersedes` links to the settlement that
+/// previously backed the first granted prompt, if any.
+fn record_acquisition(
+    env: &Env,
+    kind: AcquisitionKind,
+    acquisition_id: u128,
+    escrow: &PurchaseEscrow,
+    granted: &Vec<u64>,
+) -> Result<u128, Error> {
+    let buyer = &escrow.buyer;
+    let mut supersedes = None;
+    for prompt_id in granted.iter() {
+        supersedes = Storage::resolve_entitlement_settlement(env, prompt_id, buyer);
+        if supersedes.is_some() {
+            break;
+        }
+    }
+
+    let settlement_id = InstanceStorage::next_settlement_id(env)?;
+    let record = SettlementRecord {
+        settlement_id,
+        prompt_id: escrow.prompt_id,
+        acquisition_id,
+        buyer: buyer.clone(),
+        kind: kind.clone(),
+        amount: escrow.amount,
+        asset: escrow.asset.clone(),
+        status: escrow.status.clone(),
+        created_at: escrow.created_at,
+        settled_at: escrow.settled_at,
+        supersedes,
+        payout_plan: escrow.payout_plan.clone(),
+    };
+    Storage::save_settlement(env, &record);
+    Storage::save_settlement_escrow(env, settlement_id, escrow)?;
+
+    for prompt_id in granted.iter() {
+        Storage::set_entitlement_settlement(env, prompt_id, buyer, settlement_id);
+        Storage::add_buyer_settlement(env, prompt_id, buyer, settlement_id);
+    }
+    Storage::add_buyer_settlement_history(env, buyer, settlement_id);
+
+    Events::emit_settlement_recorded(
+        env,
+        settlement_id,
+        buyer.clone(),
+        kind,
+        acquisition_id,
+        escrow.amount,
+        supersedes,
+    );
+    Ok(settlement_id)
+}
+
+/// A buyer's own bond if the owner set one, else the default.
+fn dispute_bond_bps(env: &Env, buyer: &Address) -> u32 {
+    Storage::get_buyer_dispute_bond(env, buyer)
+        .unwrap_or_else(|| InstanceStorage::get_dispute_bond_bps(env))
+}
+
+/// Bundles and passes record prompt 0 on their escrow and cover many
+/// prompts, so per-prompt checks do not apply to them.
+fn backs_single_prompt(kind: &AcquisitionKind) -> bool {
+    !matches!(kind, AcquisitionKind::Bundle | AcquisitionKind::AccessPass)
+}
+
+fn execute_open_dispute(
     env: &Env,
     buyer: &Address,
-    prompt_id: u64,
-    referrer: &Option<Address>,
-    payment_amount_stroops: i128,
-    voucher: Option<Bytes>,
+    settlement_id: u128,
+    reason: DisputeReason,
 ) -> Result<(), Error> {
-    let prompt = Storage::require_prompt(env, prompt_id)?;
     let now = env.ledger().timestamp();
+    let record = Storage::require_settlement(env, settlement_id)?;
+    ensure(record.buyer == *buyer, Error::Unauthorized)?;
+    // Only the settlement currently backing the buyer's access may be
+    // disputed — a superseded or resold acquisition no longer can be.
+    if backs_single_prompt(&record.kind) {
+        ensure(
+            Storage::resolve_entitlement_settlement(env, record.prompt_id, buyer)
+                == Some(settlement_id),
+            Error::SettlementEntitlementMismatch,
+        )?;
+    }
 
+    // Only a pending escrow may be disputed, and only within the
+    // purchase-relative window; once it closes the escrow is eligible for
+    // permissionless settlement (#541). Lease, bundle and pass escrows
+    // are only pending when their product's dispute window policy is set;
+    // ones that settled at purchase time have nothing left to dispute.
+    let escrow =
+        Storage::get_settlement_escrow(env, settlement_id).ok_or(Error::SettlementNotFound)?;
     ensure(
-        prompt.status == PromptSaleStatus::Active,
-        Error::PromptInactive,
+        escrow.status == SettlementStatus::Pending,
+        Error::DisputeWindowClosed,
     )?;
-    ensure(prompt.creator != *buyer, Error::CreatorCannotBuy)?;
+    // A wrapped key still owed past its delivery deadline is a verifiable
+    // failure, so it stays disputable after the normal window closes —
+    // the escrow cannot have settled meanwhile, since settlement is
+    // blocked while the delivery is outstanding.
+    let key_overdue = reason == DisputeReason::InvalidEncryptedPayload
+        && backs_single_prompt(&record.kind)
+        && outstanding_key_delivery(env, record.prompt_id, buyer, escrow.created_at)
+            .map(|deadline| now > deadline)
+            .unwrap_or(false);
     ensure(
-        !Storage::has_active_purchase(env, prompt_id, buyer, now),
-        Error::AlreadyPurchased,
+        now <= escrow.dispute_deadline || key_overdue,
+        Error::DisputeWindowClosed,
     )?;
+    if let Some(dispute) = Storage::get_settlement_dispute(env, settlement_id) {
+        ensure(
+            dispute.status != DisputeStatus::Open,
+            Error::DisputeAlreadyOpen,
+        )?;
+        // A partial refund already settled the dispute on its merits; the
+        // remaining escrow is no longer open to another claim.
+        ensure(
+            dispute.status != DisputeStatus::PartiallyRefunded,
+            Error::DisputeResolved,
+        )?;
+    }
 
-    if prompt.expires_at != 0 {
-        ensure(prompt.expires_at >= now, Error::ListingExpired)?;
+    // The escrowed amount is now at risk of refund rather than
+    // settlement — move it out of `pending` into `disputed` so per-asset
+    // liability reflects the dispute (#570). Applied only once every
+    // other guard above has passed, so a rejected duplicate-open attempt
+    // never touches the ledger.
+    Storage::move_pending_to_disputed(env, &escrow.asset, escrow.amount)?;
+
+    // The bond is held in the escrow's asset until the dispute resolves.
+    // The buyer must have approved the contract for it.
+    let bond_amount = escrow
+        .amount
+        .checked_mul(dispute_bond_bps(env, buyer) as i128)
+        .ok_or(Error::ArithmeticOverflow)?
+        / MAX_BPS as i128;
+    if bond_amount > 0 {
+        let this_contract = env.current_contract_address();
+        token::StellarAssetClient::new(env, &escrow.asset).transfer_from(
+            &this_contract,
+            buyer,
+            &this_contract,
+            &bond_amount,
+        );
+        Storage::add_bond_liability(env, &escrow.asset, bond_amount)?;
     }
+    let mut stats = Storage::get_buyer_dispute_stats(env, buyer);
+    stats.opened = stats.opened.saturating_add(1);
+    Storage::save_buyer_dispute_stats(env, buyer, &stats);
 
-    // Fail fast before spending gas on voucher validation; the actual
-    // reservation is (re)computed against a fresh fetch in
-    // `execute_buy_with_required_price`, which is also reachable directly
-    // from `buy_prompt_with_auth`.
-    reserve_supply(prompt.sales_count, prompt.max_supply)?;
+    let dispute = PurchaseDispute {
+        prompt_id: record.prompt_id,
+        buyer: buyer.clone(),
+        reason,
+        opened_at: now,
+        resolved_at: 0,
+        status: DisputeStatus::Open,
+        bond_amount,
+    };
+    Storage::save_settlement_dispute(env, settlement_id, &dispute);
+    Events::emit_dispute_opened(env, record.prompt_id, buyer.clone());
 
-    let mut required_price = prompt.price_stroops;
-    if let Some(code) = voucher {
-        let hashed_raw = env.crypto().sha256(&code);
-        let hashed = BytesN::from_array(env, &hashed_raw.to_array());
-        if let Some(discount_bps) = Storage::get_voucher(env, prompt_id, &hashed) {
-            let discount_amount = required_price
-                .checked_mul(discount_bps as i128)
-                .ok_or(Error::ArithmeticOverflow)?
-                / MAX_BPS as i128;
-            required_price = required_price
-                .checked_sub(discount_amount)
-                .ok_or(Error::ArithmeticOverflow)?;
-            Storage::remove_voucher(env, prompt_id, &hashed);
+    // With enough arbiters registered the dispute goes before a panel;
+    // otherwise it stays with the owner as before.
+    match seat_arbitration_panel(
+        env,
+        settlement_id,
+        0,
+        ARBITRATION_PANEL_SIZE,
+        ARBITRATION_PANEL_SIZE,
+        &Vec::new(env),
+    ) {
+        Ok(()) | Err(Error::NotEnoughArbiters) => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+// ─── Dispute arbitration ─────────────────────────────────────────────────────
+
+/// The buyer and the creator of a disputed settlement.
+fn dispute_parties(env: &Env, settlement_id: u128) -> Result<(Address, Address), Error> {
+    let escrow =
+        Storage::get_settlement_escrow(env, settlement_id).ok_or(Error::SettlementNotFound)?;
+    Ok((escrow.buyer, escrow.payout_plan.creator))
+}
+
+fn require_open_dispute(env: &Env, settlement_id: u128) -> Result<(), Error> {
+    let dispute =
+        Storage::get_settlement_dispute(env, settlement_id).ok_or(Error::DisputeNotFound)?;
+    ensure(
+        dispute.status == DisputeStatus::Open,
+        Error::DisputeResolved,
+    )
+}
+
+/// The owner may resolve a dispute directly unless a panel is seated on it
+/// and still within its voting deadline.
+fn check_owner_may_resolve(env: &Env, settlement_id: u128) -> Result<(), Error> {
+    if let Some(arbitration) = Storage::get_arbitration(env, settlement_id) {
+        ensure(
+            arbitration.outcome.is_none() && env.ledger().timestamp() > arbitration.voting_deadline,
+            Error::DisputeUnderArbitration,
+        )?;
+    }
+    Ok(())
+}
+
+/// Panels rule on the dispute as a whole: a refund returns the full escrow.
+fn resolve_arbitrated_dispute(env: &Env, settlement_id: u128, refund: bool) -> Result<(), Error> {
+    let escrow =
+        Storage::get_settlement_escrow(env, settlement_id).ok_or(Error::SettlementNotFound)?;
+    let refund_amount = if refund { escrow.amount } else { 0 };
+    execute_resolve_dispute(env, settlement_id, refund_amount)
+}
+
+/// Seat a panel for `settlement_id`, walking the registry round-robin from
+/// the stored cursor. Neither party to the dispute nor anyone in `exclude`
+/// (the previous panel, on appeal) may sit. The panel takes as many
+/// eligible arbiters as it can up to `max_size`, kept odd so a majority
+/// always decides, and fails below `min_size`. Any panel already recorded
+/// for the settlement is archived first, so a reopened dispute keeps its
+/// earlier arbitration on record.
+fn seat_arbitration_panel(
+    env: &Env,
+    settlement_id: u128,
+    round: u32,
+    min_size: u32,
+    max_size: u32,
+    exclude: &Vec<Address>,
+) -> Result<(), Error> {
+    Storage::archive_arbitration(env, settlement_id);
+    let (buyer, creator) = dispute_parties(env, settlement_id)?;
+    let arbiters = Storage::get_arbiters(env);
+    let count = arbiters.len();
+    ensure(count >= min_size, Error::NotEnoughArbiters)?;
+
+    let eligible = arbiters
+        .iter()
+        .filter(|arbiter| *arbiter != buyer && *arbiter != creator && !exclude.contains(arbiter))
+        .count() as u32;
+    let mut size = eligible.min(max_size);
+    if size.is_multiple_of(2) {
+        size = size.saturating_sub(1);
+    }
+    ensure(size >= min_size, Error::NotEnoughArbiters)?;
+    let quorum = size / 2 + 1;
+
+    let start = InstanceStorage::get_arbiter_cursor(env) % count;
+    let mut panel = Vec::new(env);
+    let mut next = start;
+    for offset in 0..count {
+        if panel.len() == size {
+            break;
+        }
+        let index = (start + offset) % count;
+        let arbiter = arbiters.get(index).unwrap();
+        if arbiter != buyer && arbiter != creator && !exclude.contains(&arbiter) {
+            panel.push_back(arbiter);
+            next = index + 1;
+        }
+    }
+    ensure(panel.len() == size, Error::NotEnoughArbiters)?;
+    InstanceStorage::set_arbiter_cursor(env, next % count);
+
+    let now = env.ledger().timestamp();
+    let arbitration = Arbitration {
+        settlement_id,
+        round,
+        panel: panel.clone(),
+        quorum,
+        voted: Vec::new(env),
+        refund_votes: 0,
+        reject_votes: 0,
+        assigned_at: now,
+        voting_deadline: now
+            .checked_add(ARBITRATION_VOTING_SECS)
+            .ok_or(Error::ArithmeticOverflow)?,
+        outcome: None,
+        appeal_deadline: 0,
+    };
+    Storage::save_arbitration(env, &arbitration);
+    Events::emit_arbitration_assigned(env, settlement_id, round, panel, quorum);
+    Ok(())
+}
+
+fn execute_resolve_dispute(
+    env: &Env,
+    settlement_id: u128,
+    refund_amount: i128,
+) -> Result<(), Error> {
+    let record = Storage::require_settlement(env, settlement_id)?;
+    let mut dispute =
+        Storage::get_settlement_dispute(env, settlement_id).ok_or(Error::DisputeNotFound)?;
+    ensure(
+        dispute.status == DisputeStatus::Open,
+        Error::DisputeResolved,
+    )?;
+    let mut escrow =
+        Storage::get_settlement_escrow(env, settlement_id).ok_or(Error::SettlementNotFound)?;
+    ensure(
+        refund_amount >= 0 && refund_amount <= escrow.amount,
+        Error::InvalidRefundAmount,
+    )?;
+    let now = env.ledger().timestamp();
+    dispute.resolved_at = now;
+    if refund_amount > 0 {
+        // Refund from the contract's escrowed balance (#454).  The
+        // funds were routed to the contract during purchase so that
+        // a refund is always possible without additional auth.
+        refund_escrow(env, &mut escrow, refund_amount)?;
+
+        // The refunded amount is now owed to the buyer as a claimable
+        // balance rather than held in dispute (#570). The settlement record
+        // is kept, marked Refunded, so the receipt history stays complete.
+        Storage::remove_disputed_liability(env, &escrow.asset, refund_amount)?;
+        release_lease_payment(env, &record, refund_amount);
+        if escrow.amount == 0 {
+            revoke_refunded_entitlement(env, &record)?;
+            dispute.status = DisputeStatus::Refunded;
+            escrow.status = SettlementStatus::Refunded;
+            escrow.settled_at = now;
         } else {
-            return Err(Error::InvalidVoucher);
+            // The remainder stays Pending against the scaled-down plan.
+            dispute.status = DisputeStatus::PartiallyRefunded;
+            Storage::move_disputed_to_pending(env, &escrow.asset, escrow.amount)?;
+        }
+        Storage::save_settlement_escrow(env, settlement_id, &escrow)?;
+    } else {
+        dispute.status = DisputeStatus::Rejected;
+        // The escrow stays Pending, awaiting a future `settle_purchase`
+        // call — move its amount back out of `disputed` into `pending`
+        // (#570).
+        if escrow.status == SettlementStatus::Pending {
+            Storage::move_disputed_to_pending(env, &escrow.asset, escrow.amount)?;
+        }
+    }
+    Storage::save_settlement_dispute(env, settlement_id, &dispute);
+
+    // Any refund vindicates the buyer and returns their bond; a rejection
+    // forfeits it to the creator whose payout it held up.
+    let refunded = refund_amount > 0;
+    if dispute.bond_amount > 0 {
+        Storage::remove_bond_liability(env, &escrow.asset, dispute.bond_amount)?;
+        let recipient = if refunded {
+            record.buyer.clone()
+        } else {
+            escrow.payout_plan.creator.clone()
+        };
+        Storage::credit_claimable(env, &recipient, &escrow.asset, dispute.bond_amount)?;
+        Events::emit_dispute_bond_released(
+            env,
+            settlement_id,
+            recipient,
+            dispute.bond_amount,
+            !refunded,
+        );
+    }
+    let mut stats = Storage::get_buyer_dispute_stats(env, &record.buyer);
+    if refunded {
+        stats.refunded = stats.refunded.saturating_add(1);
+    } else {
+        stats.rejected = stats.rejected.saturating_add(1);
+    }
+    Storage::save_buyer_dispute_stats(env, &record.buyer, &stats);
+
+    Events::emit_dispute_resolved(env, record.prompt_id, record.buyer, refunded, refund_amount);
+    Ok(())
+}
+
+/// Credit `refund_amount` of a Pending escrow to its buyer's claimable
+/// balance and shrink every share of the payout plan pro rata to what
+/// remains, so a later settlement still pays out exactly `escrow.amount`.
+/// The creator absorbs rounding. Removing the refund from the escrow's
+/// pending or disputed liability is left to the caller.
+fn refund_escrow(env: &Env, escrow: &mut PurchaseEscrow, refund_amount: i128) -> Result<(), Error> {
+    Storage::credit_claimable(env, &escrow.buyer, &escrow.asset, refund_amount)?;
+
+    let remaining = escrow
+        .amount
+        .checked_sub(refund_amount)
+        .ok_or(Error::ArithmeticOverflow)?;
+    let scale = |share: i128| -> Result<i128, Error> {
+        if escrow.amount == 0 {
+            return Ok(0);
+        }
+        Ok(share
+            .checked_mul(remaining)
+            .ok_or(Error::ArithmeticOverflow)?
+            / escrow.amount)
+    };
+
+    let plan = &mut escrow.payout_plan;
+    let fee_amount = scale(plan.fee_amount)?;
+    let referral_amount = scale(plan.referral_amount)?;
+    let mut splits = Vec::new(env);
+    let mut creator_amount = remaining
+        .checked_sub(fee_amount)
+        .and_then(|rest| rest.checked_sub(referral_amount))
+        .ok_or(Error::ArithmeticOverflow)?;
+    for split in plan.splits.iter() {
+        let amount = scale(split.amount)?;
+        creator_amount = creator_amount
+            .checked_sub(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        splits.push_back(super::types::PayoutSplit {
+            recipient: split.recipient,
+            amount,
+        });
+    }
+    plan.fee_amount = fee_amount;
+    plan.referral_amount = referral_amount;
+    plan.splits = splits;
+    plan.creator_amount = creator_amount;
+
+    escrow.fee_amount = fee_amount;
+    escrow.referral_amount = referral_amount;
+    escrow.creator_amount = creator_amount;
+    escrow.amount = remaining;
+    escrow.refunded_amount = escrow
+        .refunded_amount
+        .checked_add(refund_amount)
+        .ok_or(Error::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// A fully refunded acquisition no longer grants access: revoke the
+/// license and release its reserved supply unit back to the pool so
+/// another buyer can acquire it (#538). A bundle does this for every
+/// prompt it still backs; a pass gives back the period it bought.
+fn revoke_refunded_entitlement(env: &Env, record: &SettlementRecord) -> Result<(), Error> {
+    match record.kind {
+        AcquisitionKind::Bundle => {
+            let mut bundle = Storage::require_bundle(env, record.acquisition_id)?;
+            for prompt_id in bundle.prompt_ids.iter() {
+                // Prompts the buyer already owned, or has since re-acquired
+                // elsewhere, are not this bundle's to take back.
+                if Storage::resolve_entitlement_settlement(env, prompt_id, &record.buyer)
+                    == Some(record.settlement_id)
+                {
+                    release_refunded_license(env, prompt_id, &record.buyer)?;
+                }
+            }
+            bundle.sales_count = bundle.sales_count.saturating_sub(1);
+            Storage::update_bundle(env, &bundle);
+        }
+        AcquisitionKind::AccessPass => {
+            let mut access_pass = Storage::require_access_pass(env, record.acquisition_id)?;
+            if let Some(mut grant) =
+                Storage::get_catalog_pass_purchase(env, &access_pass.creator, &record.buyer)
+            {
+                grant.expires_at = grant.expires_at.saturating_sub(access_pass.duration_secs);
+                Storage::save_catalog_pass_purchase(env, &grant);
+            }
+            access_pass.sales_count = access_pass.sales_count.saturating_sub(1);
+            Storage::update_access_pass(env, &access_pass);
         }
+        AcquisitionKind::Lease => match Storage::get_lease_renewal(env, record.settlement_id) {
+            Some(extension_secs) => roll_back_lease_renewal(env, record, extension_secs),
+            None => release_refunded_license(env, record.prompt_id, &record.buyer)?,
+        },
+        _ => release_refunded_license(env, record.prompt_id, &record.buyer)?,
     }
+    Ok(())
+}
+
+/// A refunded renewal takes back only the time it added. The original
+/// lease keeps its license and supply unit, and again backs the lessee's
+/// entitlement.
+fn roll_back_lease_renewal(env: &Env, record: &SettlementRecord, extension_secs: u64) {
+    let (Some(mut lease), Some(mut purchase)) = (
+        Storage::get_lease(env, record.prompt_id, &record.buyer),
+        Storage::get_purchase(env, record.prompt_id, &record.buyer),
+    ) else {
+        return;
+    };
+    // Converted or moved on since: the renewal no longer backs the license.
+    if purchase.expires_at != lease.expires_at {
+        return;
+    }
+    lease.expires_at = lease.expires_at.saturating_sub(extension_secs);
+    purchase.expires_at = lease.expires_at;
+    Storage::save_lease(env, &lease);
+    Storage::save_purchase(env, &purchase);
+    if let Some(previous) = record.supersedes {
+        Storage::set_entitlement_settlement(env, record.prompt_id, &record.buyer, previous);
+    }
+}
+
+/// Refunds of a lease settlement come off what the lease has been paid, so
+/// a later conversion only credits money the lessee still has in it.
+fn release_lease_payment(env: &Env, record: &SettlementRecord, amount: i128) {
+    if record.kind != AcquisitionKind::Lease {
+        return;
+    }
+    if let Some(mut lease) = Storage::get_lease(env, record.prompt_id, &record.buyer) {
+        lease.paid_total = lease.paid_total.saturating_sub(amount).max(0);
+        Storage::save_lease(env, &lease);
+    }
+}
+
+fn release_refunded_license(env: &Env, prompt_id: u64, buyer: &Address) -> Result<(), Error> {
+    revoke_license(env, prompt_id, buyer);
+    let mut prompt = Storage::require_prompt(env, prompt_id)?;
+    prompt.sales_count = prompt.sales_count.saturating_sub(1);
+    Storage::update_prompt(env, &prompt);
+    Ok(())
+}
 
+/// Release escrowed funds using the immutable payout plan snapshotted at
+/// purchase time (#562), not the current listing state. The contract
+/// owner (admin) or the snapshotted creator may settle at any time; any
+/// other caller may settle only once the purchase-relative dispute
+/// window has closed with no open dispute, guaranteeing every escrow
+/// has a bounded path to Released even if the admin/creator never
+/// act (#454/#541).
+fn execute_settle(env: &Env, caller: &Address, settlement_id: u128) -> Result<(), Error> {
+    let owner = ownable::get_owner(env).ok_or(Error::Unauthorized)?;
+    let mut escrow =
+        Storage::get_settlement_escrow(env, settlement_id).ok_or(Error::SettlementNotFound)?;
     ensure(
-        payment_amount_stroops >= required_price,
-        Error::InvalidPaymentAmount,
+        escrow.status == SettlementStatus::Pending,
+        Error::SettlementAlreadyFinalized,
     )?;
 
-    if let Some(ref r) = referrer {
+    // An open dispute must be resolved via `resolve_dispute`, not
+    // settled — this holds even for the admin/creator fast path (#541).
+    if let Some(dispute) = Storage::get_settlement_dispute(env, settlement_id) {
         ensure(
-            r != buyer && r != &prompt.creator,
-            Error::ReferrerCannotBeBuyerOrCreator,
+            dispute.status != DisputeStatus::Open,
+            Error::DisputeAlreadyOpen,
         )?;
     }
+    // Funds for a purchase whose wrapped key is still owed cannot be
+    // released, not even by the privileged fast path.
+    let record = Storage::require_settlement(env, settlement_id)?;
+    ensure(
+        !backs_single_prompt(&record.kind)
+            || outstanding_key_delivery(env, escrow.prompt_id, &escrow.buyer, escrow.created_at)
+                .is_none(),
+        Error::KeyDeliveryPending,
+    )?;
 
-    execute_buy_with_required_price(
+    let now = env.ledger().timestamp();
+    let is_privileged = *caller == owner || *caller == escrow.payout_plan.creator;
+    if !is_privileged {
+        // Permissionless fallback: once the dispute window has closed
+        // with no open dispute, anyone may finalize the escrow — this
+        // guarantees a bounded path to Released even if the admin or
+        // creator never act (#541).
+        ensure(
+            now > escrow.dispute_deadline,
+            Error::DisputeWindowNotElapsed,
+        )?;
+    }
+
+    // Use the snapshotted payout plan, not the current listing state (#562).
+    pay_out_plan(env, &escrow.asset, &escrow.payout_plan)?;
+
+    // The escrow guards above guarantee this amount was still in the
+    // pending bucket (Pending status, no open dispute) — release it
+    // from tracked liability now that it's been paid out (#570).
+    Storage::remove_pending_liability(env, &escrow.asset, escrow.amount)?;
+
+    escrow.status = SettlementStatus::Settled;
+    escrow.settled_at = now;
+    Storage::save_settlement_escrow(env, settlement_id, &escrow)?;
+    Events::emit_prompt_purchased(
         env,
-        buyer,
-        prompt_id,
-        referrer,
-        payment_amount_stroops,
-        required_price,
-    )
+        escrow.prompt_id,
+        escrow.buyer,
+        escrow.payout_plan.creator,
+        escrow.amount,
+        escrow.referrer,
+    );
+    Ok(())
 }
 
-/// Buy execution after all price and voucher validation is done.
-/// Shared between `execute_buy` (legacy vouchers) and `buy_prompt_with_auth`
-/// (signed discount authorizations).
-fn execute_buy_with_required_price(
-    env: &Env,
-    buyer: &Address,
-    prompt_id: u64,
-    referrer: &Option<Address>,
-    payment_amount_stroops: i128,
-    required_price: i128,
-) -> Result<(), Error> {
-    let mut prompt = Storage::require_prompt(env, prompt_id)?;
-    let reserved_sales_count = reserve_supply(prompt.sales_count, prompt.max_supply)?;
+/// Credit every share of `plan` to its recipient's claimable balance. The
+/// funds stay in the contract until each recipient calls `withdraw`, so a
+/// frozen or deauthorized trustline only ever blocks its own withdrawal.
+fn pay_out_plan(env: &Env, asset: &Address, plan: &super::types::PayoutPlan) -> Result<(), Error> {
+    // Credit fee to the snapshotted fee wallet
+    if plan.fee_amount > 0 {
+        Storage::credit_claimable(env, &plan.fee_wallet, asset, plan.fee_amount)?;
+    }
 
-    InstanceStorage::set_reentrancy_guard(env)?;
+    // Credit referral to the snapshotted referrer
+    if let Some(ref r) = plan.referrer {
+        if plan.referral_amount > 0 {
+            Storage::credit_claimable(env, r, asset, plan.referral_amount)?;
+        }
+    }
 
-    let this_contract = env.current_contract_address();
+    // Credit collaborator splits from the snapshotted amounts
+    for i in 0..plan.splits.len() {
+        let split = plan.splits.get(i).unwrap();
+        if split.amount > 0 {
+            Storage::credit_claimable(env, &split.recipient, asset, split.amount)?;
+        }
+    }
 
-    let fee_percentage = InstanceStorage::get_fee_percentage(env);
-    ensure(fee_percentage <= MAX_BPS, Error::InvalidFeePercentage)?;
+    // Credit the creator's escrowed share to the snapshotted creator
+    if plan.creator_amount > 0 {
+        Storage::credit_claimable(env, &plan.creator, asset, plan.creator_amount)?;
+    }
+    Ok(())
+}
 
-    let fee_amount = payment_amount_stroops
-        .checked_mul(fee_percentage as i128)
-        .ok_or(Error::ArithmeticOverflow)?
-        / MAX_BPS as i128;
+// ─── Quote-bound checkout (#565) ───────────────────────────────────────────
+//
+// A quote binds the buyer's authorization to the exact commercial terms seen
+// during simulation. The terms hash is re-derived from live state on every
+// redemption, so a revision, fee change, split change, bundle membership
+// change or pass duration change between simulation and submission makes the
+// quote fail closed before any funds move.
+
+/// Validate everything about `quote` that does not depend on the acquisition
+/// path, then consume its nonce. `charge` is the amount the path will debit,
+/// excluding any tip.
+fn verify_quote(
+    env: &Env,
+    quote: &QuoteCommitment,
+    kind: AcquisitionKind,
+    asset: &Address,
+    terms_hash: &BytesN<32>,
+    charge: i128,
+) -> Result<(), Error> {
+    ensure(
+        quote.network_id == env.ledger().network_id(),
+        Error::QuoteDomainMismatch,
+    )?;
+    ensure(
+        quote.contract_id == current_contract_id_hash(env),
+        Error::QuoteDomainMismatch,
+    )?;
+    ensure(quote.kind == kind, Error::QuoteAcquisitionMismatch)?;
 
-    let referral_percentage = InstanceStorage::get_referral_percentage(env);
-    let referral_amount = if referrer.is_some() {
-        payment_amount_stroops
-            .checked_mul(referral_percentage as i128)
-            .ok_or(Error::ArithmeticOverflow)?
-            / MAX_BPS as i128
-    } else {
-        0
-    };
+    let ledger = env.ledger().sequence();
+    ensure(ledger >= quote.not_before_ledger, Error::QuoteNotYetValid)?;
+    ensure(ledger <= quote.expiry_ledger, Error::QuoteExpired)?;
+    ensure(
+        !Storage::is_quote_nonce_consumed(env, &quote.buyer, &quote.nonce),
+        Error::QuoteNonceConsumed,
+    )?;
 
-    let deductions = fee_amount
-        .checked_add(referral_amount)
-        .ok_or(Error::ArithmeticOverflow)?;
+    ensure(quote.asset == *asset, Error::QuoteAssetMismatch)?;
+    ensure(quote.terms_hash == *terms_hash, Error::QuoteTermsChanged)?;
 
-    let mut split_total: i128 = 0;
-    for i in 0..prompt.splits.len() {
-        let split = prompt.splits.get(i).unwrap();
-        let split_amount = payment_amount_stroops
-            .checked_mul(split.bps as i128)
-            .ok_or(Error::ArithmeticOverflow)?
-            / MAX_BPS as i128;
-        split_total = split_total
-            .checked_add(split_amount)
-            .ok_or(Error::ArithmeticOverflow)?;
+    // Only direct purchases have ever accepted a gratuity; every other path
+    // must carry an explicit zero tip.
+    if kind == AcquisitionKind::DirectPurchase {
+        ensure(quote.tip_amount >= 0, Error::UnauthorizedTip)?;
+    } else {
+        ensure(quote.tip_amount == 0, Error::UnauthorizedTip)?;
     }
+    ensure(charge <= quote.max_charge, Error::QuoteChargeExceeded)?;
 
-    let total_deductions = deductions
-        .checked_add(split_total)
-        .ok_or(Error::ArithmeticOverflow)?;
-    let creator_amount = payment_amount_stroops
-        .checked_sub(total_deductions)
-        .ok_or(Error::ArithmeticOverflow)?;
+    Storage::consume_quote_nonce(env, &quote.buyer, &quote.nonce);
+    Ok(())
+}
 
-    ensure(creator_amount >= 0, Error::InvalidSplits)?;
+/// One page of a sharded prompt index, with the cursor for the next page.
+fn prompt_page(
+    env: &Env,
+    index: &PromptIndex,
+    cursor: Option<String>,
+    limit: u64,
+) -> Result<(Vec<Prompt>, Option<String>), Error> {
+    use crate::pagination::{decode_cursor, encode_cursor};
+
+    let after = match cursor {
+        Some(c) => Some(decode_cursor(env, &c, index)?),
+        None => None,
+    };
+    let (prompts, last) = Storage::get_prompts_paginated(env, index, after, limit);
+    let next_cursor = last.map(|position| encode_cursor(env, index, &position));
+    Ok((prompts, next_cursor))
+}
 
-    let asset_client = token::StellarAssetClient::new(env, &prompt.asset);
+fn quote_prompt_id(acquisition_id: u128) -> Result<u64, Error> {
+    u64::try_from(acquisition_id).map_err(|_| Error::PromptNotFound)
+}
 
-    // Route the full payment through the contract so it holds
-    // escrow for dispute refunds (#454).  The buyer must have
-    // approved the contract for at least `payment_amount_stroops`.
-    asset_client.transfer_from(
-        &this_contract,
-        buyer,
-        &this_contract,
-        &payment_amount_stroops,
-    );
+/// Commercial terms shared by every quote: the acquisition path and the
+/// platform fee schedule in effect.
+fn quote_terms_prefix(env: &Env, kind: AcquisitionKind) -> Vec<Val> {
+    let mut buf: Vec<Val> = Vec::new(env);
+    buf.push_back(kind.into_val(env));
+    buf.push_back((InstanceStorage::get_fee_percentage(env) as u128).into_val(env));
+    buf.push_back((InstanceStorage::get_referral_percentage(env) as u128).into_val(env));
+    buf
+}
 
-    // All funds are held in the contract.  No distribution occurs
-    // here — `settle_purchase` releases them to the creator, fee wallet,
-    // referrer, and split recipients, or a dispute refund returns them
-    // to the buyer.
-    prompt.sales_count = reserved_sales_count;
-    Storage::update_prompt(env, &prompt);
-    Storage::grant_purchase(
-        env,
-        &prompt,
-        buyer,
-        payment_amount_stroops,
-        MAX_ACCESS_EXPIRY,
-    );
+fn push_prompt_terms(env: &Env, buf: &mut Vec<Val>, prompt: &Prompt) {
+    buf.push_back((prompt.id as u128).into_val(env));
+    buf.push_back((prompt.revision as u128).into_val(env));
+    buf.push_back(prompt.price_stroops.into_val(env));
+    buf.push_back(prompt.asset.to_val());
+    buf.push_back(prompt.splits.to_val());
+    buf.push_back(prompt.pricing.to_val());
+    buf.push_back(Storage::get_reference_price(env, prompt.id).into_val(env));
+}
 
-    // Escrow is created as Pending — the creator's share is held in
-    // the contract until `settle_purchase` is called (#454).
-    let now = env.ledger().timestamp();
-    let fee_wallet = InstanceStorage::get_fee_wallet(env).ok_or(Error::FeeWalletNotSet)?;
+fn hash_quote_terms(env: &Env, buf: &Vec<Val>) -> BytesN<32> {
+    env.crypto().sha256(&buf.to_xdr(env)).to_bytes()
+}
 
-    // Snapshot the complete payout plan at purchase time (#562).
-    let mut payout_splits: Vec<super::types::PayoutSplit> = Vec::new(env);
-    let mut split_total: i128 = 0;
-    for i in 0..prompt.splits.len() {
-        let split = prompt.splits.get(i).unwrap();
-        let split_amount = payment_amount_stroops
-            .checked_mul(split.bps as i128)
-            .ok_or(Error::ArithmeticOverflow)?
-            / MAX_BPS as i128;
-        split_total = split_total
-            .checked_add(split_amount)
-            .ok_or(Error::ArithmeticOverflow)?;
-        if split_amount > 0 {
-            payout_splits.push_back(super::types::PayoutSplit {
-                recipient: split.recipient.clone(),
-                amount: split_amount,
-            });
+/// Live terms hash for a single-item acquisition.
+fn quote_terms_hash(
+    env: &Env,
+    kind: AcquisitionKind,
+    acquisition_id: u128,
+) -> Result<BytesN<32>, Error> {
+    let mut buf = quote_terms_prefix(env, kind.clone());
+    match kind {
+        AcquisitionKind::DirectPurchase => {
+            let prompt = Storage::require_prompt(env, quote_prompt_id(acquisition_id)?)?;
+            push_prompt_terms(env, &mut buf, &prompt);
+        }
+        AcquisitionKind::Bundle => {
+            let bundle = Storage::require_bundle(env, acquisition_id)?;
+            buf.push_back(acquisition_id.into_val(env));
+            buf.push_back(bundle.price_stroops.into_val(env));
+            buf.push_back(bundle.asset.to_val());
+            for index in 0..bundle.prompt_ids.len() {
+                let prompt = Storage::require_prompt(env, bundle.prompt_ids.get(index).unwrap())?;
+                push_prompt_terms(env, &mut buf, &prompt);
+            }
+        }
+        AcquisitionKind::AccessPass => {
+            let access_pass = Storage::require_access_pass(env, acquisition_id)?;
+            buf.push_back(acquisition_id.into_val(env));
+            buf.push_back(access_pass.price_stroops.into_val(env));
+            buf.push_back(access_pass.asset.to_val());
+            buf.push_back((access_pass.duration_secs as u128).into_val(env));
+        }
+        AcquisitionKind::Lease
+        | AcquisitionKind::BulkCheckout
+        | AcquisitionKind::ResaleFill
+        | AcquisitionKind::LicenseTokenTransfer
+        | AcquisitionKind::Auction
+        | AcquisitionKind::FreeClaim => {
+            return Err(Error::QuoteAcquisitionMismatch);
         }
     }
+    Ok(hash_quote_terms(env, &buf))
+}
 
-    let payout_plan = super::types::PayoutPlan {
-        creator: prompt.creator.clone(),
-        fee_wallet: fee_wallet.clone(),
-        fee_amount,
-        referrer: referrer.clone(),
-        referral_amount,
-        splits: payout_splits,
-        creator_amount,
-    };
-
-    let escrow = PurchaseEscrow {
-        prompt_id,
-        buyer: buyer.clone(),
-        amount: payment_amount_stroops,
-        asset: prompt.asset.clone(),
-        referrer: referrer.clone(),
-        status: SettlementStatus::Pending,
-        created_at: now,
-        settled_at: 0,
-        dispute_deadline: now
-            .checked_add(DISPUTE_WINDOW_SECS)
-            .ok_or(Error::ArithmeticOverflow)?,
-        creator_amount,
-        fee_amount,
-        referral_amount,
-        payout_plan,
-    };
-    Storage::save_purchase_escrow(env, &escrow);
-    // Escrow was just created Pending — its full amount is now tracked
-    // liability for this asset until settled or refunded (#570).
-    Storage::add_pending_liability(env, &escrow.asset, escrow.amount)?;
-
-    InstanceStorage::clear_reentrancy_guard(env);
-
-    Events::emit_prompt_purchased(
-        env,
-        prompt_id,
-        buyer.clone(),
-        prompt.creator,
-        payment_amount_stroops,
-        referrer.clone(),
-    );
+/// Live terms hash for a lease, which also commits to the lease duration
+/// and the listing's lease terms, since those set the lease price.
+fn lease_quote_terms_hash(
+    env: &Env,
+    prompt_id: u64,
+    lease_duration_secs: u64,
+) -> Result<BytesN<32>, Error> {
+    let prompt = Storage::require_prompt(env, prompt_id)?;
+    let mut buf = quote_terms_prefix(env, AcquisitionKind::Lease);
+    push_prompt_terms(env, &mut buf, &prompt);
+    buf.push_back((lease_duration_secs as u128).into_val(env));
+    buf.push_back(Storage::get_lease_terms(env, prompt_id).into_val(env));
+    Ok(hash_quote_terms(env, &buf))
+}
 
-    if payment_amount_stroops > required_price {
-        Events::emit_prompt_tipped(
-            env,
-            prompt_id,
-            buyer.clone(),
-            payment_amount_stroops - required_price,
-        );
+/// Live terms hash for a bulk checkout, covering every prompt in order.
+fn bulk_quote_terms_hash(env: &Env, prompt_ids: &Vec<u64>) -> Result<BytesN<32>, Error> {
+    let mut buf = quote_terms_prefix(env, AcquisitionKind::BulkCheckout);
+    for index in 0..prompt_ids.len() {
+        let prompt = Storage::require_prompt(env, prompt_ids.get(index).unwrap())?;
+        push_prompt_terms(env, &mut buf, &prompt);
     }
-
-    Ok(())
+    Ok(hash_quote_terms(env, &buf))
 }
 
 // ─── Supply accounting ─────────────────────────────────────────────────────
@@ -2051,8 +5666,9 @@ fn execute_buy_with_required_price(
 // Every acquisition path that grants a new unit of access (direct purchase,
 // bulk purchase, lease, bundle) must reserve supply through these helpers so
 // `max_supply` means the same thing everywhere (#538). Resale
-// (`transfer_license`) intentionally does not call these — it moves an
-// already-reserved unit between owners rather than minting a new one.
+// (`fill_resale_order`, `transfer_license`) intentionally does not call
+// these — it moves an already-reserved unit between owners rather than
+// minting a new one.
 
 /// Atomically check-and-reserve one unit of a prompt's capped supply.
 /// `max_supply == 0` means unlimited.
@@ -2136,7 +5752,8 @@ fn validate_prompt_fields(
     wrapped_key: &String,
     price_stroops: i128,
 ) -> Result<(), Error> {
-    ensure(price_stroops > 0, Error::InvalidPrice)?;
+    // Zero lists the prompt for free claims.
+    ensure(price_stroops >= 0, Error::InvalidPrice)?;
     validate_len(image_url, MAX_IMAGE_URL_LEN, Error::InvalidImageUrlLength)?;
     validate_len(title, MAX_TITLE_LEN, Error::InvalidTitleLength)?;
     validate_len(category, MAX_CATEGORY_LEN, Error::InvalidCategoryLength)?;
@@ -2167,6 +5784,132 @@ fn validate_tags(tags: &Vec<String>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Extra listing prices: positive, at most one per asset, never repeating
+/// the primary asset, and each asset must be a token contract.
+fn validate_pricing(
+    env: &Env,
+    primary_asset: &Address,
+    pricing: &Vec<PricingConfig>,
+) -> Result<(), Error> {
+    ensure(pricing.len() <= MAX_PRICING_ASSETS, Error::InvalidPricing)?;
+    for i in 0..pricing.len() {
+        let entry = pricing.get(i).unwrap();
+        ensure(entry.price > 0, Error::InvalidPricing)?;
+        ensure(entry.asset != *primary_asset, Error::InvalidPricing)?;
+        for j in (i + 1)..pricing.len() {
+            ensure(
+                entry.asset != pricing.get(j).unwrap().asset,
+                Error::InvalidPricing,
+            )?;
+        }
+        token::Client::new(env, &entry.asset).decimals();
+    }
+    Ok(())
+}
+
+/// Price of `prompt` in `asset`: the primary price for the primary asset,
+/// otherwise the matching extra price. `InvalidAsset` if not accepted.
+fn price_in(prompt: &Prompt, asset: &Address) -> Result<i128, Error> {
+    if prompt.asset == *asset {
+        return Ok(prompt.price_stroops);
+    }
+    prompt
+        .pricing
+        .iter()
+        .find(|entry| entry.asset == *asset)
+        .map(|entry| entry.price)
+        .ok_or(Error::InvalidAsset)
+}
+
+/// Live price of `prompt` in `asset`. A reference-priced listing is
+/// converted through the oracle when bought in its primary asset; the
+/// conversion is returned so it can be recorded in the payout plan. A
+/// pay-what-you-want listing costs at least its minimum.
+fn listing_price(
+    env: &Env,
+    prompt: &Prompt,
+    asset: &Address,
+) -> Result<(i128, Option<OracleConversion>), Error> {
+    if prompt.asset == *asset {
+        if let Some(reference_amount) = Storage::get_reference_price(env, prompt.id) {
+            let conversion = oracle_conversion(env, asset, reference_amount)?;
+            return Ok((converted_amount(env, asset, &conversion)?, Some(conversion)));
+        }
+        if let Some(minimum) = Storage::get_pay_what_you_want(env, prompt.id) {
+            return Ok((minimum, None));
+        }
+    }
+    Ok((price_in(prompt, asset)?, None))
+}
+
+/// Latest oracle rate for `asset`, rejecting missing and stale prices.
+fn oracle_conversion(
+    env: &Env,
+    asset: &Address,
+    reference_amount: i128,
+) -> Result<OracleConversion, Error> {
+    let config = InstanceStorage::get_price_oracle(env).ok_or(Error::OracleNotConfigured)?;
+    let oracle = PriceOracleClient::new(env, &config.oracle);
+    let price = oracle
+        .lastprice(&OracleAsset::Stellar(asset.clone()))
+        .ok_or(Error::OraclePriceUnavailable)?;
+    ensure(price.price > 0, Error::OraclePriceUnavailable)?;
+    // A price stamped after the current ledger cannot be trusted, and would
+    // otherwise pass the age check however old it really is.
+    let now = env.ledger().timestamp();
+    ensure(price.timestamp <= now, Error::OraclePriceUnavailable)?;
+    ensure(
+        now - price.timestamp <= config.max_age_secs,
+        Error::StaleOraclePrice,
+    )?;
+    Ok(OracleConversion {
+        reference_amount,
+        oracle_price: price.price,
+        oracle_decimals: oracle.decimals(),
+        price_timestamp: price.timestamp,
+    })
+}
+
+/// Amount of `asset`, in its smallest unit, worth the reference amount at
+/// the conversion's rate. Rounds up so the creator is never short-paid.
+fn converted_amount(
+    env: &Env,
+    asset: &Address,
+    conversion: &OracleConversion,
+) -> Result<i128, Error> {
+    let asset_decimals = token::Client::new(env, asset).decimals();
+    let scale = 10i128
+        .checked_pow(conversion.oracle_decimals + asset_decimals)
+        .ok_or(Error::ArithmeticOverflow)?;
+    let numerator = conversion
+        .reference_amount
+        .checked_mul(scale)
+        .ok_or(Error::ArithmeticOverflow)?;
+    let denominator = conversion
+        .oracle_price
+        .checked_mul(10i128.pow(REFERENCE_PRICE_DECIMALS))
+        .ok_or(Error::ArithmeticOverflow)?;
+    numerator
+        .checked_add(denominator - 1)
+        .map(|rounded| rounded / denominator)
+        .ok_or(Error::ArithmeticOverflow)
+}
+
+/// Royalty splits share the resale royalty itself, so unlike primary-sale
+/// splits they are not bounded against the platform fee.
+fn validate_royalty_splits(splits: &Vec<Split>) -> Result<(), Error> {
+    ensure(splits.len() <= MAX_SPLITS, Error::TooManySplits)?;
+    let mut total_bps: u32 = 0;
+    for split in splits.iter() {
+        ensure(split.bps > 0, Error::InvalidSplits)?;
+        total_bps = total_bps
+            .checked_add(split.bps)
+            .ok_or(Error::ArithmeticOverflow)?;
+    }
+    ensure(total_bps <= MAX_BPS, Error::InvalidSplits)?;
+    validate_no_duplicate_recipients(splits)
+}
+
 fn validate_no_duplicate_recipients(splits: &Vec<Split>) -> Result<(), Error> {
     for i in 0..splits.len() {
         for j in (i + 1)..splits.len() {
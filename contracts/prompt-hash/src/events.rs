@@ -1,5 +1,8 @@
-use super::types::PromptSaleStatus;
-use soroban_sdk::{contractevent, Address, BytesN, Env};
+use super::types::{
+    AcquisitionKind, AuctionKind, DisputeWindowConfig, GovernanceAction, PricingConfig,
+    PromptSaleStatus, SubscriptionEndReason,
+};
+use soroban_sdk::{contractevent, Address, BytesN, Env, String, Vec};
 
 #[contractevent]
 struct PromptCreated {
@@ -32,6 +35,40 @@ struct PromptPriceUpdated {
     pub price_stroops: i128,
 }
 
+#[contractevent]
+struct ListingPricingUpdated {
+    #[topic]
+    pub prompt_id: u64,
+    pub pricing: Vec<PricingConfig>,
+}
+
+#[contractevent]
+struct PriceOracleUpdated {
+    pub oracle: Address,
+    pub max_age_secs: u64,
+}
+
+#[contractevent]
+struct ReferencePriceUpdated {
+    #[topic]
+    pub prompt_id: u64,
+    pub amount: Option<i128>,
+}
+
+#[contractevent]
+struct PromptClaimed {
+    #[topic]
+    pub prompt_id: u64,
+    pub buyer: Address,
+}
+
+#[contractevent]
+struct PayWhatYouWantUpdated {
+    #[topic]
+    pub prompt_id: u64,
+    pub minimum: Option<i128>,
+}
+
 #[contractevent]
 struct PromptPurchased {
     #[topic]
@@ -116,6 +153,15 @@ struct SolvencyViolationDetected {
     pub actual_balance: i128,
 }
 
+/// Emitted when a creator retags a listing, for the off-chain search indexer.
+#[contractevent]
+struct TagsUpdated {
+    #[topic]
+    pub prompt_id: u64,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
 #[contractevent]
 struct ListingExtended {
     #[topic]
@@ -159,6 +205,44 @@ struct ListingRevised {
     pub new_revision: u32,
 }
 
+#[contractevent]
+struct LeaseTermsUpdated {
+    #[topic]
+    pub prompt_id: u64,
+    pub enabled: bool,
+}
+
+#[contractevent]
+struct LeaseRenewed {
+    #[topic]
+    pub prompt_id: u64,
+    #[topic]
+    pub lessee: Address,
+    pub price: i128,
+    pub expires_at: u64,
+}
+
+/// Emitted when a lease is converted into a permanent purchase.
+#[contractevent]
+struct LeaseConverted {
+    #[topic]
+    pub prompt_id: u64,
+    #[topic]
+    pub buyer: Address,
+    pub credit: i128,
+    pub charge: i128,
+}
+
+/// Emitted when a creator ships a new content revision.
+#[contractevent]
+struct ContentRevised {
+    #[topic]
+    pub prompt_id: u64,
+    pub new_revision: u32,
+    pub previous_content_hash: BytesN<32>,
+    pub content_hash: BytesN<32>,
+}
+
 /// Emitted when a creator updates the collaborator splits (#217).
 #[contractevent]
 struct SplitsUpdated {
@@ -173,12 +257,137 @@ struct DisputeOpened {
     pub buyer: Address,
 }
 
+#[contractevent]
+struct ArbiterRegistered {
+    #[topic]
+    pub arbiter: Address,
+}
+
+#[contractevent]
+struct ArbiterRemoved {
+    #[topic]
+    pub arbiter: Address,
+}
+
+/// Emitted when a panel is seated for a disputed settlement, including the
+/// appeal panel.
+#[contractevent]
+struct ArbitrationAssigned {
+    #[topic]
+    pub settlement_id: u128,
+    pub round: u32,
+    pub panel: Vec<Address>,
+    pub quorum: u32,
+}
+
+#[contractevent]
+struct ArbitrationVoteCast {
+    #[topic]
+    pub settlement_id: u128,
+    #[topic]
+    pub arbiter: Address,
+    pub refund: bool,
+}
+
+/// Emitted when a panel reaches quorum. A first-round decision only takes
+/// effect once its appeal window closes.
+#[contractevent]
+struct ArbitrationDecided {
+    #[topic]
+    pub settlement_id: u128,
+    pub round: u32,
+    pub refund: bool,
+}
+
+#[contractevent]
+struct ArbitrationAppealed {
+    #[topic]
+    pub settlement_id: u128,
+    pub appellant: Address,
+}
+
+#[contractevent]
+struct DisputeEvidenceSubmitted {
+    #[topic]
+    pub settlement_id: u128,
+    #[topic]
+    pub submitter: Address,
+    pub evidence_hash: BytesN<32>,
+}
+
 #[contractevent]
 struct DisputeResolved {
     #[topic]
     pub prompt_id: u64,
     pub buyer: Address,
     pub refunded: bool,
+    pub refund_amount: i128,
+}
+
+#[contractevent]
+struct DisputeWindowPolicyUpdated {
+    #[topic]
+    pub kind: AcquisitionKind,
+    pub window_secs: u64,
+}
+
+#[contractevent]
+struct DisputeWindowConfigUpdated {
+    pub default_secs: u64,
+    pub min_secs: u64,
+    pub max_secs: u64,
+}
+
+#[contractevent]
+struct ListingDisputeWindowUpdated {
+    #[topic]
+    pub prompt_id: u64,
+    pub window_secs: Option<u64>,
+}
+
+#[contractevent]
+struct DisputeBondUpdated {
+    pub bond_bps: u32,
+}
+
+#[contractevent]
+struct BuyerDisputeBondUpdated {
+    #[topic]
+    pub buyer: Address,
+    pub bond_bps: Option<u32>,
+}
+
+/// Emitted when a dispute bond leaves escrow: back to the buyer after a
+/// refund, or to the creator when `forfeited`.
+#[contractevent]
+struct DisputeBondReleased {
+    #[topic]
+    pub settlement_id: u128,
+    pub recipient: Address,
+    pub amount: i128,
+    pub forfeited: bool,
+}
+
+#[contractevent]
+struct BalanceWithdrawn {
+    #[topic]
+    pub recipient: Address,
+    #[topic]
+    pub asset: Address,
+    pub amount: i128,
+}
+
+/// Emitted when a creator voluntarily refunds a buyer. `settled` tells
+/// whether the refund came from escrow or from the creator's own balance.
+#[contractevent]
+struct CreatorRefunded {
+    #[topic]
+    pub prompt_id: u64,
+    #[topic]
+    pub buyer: Address,
+    pub creator: Address,
+    pub amount: i128,
+    pub settled: bool,
 }
 
 #[contractevent]
@@ -243,6 +452,220 @@ struct PromptMaxSupplyUpdated {
     pub max_supply: u64,
 }
 
+/// Emitted when a quote commitment is redeemed by any checkout path (#565).
+/// `amount_charged` is in `asset`, the quoted asset, and excludes the tip,
+/// which is reported separately.
+#[contractevent]
+struct QuoteRedeemed {
+    #[topic]
+    pub buyer: Address,
+    pub kind: AcquisitionKind,
+    pub acquisition_id: u128,
+    pub amount_charged: i128,
+    pub asset: Address,
+    pub tip_amount: i128,
+    pub nonce: BytesN<32>,
+}
+
+/// A new append-only settlement record was allocated (#567).
+#[contractevent]
+struct SettlementRecorded {
+    #[topic]
+    pub settlement_id: u128,
+    #[topic]
+    pub buyer: Address,
+    pub kind: AcquisitionKind,
+    pub acquisition_id: u128,
+    pub amount: i128,
+    pub supersedes: Option<u128>,
+}
+
+/// A seller posted a resale order (#568).
+#[contractevent]
+struct ResaleOrderPosted {
+    #[topic]
+    pub seller: Address,
+    #[topic]
+    pub prompt_id: u64,
+    pub order_hash: BytesN<32>,
+    pub price: i128,
+    pub buyer: Option<Address>,
+}
+
+#[contractevent]
+struct ResaleOrderCancelled {
+    #[topic]
+    pub seller: Address,
+    pub order_hash: BytesN<32>,
+}
+
+#[contractevent]
+struct ResaleOrderFilled {
+    #[topic]
+    pub buyer: Address,
+    #[topic]
+    pub prompt_id: u64,
+    pub order_hash: BytesN<32>,
+    pub seller: Address,
+    pub settlement_id: u128,
+}
+
+/// A governance action entered its observation window (#569).
+#[contractevent]
+struct GovernanceProposed {
+    #[topic]
+    pub proposal_hash: BytesN<32>,
+    pub action: GovernanceAction,
+    pub proposer: Address,
+    pub executable_at_ledger: u32,
+    pub expiry_ledger: u32,
+}
+
+#[contractevent]
+struct GovernanceExecuted {
+    #[topic]
+    pub proposal_hash: BytesN<32>,
+    pub action: GovernanceAction,
+    pub executor: Address,
+}
+
+#[contractevent]
+struct GovernanceCancelled {
+    #[topic]
+    pub proposal_hash: BytesN<32>,
+    pub action: GovernanceAction,
+}
+
+#[contractevent]
+struct EncryptionKeyRegistered {
+    #[topic]
+    pub buyer: Address,
+    pub public_key: BytesN<32>,
+}
+
+#[contractevent]
+struct WrappedKeyDelivered {
+    #[topic]
+    pub prompt_id: u64,
+    #[topic]
+    pub buyer: Address,
+    pub delivered_by: Address,
+    pub recipient_key: BytesN<32>,
+}
+
+#[contractevent]
+struct PassSubscribed {
+    #[topic]
+    pub pass_id: u128,
+    #[topic]
+    pub buyer: Address,
+    pub max_price: i128,
+    pub max_periods: u32,
+}
+
+#[contractevent]
+struct PassRenewed {
+    #[topic]
+    pub pass_id: u128,
+    #[topic]
+    pub buyer: Address,
+    pub price: i128,
+    pub periods_remaining: u32,
+}
+
+#[contractevent]
+struct PassSubscriptionEnded {
+    #[topic]
+    pub pass_id: u128,
+    #[topic]
+    pub buyer: Address,
+    pub reason: SubscriptionEndReason,
+}
+
+#[contractevent]
+struct AuctionStarted {
+    #[topic]
+    pub prompt_id: u64,
+    pub kind: AuctionKind,
+    pub start_price: i128,
+    pub reserve_price: i128,
+}
+
+#[contractevent]
+struct AuctionBidPlaced {
+    #[topic]
+    pub prompt_id: u64,
+    pub bidder: Address,
+    pub amount: i128,
+    /// Outbid bidder refunded by this bid, if any.
+    pub refunded: Option<Address>,
+}
+
+#[contractevent]
+struct AuctionSale {
+    #[topic]
+    pub prompt_id: u64,
+    pub buyer: Address,
+    pub price: i128,
+}
+
+#[contractevent]
+struct AuctionClosed {
+    #[topic]
+    pub prompt_id: u64,
+}
+
+// License token events use the SEP-50 topic names so wallets and indexers
+// that track non-fungible tokens pick them up unchanged.
+
+#[contractevent(topics = ["mint"], data_format = "single-value")]
+struct LicenseTokenMinted {
+    #[topic]
+    pub to: Address,
+    pub token_id: u32,
+}
+
+#[contractevent(topics = ["transfer"], data_format = "single-value")]
+struct LicenseTokenTransferred {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub token_id: u32,
+}
+
+#[contractevent(topics = ["burn"], data_format = "single-value")]
+struct LicenseTokenBurned {
+    #[topic]
+    pub from: Address,
+    pub token_id: u32,
+}
+
+#[contractevent(topics = ["approve"])]
+struct LicenseTokenApproved {
+    #[topic]
+    pub approver: Address,
+    #[topic]
+    pub token_id: u32,
+    pub approved: Address,
+    pub live_until_ledger: u32,
+}
+
+#[contractevent(topics = ["approve_for_all"])]
+struct LicenseTokenApprovedForAll {
+    #[topic]
+    pub owner: Address,
+    pub operator: Address,
+    pub live_until_ledger: u32,
+}
+
+#[contractevent]
+struct LicenseTokenModeUpdated {
+    #[topic]
+    pub prompt_id: u64,
+    pub enabled: bool,
+}
+
 pub struct Events;
 
 impl Events {
@@ -288,6 +711,39 @@ impl Events {
         .publish(env);
     }
 
+    pub fn emit_listing_pricing_updated(env: &Env, prompt_id: u64, pricing: Vec<PricingConfig>) {
+        ListingPricingUpdated { prompt_id, pricing }.publish(env);
+    }
+
+    pub fn emit_price_oracle_updated(env: &Env, oracle: Address, max_age_secs: u64) {
+        PriceOracleUpdated {
+            oracle,
+            max_age_secs,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_reference_price_updated(env: &Env, prompt_id: u64, amount: Option<i128>) {
+        ReferencePriceUpdated { prompt_id, amount }.publish(env);
+    }
+
+    pub fn emit_tags_updated(env: &Env, prompt_id: u64, added: Vec<String>, removed: Vec<String>) {
+        TagsUpdated {
+            prompt_id,
+            added,
+            removed,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_prompt_claimed(env: &Env, prompt_id: u64, buyer: Address) {
+        PromptClaimed { prompt_id, buyer }.publish(env);
+    }
+
+    pub fn emit_pay_what_you_want_updated(env: &Env, prompt_id: u64, minimum: Option<i128>) {
+        PayWhatYouWantUpdated { prompt_id, minimum }.publish(env);
+    }
+
     pub fn emit_prompt_purchased(
         env: &Env,
         prompt_id: u64,
@@ -454,6 +910,58 @@ impl Events {
         .publish(env);
     }
 
+    pub fn emit_lease_terms_updated(env: &Env, prompt_id: u64, enabled: bool) {
+        LeaseTermsUpdated { prompt_id, enabled }.publish(env);
+    }
+
+    pub fn emit_lease_renewed(
+        env: &Env,
+        prompt_id: u64,
+        lessee: Address,
+        price: i128,
+        expires_at: u64,
+    ) {
+        LeaseRenewed {
+            prompt_id,
+            lessee,
+            price,
+            expires_at,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_lease_converted(
+        env: &Env,
+        prompt_id: u64,
+        buyer: Address,
+        credit: i128,
+        charge: i128,
+    ) {
+        LeaseConverted {
+            prompt_id,
+            buyer,
+            credit,
+            charge,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_content_revised(
+        env: &Env,
+        prompt_id: u64,
+        new_revision: u32,
+        previous_content_hash: BytesN<32>,
+        content_hash: BytesN<32>,
+    ) {
+        ContentRevised {
+            prompt_id,
+            new_revision,
+            previous_content_hash,
+            content_hash,
+        }
+        .publish(env);
+    }
+
     pub fn emit_splits_updated(env: &Env, prompt_id: u64) {
         SplitsUpdated { prompt_id }.publish(env);
     }
@@ -462,11 +970,163 @@ impl Events {
         DisputeOpened { prompt_id, buyer }.publish(env);
     }
 
-    pub fn emit_dispute_resolved(env: &Env, prompt_id: u64, buyer: Address, refunded: bool) {
+    pub fn emit_arbiter_registered(env: &Env, arbiter: Address) {
+        ArbiterRegistered { arbiter }.publish(env);
+    }
+
+    pub fn emit_arbiter_removed(env: &Env, arbiter: Address) {
+        ArbiterRemoved { arbiter }.publish(env);
+    }
+
+    pub fn emit_arbitration_assigned(
+        env: &Env,
+        settlement_id: u128,
+        round: u32,
+        panel: Vec<Address>,
+        quorum: u32,
+    ) {
+        ArbitrationAssigned {
+            settlement_id,
+            round,
+            panel,
+            quorum,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_arbitration_vote_cast(
+        env: &Env,
+        settlement_id: u128,
+        arbiter: Address,
+        refund: bool,
+    ) {
+        ArbitrationVoteCast {
+            settlement_id,
+            arbiter,
+            refund,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_arbitration_decided(env: &Env, settlement_id: u128, round: u32, refund: bool) {
+        ArbitrationDecided {
+            settlement_id,
+            round,
+            refund,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_arbitration_appealed(env: &Env, settlement_id: u128, appellant: Address) {
+        ArbitrationAppealed {
+            settlement_id,
+            appellant,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_dispute_evidence_submitted(
+        env: &Env,
+        settlement_id: u128,
+        submitter: Address,
+        evidence_hash: BytesN<32>,
+    ) {
+        DisputeEvidenceSubmitted {
+            settlement_id,
+            submitter,
+            evidence_hash,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_dispute_resolved(
+        env: &Env,
+        prompt_id: u64,
+        buyer: Address,
+        refunded: bool,
+        refund_amount: i128,
+    ) {
         DisputeResolved {
             prompt_id,
             buyer,
             refunded,
+            refund_amount,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_dispute_window_policy_updated(env: &Env, kind: AcquisitionKind, window_secs: u64) {
+        DisputeWindowPolicyUpdated { kind, window_secs }.publish(env);
+    }
+
+    pub fn emit_dispute_window_config_updated(env: &Env, config: DisputeWindowConfig) {
+        DisputeWindowConfigUpdated {
+            default_secs: config.default_secs,
+            min_secs: config.min_secs,
+            max_secs: config.max_secs,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_listing_dispute_window_updated(
+        env: &Env,
+        prompt_id: u64,
+        window_secs: Option<u64>,
+    ) {
+        ListingDisputeWindowUpdated {
+            prompt_id,
+            window_secs,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_dispute_bond_updated(env: &Env, bond_bps: u32) {
+        DisputeBondUpdated { bond_bps }.publish(env);
+    }
+
+    pub fn emit_buyer_dispute_bond_updated(env: &Env, buyer: Address, bond_bps: Option<u32>) {
+        BuyerDisputeBondUpdated { buyer, bond_bps }.publish(env);
+    }
+
+    pub fn emit_dispute_bond_released(
+        env: &Env,
+        settlement_id: u128,
+        recipient: Address,
+        amount: i128,
+        forfeited: bool,
+    ) {
+        DisputeBondReleased {
+            settlement_id,
+            recipient,
+            amount,
+            forfeited,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_balance_withdrawn(env: &Env, recipient: Address, asset: Address, amount: i128) {
+        BalanceWithdrawn {
+            recipient,
+            asset,
+            amount,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_creator_refunded(
+        env: &Env,
+        prompt_id: u64,
+        buyer: Address,
+        creator: Address,
+        amount: i128,
+        settled: bool,
+    ) {
+        CreatorRefunded {
+            prompt_id,
+            buyer,
+            creator,
+            amount,
+            settled,
         }
         .publish(env);
     }
@@ -549,4 +1209,287 @@ impl Events {
         }
         .publish(env);
     }
+
+    pub fn emit_quote_redeemed(
+        env: &Env,
+        buyer: Address,
+        kind: AcquisitionKind,
+        acquisition_id: u128,
+        amount_charged: i128,
+        asset: Address,
+        tip_amount: i128,
+        nonce: BytesN<32>,
+    ) {
+        QuoteRedeemed {
+            buyer,
+            kind,
+            acquisition_id,
+            amount_charged,
+            asset,
+            tip_amount,
+            nonce,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_settlement_recorded(
+        env: &Env,
+        settlement_id: u128,
+        buyer: Address,
+        kind: AcquisitionKind,
+        acquisition_id: u128,
+        amount: i128,
+        supersedes: Option<u128>,
+    ) {
+        SettlementRecorded {
+            settlement_id,
+            buyer,
+            kind,
+            acquisition_id,
+            amount,
+            supersedes,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_resale_order_posted(
+        env: &Env,
+        seller: Address,
+        prompt_id: u64,
+        order_hash: BytesN<32>,
+        price: i128,
+        buyer: Option<Address>,
+    ) {
+        ResaleOrderPosted {
+            seller,
+            prompt_id,
+            order_hash,
+            price,
+            buyer,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_resale_order_cancelled(env: &Env, seller: Address, order_hash: BytesN<32>) {
+        ResaleOrderCancelled { seller, order_hash }.publish(env);
+    }
+
+    pub fn emit_resale_order_filled(
+        env: &Env,
+        buyer: Address,
+        prompt_id: u64,
+        order_hash: BytesN<32>,
+        seller: Address,
+        settlement_id: u128,
+    ) {
+        ResaleOrderFilled {
+            buyer,
+            prompt_id,
+            order_hash,
+            seller,
+            settlement_id,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_governance_proposed(
+        env: &Env,
+        proposal_hash: BytesN<32>,
+        action: GovernanceAction,
+        proposer: Address,
+        executable_at_ledger: u32,
+        expiry_ledger: u32,
+    ) {
+        GovernanceProposed {
+            proposal_hash,
+            action,
+            proposer,
+            executable_at_ledger,
+            expiry_ledger,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_governance_executed(
+        env: &Env,
+        proposal_hash: BytesN<32>,
+        action: GovernanceAction,
+        executor: Address,
+    ) {
+        GovernanceExecuted {
+            proposal_hash,
+            action,
+            executor,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_governance_cancelled(
+        env: &Env,
+        proposal_hash: BytesN<32>,
+        action: GovernanceAction,
+    ) {
+        GovernanceCancelled {
+            proposal_hash,
+            action,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_license_token_minted(env: &Env, to: Address, token_id: u32) {
+        LicenseTokenMinted { to, token_id }.publish(env);
+    }
+
+    pub fn emit_license_token_transferred(env: &Env, from: Address, to: Address, token_id: u32) {
+        LicenseTokenTransferred { from, to, token_id }.publish(env);
+    }
+
+    pub fn emit_license_token_burned(env: &Env, from: Address, token_id: u32) {
+        LicenseTokenBurned { from, token_id }.publish(env);
+    }
+
+    pub fn emit_license_token_approved(
+        env: &Env,
+        approver: Address,
+        token_id: u32,
+        approved: Address,
+        live_until_ledger: u32,
+    ) {
+        LicenseTokenApproved {
+            approver,
+            token_id,
+            approved,
+            live_until_ledger,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_license_token_approved_for_all(
+        env: &Env,
+        owner: Address,
+        operator: Address,
+        live_until_ledger: u32,
+    ) {
+        LicenseTokenApprovedForAll {
+            owner,
+            operator,
+            live_until_ledger,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_license_token_mode_updated(env: &Env, prompt_id: u64, enabled: bool) {
+        LicenseTokenModeUpdated { prompt_id, enabled }.publish(env);
+    }
+
+    pub fn emit_auction_started(
+        env: &Env,
+        prompt_id: u64,
+        kind: AuctionKind,
+        start_price: i128,
+        reserve_price: i128,
+    ) {
+        AuctionStarted {
+            prompt_id,
+            kind,
+            start_price,
+            reserve_price,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_auction_bid_placed(
+        env: &Env,
+        prompt_id: u64,
+        bidder: Address,
+        amount: i128,
+        refunded: Option<Address>,
+    ) {
+        AuctionBidPlaced {
+            prompt_id,
+            bidder,
+            amount,
+            refunded,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_auction_sale(env: &Env, prompt_id: u64, buyer: Address, price: i128) {
+        AuctionSale {
+            prompt_id,
+            buyer,
+            price,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_auction_closed(env: &Env, prompt_id: u64) {
+        AuctionClosed { prompt_id }.publish(env);
+    }
+
+    pub fn emit_pass_subscribed(
+        env: &Env,
+        pass_id: u128,
+        buyer: Address,
+        max_price: i128,
+        max_periods: u32,
+    ) {
+        PassSubscribed {
+            pass_id,
+            buyer,
+            max_price,
+            max_periods,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_pass_renewed(
+        env: &Env,
+        pass_id: u128,
+        buyer: Address,
+        price: i128,
+        periods_remaining: u32,
+    ) {
+        PassRenewed {
+            pass_id,
+            buyer,
+            price,
+            periods_remaining,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_pass_subscription_ended(
+        env: &Env,
+        pass_id: u128,
+        buyer: Address,
+        reason: SubscriptionEndReason,
+    ) {
+        PassSubscriptionEnded {
+            pass_id,
+            buyer,
+            reason,
+        }
+        .publish(env);
+    }
+
+    pub fn emit_encryption_key_registered(env: &Env, buyer: Address, public_key: BytesN<32>) {
+        EncryptionKeyRegistered { buyer, public_key }.publish(env);
+    }
+
+    pub fn emit_wrapped_key_delivered(
+        env: &Env,
+        prompt_id: u64,
+        buyer: Address,
+        delivered_by: Address,
+        recipient_key: BytesN<32>,
+    ) {
+        WrappedKeyDelivered {
+            prompt_id,
+            buyer,
+            delivered_by,
+            recipient_key,
+        }
+        .publish(env);
+    }
 }
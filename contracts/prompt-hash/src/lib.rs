@@ -4,6 +4,7 @@
 
 mod contract;
 mod events;
+mod oracle;
 mod pagination;
 mod pagination_test;
 mod storage;
@@ -14,6 +15,8 @@ mod types;
 
 #[cfg(test)]
 mod mock_asset;
+#[cfg(test)]
+mod mock_oracle;
 
 pub use contract::PromptHashContract;
 pub use types::{DataKey, Error, Prompt};
@@ -0,0 +1,35 @@
+use crate::oracle::{OracleAsset, PriceData};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+#[contracttype]
+enum MockOracleKey {
+    Price(Address),
+}
+
+/// Minimal SEP-40 feed for tests: prices are set directly per asset and
+/// reported with 14 decimals, like the public Reflector feeds.
+#[contract]
+pub struct MockPriceOracle;
+
+#[contractimpl]
+impl MockPriceOracle {
+    pub fn set_price(e: Env, asset: Address, price: i128, timestamp: u64) {
+        e.storage().instance().set(
+            &MockOracleKey::Price(asset),
+            &PriceData { price, timestamp },
+        );
+    }
+
+    pub fn decimals(_e: Env) -> u32 {
+        14
+    }
+
+    pub fn lastprice(e: Env, asset: OracleAsset) -> Option<PriceData> {
+        match asset {
+            OracleAsset::Stellar(address) => {
+                e.storage().instance().get(&MockOracleKey::Price(address))
+            }
+            OracleAsset::Other(_) => None,
+        }
+    }
+}
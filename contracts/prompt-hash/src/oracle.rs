@@ -0,0 +1,30 @@
+//! Client side of the SEP-40 price-feed interface, used to convert
+//! reference-priced listings into their payment asset at purchase time.
+//! Only the calls this contract makes are declared.
+
+use soroban_sdk::{contractclient, contracttype, Address, Env, Symbol};
+
+/// Asset identifier as defined by SEP-40.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OracleAsset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// A price quote: `price` units of the oracle's base asset per whole unit
+/// of the quoted asset, scaled by the oracle's `decimals`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    /// Number of decimals every reported price is scaled by.
+    fn decimals(env: Env) -> u32;
+    /// Most recent price for `asset`, if the feed has one.
+    fn lastprice(env: Env, asset: OracleAsset) -> Option<PriceData>;
+}
@@ -1,15 +1,47 @@
 // Cursor pagination for bounded catalog queries.
 // Prevents full-catalog scans and respects Soroban resource limits.
 
-use crate::types::Error;
-use soroban_sdk::{Env, String as SorobanString};
+use crate::types::{CatalogFilter, CatalogSort, Error, PromptIndex};
+use soroban_sdk::{xdr::ToXdr, Env, String as SorobanString};
 
 pub const MAX_PAGE_SIZE: u64 = 50;
+/// Prompts reconciled per `rebuild_indexes` call, and legacy index
+/// entries moved per `migrate_index` call.
+pub const MAX_INDEX_REBUILD_BATCH: u64 = 6;
+/// Legacy index entries moved into buckets whenever that index is written.
+pub const INDEX_MIGRATION_STEP: u32 = 2;
+/// Cursor bucket marking a position in an index's unmigrated legacy vector.
+pub const LEGACY_INDEX_BUCKET: u32 = u32::MAX;
+/// Prompt IDs per index bucket.
+pub const INDEX_BUCKET_SIZE: u32 = 100;
+/// Bucket heads per page of a sorted index's directory.
+pub const SORT_PAGE_SIZE: u32 = 100;
+/// Index entries examined per `query_prompts` call, however few match.
+pub const MAX_QUERY_SCAN: u32 = 200;
+
+/// Where a page stopped inside a sharded index: the bucket, the offset
+/// within it, and the prompt found there.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexPosition {
+    pub bucket: u32,
+    pub position: u32,
+    pub last_id: u64,
+}
+
+/// Where a catalog query stopped in its sort order: the sort key and ID of
+/// the last prompt examined.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SortPosition {
+    pub key: i128,
+    pub last_id: u64,
+}
 
 #[derive(Clone)]
 pub struct Cursor {
-    pub last_id: u64,
+    pub version: u8,
     pub index_type: IndexType,
+    pub fingerprint: [u8; FINGERPRINT_LEN],
+    pub position: IndexPosition,
 }
 
 #[derive(Clone, PartialEq)]
@@ -19,6 +51,8 @@ pub enum IndexType {
     Tag = 2,
     Active = 3,
     All = 4,
+    Buyer = 5,
+    Query = 6,
 }
 
 impl IndexType {
@@ -29,48 +63,208 @@ impl IndexType {
             2 => Some(IndexType::Tag),
             3 => Some(IndexType::Active),
             4 => Some(IndexType::All),
+            5 => Some(IndexType::Buyer),
+            6 => Some(IndexType::Query),
             _ => None,
         }
     }
+
+    pub fn of(index: &PromptIndex) -> Self {
+        match index {
+            PromptIndex::All => IndexType::All,
+            PromptIndex::Active => IndexType::Active,
+            PromptIndex::Category(_) => IndexType::Category,
+            PromptIndex::Tag(_) => IndexType::Tag,
+            PromptIndex::Creator(_) => IndexType::Creator,
+            PromptIndex::Buyer(_) => IndexType::Buyer,
+        }
+    }
+}
+
+// Cursors are opaque to callers. Version 1 is laid out as:
+//
+//   [0]      version
+//   [1]      index-type discriminant
+//   [2..10]  fingerprint: leading bytes of sha256 over the index key's XDR
+//   [10..14] bucket, big-endian
+//   [14..18] offset within the bucket, big-endian
+//   [18..26] last prompt ID, big-endian
+//
+// The fingerprint ties a cursor to the category, tag or account it was
+// issued for, and the bucket and offset let the next page resume without
+// searching the index. `soroban_sdk::String` has no std-only parsing
+// available inside the contract runtime (only under testutils), so the
+// cursor is carried as raw bytes rather than a human-readable string.
+//
+// `query_prompts` cursors share the first 10 bytes, then hold the sort
+// position instead of a bucket and offset:
+//
+//   [10..26] sort key of the last prompt examined, big-endian
+//   [26..34] last prompt ID, big-endian
+//
+// Sort keys change as listings are repriced and sold, so a query resumes
+// by searching for that key and ID rather than at a stored offset.
+pub const CURSOR_VERSION: u8 = 1;
+const FINGERPRINT_LEN: usize = 8;
+const HEADER_LEN: usize = 10;
+const CURSOR_LEN: usize = 26;
+const QUERY_CURSOR_LEN: usize = 34;
+
+/// Fingerprint of the index a cursor belongs to.
+pub fn index_fingerprint(env: &Env, index: &PromptIndex) -> [u8; FINGERPRINT_LEN] {
+    fingerprint(env, index.clone())
+}
+
+/// Fingerprint of a catalog query, so its cursors cannot resume a query
+/// with a different filter or order.
+pub fn query_fingerprint(
+    env: &Env,
+    filter: &CatalogFilter,
+    sort: CatalogSort,
+) -> [u8; FINGERPRINT_LEN] {
+    fingerprint(env, (filter.clone(), sort))
 }
 
-// Cursors are opaque to callers: 8 bytes of big-endian `last_id` followed by
-// a 1-byte index-type discriminant. `soroban_sdk::String` has no std-only
-// parsing available inside the contract runtime (only under testutils), so
-// the cursor is carried as raw bytes rather than a human-readable string.
-const CURSOR_LEN: usize = 9;
+fn fingerprint(env: &Env, key: impl ToXdr) -> [u8; FINGERPRINT_LEN] {
+    let digest = env.crypto().sha256(&key.to_xdr(env)).to_array();
+    let mut fingerprint = [0u8; FINGERPRINT_LEN];
+    fingerprint.copy_from_slice(&digest[0..FINGERPRINT_LEN]);
+    fingerprint
+}
 
 /// Encode a cursor for API transmission.
-pub fn encode_cursor(env: &Env, last_id: u64, index_type: IndexType) -> SorobanString {
+pub fn encode_cursor(env: &Env, index: &PromptIndex, position: &IndexPosition) -> SorobanString {
+    write_cursor(
+        env,
+        IndexType::of(index),
+        &index_fingerprint(env, index),
+        position,
+    )
+}
+
+/// Encode a `query_prompts` cursor.
+pub fn encode_query_cursor(
+    env: &Env,
+    filter: &CatalogFilter,
+    sort: CatalogSort,
+    position: &SortPosition,
+) -> SorobanString {
+    let mut bytes = [0u8; QUERY_CURSOR_LEN];
+    bytes[0..HEADER_LEN].copy_from_slice(&cursor_header(
+        IndexType::Query,
+        &query_fingerprint(env, filter, sort),
+    ));
+    bytes[10..26].copy_from_slice(&position.key.to_be_bytes());
+    bytes[26..34].copy_from_slice(&position.last_id.to_be_bytes());
+    SorobanString::from_bytes(env, &bytes)
+}
+
+fn write_cursor(
+    env: &Env,
+    index_type: IndexType,
+    fingerprint: &[u8; FINGERPRINT_LEN],
+    position: &IndexPosition,
+) -> SorobanString {
+    let mut bytes = [0u8; CURSOR_LEN];
+    bytes[0..HEADER_LEN].copy_from_slice(&cursor_header(index_type, fingerprint));
+    bytes[10..14].copy_from_slice(&position.bucket.to_be_bytes());
+    bytes[14..18].copy_from_slice(&position.position.to_be_bytes());
+    bytes[18..26].copy_from_slice(&position.last_id.to_be_bytes());
+    SorobanString::from_bytes(env, &bytes)
+}
+
+fn cursor_header(index_type: IndexType, fingerprint: &[u8; FINGERPRINT_LEN]) -> [u8; HEADER_LEN] {
     let type_num = match index_type {
         IndexType::Creator => 0u8,
         IndexType::Category => 1u8,
         IndexType::Tag => 2u8,
         IndexType::Active => 3u8,
         IndexType::All => 4u8,
+        IndexType::Buyer => 5u8,
+        IndexType::Query => 6u8,
     };
-    let mut bytes = [0u8; CURSOR_LEN];
-    bytes[0..8].copy_from_slice(&last_id.to_be_bytes());
-    bytes[8] = type_num;
-    SorobanString::from_bytes(env, &bytes)
+    let mut header = [0u8; HEADER_LEN];
+    header[0] = CURSOR_VERSION;
+    header[1] = type_num;
+    header[2..10].copy_from_slice(fingerprint);
+    header
 }
 
-/// Decode a cursor previously produced by [`encode_cursor`].
-pub fn decode_cursor(_env: &Env, cursor: &SorobanString) -> Result<Cursor, Error> {
+/// Parse a cursor previously produced by [`encode_cursor`] without checking
+/// which index it belongs to. Cursors of another version, including the
+/// unversioned 9-byte format, are rejected.
+pub fn parse_cursor(cursor: &SorobanString) -> Result<Cursor, Error> {
     if cursor.len() as usize != CURSOR_LEN {
         return Err(Error::InvalidCursor);
     }
 
     let mut buf = [0u8; CURSOR_LEN];
     cursor.copy_into_slice(&mut buf);
+    if buf[0] != CURSOR_VERSION {
+        return Err(Error::InvalidCursor);
+    }
+    let index_type = IndexType::from_u8(buf[1]).ok_or(Error::InvalidCursor)?;
 
-    let mut id_bytes = [0u8; 8];
-    id_bytes.copy_from_slice(&buf[0..8]);
-    let last_id = u64::from_be_bytes(id_bytes);
-    let index_type = IndexType::from_u8(buf[8]).ok_or(Error::InvalidCursor)?;
+    let mut fingerprint = [0u8; FINGERPRINT_LEN];
+    fingerprint.copy_from_slice(&buf[2..10]);
+    let mut bucket = [0u8; 4];
+    bucket.copy_from_slice(&buf[10..14]);
+    let mut position = [0u8; 4];
+    position.copy_from_slice(&buf[14..18]);
+    let mut last_id = [0u8; 8];
+    last_id.copy_from_slice(&buf[18..26]);
 
     Ok(Cursor {
-        last_id,
+        version: buf[0],
         index_type,
+        fingerprint,
+        position: IndexPosition {
+            bucket: u32::from_be_bytes(bucket),
+            position: u32::from_be_bytes(position),
+            last_id: u64::from_be_bytes(last_id),
+        },
+    })
+}
+
+/// Decode a cursor for `index`, rejecting one issued for a different index.
+pub fn decode_cursor(
+    env: &Env,
+    cursor: &SorobanString,
+    index: &PromptIndex,
+) -> Result<IndexPosition, Error> {
+    let parsed = parse_cursor(cursor)?;
+    if parsed.index_type != IndexType::of(index)
+        || parsed.fingerprint != index_fingerprint(env, index)
+    {
+        return Err(Error::InvalidCursor);
+    }
+    Ok(parsed.position)
+}
+
+/// Decode a cursor for the catalog query described by `filter` and `sort`.
+pub fn decode_query_cursor(
+    env: &Env,
+    cursor: &SorobanString,
+    filter: &CatalogFilter,
+    sort: CatalogSort,
+) -> Result<SortPosition, Error> {
+    if cursor.len() as usize != QUERY_CURSOR_LEN {
+        return Err(Error::InvalidCursor);
+    }
+
+    let mut buf = [0u8; QUERY_CURSOR_LEN];
+    cursor.copy_into_slice(&mut buf);
+    let header = cursor_header(IndexType::Query, &query_fingerprint(env, filter, sort));
+    if buf[0..HEADER_LEN] != header {
+        return Err(Error::InvalidCursor);
+    }
+
+    let mut key = [0u8; 16];
+    key.copy_from_slice(&buf[10..26]);
+    let mut last_id = [0u8; 8];
+    last_id.copy_from_slice(&buf[26..34]);
+    Ok(SortPosition {
+        key: i128::from_be_bytes(key),
+        last_id: u64::from_be_bytes(last_id),
     })
 }
@@ -1,35 +1,130 @@
 #[cfg(test)]
 mod tests {
-    use crate::pagination::{decode_cursor, encode_cursor, IndexType};
-    use soroban_sdk::Env;
+    use crate::pagination::{
+        decode_cursor, decode_query_cursor, encode_cursor, encode_query_cursor, parse_cursor,
+        IndexPosition, IndexType, SortPosition, CURSOR_VERSION,
+    };
+    use crate::types::{CatalogFilter, CatalogSort, Error, ListingFilter, PromptIndex, TagMatch};
+    use soroban_sdk::{testutils::Address as _, Address, Env, String, Vec};
+
+    fn position() -> IndexPosition {
+        IndexPosition {
+            bucket: 3,
+            position: 41,
+            last_id: 12345,
+        }
+    }
 
     #[test]
     fn test_encode_decode_cursor_round_trips_for_every_index_type() {
         let env = Env::default();
-        let id = 12345u64;
-        let types = [
-            IndexType::Creator,
-            IndexType::Category,
-            IndexType::Tag,
-            IndexType::Active,
-            IndexType::All,
+        let indexes = [
+            (
+                PromptIndex::Creator(Address::generate(&env)),
+                IndexType::Creator,
+            ),
+            (
+                PromptIndex::Category(String::from_str(&env, "writing")),
+                IndexType::Category,
+            ),
+            (
+                PromptIndex::Tag(String::from_str(&env, "rust")),
+                IndexType::Tag,
+            ),
+            (PromptIndex::Active, IndexType::Active),
+            (PromptIndex::All, IndexType::All),
+            (
+                PromptIndex::Buyer(Address::generate(&env)),
+                IndexType::Buyer,
+            ),
         ];
 
-        for index_type in types {
-            let encoded = encode_cursor(&env, id, index_type.clone());
-            let decoded = decode_cursor(&env, &encoded).unwrap();
-            assert_eq!(decoded.last_id, id);
-            assert!(decoded.index_type == index_type);
+        for (index, index_type) in indexes {
+            let encoded = encode_cursor(&env, &index, &position());
+            assert_eq!(decode_cursor(&env, &encoded, &index).unwrap(), position());
+            let parsed = parse_cursor(&encoded).unwrap();
+            assert_eq!(parsed.version, CURSOR_VERSION);
+            assert!(parsed.index_type == index_type);
         }
     }
 
     #[test]
-    fn test_cursor_round_trips_last_id_and_type() {
+    fn test_cursor_rejected_for_a_different_index_key() {
+        let env = Env::default();
+        let writing = PromptIndex::Category(String::from_str(&env, "writing"));
+        let coding = PromptIndex::Category(String::from_str(&env, "coding"));
+        let cursor = encode_cursor(&env, &writing, &position());
+
+        assert_eq!(
+            decode_cursor(&env, &cursor, &coding),
+            Err(Error::InvalidCursor)
+        );
+        assert_eq!(
+            decode_cursor(&env, &cursor, &PromptIndex::All),
+            Err(Error::InvalidCursor)
+        );
+    }
+
+    #[test]
+    fn test_cursor_with_unknown_version_or_legacy_layout_rejected() {
         let env = Env::default();
-        let id = 999u64;
-        let cursor = encode_cursor(&env, id, IndexType::Category);
-        let decoded = decode_cursor(&env, &cursor).unwrap();
-        assert_eq!(decoded.last_id, 999);
-        assert!(decoded.index_type == IndexType::Category);
+        let cursor = encode_cursor(&env, &PromptIndex::All, &position());
+        let mut bytes = [0u8; 26];
+        cursor.copy_into_slice(&mut bytes);
+        bytes[0] = CURSOR_VERSION + 1;
+        let future = String::from_bytes(&env, &bytes);
+        assert_eq!(
+            decode_cursor(&env, &future, &PromptIndex::All),
+            Err(Error::InvalidCursor)
+        );
+
+        // The unversioned format: 8-byte last ID and a type byte.
+        let mut legacy = [0u8; 9];
+        legacy[0..8].copy_from_slice(&12345u64.to_be_bytes());
+        legacy[8] = 4;
+        let legacy = String::from_bytes(&env, &legacy);
+        assert_eq!(
+            decode_cursor(&env, &legacy, &PromptIndex::All),
+            Err(Error::InvalidCursor)
+        );
+    }
+
+    #[test]
+    fn test_query_cursor_round_trips_its_sort_position() {
+        let env = Env::default();
+        let filter = CatalogFilter {
+            category: None,
+            tags: Vec::new(&env),
+            tag_match: TagMatch::All,
+            asset: None,
+            min_price: None,
+            max_price: None,
+            creator: None,
+            listing: ListingFilter::Any,
+        };
+        let sort_position = SortPosition {
+            key: -7,
+            last_id: 12345,
+        };
+        let cursor = encode_query_cursor(&env, &filter, CatalogSort::PriceDesc, &sort_position);
+
+        assert_eq!(
+            decode_query_cursor(&env, &cursor, &filter, CatalogSort::PriceDesc).unwrap(),
+            sort_position
+        );
+        assert_eq!(
+            decode_query_cursor(&env, &cursor, &filter, CatalogSort::PriceAsc),
+            Err(Error::InvalidCursor)
+        );
+        // Index cursors do not resume queries, nor query cursors indexes.
+        let index_cursor = encode_cursor(&env, &PromptIndex::All, &position());
+        assert_eq!(
+            decode_query_cursor(&env, &index_cursor, &filter, CatalogSort::PriceDesc),
+            Err(Error::InvalidCursor)
+        );
+        assert_eq!(
+            decode_cursor(&env, &cursor, &PromptIndex::All),
+            Err(Error::InvalidCursor)
+        );
     }
 }
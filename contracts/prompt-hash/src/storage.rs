@@ -1,8 +1,16 @@
 use super::types::{
-    AccessPass, AssetLiability, Bundle, CatalogPassPurchase, DataKey, Error, InstanceDataKey,
-    ListingRevisionRecord, Prompt, Purchase, PurchaseDispute, PurchaseEscrow,
+    AccessPass, AcquisitionKind, Arbitration, AssetLiability, Auction, AuctionStatus, Bundle,
+    BuyerDisputeStats, BuyerEncryptionKey, BuyerWrappedKey, CatalogFilter, CatalogPassPurchase,
+    CatalogSort, ContentAccessPolicy, DataKey, DisputeEvidence, DisputeWindowConfig, Error,
+    GovernanceProposal, InstanceDataKey, Lease, LeaseTerms, LegacyAssetLiability, LegacyLayout,
+    LegacyListingRevisionRecord, LegacyPrompt, LegacyPurchase, LegacyPurchaseDispute,
+    LegacyPurchaseEscrow, LicenseToken, LicenseTokenApproval, ListingFilter, ListingRevisionRecord,
+    PassSubscription, PriceOracleConfig, Prompt, PromptIndex, Purchase, PurchaseDispute,
+    PurchaseEscrow, ResaleOrder, SettlementRecord, SettlementStatus, SortBucketHead, SortEntry,
+    SortField, SortIndexDirectory, SortPageHead, TagMatch,
 };
-use soroban_sdk::{token, Address, BytesN, Env, String, Vec};
+use crate::pagination::{IndexPosition, SortPosition};
+use soroban_sdk::{token, Address, BytesN, Env, Map, String, Symbol, TryFromVal, Val, Vec};
 
 pub const DAY_IN_LEDGERS: u32 = 17280;
 pub const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
@@ -88,6 +96,103 @@ impl InstanceStorage {
         env.storage().instance().set(&key, &percentage);
     }
 
+    pub fn get_governance_delay(env: &Env) -> Option<u32> {
+        env.storage()
+            .instance()
+            .get(&InstanceDataKey::GovernanceDelayLedgers)
+    }
+
+    pub fn set_governance_delay(env: &Env, delay_ledgers: u32) {
+        let key = InstanceDataKey::GovernanceDelayLedgers;
+        env.storage().instance().set(&key, &delay_ledgers);
+    }
+
+    /// Allocate the next append-only settlement ID (#567). IDs start at 1
+    /// and are never reused.
+    pub fn next_settlement_id(env: &Env) -> Result<u128, Error> {
+        let key = InstanceDataKey::SettlementCounter;
+        let current: u128 = env.storage().instance().get(&key).unwrap_or(0);
+        let next = current.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        env.storage().instance().set(&key, &next);
+        Ok(next)
+    }
+
+    pub fn next_license_token_id(env: &Env) -> Result<u32, Error> {
+        let key = InstanceDataKey::LicenseTokenCounter;
+        let current: u32 = env.storage().instance().get(&key).unwrap_or(0);
+        let next = current.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        env.storage().instance().set(&key, &next);
+        Ok(next)
+    }
+
+    /// Allocate the counter that seeds nonces for proposals queued through
+    /// the legacy nonce-less setters (#569).
+    pub fn next_legacy_governance_nonce(env: &Env) -> Result<u64, Error> {
+        let key = InstanceDataKey::LegacyGovernanceCounter;
+        let current: u64 = env.storage().instance().get(&key).unwrap_or(0);
+        let next = current.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+        env.storage().instance().set(&key, &next);
+        Ok(next)
+    }
+
+    pub fn get_arbiter_cursor(env: &Env) -> u32 {
+        let key = InstanceDataKey::ArbiterCursor;
+        env.storage().instance().get(&key).unwrap_or(0)
+    }
+
+    pub fn set_arbiter_cursor(env: &Env, cursor: u32) {
+        let key = InstanceDataKey::ArbiterCursor;
+        env.storage().instance().set(&key, &cursor);
+    }
+
+    pub fn get_dispute_window_policy(env: &Env, kind: AcquisitionKind) -> Option<u64> {
+        let key = InstanceDataKey::DisputeWindowPolicy(kind);
+        env.storage().instance().get(&key)
+    }
+
+    pub fn set_dispute_window_policy(env: &Env, kind: AcquisitionKind, window_secs: u64) {
+        let key = InstanceDataKey::DisputeWindowPolicy(kind);
+        if window_secs == 0 {
+            env.storage().instance().remove(&key);
+        } else {
+            env.storage().instance().set(&key, &window_secs);
+        }
+    }
+
+    pub fn get_dispute_bond_bps(env: &Env) -> u32 {
+        let key = InstanceDataKey::DisputeBondBps;
+        env.storage().instance().get(&key).unwrap_or(0)
+    }
+
+    pub fn set_dispute_bond_bps(env: &Env, bond_bps: u32) {
+        let key = InstanceDataKey::DisputeBondBps;
+        if bond_bps == 0 {
+            env.storage().instance().remove(&key);
+        } else {
+            env.storage().instance().set(&key, &bond_bps);
+        }
+    }
+
+    pub fn get_price_oracle(env: &Env) -> Option<PriceOracleConfig> {
+        let key = InstanceDataKey::PriceOracle;
+        env.storage().instance().get(&key)
+    }
+
+    pub fn set_price_oracle(env: &Env, config: &PriceOracleConfig) {
+        let key = InstanceDataKey::PriceOracle;
+        env.storage().instance().set(&key, config);
+    }
+
+    pub fn get_dispute_window_config(env: &Env) -> Option<DisputeWindowConfig> {
+        let key = InstanceDataKey::DisputeWindowConfig;
+        env.storage().instance().get(&key)
+    }
+
+    pub fn set_dispute_window_config(env: &Env, config: &DisputeWindowConfig) {
+        let key = InstanceDataKey::DisputeWindowConfig;
+        env.storage().instance().set(&key, config);
+    }
+
     pub fn get_referral_percentage(env: &Env) -> u32 {
         let key = InstanceDataKey::ReferralPercentage;
         env.storage().instance().get(&key).unwrap_or(0)
@@ -141,6 +246,23 @@ impl Storage {
             .extend_ttl(key, PERSISTENT_LIFETIME_THRESHOLD, max_ttl);
     }
 
+    /// Read `key` in its current layout `T`, falling back to the
+    /// pre-upgrade layout `L` for entries written before `T` gained fields.
+    fn get_versioned<T, L>(env: &Env, key: &DataKey) -> Option<T>
+    where
+        T: TryFromVal<Env, Val>,
+        L: TryFromVal<Env, Val> + LegacyLayout<T>,
+    {
+        let raw: Map<Symbol, Val> = env.storage().persistent().get(key)?;
+        if raw.len() == L::FIELDS {
+            L::try_from_val(env, &raw.to_val())
+                .ok()
+                .map(|legacy| legacy.upgrade(env))
+        } else {
+            T::try_from_val(env, &raw.to_val()).ok()
+        }
+    }
+
     pub fn save_prompt(env: &Env, prompt: &Prompt) -> Result<(), Error> {
         let key = DataKey::Prompt(prompt.id);
         env.storage().persistent().set(&key, prompt);
@@ -153,13 +275,14 @@ impl Storage {
         Self::update_category_index(env, prompt);
         Self::update_tag_index(env, prompt);
         Self::update_status_indexes(env, prompt);
+        Self::sync_sort_indexes(env, prompt);
 
         Ok(())
     }
 
     pub fn get_prompt(env: &Env, prompt_id: u64) -> Option<Prompt> {
         let key = DataKey::Prompt(prompt_id);
-        if let Some(prompt) = env.storage().persistent().get(&key) {
+        if let Some(prompt) = Self::get_versioned::<Prompt, LegacyPrompt>(env, &key) {
             Self::extend_key_ttl(env, &key);
             Some(prompt)
         } else {
@@ -171,10 +294,45 @@ impl Storage {
         Self::get_prompt(env, prompt_id).ok_or(Error::PromptNotFound)
     }
 
+    /// Save a prompt, repositioning it in the sorted price and sales
+    /// indexes when either value changed.
     pub fn update_prompt(env: &Env, prompt: &Prompt) {
         let key = DataKey::Prompt(prompt.id);
+        let previous = Self::get_versioned::<Prompt, LegacyPrompt>(env, &key);
         env.storage().persistent().set(&key, prompt);
         Self::extend_key_ttl(env, &key);
+
+        if let Some(previous) = previous {
+            if previous.price_stroops != prompt.price_stroops {
+                Self::sync_price_index(env, prompt);
+            }
+            if previous.sales_count != prompt.sales_count {
+                Self::sort_index_set(env, SortField::Sales, prompt.id, prompt.sales_count as i128);
+            }
+        }
+    }
+
+    /// Save a listing mutation that may change its status, category, tags
+    /// or expiry, moving it between discovery indexes to match. `previous`
+    /// is the listing as stored before the change.
+    pub fn update_listing(env: &Env, previous: &Prompt, prompt: &Prompt) {
+        Self::update_prompt(env, prompt);
+
+        if previous.category != prompt.category {
+            Self::index_remove(
+                env,
+                &PromptIndex::Category(previous.category.clone()),
+                prompt.id,
+            );
+            Self::update_category_index(env, prompt);
+        }
+        for tag in previous.tags.iter() {
+            if !prompt.tags.contains(&tag) {
+                Self::index_remove(env, &PromptIndex::Tag(tag), prompt.id);
+            }
+        }
+        Self::update_tag_index(env, prompt);
+        Self::update_status_indexes(env, prompt);
     }
 
     pub fn get_all_prompts(env: &Env) -> Vec<Prompt> {
@@ -219,28 +377,12 @@ impl Storage {
     }
 
     pub fn get_prompts_by_creator(env: &Env, creator: &Address) -> Vec<Prompt> {
-        let key = DataKey::CreatorPrompts(creator.clone());
-        let ids: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or_else(|| Vec::new(env));
-        if env.storage().persistent().has(&key) {
-            Self::extend_key_ttl(env, &key);
-        }
+        let ids = Self::index_ids(env, &PromptIndex::Creator(creator.clone()));
         Self::prompts_from_ids(env, ids)
     }
 
     pub fn get_prompts_by_buyer(env: &Env, buyer: &Address) -> Vec<Prompt> {
-        let key = DataKey::BuyerPrompts(buyer.clone());
-        let ids: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or_else(|| Vec::new(env));
-        if env.storage().persistent().has(&key) {
-            Self::extend_key_ttl(env, &key);
-        }
+        let ids = Self::index_ids(env, &PromptIndex::Buyer(buyer.clone()));
         Self::prompts_from_ids(env, ids)
     }
 
@@ -256,58 +398,21 @@ impl Storage {
     }
 
     pub fn add_prompt_to_creator(env: &Env, creator: &Address, prompt_id: u64) {
-        let key = DataKey::CreatorPrompts(creator.clone());
-        let mut ids: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or_else(|| Vec::new(env));
-        ids.push_back(prompt_id);
-        env.storage().persistent().set(&key, &ids);
-        Self::extend_key_ttl(env, &key);
+        Self::index_add(env, &PromptIndex::Creator(creator.clone()), prompt_id);
     }
 
     pub fn add_prompt_to_buyer(env: &Env, buyer: &Address, prompt_id: u64) {
-        let key = DataKey::BuyerPrompts(buyer.clone());
-        let mut ids: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or_else(|| Vec::new(env));
-        for index in 0..ids.len() {
-            if ids.get(index).unwrap() == prompt_id {
-                Self::extend_key_ttl(env, &key);
-                return;
-            }
-        }
-        ids.push_back(prompt_id);
-        env.storage().persistent().set(&key, &ids);
-        Self::extend_key_ttl(env, &key);
+        Self::index_add(env, &PromptIndex::Buyer(buyer.clone()), prompt_id);
     }
 
     pub fn remove_prompt_from_buyer(env: &Env, buyer: &Address, prompt_id: u64) {
-        let key = DataKey::BuyerPrompts(buyer.clone());
-        let mut ids: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or_else(|| Vec::new(env));
-        let mut index = 0;
-        while index < ids.len() {
-            if ids.get(index).unwrap() == prompt_id {
-                ids.remove(index);
-            } else {
-                index += 1;
-            }
-        }
-        env.storage().persistent().set(&key, &ids);
-        Self::extend_key_ttl(env, &key);
+        Self::index_remove(env, &PromptIndex::Buyer(buyer.clone()), prompt_id);
     }
 
     pub fn get_purchase(env: &Env, prompt_id: u64, buyer: &Address) -> Option<Purchase> {
         let key = DataKey::Purchase(prompt_id, buyer.clone());
-        let purchase = env.storage().persistent().get(&key);
-        if env.storage().persistent().has(&key) {
+        let purchase = Self::get_versioned::<Purchase, LegacyPurchase>(env, &key);
+        if purchase.is_some() {
             Self::extend_key_ttl(env, &key);
         }
         purchase
@@ -334,13 +439,16 @@ impl Storage {
         Self::get_purchase(env, prompt_id, owner).ok_or(Error::LicenseNotFound)
     }
 
+    /// Grant `buyer` a license. For listings in token mode this also mints
+    /// the license token, returning its ID; a buyer who already holds a
+    /// token for the listing (e.g. re-acquiring after a lease) keeps it.
     pub fn grant_purchase(
         env: &Env,
         prompt: &Prompt,
         buyer: &Address,
         paid_price: i128,
         expires_at: u64,
-    ) {
+    ) -> Result<Option<u32>, Error> {
         let key = DataKey::Purchase(prompt.id, buyer.clone());
         let purchase = Purchase {
             prompt_id: prompt.id,
@@ -351,134 +459,618 @@ impl Storage {
             transfer_count: 0,
             last_transferred_at: 0,
             expires_at,
+            revision: prompt.revision,
         };
         env.storage().persistent().set(&key, &purchase);
         Self::extend_key_ttl(env, &key);
         Self::add_prompt_to_buyer(env, buyer, prompt.id);
+
+        if !Self::is_license_token_mode(env, prompt.id)
+            || Self::get_purchase_license_token(env, prompt.id, buyer).is_some()
+        {
+            return Ok(None);
+        }
+        Self::mint_license_token(env, prompt.id, buyer).map(Some)
     }
 
-    // ─── Purchase Escrow (Settlement Tracking) ──────────────────────────────────
-    // Tracks purchase state for atomic refunds and dispute resolution (#420)
+    // ─── License Tokens ─────────────────────────────────────────────────────────
+    // A token is a pointer to the `Purchase` keyed by its owner. Every path
+    // that moves or revokes a purchase moves or burns its token alongside.
 
-    pub fn save_purchase_escrow(env: &Env, escrow: &PurchaseEscrow) {
-        let key = DataKey::PurchaseEscrow(escrow.prompt_id, escrow.buyer.clone());
-        env.storage().persistent().set(&key, escrow);
+    pub fn is_license_token_mode(env: &Env, prompt_id: u64) -> bool {
+        let key = DataKey::LicenseTokenMode(prompt_id);
+        env.storage().persistent().get(&key).unwrap_or(false)
+    }
+
+    pub fn set_license_token_mode(env: &Env, prompt_id: u64, enabled: bool) {
+        let key = DataKey::LicenseTokenMode(prompt_id);
+        if enabled {
+            env.storage().persistent().set(&key, &true);
+            Self::extend_key_ttl(env, &key);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    pub fn get_license_token(env: &Env, token_id: u32) -> Option<LicenseToken> {
+        let key = DataKey::LicenseToken(token_id);
+        let token = env.storage().persistent().get(&key);
+        if env.storage().persistent().has(&key) {
+            Self::extend_key_ttl(env, &key);
+        }
+        token
+    }
+
+    pub fn require_license_token(env: &Env, token_id: u32) -> Result<LicenseToken, Error> {
+        Self::get_license_token(env, token_id).ok_or(Error::LicenseTokenNotFound)
+    }
+
+    pub fn get_purchase_license_token(env: &Env, prompt_id: u64, owner: &Address) -> Option<u32> {
+        let key = DataKey::PurchaseLicenseToken(prompt_id, owner.clone());
+        let token_id = env.storage().persistent().get(&key);
+        if env.storage().persistent().has(&key) {
+            Self::extend_key_ttl(env, &key);
+        }
+        token_id
+    }
+
+    pub fn get_license_token_balance(env: &Env, owner: &Address) -> u32 {
+        let key = DataKey::LicenseTokenBalance(owner.clone());
+        env.storage().persistent().get(&key).unwrap_or(0)
+    }
+
+    fn set_license_token_balance(env: &Env, owner: &Address, balance: u32) {
+        let key = DataKey::LicenseTokenBalance(owner.clone());
+        if balance == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &balance);
+            Self::extend_key_ttl(env, &key);
+        }
+    }
+
+    fn save_license_token(env: &Env, token: &LicenseToken) {
+        let key = DataKey::LicenseToken(token.token_id);
+        env.storage().persistent().set(&key, token);
         Self::extend_key_ttl(env, &key);
+
+        let owner_key = DataKey::PurchaseLicenseToken(token.prompt_id, token.owner.clone());
+        env.storage().persistent().set(&owner_key, &token.token_id);
+        Self::extend_key_ttl(env, &owner_key);
+    }
+
+    fn mint_license_token(env: &Env, prompt_id: u64, owner: &Address) -> Result<u32, Error> {
+        let token_id = InstanceStorage::next_license_token_id(env)?;
+        Self::save_license_token(
+            env,
+            &LicenseToken {
+                token_id,
+                prompt_id,
+                owner: owner.clone(),
+            },
+        );
+        let balance = Self::get_license_token_balance(env, owner);
+        Self::set_license_token_balance(env, owner, balance.saturating_add(1));
+        Ok(token_id)
+    }
+
+    /// Burn the token backing `owner`'s license to `prompt_id`, if any.
+    pub fn burn_license_token(env: &Env, prompt_id: u64, owner: &Address) -> Option<u32> {
+        let token_id = Self::get_purchase_license_token(env, prompt_id, owner)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::LicenseToken(token_id));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PurchaseLicenseToken(prompt_id, owner.clone()));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::LicenseTokenApproval(token_id));
+        let balance = Self::get_license_token_balance(env, owner);
+        Self::set_license_token_balance(env, owner, balance.saturating_sub(1));
+        Some(token_id)
+    }
+
+    /// Reassign the token backing `from`'s license to `to`, clearing any
+    /// single-token approval. Returns the token ID, if the license has one.
+    pub fn move_license_token(
+        env: &Env,
+        prompt_id: u64,
+        from: &Address,
+        to: &Address,
+    ) -> Option<u32> {
+        let token_id = Self::get_purchase_license_token(env, prompt_id, from)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PurchaseLicenseToken(prompt_id, from.clone()));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::LicenseTokenApproval(token_id));
+        Self::save_license_token(
+            env,
+            &LicenseToken {
+                token_id,
+                prompt_id,
+                owner: to.clone(),
+            },
+        );
+        let from_balance = Self::get_license_token_balance(env, from);
+        Self::set_license_token_balance(env, from, from_balance.saturating_sub(1));
+        let to_balance = Self::get_license_token_balance(env, to);
+        Self::set_license_token_balance(env, to, to_balance.saturating_add(1));
+        Some(token_id)
+    }
+
+    pub fn get_license_token_approval(env: &Env, token_id: u32) -> Option<LicenseTokenApproval> {
+        let key = DataKey::LicenseTokenApproval(token_id);
+        let approval: Option<LicenseTokenApproval> = env.storage().persistent().get(&key);
+        approval.filter(|approval| approval.live_until_ledger >= env.ledger().sequence())
+    }
+
+    pub fn set_license_token_approval(
+        env: &Env,
+        token_id: u32,
+        approval: Option<LicenseTokenApproval>,
+    ) {
+        let key = DataKey::LicenseTokenApproval(token_id);
+        match approval {
+            Some(approval) => {
+                env.storage().persistent().set(&key, &approval);
+                Self::extend_key_ttl(env, &key);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    pub fn is_license_token_operator(env: &Env, owner: &Address, operator: &Address) -> bool {
+        let key = DataKey::LicenseTokenOperator(owner.clone(), operator.clone());
+        env.storage()
+            .persistent()
+            .get::<_, u32>(&key)
+            .map(|live_until_ledger| live_until_ledger >= env.ledger().sequence())
+            .unwrap_or(false)
+    }
+
+    pub fn set_license_token_operator(
+        env: &Env,
+        owner: &Address,
+        operator: &Address,
+        live_until_ledger: u32,
+    ) {
+        let key = DataKey::LicenseTokenOperator(owner.clone(), operator.clone());
+        if live_until_ledger == 0 {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, &live_until_ledger);
+            Self::extend_key_ttl(env, &key);
+        }
     }
 
+    // ─── Purchase Escrow (Settlement Tracking) ──────────────────────────────────
+    // Tracks purchase state for atomic refunds and dispute resolution (#420).
+    // Escrows are keyed by settlement ID (#567); the `(prompt_id, buyer)`
+    // accessors below resolve the buyer's current entitlement pointer.
+
+    /// Read-only: an escrow not yet migrated off the legacy key is read
+    /// from there as-is.
     pub fn get_purchase_escrow(
         env: &Env,
         prompt_id: u64,
         buyer: &Address,
     ) -> Option<PurchaseEscrow> {
-        let key = DataKey::PurchaseEscrow(prompt_id, buyer.clone());
-        let escrow = env.storage().persistent().get(&key);
-        if env.storage().persistent().has(&key) {
-            Self::extend_key_ttl(env, &key);
+        match Self::get_entitlement_settlement(env, prompt_id, buyer) {
+            Some(settlement_id) => Self::get_settlement_escrow(env, settlement_id),
+            None => Self::get_versioned::<PurchaseEscrow, LegacyPurchaseEscrow>(
+                env,
+                &DataKey::PurchaseEscrow(prompt_id, buyer.clone()),
+            ),
         }
-        escrow
     }
 
+    /// The escrow for a state-changing path, migrating a legacy escrow
+    /// onto its own settlement first.
     pub fn require_purchase_escrow(
         env: &Env,
         prompt_id: u64,
         buyer: &Address,
     ) -> Result<PurchaseEscrow, Error> {
-        Self::get_purchase_escrow(env, prompt_id, buyer).ok_or(Error::LicenseNotFound)
-    }
-
-    pub fn remove_purchase_escrow(env: &Env, prompt_id: u64, buyer: &Address) {
-        let key = DataKey::PurchaseEscrow(prompt_id, buyer.clone());
-        env.storage().persistent().remove(&key);
+        let settlement_id = Self::resolve_entitlement_settlement(env, prompt_id, buyer)
+            .ok_or(Error::LicenseNotFound)?;
+        Self::get_settlement_escrow(env, settlement_id).ok_or(Error::LicenseNotFound)
     }
 
-    // ─── Per-Asset Escrow Liability (#570) ──────────────────────────────────
-    // Aggregate pending/disputed liability per SAC asset, updated atomically
-    // alongside every escrow creation, settlement, dispute-open, and dispute
-    // resolution so it stays in lockstep with the underlying escrow records.
-
-    pub fn get_asset_liability(env: &Env, asset: &Address) -> AssetLiability {
-        let key = DataKey::AssetLiability(asset.clone());
-        env.storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or(AssetLiability {
-                pending: 0,
-                disputed: 0,
-            })
-    }
+    // ─── Append-only Settlement Ledger (#567) ───────────────────────────────────
+    // Every acquisition allocates its own settlement ID. Records are never
+    // overwritten by a later acquisition — a reacquisition points at the
+    // record it superseded instead — so receipts stay immutable.
 
-    fn save_asset_liability(env: &Env, asset: &Address, liability: &AssetLiability) {
-        let key = DataKey::AssetLiability(asset.clone());
-        env.storage().persistent().set(&key, liability);
+    pub fn save_settlement(env: &Env, record: &SettlementRecord) {
+        let key = DataKey::Settlement(record.settlement_id);
+        env.storage().persistent().set(&key, record);
         Self::extend_key_ttl(env, &key);
     }
 
-    /// A new escrow was created: `amount` becomes pending liability.
-    pub fn add_pending_liability(env: &Env, asset: &Address, amount: i128) -> Result<(), Error> {
-        let mut liability = Self::get_asset_liability(env, asset);
-        liability.pending = liability
-            .pending
-            .checked_add(amount)
-            .ok_or(Error::ArithmeticOverflow)?;
-        Self::save_asset_liability(env, asset, &liability);
-        Ok(())
+    pub fn get_settlement(env: &Env, settlement_id: u128) -> Option<SettlementRecord> {
+        let key = DataKey::Settlement(settlement_id);
+        let record = env.storage().persistent().get(&key);
+        if env.storage().persistent().has(&key) {
+            Self::extend_key_ttl(env, &key);
+        }
+        record
     }
 
-    /// An escrow settled or a rejected dispute closed with no open dispute:
-    /// `amount` leaves the pending bucket entirely.
-    pub fn remove_pending_liability(
-        env: &Env,
-        asset: &Address,
-        amount: i128,
-    ) -> Result<(), Error> {
-        let mut liability = Self::get_asset_liability(env, asset);
-        liability.pending = liability
-            .pending
-            .checked_sub(amount)
-            .ok_or(Error::ArithmeticOverflow)?;
-        Self::save_asset_liability(env, asset, &liability);
-        Ok(())
+    pub fn require_settlement(env: &Env, settlement_id: u128) -> Result<SettlementRecord, Error> {
+        Self::get_settlement(env, settlement_id).ok_or(Error::SettlementNotFound)
     }
 
-    /// A dispute was opened against a pending escrow: move `amount` from
-    /// pending into disputed.
-    pub fn move_pending_to_disputed(
+    /// Persist an escrow and mirror its status onto the owning settlement
+    /// record, so the two can never disagree.
+    pub fn save_settlement_escrow(
         env: &Env,
-        asset: &Address,
-        amount: i128,
+        settlement_id: u128,
+        escrow: &PurchaseEscrow,
     ) -> Result<(), Error> {
-        let mut liability = Self::get_asset_liability(env, asset);
-        liability.pending = liability
-            .pending
-            .checked_sub(amount)
-            .ok_or(Error::ArithmeticOverflow)?;
-        liability.disputed = liability
-            .disputed
-            .checked_add(amount)
-            .ok_or(Error::ArithmeticOverflow)?;
-        Self::save_asset_liability(env, asset, &liability);
+        let mut record = Self::require_settlement(env, settlement_id)?;
+        record.status = escrow.status.clone();
+        record.settled_at = escrow.settled_at;
+        Self::save_settlement(env, &record);
+
+        let key = DataKey::SettlementEscrow(settlement_id);
+        env.storage().persistent().set(&key, escrow);
+        Self::extend_key_ttl(env, &key);
         Ok(())
     }
 
-    /// A dispute was rejected without a refund: the escrow remains Pending,
-    /// so `amount` moves back from disputed into pending.
-    pub fn move_disputed_to_pending(
-        env: &Env,
-        asset: &Address,
-        amount: i128,
-    ) -> Result<(), Error> {
-        let mut liability = Self::get_asset_liability(env, asset);
-        liability.disputed = liability
-            .disputed
-            .checked_sub(amount)
-            .ok_or(Error::ArithmeticOverflow)?;
-        liability.pending = liability
-            .pending
-            .checked_add(amount)
-            .ok_or(Error::ArithmeticOverflow)?;
-        Self::save_asset_liability(env, asset, &liability);
-        Ok(())
+    pub fn get_settlement_escrow(env: &Env, settlement_id: u128) -> Option<PurchaseEscrow> {
+        let key = DataKey::SettlementEscrow(settlement_id);
+        let escrow = env.storage().persistent().get(&key);
+        if env.storage().persistent().has(&key) {
+            Self::extend_key_ttl(env, &key);
+        }
+        escrow
+    }
+
+    pub fn get_arbiters(env: &Env) -> Vec<Address> {
+        let key = DataKey::Arbiters;
+        let arbiters = env.storage().persistent().get(&key);
+        if arbiters.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        arbiters.unwrap_or(Vec::new(env))
+    }
+
+    pub fn save_arbiters(env: &Env, arbiters: &Vec<Address>) {
+        let key = DataKey::Arbiters;
+        env.storage().persistent().set(&key, arbiters);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn get_arbitration(env: &Env, settlement_id: u128) -> Option<Arbitration> {
+        let key = DataKey::Arbitration(settlement_id);
+        let arbitration = env.storage().persistent().get(&key);
+        if arbitration.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        arbitration
+    }
+
+    pub fn require_arbitration(env: &Env, settlement_id: u128) -> Result<Arbitration, Error> {
+        Self::get_arbitration(env, settlement_id).ok_or(Error::ArbitrationNotFound)
+    }
+
+    pub fn save_arbitration(env: &Env, arbitration: &Arbitration) {
+        let key = DataKey::Arbitration(arbitration.settlement_id);
+        env.storage().persistent().set(&key, arbitration);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn get_arbitration_history(env: &Env, settlement_id: u128) -> Vec<Arbitration> {
+        let key = DataKey::ArbitrationHistory(settlement_id);
+        let history = env.storage().persistent().get(&key);
+        if history.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        history.unwrap_or(Vec::new(env))
+    }
+
+    /// Move the panel currently recorded for `settlement_id`, if any, onto
+    /// its history before a new panel replaces it.
+    pub fn archive_arbitration(env: &Env, settlement_id: u128) {
+        let Some(arbitration) = Self::get_arbitration(env, settlement_id) else {
+            return;
+        };
+        let mut history = Self::get_arbitration_history(env, settlement_id);
+        history.push_back(arbitration);
+        let key = DataKey::ArbitrationHistory(settlement_id);
+        env.storage().persistent().set(&key, &history);
+        Self::extend_key_ttl(env, &key);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Arbitration(settlement_id));
+    }
+
+    pub fn get_dispute_evidence(env: &Env, settlement_id: u128) -> Vec<DisputeEvidence> {
+        let key = DataKey::DisputeEvidence(settlement_id);
+        let evidence = env.storage().persistent().get(&key);
+        if evidence.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        evidence.unwrap_or(Vec::new(env))
+    }
+
+    pub fn save_dispute_evidence(env: &Env, settlement_id: u128, evidence: &Vec<DisputeEvidence>) {
+        let key = DataKey::DisputeEvidence(settlement_id);
+        env.storage().persistent().set(&key, evidence);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn save_settlement_dispute(env: &Env, settlement_id: u128, dispute: &PurchaseDispute) {
+        let key = DataKey::SettlementDispute(settlement_id);
+        env.storage().persistent().set(&key, dispute);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn get_settlement_dispute(env: &Env, settlement_id: u128) -> Option<PurchaseDispute> {
+        let key = DataKey::SettlementDispute(settlement_id);
+        let dispute = env.storage().persistent().get(&key);
+        if env.storage().persistent().has(&key) {
+            Self::extend_key_ttl(env, &key);
+        }
+        dispute
+    }
+
+    /// The settlement currently backing `buyer`'s entitlement to `prompt_id`.
+    /// Read-only, so an escrow still under the legacy key has none yet; use
+    /// [`Self::resolve_entitlement_settlement`] on state-changing paths.
+    pub fn get_entitlement_settlement(env: &Env, prompt_id: u64, buyer: &Address) -> Option<u128> {
+        let key = DataKey::EntitlementPointer(prompt_id, buyer.clone());
+        let settlement_id = env.storage().persistent().get(&key);
+        if settlement_id.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        settlement_id
+    }
+
+    /// Like [`Self::get_entitlement_settlement`], for state-changing paths.
+    ///
+    /// Escrows written before #567 live under the legacy
+    /// `PurchaseEscrow(prompt_id, buyer)` key. They are migrated lazily on
+    /// first use into a fresh settlement record, carrying any dispute with
+    /// them, so the legacy keys never need a bulk migration.
+    pub fn resolve_entitlement_settlement(
+        env: &Env,
+        prompt_id: u64,
+        buyer: &Address,
+    ) -> Option<u128> {
+        Self::get_entitlement_settlement(env, prompt_id, buyer)
+            .or_else(|| Self::migrate_legacy_escrow(env, prompt_id, buyer))
+    }
+
+    pub fn set_entitlement_settlement(
+        env: &Env,
+        prompt_id: u64,
+        buyer: &Address,
+        settlement_id: u128,
+    ) {
+        let key = DataKey::EntitlementPointer(prompt_id, buyer.clone());
+        env.storage().persistent().set(&key, &settlement_id);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn remove_entitlement_settlement(env: &Env, prompt_id: u64, buyer: &Address) {
+        let key = DataKey::EntitlementPointer(prompt_id, buyer.clone());
+        env.storage().persistent().remove(&key);
+    }
+
+    /// Settlement IDs for one `(prompt_id, buyer)` pair, oldest first.
+    pub fn get_buyer_settlements(env: &Env, prompt_id: u64, buyer: &Address) -> Vec<u128> {
+        let key = DataKey::BuyerSettlements(prompt_id, buyer.clone());
+        let ids = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        if env.storage().persistent().has(&key) {
+            Self::extend_key_ttl(env, &key);
+        }
+        ids
+    }
+
+    pub fn add_buyer_settlement(env: &Env, prompt_id: u64, buyer: &Address, settlement_id: u128) {
+        let key = DataKey::BuyerSettlements(prompt_id, buyer.clone());
+        let mut ids: Vec<u128> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        ids.push_back(settlement_id);
+        env.storage().persistent().set(&key, &ids);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    /// Number of settlement history pages allocated to `buyer`.
+    pub fn get_buyer_settlement_page_count(env: &Env, buyer: &Address) -> u32 {
+        let key = DataKey::BuyerSettlementPageCount(buyer.clone());
+        let count = env.storage().persistent().get(&key);
+        if count.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        count.unwrap_or(0)
+    }
+
+    /// One page of `buyer`'s settlement IDs, in allocation order.
+    pub fn get_buyer_settlement_page(env: &Env, buyer: &Address, page: u32) -> Vec<u128> {
+        let key = DataKey::BuyerSettlementPage(buyer.clone(), page);
+        let ids = env.storage().persistent().get(&key);
+        if ids.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        ids.unwrap_or(Vec::new(env))
+    }
+
+    /// Append to `buyer`'s history, opening a new page when the last one is
+    /// full so a purchase never rewrites more than one page.
+    pub fn add_buyer_settlement_history(env: &Env, buyer: &Address, settlement_id: u128) {
+        use crate::pagination::INDEX_BUCKET_SIZE;
+
+        let count = Self::get_buyer_settlement_page_count(env, buyer);
+        let mut page = count.saturating_sub(1);
+        let mut ids = if count == 0 {
+            Vec::new(env)
+        } else {
+            Self::get_buyer_settlement_page(env, buyer, page)
+        };
+        if count == 0 || ids.len() >= INDEX_BUCKET_SIZE {
+            page = count;
+            ids = Vec::new(env);
+            let count_key = DataKey::BuyerSettlementPageCount(buyer.clone());
+            env.storage().persistent().set(&count_key, &(count + 1));
+            Self::extend_key_ttl(env, &count_key);
+        }
+        ids.push_back(settlement_id);
+        let key = DataKey::BuyerSettlementPage(buyer.clone(), page);
+        env.storage().persistent().set(&key, &ids);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    /// Move a pre-#567 `(prompt_id, buyer)` escrow and dispute onto a newly
+    /// allocated settlement ID and point the entitlement at it.
+    fn migrate_legacy_escrow(env: &Env, prompt_id: u64, buyer: &Address) -> Option<u128> {
+        let legacy_key = DataKey::PurchaseEscrow(prompt_id, buyer.clone());
+        let escrow = Self::get_versioned::<PurchaseEscrow, LegacyPurchaseEscrow>(env, &legacy_key)?;
+        let settlement_id = InstanceStorage::next_settlement_id(env).ok()?;
+
+        // The legacy layout did not record how the escrow was acquired; a
+        // settled zero-window escrow can only have come from a lease.
+        let kind = if escrow.status == SettlementStatus::Settled
+            && escrow.dispute_deadline == escrow.created_at
+        {
+            AcquisitionKind::Lease
+        } else {
+            AcquisitionKind::DirectPurchase
+        };
+        let record = SettlementRecord {
+            settlement_id,
+            prompt_id,
+            acquisition_id: prompt_id as u128,
+            buyer: buyer.clone(),
+            kind,
+            amount: escrow.amount,
+            asset: escrow.asset.clone(),
+            status: escrow.status.clone(),
+            created_at: escrow.created_at,
+            settled_at: escrow.settled_at,
+            supersedes: None,
+            payout_plan: escrow.payout_plan.clone(),
+        };
+        Self::save_settlement(env, &record);
+        let escrow_key = DataKey::SettlementEscrow(settlement_id);
+        env.storage().persistent().set(&escrow_key, &escrow);
+        Self::extend_key_ttl(env, &escrow_key);
+        env.storage().persistent().remove(&legacy_key);
+
+        let legacy_dispute_key = DataKey::PurchaseDispute(prompt_id, buyer.clone());
+        if let Some(dispute) =
+            Self::get_versioned::<PurchaseDispute, LegacyPurchaseDispute>(env, &legacy_dispute_key)
+        {
+            Self::save_settlement_dispute(env, settlement_id, &dispute);
+            env.storage().persistent().remove(&legacy_dispute_key);
+        }
+
+        Self::set_entitlement_settlement(env, prompt_id, buyer, settlement_id);
+        Self::add_buyer_settlement(env, prompt_id, buyer, settlement_id);
+        Self::add_buyer_settlement_history(env, buyer, settlement_id);
+        Some(settlement_id)
+    }
+
+    // ─── Per-Asset Escrow Liability (#570) ──────────────────────────────────
+    // Aggregate pending/disputed liability per SAC asset, updated atomically
+    // alongside every escrow creation, settlement, dispute-open, and dispute
+    // resolution so it stays in lockstep with the underlying escrow records.
+
+    pub fn get_asset_liability(env: &Env, asset: &Address) -> AssetLiability {
+        let key = DataKey::AssetLiability(asset.clone());
+        Self::get_versioned::<AssetLiability, LegacyAssetLiability>(env, &key).unwrap_or(
+            AssetLiability {
+                pending: 0,
+                disputed: 0,
+                bonds: 0,
+                claimable: 0,
+            },
+        )
+    }
+
+    fn save_asset_liability(env: &Env, asset: &Address, liability: &AssetLiability) {
+        let key = DataKey::AssetLiability(asset.clone());
+        env.storage().persistent().set(&key, liability);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    /// A new escrow was created: `amount` becomes pending liability.
+    pub fn add_pending_liability(env: &Env, asset: &Address, amount: i128) -> Result<(), Error> {
+        let mut liability = Self::get_asset_liability(env, asset);
+        liability.pending = liability
+            .pending
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        Self::save_asset_liability(env, asset, &liability);
+        Ok(())
+    }
+
+    /// An escrow settled or a rejected dispute closed with no open dispute:
+    /// `amount` leaves the pending bucket entirely.
+    pub fn remove_pending_liability(
+        env: &Env,
+        asset: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let mut liability = Self::get_asset_liability(env, asset);
+        liability.pending = liability
+            .pending
+            .checked_sub(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        Self::save_asset_liability(env, asset, &liability);
+        Ok(())
+    }
+
+    /// A dispute was opened against a pending escrow: move `amount` from
+    /// pending into disputed.
+    pub fn move_pending_to_disputed(
+        env: &Env,
+        asset: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let mut liability = Self::get_asset_liability(env, asset);
+        liability.pending = liability
+            .pending
+            .checked_sub(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        liability.disputed = liability
+            .disputed
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        Self::save_asset_liability(env, asset, &liability);
+        Ok(())
+    }
+
+    /// A dispute was rejected without a refund: the escrow remains Pending,
+    /// so `amount` moves back from disputed into pending.
+    pub fn move_disputed_to_pending(
+        env: &Env,
+        asset: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let mut liability = Self::get_asset_liability(env, asset);
+        liability.disputed = liability
+            .disputed
+            .checked_sub(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        liability.pending = liability
+            .pending
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        Self::save_asset_liability(env, asset, &liability);
+        Ok(())
     }
 
     /// Migration-only: credit `amount` directly into the disputed bucket for
@@ -511,6 +1103,115 @@ impl Storage {
         Ok(())
     }
 
+    /// A dispute bond was posted: `amount` is held until the dispute
+    /// resolves.
+    pub fn add_bond_liability(env: &Env, asset: &Address, amount: i128) -> Result<(), Error> {
+        let mut liability = Self::get_asset_liability(env, asset);
+        liability.bonds = liability
+            .bonds
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        Self::save_asset_liability(env, asset, &liability);
+        Ok(())
+    }
+
+    /// A dispute bond was returned or forfeited: `amount` leaves the bonds
+    /// bucket.
+    pub fn remove_bond_liability(env: &Env, asset: &Address, amount: i128) -> Result<(), Error> {
+        let mut liability = Self::get_asset_liability(env, asset);
+        liability.bonds = liability
+            .bonds
+            .checked_sub(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        Self::save_asset_liability(env, asset, &liability);
+        Ok(())
+    }
+
+    pub fn get_claimable_balance(env: &Env, recipient: &Address, asset: &Address) -> i128 {
+        let key = DataKey::ClaimableBalance(recipient.clone(), asset.clone());
+        let balance = env.storage().persistent().get(&key);
+        if balance.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        balance.unwrap_or(0)
+    }
+
+    /// Credit `amount` to `recipient`'s claimable balance and count it as
+    /// claimable liability.
+    pub fn credit_claimable(
+        env: &Env,
+        recipient: &Address,
+        asset: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let key = DataKey::ClaimableBalance(recipient.clone(), asset.clone());
+        let balance = Self::get_claimable_balance(env, recipient, asset)
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        env.storage().persistent().set(&key, &balance);
+        Self::extend_key_ttl(env, &key);
+
+        let mut liability = Self::get_asset_liability(env, asset);
+        liability.claimable = liability
+            .claimable
+            .checked_add(amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        Self::save_asset_liability(env, asset, &liability);
+        Ok(())
+    }
+
+    /// Zero `recipient`'s claimable balance and release it from liability,
+    /// returning what was owed.
+    pub fn take_claimable(env: &Env, recipient: &Address, asset: &Address) -> Result<i128, Error> {
+        let balance = Self::get_claimable_balance(env, recipient, asset);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::ClaimableBalance(recipient.clone(), asset.clone()));
+
+        let mut liability = Self::get_asset_liability(env, asset);
+        liability.claimable = liability
+            .claimable
+            .checked_sub(balance)
+            .ok_or(Error::ArithmeticOverflow)?;
+        Self::save_asset_liability(env, asset, &liability);
+        Ok(balance)
+    }
+
+    pub fn get_buyer_dispute_bond(env: &Env, buyer: &Address) -> Option<u32> {
+        let key = DataKey::BuyerDisputeBond(buyer.clone());
+        let bond_bps = env.storage().persistent().get(&key);
+        if bond_bps.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        bond_bps
+    }
+
+    pub fn set_buyer_dispute_bond(env: &Env, buyer: &Address, bond_bps: Option<u32>) {
+        let key = DataKey::BuyerDisputeBond(buyer.clone());
+        match bond_bps {
+            Some(bond_bps) => {
+                env.storage().persistent().set(&key, &bond_bps);
+                Self::extend_key_ttl(env, &key);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    pub fn get_buyer_dispute_stats(env: &Env, buyer: &Address) -> BuyerDisputeStats {
+        let key = DataKey::BuyerDisputeStats(buyer.clone());
+        let stats = env.storage().persistent().get(&key);
+        if stats.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        stats.unwrap_or_default()
+    }
+
+    pub fn save_buyer_dispute_stats(env: &Env, buyer: &Address, stats: &BuyerDisputeStats) {
+        let key = DataKey::BuyerDisputeStats(buyer.clone());
+        env.storage().persistent().set(&key, stats);
+        Self::extend_key_ttl(env, &key);
+    }
+
     pub fn is_escrow_liability_migrated(env: &Env, prompt_id: u64, buyer: &Address) -> bool {
         let key = DataKey::EscrowLiabilityMigrated(prompt_id, buyer.clone());
         env.storage().persistent().has(&key)
@@ -690,35 +1391,253 @@ impl Storage {
         purchase
     }
 
-    pub fn has_active_creator_pass(
+    pub fn save_pass_subscription(env: &Env, subscription: &PassSubscription) {
+        let key = DataKey::PassSubscription(subscription.buyer.clone(), subscription.pass_id);
+        env.storage().persistent().set(&key, subscription);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn get_pass_subscription(
         env: &Env,
-        creator: &Address,
         buyer: &Address,
-        now: u64,
-    ) -> bool {
-        let key = DataKey::CatalogPass(creator.clone(), buyer.clone());
-        let purchase: Option<CatalogPassPurchase> = env.storage().persistent().get(&key);
+        pass_id: u128,
+    ) -> Option<PassSubscription> {
+        let key = DataKey::PassSubscription(buyer.clone(), pass_id);
+        let subscription = env.storage().persistent().get(&key);
         if env.storage().persistent().has(&key) {
             Self::extend_key_ttl(env, &key);
         }
-        purchase
-            .map(|catalog_pass| catalog_pass.expires_at >= now)
-            .unwrap_or(false)
+        subscription
     }
 
-    pub fn save_dispute(env: &Env, dispute: &PurchaseDispute) {
-        let key = DataKey::PurchaseDispute(dispute.prompt_id, dispute.buyer.clone());
-        env.storage().persistent().set(&key, dispute);
+    pub fn require_pass_subscription(
+        env: &Env,
+        buyer: &Address,
+        pass_id: u128,
+    ) -> Result<PassSubscription, Error> {
+        Self::get_pass_subscription(env, buyer, pass_id).ok_or(Error::PassSubscriptionNotFound)
+    }
+
+    pub fn remove_pass_subscription(env: &Env, buyer: &Address, pass_id: u128) {
+        let key = DataKey::PassSubscription(buyer.clone(), pass_id);
+        env.storage().persistent().remove(&key);
+    }
+
+    pub fn get_buyer_encryption_key(env: &Env, buyer: &Address) -> Option<BuyerEncryptionKey> {
+        let key = DataKey::BuyerEncryptionKey(buyer.clone());
+        let registered = env.storage().persistent().get(&key);
+        if env.storage().persistent().has(&key) {
+            Self::extend_key_ttl(env, &key);
+        }
+        registered
+    }
+
+    pub fn save_buyer_encryption_key(env: &Env, buyer: &Address, key_record: &BuyerEncryptionKey) {
+        let key = DataKey::BuyerEncryptionKey(buyer.clone());
+        env.storage().persistent().set(&key, key_record);
         Self::extend_key_ttl(env, &key);
     }
 
-    pub fn get_dispute(env: &Env, prompt_id: u64, buyer: &Address) -> Option<PurchaseDispute> {
-        let key = DataKey::PurchaseDispute(prompt_id, buyer.clone());
-        let dispute = env.storage().persistent().get(&key);
+    pub fn get_key_delivery_agent(env: &Env, prompt_id: u64) -> Option<Address> {
+        let key = DataKey::KeyDeliveryAgent(prompt_id);
+        env.storage().persistent().get(&key)
+    }
+
+    pub fn get_lease_terms(env: &Env, prompt_id: u64) -> Option<LeaseTerms> {
+        let key = DataKey::LeaseTerms(prompt_id);
+        let terms = env.storage().persistent().get(&key);
+        if terms.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        terms
+    }
+
+    pub fn set_lease_terms(env: &Env, prompt_id: u64, terms: &Option<LeaseTerms>) {
+        let key = DataKey::LeaseTerms(prompt_id);
+        match terms {
+            Some(terms) => {
+                env.storage().persistent().set(&key, terms);
+                Self::extend_key_ttl(env, &key);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    pub fn get_lease(env: &Env, prompt_id: u64, lessee: &Address) -> Option<Lease> {
+        let key = DataKey::Lease(prompt_id, lessee.clone());
+        let lease = env.storage().persistent().get(&key);
+        if lease.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        lease
+    }
+
+    pub fn save_lease(env: &Env, lease: &Lease) {
+        let key = DataKey::Lease(lease.prompt_id, lease.lessee.clone());
+        env.storage().persistent().set(&key, lease);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn remove_lease(env: &Env, prompt_id: u64, lessee: &Address) {
+        let key = DataKey::Lease(prompt_id, lessee.clone());
+        env.storage().persistent().remove(&key);
+    }
+
+    pub fn get_lease_renewal(env: &Env, settlement_id: u128) -> Option<u64> {
+        let key = DataKey::LeaseRenewal(settlement_id);
+        let extension_secs = env.storage().persistent().get(&key);
+        if extension_secs.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        extension_secs
+    }
+
+    pub fn save_lease_renewal(env: &Env, settlement_id: u128, extension_secs: u64) {
+        let key = DataKey::LeaseRenewal(settlement_id);
+        env.storage().persistent().set(&key, &extension_secs);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn get_content_access_policy(env: &Env, prompt_id: u64) -> ContentAccessPolicy {
+        let key = DataKey::ContentAccessPolicy(prompt_id);
+        env.storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(ContentAccessPolicy::LatestRevision)
+    }
+
+    pub fn set_content_access_policy(env: &Env, prompt_id: u64, policy: &ContentAccessPolicy) {
+        let key = DataKey::ContentAccessPolicy(prompt_id);
+        match policy {
+            ContentAccessPolicy::LatestRevision => env.storage().persistent().remove(&key),
+            ContentAccessPolicy::PurchasedRevision => {
+                env.storage().persistent().set(&key, policy);
+                Self::extend_key_ttl(env, &key);
+            }
+        }
+    }
+
+    pub fn get_listing_dispute_window(env: &Env, prompt_id: u64) -> Option<u64> {
+        let key = DataKey::ListingDisputeWindow(prompt_id);
+        let window_secs = env.storage().persistent().get(&key);
+        if window_secs.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        window_secs
+    }
+
+    pub fn set_listing_dispute_window(env: &Env, prompt_id: u64, window_secs: Option<u64>) {
+        let key = DataKey::ListingDisputeWindow(prompt_id);
+        match window_secs {
+            Some(window_secs) => {
+                env.storage().persistent().set(&key, &window_secs);
+                Self::extend_key_ttl(env, &key);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    pub fn get_reference_price(env: &Env, prompt_id: u64) -> Option<i128> {
+        let key = DataKey::ReferencePrice(prompt_id);
+        let amount = env.storage().persistent().get(&key);
+        if amount.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        amount
+    }
+
+    pub fn set_reference_price(env: &Env, prompt_id: u64, amount: Option<i128>) {
+        let key = DataKey::ReferencePrice(prompt_id);
+        match amount {
+            Some(amount) => {
+                env.storage().persistent().set(&key, &amount);
+                Self::extend_key_ttl(env, &key);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    pub fn get_pay_what_you_want(env: &Env, prompt_id: u64) -> Option<i128> {
+        let key = DataKey::PayWhatYouWant(prompt_id);
+        let minimum = env.storage().persistent().get(&key);
+        if minimum.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        minimum
+    }
+
+    pub fn set_pay_what_you_want(env: &Env, prompt_id: u64, minimum: Option<i128>) {
+        let key = DataKey::PayWhatYouWant(prompt_id);
+        match minimum {
+            Some(minimum) => {
+                env.storage().persistent().set(&key, &minimum);
+                Self::extend_key_ttl(env, &key);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    pub fn set_key_delivery_agent(env: &Env, prompt_id: u64, agent: &Option<Address>) {
+        let key = DataKey::KeyDeliveryAgent(prompt_id);
+        match agent {
+            Some(agent) => {
+                env.storage().persistent().set(&key, agent);
+                Self::extend_key_ttl(env, &key);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    pub fn get_buyer_wrapped_key(
+        env: &Env,
+        prompt_id: u64,
+        buyer: &Address,
+    ) -> Option<BuyerWrappedKey> {
+        let key = DataKey::BuyerWrappedKey(prompt_id, buyer.clone());
+        let wrapped = env.storage().persistent().get(&key);
         if env.storage().persistent().has(&key) {
             Self::extend_key_ttl(env, &key);
         }
-        dispute
+        wrapped
+    }
+
+    pub fn save_buyer_wrapped_key(
+        env: &Env,
+        prompt_id: u64,
+        buyer: &Address,
+        wrapped: &BuyerWrappedKey,
+    ) {
+        let key = DataKey::BuyerWrappedKey(prompt_id, buyer.clone());
+        env.storage().persistent().set(&key, wrapped);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn has_active_creator_pass(
+        env: &Env,
+        creator: &Address,
+        buyer: &Address,
+        now: u64,
+    ) -> bool {
+        let key = DataKey::CatalogPass(creator.clone(), buyer.clone());
+        let purchase: Option<CatalogPassPurchase> = env.storage().persistent().get(&key);
+        if env.storage().persistent().has(&key) {
+            Self::extend_key_ttl(env, &key);
+        }
+        purchase
+            .map(|catalog_pass| catalog_pass.expires_at >= now)
+            .unwrap_or(false)
+    }
+
+    /// Dispute on `buyer`'s current entitlement to `prompt_id` (#567).
+    /// Read-only, like [`Self::get_purchase_escrow`].
+    pub fn get_dispute(env: &Env, prompt_id: u64, buyer: &Address) -> Option<PurchaseDispute> {
+        match Self::get_entitlement_settlement(env, prompt_id, buyer) {
+            Some(settlement_id) => Self::get_settlement_dispute(env, settlement_id),
+            None => Self::get_versioned::<PurchaseDispute, LegacyPurchaseDispute>(
+                env,
+                &DataKey::PurchaseDispute(prompt_id, buyer.clone()),
+            ),
+        }
     }
 
     pub fn require_dispute(
@@ -770,6 +1689,169 @@ impl Storage {
         true
     }
 
+    // ─── Quote Commitment Nonces (#565) ─────────────────────────────────────────
+    // Scoped to the buyer, so a nonce only has to be unique per buyer.
+
+    pub fn is_quote_nonce_consumed(env: &Env, buyer: &Address, nonce: &BytesN<32>) -> bool {
+        let key = DataKey::QuoteNonceConsumed(buyer.clone(), nonce.clone());
+        env.storage().persistent().has(&key)
+    }
+
+    pub fn consume_quote_nonce(env: &Env, buyer: &Address, nonce: &BytesN<32>) {
+        let key = DataKey::QuoteNonceConsumed(buyer.clone(), nonce.clone());
+        env.storage().persistent().set(&key, &true);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    // ─── Resale Orders (#568) ──────────────────────────────────────────────────
+
+    pub fn save_resale_order(env: &Env, order_hash: &BytesN<32>, order: &ResaleOrder) {
+        let key = DataKey::ResaleOrder(order_hash.clone());
+        env.storage().persistent().set(&key, order);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn get_resale_order(env: &Env, order_hash: &BytesN<32>) -> Option<ResaleOrder> {
+        let key = DataKey::ResaleOrder(order_hash.clone());
+        let order = env.storage().persistent().get(&key);
+        if env.storage().persistent().has(&key) {
+            Self::extend_key_ttl(env, &key);
+        }
+        order
+    }
+
+    pub fn require_resale_order(env: &Env, order_hash: &BytesN<32>) -> Result<ResaleOrder, Error> {
+        Self::get_resale_order(env, order_hash).ok_or(Error::ResaleOrderNotFound)
+    }
+
+    // ─── Auctions ───────────────────────────────────────────────────────────────
+
+    pub fn save_auction(env: &Env, auction: &Auction) {
+        let key = DataKey::Auction(auction.prompt_id);
+        env.storage().persistent().set(&key, auction);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn get_auction(env: &Env, prompt_id: u64) -> Option<Auction> {
+        let key = DataKey::Auction(prompt_id);
+        let auction = env.storage().persistent().get(&key);
+        if env.storage().persistent().has(&key) {
+            Self::extend_key_ttl(env, &key);
+        }
+        auction
+    }
+
+    pub fn require_auction(env: &Env, prompt_id: u64) -> Result<Auction, Error> {
+        Self::get_auction(env, prompt_id).ok_or(Error::AuctionNotFound)
+    }
+
+    pub fn has_open_auction(env: &Env, prompt_id: u64) -> bool {
+        Self::get_auction(env, prompt_id)
+            .map(|auction| auction.status == AuctionStatus::Open)
+            .unwrap_or(false)
+    }
+
+    pub fn is_resale_nonce_consumed(env: &Env, seller: &Address, nonce: &BytesN<32>) -> bool {
+        let key = DataKey::ResaleOrderNonce(seller.clone(), nonce.clone());
+        env.storage().persistent().has(&key)
+    }
+
+    pub fn consume_resale_nonce(env: &Env, seller: &Address, nonce: &BytesN<32>) {
+        let key = DataKey::ResaleOrderNonce(seller.clone(), nonce.clone());
+        env.storage().persistent().set(&key, &true);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    // ─── Governance Proposals (#569) ───────────────────────────────────────────
+
+    pub fn save_governance_proposal(
+        env: &Env,
+        proposal_hash: &BytesN<32>,
+        proposal: &GovernanceProposal,
+    ) {
+        let key = DataKey::GovernanceProposal(proposal_hash.clone());
+        env.storage().persistent().set(&key, proposal);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn get_governance_proposal(
+        env: &Env,
+        proposal_hash: &BytesN<32>,
+    ) -> Option<GovernanceProposal> {
+        let key = DataKey::GovernanceProposal(proposal_hash.clone());
+        let proposal = env.storage().persistent().get(&key);
+        if env.storage().persistent().has(&key) {
+            Self::extend_key_ttl(env, &key);
+        }
+        proposal
+    }
+
+    pub fn require_governance_proposal(
+        env: &Env,
+        proposal_hash: &BytesN<32>,
+    ) -> Result<GovernanceProposal, Error> {
+        Self::get_governance_proposal(env, proposal_hash).ok_or(Error::GovernanceProposalNotFound)
+    }
+
+    /// Remove a proposal and drop it from the pending list.
+    pub fn remove_governance_proposal(env: &Env, proposal_hash: &BytesN<32>) {
+        let key = DataKey::GovernanceProposal(proposal_hash.clone());
+        env.storage().persistent().remove(&key);
+
+        let mut pending = Self::get_pending_governance_proposals(env);
+        if let Some(index) = pending.first_index_of(proposal_hash.clone()) {
+            pending.remove(index);
+            Self::save_pending_governance_proposals(env, &pending);
+        }
+    }
+
+    /// Delete proposals whose execution window closed before `now_ledger`
+    /// and return the pending hashes that are still live. The caller saves
+    /// the returned list.
+    pub fn prune_expired_governance_proposals(env: &Env, now_ledger: u32) -> Vec<BytesN<32>> {
+        let mut live = Vec::new(env);
+        for hash in Self::get_pending_governance_proposals(env).iter() {
+            let key = DataKey::GovernanceProposal(hash.clone());
+            let proposal: Option<GovernanceProposal> = env.storage().persistent().get(&key);
+            match proposal {
+                Some(existing) if existing.expiry_ledger >= now_ledger => live.push_back(hash),
+                Some(_) => env.storage().persistent().remove(&key),
+                None => {}
+            }
+        }
+        live
+    }
+
+    pub fn get_pending_governance_proposals(env: &Env) -> Vec<BytesN<32>> {
+        let key = DataKey::PendingGovernanceProposals;
+        let pending = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(Vec::new(env));
+        if env.storage().persistent().has(&key) {
+            Self::extend_key_ttl(env, &key);
+        }
+        pending
+    }
+
+    pub fn save_pending_governance_proposals(env: &Env, pending: &Vec<BytesN<32>>) {
+        let key = DataKey::PendingGovernanceProposals;
+        env.storage().persistent().set(&key, pending);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    pub fn is_governance_nonce_consumed(env: &Env, nonce: &BytesN<32>) -> bool {
+        let key = DataKey::GovernanceNonce(nonce.clone());
+        env.storage().persistent().has(&key)
+    }
+
+    pub fn consume_governance_nonce(env: &Env, nonce: &BytesN<32>) {
+        let key = DataKey::GovernanceNonce(nonce.clone());
+        env.storage().persistent().set(&key, &true);
+        Self::extend_key_ttl(env, &key);
+    }
+
     pub fn save_listing_revision(env: &Env, record: &ListingRevisionRecord) {
         let key = DataKey::ListingRevision(record.prompt_id, record.revision);
         env.storage().persistent().set(&key, record);
@@ -782,8 +1864,9 @@ impl Storage {
         revision: u32,
     ) -> Option<ListingRevisionRecord> {
         let key = DataKey::ListingRevision(prompt_id, revision);
-        let record = env.storage().persistent().get(&key);
-        if env.storage().persistent().has(&key) {
+        let record =
+            Self::get_versioned::<ListingRevisionRecord, LegacyListingRevisionRecord>(env, &key);
+        if record.is_some() {
             Self::extend_key_ttl(env, &key);
         }
         record
@@ -802,21 +1885,253 @@ impl Storage {
                             Self::extend_key_ttl(env, &rev_key);
                         }
                     }
-                    let creator_key = DataKey::CreatorPrompts(prompt.creator.clone());
-                    if env.storage().persistent().has(&creator_key) {
-                        Self::extend_key_ttl(env, &creator_key);
+                    Self::extend_index_ttl(env, &PromptIndex::Creator(prompt.creator.clone()));
+                }
+            }
+        }
+    }
+
+    // ====== SHARDED PROMPT INDEXES ======
+    //
+    // Each index is a run of fixed-size buckets, `IndexBucket(index, n)`,
+    // appended to at the tail, plus an `IndexEntry(index, prompt_id)` entry
+    // recording which bucket holds each member. Membership checks and
+    // removals touch one bucket, and a page reads only the buckets it spans.
+    //
+    // An index still held in its pre-sharding vector is migrated a few
+    // entries at a time whenever it is written. Until the vector is empty,
+    // its remainder follows the buckets as one more segment: reads walk it
+    // after the last bucket, and new members join its tail so insertion
+    // order is kept.
+
+    /// Number of buckets allocated to `index`.
+    fn index_bucket_count(env: &Env, index: &PromptIndex) -> u32 {
+        let key = DataKey::IndexBucketCount(index.clone());
+        let count = env.storage().persistent().get(&key);
+        if count.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        count.unwrap_or(0)
+    }
+
+    fn index_bucket(env: &Env, index: &PromptIndex, bucket: u32) -> Vec<u64> {
+        let key = DataKey::IndexBucket(index.clone(), bucket);
+        let ids = env.storage().persistent().get(&key);
+        if ids.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        ids.unwrap_or(Vec::new(env))
+    }
+
+    fn save_index_bucket(env: &Env, index: &PromptIndex, bucket: u32, ids: &Vec<u64>) {
+        let key = DataKey::IndexBucket(index.clone(), bucket);
+        env.storage().persistent().set(&key, ids);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    /// Bucket holding `prompt_id` in `index`, if it is a member.
+    fn index_entry(env: &Env, index: &PromptIndex, prompt_id: u64) -> Option<u32> {
+        let key = DataKey::IndexEntry(index.clone(), prompt_id);
+        let bucket = env.storage().persistent().get(&key);
+        if bucket.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        bucket
+    }
+
+    /// Pre-sharding single-vector key that held `index`.
+    fn legacy_index_key(index: &PromptIndex) -> DataKey {
+        match index {
+            PromptIndex::All => DataKey::AllPrompts,
+            PromptIndex::Active => DataKey::ActivePrompts,
+            PromptIndex::Category(category) => DataKey::CategoryPrompts(category.clone()),
+            PromptIndex::Tag(tag) => DataKey::TagPrompts(tag.clone()),
+            PromptIndex::Creator(creator) => DataKey::CreatorPrompts(creator.clone()),
+            PromptIndex::Buyer(buyer) => DataKey::BuyerPrompts(buyer.clone()),
+        }
+    }
+
+    /// Members of `index` not yet migrated out of its legacy vector.
+    fn legacy_index_ids(env: &Env, index: &PromptIndex) -> Option<Vec<u64>> {
+        let key = Self::legacy_index_key(index);
+        let ids = env.storage().persistent().get(&key);
+        if ids.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        ids
+    }
+
+    fn save_legacy_index(env: &Env, index: &PromptIndex, ids: &Vec<u64>) {
+        let key = Self::legacy_index_key(index);
+        if ids.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, ids);
+            Self::extend_key_ttl(env, &key);
+        }
+    }
+
+    /// Segment `segment` of an index with `bucket_count` buckets: a bucket,
+    /// or past the last one, the legacy remainder.
+    fn index_segment(env: &Env, index: &PromptIndex, segment: u32, bucket_count: u32) -> Vec<u64> {
+        if segment < bucket_count {
+            Self::index_bucket(env, index, segment)
+        } else {
+            Self::legacy_index_ids(env, index).unwrap_or(Vec::new(env))
+        }
+    }
+
+    /// Number of segments of `index`, counting a legacy remainder as one.
+    fn index_segment_count(env: &Env, index: &PromptIndex, bucket_count: u32) -> u32 {
+        if env
+            .storage()
+            .persistent()
+            .has(&Self::legacy_index_key(index))
+        {
+            bucket_count + 1
+        } else {
+            bucket_count
+        }
+    }
+
+    /// Segment a page resumes in. A cursor left inside the legacy vector
+    /// follows its prompt into the buckets once that prompt is migrated.
+    fn resume_segment(
+        env: &Env,
+        index: &PromptIndex,
+        cursor: &IndexPosition,
+        bucket_count: u32,
+    ) -> u32 {
+        use crate::pagination::LEGACY_INDEX_BUCKET;
+
+        if cursor.bucket != LEGACY_INDEX_BUCKET {
+            return cursor.bucket;
+        }
+        Self::index_entry(env, index, cursor.last_id).unwrap_or(bucket_count)
+    }
+
+    /// Cursor bucket for segment `segment`.
+    fn segment_bucket(segment: u32, bucket_count: u32) -> u32 {
+        use crate::pagination::LEGACY_INDEX_BUCKET;
+
+        if segment < bucket_count {
+            segment
+        } else {
+            LEGACY_INDEX_BUCKET
+        }
+    }
+
+    /// Append `prompt_id` to `index` unless it is already a member, first
+    /// migrating a step of any legacy vector the index still has.
+    pub fn index_add(env: &Env, index: &PromptIndex, prompt_id: u64) {
+        use crate::pagination::INDEX_MIGRATION_STEP;
+
+        match Self::migrate_legacy_index(env, index, INDEX_MIGRATION_STEP) {
+            Some(mut legacy) => {
+                // Unmigrated members precede this one, so it joins their tail.
+                if Self::index_entry(env, index, prompt_id).is_none() && !legacy.contains(prompt_id)
+                {
+                    legacy.push_back(prompt_id);
+                    Self::save_legacy_index(env, index, &legacy);
+                }
+            }
+            None => Self::bucket_add(env, index, prompt_id),
+        }
+    }
+
+    /// Append `prompt_id` to the buckets of `index` unless it is already in
+    /// one.
+    fn bucket_add(env: &Env, index: &PromptIndex, prompt_id: u64) {
+        use crate::pagination::INDEX_BUCKET_SIZE;
+
+        if Self::index_entry(env, index, prompt_id).is_some() {
+            return;
+        }
+        let count = Self::index_bucket_count(env, index);
+        let mut bucket = count.saturating_sub(1);
+        let mut ids = if count == 0 {
+            Vec::new(env)
+        } else {
+            Self::index_bucket(env, index, bucket)
+        };
+        if count == 0 || ids.len() >= INDEX_BUCKET_SIZE {
+            bucket = count;
+            ids = Vec::new(env);
+            let count_key = DataKey::IndexBucketCount(index.clone());
+            env.storage().persistent().set(&count_key, &(count + 1));
+            Self::extend_key_ttl(env, &count_key);
+        }
+        ids.push_back(prompt_id);
+        Self::save_index_bucket(env, index, bucket, &ids);
+
+        let entry_key = DataKey::IndexEntry(index.clone(), prompt_id);
+        env.storage().persistent().set(&entry_key, &bucket);
+        Self::extend_key_ttl(env, &entry_key);
+    }
+
+    /// Remove `prompt_id` from `index`, first migrating a step of any legacy
+    /// vector the index still has. Its bucket keeps its place, so later
+    /// members do not move.
+    pub fn index_remove(env: &Env, index: &PromptIndex, prompt_id: u64) {
+        use crate::pagination::INDEX_MIGRATION_STEP;
+
+        let legacy = Self::migrate_legacy_index(env, index, INDEX_MIGRATION_STEP);
+        let bucket = match Self::index_entry(env, index, prompt_id) {
+            Some(bucket) => bucket,
+            None => {
+                if let Some(mut legacy) = legacy {
+                    if let Some(position) = legacy.first_index_of(prompt_id) {
+                        legacy.remove(position);
+                        Self::save_legacy_index(env, index, &legacy);
                     }
                 }
+                return;
             }
+        };
+        let mut ids = Self::index_bucket(env, index, bucket);
+        if let Some(position) = ids.first_index_of(prompt_id) {
+            ids.remove(position);
+            Self::save_index_bucket(env, index, bucket, &ids);
         }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::IndexEntry(index.clone(), prompt_id));
     }
 
+    /// Every member of `index`, in insertion order. Reads all buckets, so
+    /// it is meant for small per-account indexes.
+    pub fn index_ids(env: &Env, index: &PromptIndex) -> Vec<u64> {
+        let mut ids = Vec::new(env);
+        for bucket in 0..Self::index_bucket_count(env, index) {
+            ids.append(&Self::index_bucket(env, index, bucket));
+        }
+        if let Some(legacy) = Self::legacy_index_ids(env, index) {
+            ids.append(&legacy);
+        }
+        ids
+    }
+
+    /// Renew every bucket of `index`, and its legacy vector if it has one.
+    pub fn extend_index_ttl(env: &Env, index: &PromptIndex) {
+        for bucket in 0..Self::index_bucket_count(env, index) {
+            let key = DataKey::IndexBucket(index.clone(), bucket);
+            if env.storage().persistent().has(&key) {
+                Self::extend_key_ttl(env, &key);
+            }
+        }
+        Self::legacy_index_ids(env, index);
+    }
+
+    /// Up to `limit` prompts of `index` following `after`, plus the
+    /// position of the last one returned. The page resumes directly at the
+    /// cursor's bucket and offset; if members of that bucket were removed
+    /// since, the cursor's prompt is looked up within that one bucket.
     pub fn get_prompts_paginated(
         env: &Env,
-        key: &DataKey,
-        cursor: Option<u64>,
+        index: &PromptIndex,
+        after: Option<IndexPosition>,
         limit: u64,
-    ) -> Vec<Prompt> {
+    ) -> (Vec<Prompt>, Option<IndexPosition>) {
         use crate::pagination::MAX_PAGE_SIZE;
 
         let limit = if limit < MAX_PAGE_SIZE {
@@ -824,96 +2139,744 @@ impl Storage {
         } else {
             MAX_PAGE_SIZE
         };
-        let ids: Vec<u64> = env.storage().persistent().get(key).unwrap_or(Vec::new(env));
+        let bucket_count = Self::index_bucket_count(env, index);
+        let segment_count = Self::index_segment_count(env, index, bucket_count);
 
         let mut results = Vec::new(env);
-        let mut start_idx = 0u32;
-
-        // Find start position if cursor provided
-        if let Some(cursor_id) = cursor {
-            for (i, id) in ids.iter().enumerate() {
-                if id == cursor_id {
-                    start_idx = i as u32 + 1;
+        let mut last = None;
+        let mut bucket = after
+            .as_ref()
+            .map(|cursor| Self::resume_segment(env, index, cursor, bucket_count))
+            .unwrap_or(0);
+        let mut resume = after;
+
+        while bucket < segment_count && (results.len() as u64) < limit {
+            let ids = Self::index_segment(env, index, bucket, bucket_count);
+            let start = match resume.take() {
+                Some(cursor) if ids.get(cursor.position) == Some(cursor.last_id) => {
+                    cursor.position + 1
+                }
+                Some(cursor) => match ids.first_index_of(cursor.last_id) {
+                    Some(position) => position + 1,
+                    // The cursor's prompt left this bucket; later members
+                    // shifted down into its offset.
+                    None => cursor.position.min(ids.len()),
+                },
+                None => 0,
+            };
+            for i in start..ids.len() {
+                if results.len() as u64 >= limit {
                     break;
                 }
+                let prompt_id = ids.get(i).unwrap();
+                if let Some(prompt) = Self::get_prompt(env, prompt_id) {
+                    if Self::index_matches(env, index, &prompt) {
+                        results.push_back(prompt);
+                        last = Some(IndexPosition {
+                            bucket: Self::segment_bucket(bucket, bucket_count),
+                            position: i,
+                            last_id: prompt_id,
+                        });
+                    }
+                }
+            }
+            bucket += 1;
+        }
+
+        (results, last)
+    }
+
+    /// A page of `query_prompts`: up to `limit` prompts matching `filter`
+    /// in `sort` order, resuming after `after`. At most `MAX_QUERY_SCAN`
+    /// index entries are examined, so a selective filter may return a short
+    /// page. The returned position is where the scan stopped, or `None`
+    /// once the walk reached the end of the index.
+    pub fn query_catalog(
+        env: &Env,
+        filter: &CatalogFilter,
+        sort: CatalogSort,
+        after: Option<SortPosition>,
+        limit: u64,
+    ) -> (Vec<Prompt>, Option<SortPosition>) {
+        use crate::pagination::{MAX_PAGE_SIZE, MAX_QUERY_SCAN};
+
+        let limit = if limit < MAX_PAGE_SIZE {
+            limit
+        } else {
+            MAX_PAGE_SIZE
+        };
+        let mut results = Vec::new(env);
+        let mut last = None;
+        let mut scanned = 0u32;
+
+        let mut visit = |prompt_id: u64, position: SortPosition| -> bool {
+            if results.len() as u64 >= limit || scanned >= MAX_QUERY_SCAN {
+                return false;
+            }
+            scanned += 1;
+            if let Some(prompt) = Self::get_prompt(env, prompt_id) {
+                if Self::catalog_matches(env, filter, &prompt) {
+                    results.push_back(prompt);
+                }
             }
+            last = Some(position);
+            true
+        };
+
+        let completed = match sort {
+            CatalogSort::Newest => {
+                Self::walk_newest(env, &Self::newest_index(filter), after, &mut visit)
+            }
+            CatalogSort::PriceAsc => {
+                Self::walk_sort_index(env, SortField::Price, true, after, &mut visit)
+            }
+            CatalogSort::PriceDesc => {
+                Self::walk_sort_index(env, SortField::Price, false, after, &mut visit)
+            }
+            CatalogSort::BestSelling => {
+                Self::walk_sort_index(env, SortField::Sales, false, after, &mut visit)
+            }
+        };
+
+        (results, if completed { None } else { last })
+    }
+
+    /// Index walked for a newest-first query. The creator and all-prompt
+    /// indexes only grow, in creation order, so they are sorted by ID.
+    fn newest_index(filter: &CatalogFilter) -> PromptIndex {
+        match &filter.creator {
+            Some(creator) => PromptIndex::Creator(creator.clone()),
+            None => PromptIndex::All,
         }
+    }
 
-        // Collect up to `limit` items
-        for i in start_idx..ids.len() {
-            if results.len() as u64 >= limit {
-                break;
+    fn catalog_matches(env: &Env, filter: &CatalogFilter, prompt: &Prompt) -> bool {
+        let expired = prompt.expires_at != 0 && prompt.expires_at < env.ledger().timestamp();
+        let listing = match filter.listing {
+            ListingFilter::Any => true,
+            ListingFilter::Listed => Self::is_listed(env, prompt),
+            ListingFilter::Expired => expired,
+        };
+        let tags = filter.tags.is_empty()
+            || match filter.tag_match {
+                TagMatch::All => filter.tags.iter().all(|tag| prompt.tags.contains(&tag)),
+                TagMatch::Any => filter.tags.iter().any(|tag| prompt.tags.contains(&tag)),
+            };
+        let price = match &filter.asset {
+            None => Some(prompt.price_stroops),
+            Some(asset) if prompt.asset == *asset => Some(prompt.price_stroops),
+            Some(asset) => prompt
+                .pricing
+                .iter()
+                .find(|entry| entry.asset == *asset)
+                .map(|entry| entry.price),
+        };
+        let price = match price {
+            Some(price) => {
+                filter.min_price.is_none_or(|min| price >= min)
+                    && filter.max_price.is_none_or(|max| price <= max)
             }
-            if let Some(prompt) = Self::get_prompt(env, ids.get(i).unwrap()) {
-                results.push_back(prompt);
+            None => false,
+        };
+
+        listing
+            && tags
+            && price
+            && filter
+                .category
+                .as_ref()
+                .is_none_or(|category| prompt.category == *category)
+            && filter
+                .creator
+                .as_ref()
+                .is_none_or(|creator| prompt.creator == *creator)
+    }
+
+    /// Visit the members of an ID-ordered index newest first, resuming
+    /// below `after`. Returns `false` if `visit` stopped the walk.
+    fn walk_newest(
+        env: &Env,
+        index: &PromptIndex,
+        after: Option<SortPosition>,
+        visit: &mut dyn FnMut(u64, SortPosition) -> bool,
+    ) -> bool {
+        let bucket_count = Self::index_bucket_count(env, index);
+        let segment_count = Self::index_segment_count(env, index, bucket_count);
+        if segment_count == 0 {
+            return true;
+        }
+
+        // Last segment starting below the cursor's prompt, and the offset
+        // of the first ID in it that is not.
+        let mut segment = segment_count - 1;
+        if let Some(cursor) = &after {
+            let (mut low, mut high) = (0u32, segment_count);
+            while high - low > 1 {
+                let mid = (low + high) / 2;
+                match Self::index_segment(env, index, mid, bucket_count).first() {
+                    Some(first) if first >= cursor.last_id => high = mid,
+                    _ => low = mid,
+                }
             }
+            segment = low;
         }
+        let mut ids = Self::index_segment(env, index, segment, bucket_count);
+        let mut end = match &after {
+            Some(cursor) => match ids.binary_search(cursor.last_id) {
+                Ok(position) | Err(position) => position,
+            },
+            None => ids.len(),
+        };
 
-        results
+        loop {
+            for position in (0..end).rev() {
+                let prompt_id = ids.get(position).unwrap();
+                let at = SortPosition {
+                    key: prompt_id as i128,
+                    last_id: prompt_id,
+                };
+                if !visit(prompt_id, at) {
+                    return false;
+                }
+            }
+            if segment == 0 {
+                return true;
+            }
+            segment -= 1;
+            ids = Self::index_segment(env, index, segment, bucket_count);
+            end = ids.len();
+        }
     }
 
-    /// Update index for category
-    pub fn update_category_index(env: &Env, prompt: &Prompt) {
-        let key = DataKey::CategoryPrompts(prompt.category.clone());
-        let mut ids: Vec<u64> = env
-            .storage()
-            .persistent()
-            .get(&key)
-            .unwrap_or(Vec::new(env));
+    /// Visit the members of a sorted index in key order, resuming past
+    /// `after`. The cursor's key and ID are searched for afresh, so entries
+    /// refiled since, the cursor's own included, do not shift the walk.
+    /// Returns `false` if `visit` stopped the walk.
+    fn walk_sort_index(
+        env: &Env,
+        field: SortField,
+        ascending: bool,
+        after: Option<SortPosition>,
+        visit: &mut dyn FnMut(u64, SortPosition) -> bool,
+    ) -> bool {
+        let pages = Self::sort_directory(env, field).pages;
+        if pages.is_empty() {
+            return true;
+        }
+        let last_page = pages.len() - 1;
+
+        let (mut page_slot, mut resume) = match after {
+            None => (if ascending { 0 } else { last_page }, None),
+            Some(cursor) => {
+                let entry = SortEntry {
+                    key: cursor.key,
+                    prompt_id: cursor.last_id,
+                };
+                (Self::sort_page_slot(&pages, &entry), Some(entry))
+            }
+        };
 
-        if !ids.contains(prompt.id) {
-            ids.push_back(prompt.id);
-            env.storage().persistent().set(&key, &ids);
-            Self::extend_key_ttl(env, &key);
+        loop {
+            let heads = Self::sort_page(env, field, pages.get(page_slot).unwrap().page);
+            let last_slot = heads.len() - 1;
+            let mut slot = match &resume {
+                None if ascending => 0,
+                None => last_slot,
+                Some(entry) => Self::sort_head_slot(&heads, entry),
+            };
+
+            loop {
+                let entries = Self::sort_bucket(env, field, heads.get(slot).unwrap().bucket);
+                let len = entries.len();
+                let (start, end) = match resume.take() {
+                    None => (0, len),
+                    Some(entry) if ascending => (Self::sort_partition(&entries, &entry, true), len),
+                    Some(entry) => (0, Self::sort_partition(&entries, &entry, false)),
+                };
+                let mut visit_at = |position: u32| {
+                    let entry = entries.get(position).unwrap();
+                    let at = SortPosition {
+                        key: entry.key,
+                        last_id: entry.prompt_id,
+                    };
+                    visit(entry.prompt_id, at)
+                };
+                if ascending {
+                    for position in start..end {
+                        if !visit_at(position) {
+                            return false;
+                        }
+                    }
+                    if slot == last_slot {
+                        break;
+                    }
+                    slot += 1;
+                } else {
+                    for position in (start..end).rev() {
+                        if !visit_at(position) {
+                            return false;
+                        }
+                    }
+                    if slot == 0 {
+                        break;
+                    }
+                    slot -= 1;
+                }
+            }
+
+            if ascending {
+                if page_slot == last_page {
+                    return true;
+                }
+                page_slot += 1;
+            } else {
+                if page_slot == 0 {
+                    return true;
+                }
+                page_slot -= 1;
+            }
         }
     }
 
-    /// Update index for tags
-    pub fn update_tag_index(env: &Env, prompt: &Prompt) {
-        for tag in prompt.tags.iter() {
-            let key = DataKey::TagPrompts(tag);
-            let mut ids: Vec<u64> = env
-                .storage()
-                .persistent()
-                .get(&key)
-                .unwrap_or(Vec::new(env));
+    /// Move up to `batch` entries from the front of a pre-sharding
+    /// single-vector index into buckets, deleting the legacy entry once it
+    /// is empty. Returns the entries still to migrate, if any.
+    pub fn migrate_legacy_index(env: &Env, index: &PromptIndex, batch: u32) -> Option<Vec<u64>> {
+        let ids = Self::legacy_index_ids(env, index)?;
+        let moved = batch.min(ids.len());
+        for position in 0..moved {
+            Self::bucket_add(env, index, ids.get(position).unwrap());
+        }
+        let rest = ids.slice(moved..);
+        Self::save_legacy_index(env, index, &rest);
+        if rest.is_empty() {
+            None
+        } else {
+            Some(rest)
+        }
+    }
 
-            if !ids.contains(prompt.id) {
-                ids.push_back(prompt.id);
-                env.storage().persistent().set(&key, &ids);
+    // ====== SORTED SECONDARY INDEXES ======
+    //
+    // Price and sales count change after listing, so ordering by them needs
+    // an index that is repositioned on each change. Entries are kept in
+    // sorted buckets of at most `INDEX_BUCKET_SIZE`. Bucket heads are kept
+    // in key order on pages of at most `SORT_PAGE_SIZE`, and a root
+    // directory lists the pages by their first entry. Buckets and pages
+    // split in two when full and are dropped when emptied.
+    //
+    // An insert or removal rewrites its bucket. The page above it is only
+    // rewritten when the bucket's first entry changes or the bucket splits
+    // or empties, and the root only when that reaches the page's first
+    // entry, a bucket or page is allocated, or a page empties. Every
+    // purchase refiles its listing in the sales index, but moving a listing
+    // up one sale usually stays within its bucket, so purchases of
+    // unrelated listings mostly write different entries, and no write
+    // grows past a page.
+
+    fn sort_directory(env: &Env, field: SortField) -> SortIndexDirectory {
+        let key = DataKey::SortIndex(field);
+        match env.storage().persistent().get(&key) {
+            Some(directory) => {
                 Self::extend_key_ttl(env, &key);
+                directory
             }
+            None => SortIndexDirectory {
+                next_bucket: 0,
+                next_page: 0,
+                pages: Vec::new(env),
+            },
         }
     }
 
-    /// Update active/all indexes
-    pub fn update_status_indexes(env: &Env, prompt: &Prompt) {
-        // AllPrompts index
-        let all_key = DataKey::AllPrompts;
-        let mut all_ids: Vec<u64> = env
-            .storage()
+    fn save_sort_directory(env: &Env, field: SortField, directory: &SortIndexDirectory) {
+        let key = DataKey::SortIndex(field);
+        env.storage().persistent().set(&key, directory);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    fn sort_page(env: &Env, field: SortField, page: u32) -> Vec<SortBucketHead> {
+        let key = DataKey::SortPage(field, page);
+        let heads = env.storage().persistent().get(&key);
+        if heads.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        heads.unwrap_or(Vec::new(env))
+    }
+
+    fn save_sort_page(env: &Env, field: SortField, page: u32, heads: &Vec<SortBucketHead>) {
+        let key = DataKey::SortPage(field, page);
+        env.storage().persistent().set(&key, heads);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    fn sort_bucket(env: &Env, field: SortField, bucket: u32) -> Vec<SortEntry> {
+        let key = DataKey::SortBucket(field, bucket);
+        let entries = env.storage().persistent().get(&key);
+        if entries.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        entries.unwrap_or(Vec::new(env))
+    }
+
+    fn save_sort_bucket(env: &Env, field: SortField, bucket: u32, entries: &Vec<SortEntry>) {
+        let key = DataKey::SortBucket(field, bucket);
+        env.storage().persistent().set(&key, entries);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    /// Key `prompt_id` is currently filed under in `field`'s index.
+    fn sort_slot(env: &Env, field: SortField, prompt_id: u64) -> Option<i128> {
+        let key = DataKey::SortSlot(field, prompt_id);
+        let indexed = env.storage().persistent().get(&key);
+        if indexed.is_some() {
+            Self::extend_key_ttl(env, &key);
+        }
+        indexed
+    }
+
+    fn sort_precedes(a: &SortEntry, b: &SortEntry) -> bool {
+        (a.key, a.prompt_id) < (b.key, b.prompt_id)
+    }
+
+    /// Slot of the last bucket on a page whose head does not follow
+    /// `entry`; the first slot when `entry` precedes every head.
+    fn sort_head_slot(heads: &Vec<SortBucketHead>, entry: &SortEntry) -> u32 {
+        Self::sort_search(heads.len(), |slot| heads.get(slot).unwrap().first, entry)
+    }
+
+    /// Slot of the last page whose first entry does not follow `entry`.
+    fn sort_page_slot(pages: &Vec<SortPageHead>, entry: &SortEntry) -> u32 {
+        Self::sort_search(pages.len(), |slot| pages.get(slot).unwrap().first, entry)
+    }
+
+    fn sort_search(len: u32, first: impl Fn(u32) -> SortEntry, entry: &SortEntry) -> u32 {
+        let (mut low, mut high) = (0u32, len);
+        while high - low > 1 {
+            let mid = (low + high) / 2;
+            if Self::sort_precedes(entry, &first(mid)) {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        low
+    }
+
+    /// Number of leading `entries` that precede `entry`, counting `entry`
+    /// itself when `inclusive`.
+    fn sort_partition(entries: &Vec<SortEntry>, entry: &SortEntry, inclusive: bool) -> u32 {
+        let (mut low, mut high) = (0u32, entries.len());
+        while low < high {
+            let mid = (low + high) / 2;
+            let probe = entries.get(mid).unwrap();
+            if Self::sort_precedes(&probe, entry) || (inclusive && probe == *entry) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    fn entry_position(entries: &Vec<SortEntry>, prompt_id: u64) -> Option<u32> {
+        entries
+            .iter()
+            .position(|entry| entry.prompt_id == prompt_id)
+            .map(|position| position as u32)
+    }
+
+    /// Place `prompt` in the price and sales indexes under its current
+    /// values, moving it if it was indexed under stale ones.
+    pub fn sync_sort_indexes(env: &Env, prompt: &Prompt) {
+        Self::sync_price_index(env, prompt);
+        Self::sort_index_set(env, SortField::Sales, prompt.id, prompt.sales_count as i128);
+    }
+
+    /// Place `prompt` in the price index under `price_stroops`, or leave it
+    /// out while it is priced in reference units or by the buyer, since
+    /// `price_stroops` is then not what a purchase charges.
+    pub fn sync_price_index(env: &Env, prompt: &Prompt) {
+        if Self::get_reference_price(env, prompt.id).is_some()
+            || Self::get_pay_what_you_want(env, prompt.id).is_some()
+        {
+            if let Some(indexed) = Self::sort_slot(env, SortField::Price, prompt.id) {
+                Self::sort_index_remove(
+                    env,
+                    SortField::Price,
+                    &SortEntry {
+                        key: indexed,
+                        prompt_id: prompt.id,
+                    },
+                );
+            }
+            return;
+        }
+        Self::sort_index_set(env, SortField::Price, prompt.id, prompt.price_stroops);
+    }
+
+    fn sort_index_set(env: &Env, field: SortField, prompt_id: u64, key: i128) {
+        if let Some(indexed) = Self::sort_slot(env, field, prompt_id) {
+            if indexed == key {
+                return;
+            }
+            Self::sort_index_remove(
+                env,
+                field,
+                &SortEntry {
+                    key: indexed,
+                    prompt_id,
+                },
+            );
+        }
+        Self::sort_index_insert(env, field, SortEntry { key, prompt_id });
+    }
+
+    fn sort_index_insert(env: &Env, field: SortField, entry: SortEntry) {
+        use crate::pagination::{INDEX_BUCKET_SIZE, SORT_PAGE_SIZE};
+
+        let slot_key = DataKey::SortSlot(field, entry.prompt_id);
+        env.storage().persistent().set(&slot_key, &entry.key);
+        Self::extend_key_ttl(env, &slot_key);
+
+        let mut directory = Self::sort_directory(env, field);
+        if directory.pages.is_empty() {
+            let bucket = directory.next_bucket;
+            let page = directory.next_page;
+            directory.next_bucket += 1;
+            directory.next_page += 1;
+            Self::save_sort_bucket(env, field, bucket, &Vec::from_array(env, [entry.clone()]));
+            let head = SortBucketHead {
+                bucket,
+                first: entry.clone(),
+            };
+            Self::save_sort_page(env, field, page, &Vec::from_array(env, [head]));
+            directory
+                .pages
+                .push_back(SortPageHead { page, first: entry });
+            Self::save_sort_directory(env, field, &directory);
+            return;
+        }
+
+        let page_slot = Self::sort_page_slot(&directory.pages, &entry);
+        let mut page_head = directory.pages.get(page_slot).unwrap();
+        let mut heads = Self::sort_page(env, field, page_head.page);
+        let slot = Self::sort_head_slot(&heads, &entry);
+        let mut head = heads.get(slot).unwrap();
+        let mut entries = Self::sort_bucket(env, field, head.bucket);
+        let position = entries
+            .iter()
+            .position(|existing| Self::sort_precedes(&entry, &existing))
+            .unwrap_or(entries.len() as usize) as u32;
+        entries.insert(position, entry.clone());
+
+        let mut page_changed = false;
+        let mut directory_changed = false;
+        if position == 0 {
+            head.first = entry.clone();
+            heads.set(slot, head.clone());
+            page_changed = true;
+            if slot == 0 {
+                page_head.first = entry;
+                directory.pages.set(page_slot, page_head.clone());
+                directory_changed = true;
+            }
+        }
+
+        if entries.len() > INDEX_BUCKET_SIZE {
+            let split = entries.len() / 2;
+            let upper = entries.slice(split..);
+            entries = entries.slice(0..split);
+            let bucket = directory.next_bucket;
+            directory.next_bucket += 1;
+            heads.insert(
+                slot + 1,
+                SortBucketHead {
+                    bucket,
+                    first: upper.get(0).unwrap(),
+                },
+            );
+            Self::save_sort_bucket(env, field, bucket, &upper);
+            page_changed = true;
+            directory_changed = true;
+        }
+        if heads.len() > SORT_PAGE_SIZE {
+            let split = heads.len() / 2;
+            let upper = heads.slice(split..);
+            heads = heads.slice(0..split);
+            let page = directory.next_page;
+            directory.next_page += 1;
+            directory.pages.insert(
+                page_slot + 1,
+                SortPageHead {
+                    page,
+                    first: upper.get(0).unwrap().first,
+                },
+            );
+            Self::save_sort_page(env, field, page, &upper);
+        }
+
+        Self::save_sort_bucket(env, field, head.bucket, &entries);
+        if page_changed {
+            Self::save_sort_page(env, field, page_head.page, &heads);
+        }
+        if directory_changed {
+            Self::save_sort_directory(env, field, &directory);
+        }
+    }
+
+    fn sort_index_remove(env: &Env, field: SortField, entry: &SortEntry) {
+        env.storage()
             .persistent()
-            .get(&all_key)
-            .unwrap_or(Vec::new(env));
-        if !all_ids.contains(prompt.id) {
-            all_ids.push_back(prompt.id);
-            env.storage().persistent().set(&all_key, &all_ids);
-            Self::extend_key_ttl(env, &all_key);
+            .remove(&DataKey::SortSlot(field, entry.prompt_id));
+
+        let mut directory = Self::sort_directory(env, field);
+        if directory.pages.is_empty() {
+            return;
         }
+        let page_slot = Self::sort_page_slot(&directory.pages, entry);
+        let page = directory.pages.get(page_slot).unwrap().page;
+        let mut heads = Self::sort_page(env, field, page);
+        let slot = Self::sort_head_slot(&heads, entry);
+        let bucket = heads.get(slot).unwrap().bucket;
+        let mut entries = Self::sort_bucket(env, field, bucket);
+        let position = match Self::entry_position(&entries, entry.prompt_id) {
+            Some(position) => position,
+            None => return,
+        };
+        entries.remove(position);
 
-        // ActivePrompts index (if active)
-        if matches!(prompt.status, super::types::PromptSaleStatus::Active) {
-            let active_key = DataKey::ActivePrompts;
-            let mut active_ids: Vec<u64> = env
-                .storage()
+        if entries.is_empty() {
+            env.storage()
                 .persistent()
-                .get(&active_key)
-                .unwrap_or(Vec::new(env));
-            if !active_ids.contains(prompt.id) {
-                active_ids.push_back(prompt.id);
-                env.storage().persistent().set(&active_key, &active_ids);
-                Self::extend_key_ttl(env, &active_key);
+                .remove(&DataKey::SortBucket(field, bucket));
+            heads.remove(slot);
+        } else {
+            Self::save_sort_bucket(env, field, bucket, &entries);
+            if position != 0 {
+                return;
             }
+            heads.set(
+                slot,
+                SortBucketHead {
+                    bucket,
+                    first: entries.get(0).unwrap(),
+                },
+            );
+        }
+
+        if heads.is_empty() {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::SortPage(field, page));
+            directory.pages.remove(page_slot);
+            Self::save_sort_directory(env, field, &directory);
+            return;
+        }
+        Self::save_sort_page(env, field, page, &heads);
+        if slot == 0 {
+            directory.pages.set(
+                page_slot,
+                SortPageHead {
+                    page,
+                    first: heads.get(0).unwrap().first,
+                },
+            );
+            Self::save_sort_directory(env, field, &directory);
+        }
+    }
+
+    /// Update index for category
+    /// Index `prompt` under its category, leaving the category it was last
+    /// indexed under if that has since changed.
+    pub fn update_category_index(env: &Env, prompt: &Prompt) {
+        let key = DataKey::IndexedCategory(prompt.id);
+        let indexed: Option<String> = env.storage().persistent().get(&key);
+        if let Some(indexed) = indexed {
+            if indexed != prompt.category {
+                Self::index_remove(env, &PromptIndex::Category(indexed), prompt.id);
+            }
+        }
+        Self::index_add(
+            env,
+            &PromptIndex::Category(prompt.category.clone()),
+            prompt.id,
+        );
+        env.storage().persistent().set(&key, &prompt.category);
+        Self::extend_key_ttl(env, &key);
+    }
+
+    /// Update index for tags
+    pub fn update_tag_index(env: &Env, prompt: &Prompt) {
+        for tag in prompt.tags.iter() {
+            Self::index_add(env, &PromptIndex::Tag(tag), prompt.id);
+        }
+    }
+
+    /// Update active/all indexes. Only listed prompts (active and not
+    /// expired) belong in the active index.
+    pub fn update_status_indexes(env: &Env, prompt: &Prompt) {
+        Self::index_add(env, &PromptIndex::All, prompt.id);
+        if Self::is_listed(env, prompt) {
+            Self::index_add(env, &PromptIndex::Active, prompt.id);
+        } else {
+            Self::index_remove(env, &PromptIndex::Active, prompt.id);
+        }
+    }
+
+    /// Whether `prompt` can currently be bought: active and not expired.
+    pub fn is_listed(env: &Env, prompt: &Prompt) -> bool {
+        prompt.status == super::types::PromptSaleStatus::Active
+            && (prompt.expires_at == 0 || prompt.expires_at >= env.ledger().timestamp())
+    }
+
+    /// Whether an entry of `index` still describes `prompt`. Entries may
+    /// lag behind listings that expired without a transaction, or that
+    /// drifted before indexes were maintained, until `rebuild_indexes`
+    /// reaches them.
+    fn index_matches(env: &Env, index: &PromptIndex, prompt: &Prompt) -> bool {
+        match index {
+            PromptIndex::Active => Self::is_listed(env, prompt),
+            PromptIndex::Category(category) => prompt.category == *category,
+            PromptIndex::Tag(tag) => prompt.tags.contains(tag),
+            _ => true,
+        }
+    }
+
+    /// Reconcile the discovery indexes for a bounded batch of prompts
+    /// starting at `cursor`: add each to the indexes it belongs in and drop
+    /// it from the active index and from the categories of its earlier
+    /// revisions when they no longer apply. Returns the next cursor while
+    /// prompts remain.
+    pub fn rebuild_indexes(env: &Env, cursor: Option<u64>) -> Option<u64> {
+        use crate::pagination::MAX_INDEX_REBUILD_BATCH;
+
+        let prompt_count = InstanceStorage::get_prompt_counter(env);
+        let start = cursor.unwrap_or(0);
+        let end = start
+            .saturating_add(MAX_INDEX_REBUILD_BATCH)
+            .min(prompt_count);
+
+        for prompt_id in start..end {
+            let prompt = match Self::get_prompt(env, prompt_id) {
+                Some(prompt) => prompt,
+                None => continue,
+            };
+            Self::index_add(
+                env,
+                &PromptIndex::Creator(prompt.creator.clone()),
+                prompt_id,
+            );
+            Self::update_category_index(env, &prompt);
+            Self::update_tag_index(env, &prompt);
+            Self::update_status_indexes(env, &prompt);
+            Self::sync_sort_indexes(env, &prompt);
+        }
+
+        if end < prompt_count {
+            Some(end)
+        } else {
+            None
         }
     }
 
@@ -4,7 +4,15 @@ extern crate std;
 
 use crate::contract::{PromptHashContract, PromptHashContractClient};
 use crate::mock_asset::FungibleTokenContract;
-use crate::types::{Error, ListingConfig, PromptSaleStatus, Split};
+use crate::mock_oracle::{MockPriceOracle, MockPriceOracleClient};
+use crate::pagination::{INDEX_BUCKET_SIZE, INDEX_MIGRATION_STEP, MAX_INDEX_REBUILD_BATCH};
+use crate::storage::Storage;
+use crate::types::{
+    AcquisitionKind, AuctionStatus, CatalogFilter, CatalogSort, ContentAccessPolicy, Error,
+    GovernanceAction, LeaseTerms, ListingConfig, ListingFilter, PriceConversion, PricingConfig,
+    PromptIndex, PromptSaleStatus, QuoteCommitment, ResaleOrder, ResaleOrderStatus, Split,
+    TagMatch,
+};
 use soroban_sdk::{
     testutils::{Address as _, Events, Ledger},
     token, Address, Bytes, BytesN, Env, String, Vec,
@@ -78,6 +86,17 @@ fn hash(env: &Env, byte: u8) -> BytesN<32> {
     BytesN::from_array(env, &[byte; 32])
 }
 
+/// Advances past the delay of the most recently queued governance proposal
+/// and executes it as its proposer (#569).
+fn execute_latest_governance(env: &Env, client: &PromptHashContractClient) {
+    let pending = client.get_pending_governance_proposals();
+    let proposal_hash = pending.get(pending.len() - 1).unwrap();
+    let proposal = client.get_governance_proposal(&proposal_hash);
+    env.ledger()
+        .with_mut(|li| li.sequence_number = proposal.executable_at_ledger);
+    client.execute_governance(&proposal.proposer, &proposal_hash);
+}
+
 /// Convenience helper: creates a prompt with no expiry and no splits.
 fn create_prompt(
     env: &Env,
@@ -87,7 +106,39 @@ fn create_prompt(
     price_stroops: i128,
     asset: &Address,
 ) -> u64 {
-    client.create_prompt(
+    try_create_prompt_with_config(
+        env,
+        client,
+        creator,
+        title,
+        &listing_config(env, price_stroops, asset),
+    )
+    .unwrap()
+}
+
+fn listing_config(env: &Env, price_stroops: i128, asset: &Address) -> ListingConfig {
+    ListingConfig {
+        price: price_stroops,
+        asset: asset.clone(),
+        expires_at: 0,
+        splits: Vec::new(env),
+        tags: Vec::new(env),
+        max_supply: 0,
+        royalty_bps: 500,
+        royalty_splits: Vec::new(env),
+        resale_allowed: true,
+        pricing: Vec::new(env),
+    }
+}
+
+fn try_create_prompt_with_config(
+    env: &Env,
+    client: &PromptHashContractClient,
+    creator: &Address,
+    title: &str,
+    config: &ListingConfig,
+) -> Result<u64, Error> {
+    match client.try_create_prompt(
         creator,
         &String::from_str(env, "https://example.com/image.png"),
         &String::from_str(env, title),
@@ -97,15 +148,12 @@ fn create_prompt(
         &String::from_str(env, "iv"),
         &String::from_str(env, "wrapped-key"),
         &hash(env, 7),
-        &ListingConfig {
-            price: price_stroops,
-            asset: asset.clone(),
-            expires_at: 0,
-            splits: Vec::new(env),
-            tags: Vec::new(env),
-            max_supply: 0,
-        },
-    )
+        config,
+    ) {
+        Ok(Ok(prompt_id)) => Ok(prompt_id),
+        Err(Ok(err)) => Err(err),
+        other => panic!("unexpected create_prompt result {:?}", other),
+    }
 }
 
 fn create_prompt_with_supply(
@@ -136,6 +184,10 @@ fn create_prompt_with_supply(
             splits: Vec::new(env),
             tags: Vec::new(env),
             max_supply: max_supply as u64,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(env),
+            resale_allowed: true,
+            pricing: Vec::new(env),
         },
     )
 }
@@ -150,6 +202,16 @@ fn fund_buyer(
     xlm_client.approve(buyer, spender, &amount, &1_000);
 }
 
+/// What `who` has been paid in `token`'s asset: its wallet balance plus
+/// anything credited to it and not yet withdrawn.
+fn paid_balance(
+    client: &PromptHashContractClient,
+    token: &token::StellarAssetClient<'_>,
+    who: &Address,
+) -> i128 {
+    token.balance(who) + client.get_claimable_balance(who, &token.address)
+}
+
 fn create_prompt_with_splits(
     env: &Env,
     client: &PromptHashContractClient,
@@ -176,6 +238,10 @@ fn create_prompt_with_splits(
             splits,
             tags: Vec::new(env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(env),
+            resale_allowed: true,
+            pricing: Vec::new(env),
         },
     )
 }
@@ -264,8 +330,8 @@ fn test_buy_prompt_grants_access_to_multiple_buyers_and_tracks_exact_fees() {
     fund_buyer(&xlm_client, &buyer_one, &context.contract, 100_000);
     fund_buyer(&xlm_client, &buyer_two, &context.contract, 100_000);
 
-    let seller_start = xlm_client.balance(&creator);
-    let fee_start = xlm_client.balance(&context.fee_wallet);
+    let seller_start = paid_balance(&client, &xlm_client, &creator);
+    let fee_start = paid_balance(&client, &xlm_client, &context.fee_wallet);
 
     client.buy_prompt(
         &buyer_one,
@@ -293,11 +359,11 @@ fn test_buy_prompt_grants_access_to_multiple_buyers_and_tracks_exact_fees() {
     let single_fee = 12_345 * 500 / 10_000;
     let single_creator_amount = 12_345 - single_fee;
     assert_eq!(
-        xlm_client.balance(&creator),
+        paid_balance(&client, &xlm_client, &creator),
         seller_start + (single_creator_amount * 2) as i128
     );
     assert_eq!(
-        xlm_client.balance(&context.fee_wallet),
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
         fee_start + (single_fee * 2) as i128
     );
 }
@@ -324,8 +390,8 @@ fn test_fee_routing_pays_seller_and_platform_wallet_for_exact_purchase() {
     fund_buyer(&xlm_client, &buyer, &context.contract, price);
 
     let buyer_start = xlm_client.balance(&buyer);
-    let seller_start = xlm_client.balance(&creator);
-    let fee_start = xlm_client.balance(&context.fee_wallet);
+    let seller_start = paid_balance(&client, &xlm_client, &creator);
+    let fee_start = paid_balance(&client, &xlm_client, &context.fee_wallet);
 
     client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
 
@@ -336,11 +402,11 @@ fn test_fee_routing_pays_seller_and_platform_wallet_for_exact_purchase() {
 
     assert_eq!(xlm_client.balance(&buyer), buyer_start - price);
     assert_eq!(
-        xlm_client.balance(&creator),
+        paid_balance(&client, &xlm_client, &creator),
         seller_start + expected_seller_payout
     );
     assert_eq!(
-        xlm_client.balance(&context.fee_wallet),
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
         fee_start + expected_fee
     );
     assert!(client.has_access(&buyer, &prompt_id));
@@ -368,16 +434,22 @@ fn test_small_price_fee_rounding_keeps_fractional_fee_with_seller() {
 
     fund_buyer(&xlm_client, &buyer, &context.contract, price);
 
-    let seller_start = xlm_client.balance(&creator);
-    let fee_start = xlm_client.balance(&context.fee_wallet);
+    let seller_start = paid_balance(&client, &xlm_client, &creator);
+    let fee_start = paid_balance(&client, &xlm_client, &context.fee_wallet);
 
     client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
 
     client.settle_purchase(&context.admin, &prompt_id, &buyer);
 
     assert_eq!(price * 500 / 10_000, 0);
-    assert_eq!(xlm_client.balance(&creator), seller_start + price);
-    assert_eq!(xlm_client.balance(&context.fee_wallet), fee_start);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &creator),
+        seller_start + price
+    );
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
+        fee_start
+    );
     assert!(client.has_access(&buyer, &prompt_id));
 }
 
@@ -411,9 +483,9 @@ fn test_seller_payout_split_rounding_uses_integer_stroops() {
 
     fund_buyer(&xlm_client, &buyer, &context.contract, price);
 
-    let seller_start = xlm_client.balance(&creator);
-    let co_creator_start = xlm_client.balance(&co_creator);
-    let fee_start = xlm_client.balance(&context.fee_wallet);
+    let seller_start = paid_balance(&client, &xlm_client, &creator);
+    let co_creator_start = paid_balance(&client, &xlm_client, &co_creator);
+    let fee_start = paid_balance(&client, &xlm_client, &context.fee_wallet);
 
     client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
 
@@ -426,15 +498,15 @@ fn test_seller_payout_split_rounding_uses_integer_stroops() {
     assert_eq!(expected_fee, 5);
     assert_eq!(expected_split, 3);
     assert_eq!(
-        xlm_client.balance(&creator),
+        paid_balance(&client, &xlm_client, &creator),
         seller_start + expected_seller_payout
     );
     assert_eq!(
-        xlm_client.balance(&co_creator),
+        paid_balance(&client, &xlm_client, &co_creator),
         co_creator_start + expected_split
     );
     assert_eq!(
-        xlm_client.balance(&context.fee_wallet),
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
         fee_start + expected_fee
     );
 }
@@ -492,8 +564,12 @@ fn test_admin_can_update_platform_fee_within_bounds() {
     let context = setup(&env);
     let client = PromptHashContractClient::new(&env, &context.contract);
 
-    // admin sets platform fee to 300 BPS (3%)
+    // admin queues platform fee of 300 BPS (3%); it only applies once the
+    // governance delay has elapsed
+    let before = client.get_platform_fee();
     client.update_platform_fee(&context.admin, &300u32);
+    assert_eq!(client.get_platform_fee(), before);
+    execute_latest_governance(&env, &client);
     assert_eq!(client.get_platform_fee(), 300u32);
 }
 
@@ -534,6 +610,7 @@ fn test_update_platform_fee_emits_event() {
     // Capture event count before
     let before = env.events().all().events().len();
     client.update_platform_fee(&context.admin, &400u32);
+    execute_latest_governance(&env, &client);
     let after = env.events().all().events().len();
     assert!(after > before, "expected at least one new event");
 }
@@ -626,10 +703,11 @@ fn test_license_owner_can_transfer_and_creator_receives_royalty() {
         &10_000i128,
         &None::<Bytes>,
     );
+    client.settle_purchase(&context.admin, &prompt_id, &seller);
 
     fund_buyer(&xlm_client, &buyer, &context.contract, 100_000);
-    let creator_before = xlm_client.balance(&creator);
-    let seller_before = xlm_client.balance(&seller);
+    let creator_before = paid_balance(&client, &xlm_client, &creator);
+    let seller_before = paid_balance(&client, &xlm_client, &seller);
     let buyer_before = xlm_client.balance(&buyer);
     let resale_price = 20_000i128;
 
@@ -637,8 +715,14 @@ fn test_license_owner_can_transfer_and_creator_receives_royalty() {
 
     let royalty = resale_price * 500 / 10_000;
     let seller_proceeds = resale_price - royalty;
-    assert_eq!(xlm_client.balance(&creator), creator_before + royalty);
-    assert_eq!(xlm_client.balance(&seller), seller_before + seller_proceeds);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &creator),
+        creator_before + royalty
+    );
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &seller),
+        seller_before + seller_proceeds
+    );
     assert_eq!(xlm_client.balance(&buyer), buyer_before - resale_price);
     assert!(!client.has_access(&seller, &prompt_id));
     assert!(client.has_access(&buyer, &prompt_id));
@@ -843,6 +927,7 @@ fn test_buy_prompt_with_zero_fee() {
 
     // Set fee to 0
     client.set_fee_percentage(&0);
+    execute_latest_governance(&env, &client);
 
     let creator = Address::generate(&env);
     let buyer = Address::generate(&env);
@@ -858,15 +943,21 @@ fn test_buy_prompt_with_zero_fee() {
 
     fund_buyer(&xlm_client, &buyer, &context.contract, price);
 
-    let seller_start = xlm_client.balance(&creator);
-    let fee_start = xlm_client.balance(&context.fee_wallet);
+    let seller_start = paid_balance(&client, &xlm_client, &creator);
+    let fee_start = paid_balance(&client, &xlm_client, &context.fee_wallet);
 
     client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
 
     client.settle_purchase(&context.admin, &prompt_id, &buyer);
 
-    assert_eq!(xlm_client.balance(&creator), seller_start + price);
-    assert_eq!(xlm_client.balance(&context.fee_wallet), fee_start);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &creator),
+        seller_start + price
+    );
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
+        fee_start
+    );
 }
 
 #[test]
@@ -939,6 +1030,7 @@ fn test_buy_prompt_with_max_fee() {
     // `test_set_fee_percentage_cannot_exceed_platform_fee_ceiling` below for
     // proof that gap is now closed.
     client.set_fee_percentage(&1_000);
+    execute_latest_governance(&env, &client);
 
     let creator = Address::generate(&env);
     let buyer = Address::generate(&env);
@@ -954,15 +1046,21 @@ fn test_buy_prompt_with_max_fee() {
 
     fund_buyer(&xlm_client, &buyer, &context.contract, price);
 
-    let seller_start = xlm_client.balance(&creator);
-    let fee_start = xlm_client.balance(&context.fee_wallet);
+    let seller_start = paid_balance(&client, &xlm_client, &creator);
+    let fee_start = paid_balance(&client, &xlm_client, &context.fee_wallet);
 
     client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
 
     client.settle_purchase(&context.admin, &prompt_id, &buyer);
 
-    assert_eq!(xlm_client.balance(&creator), seller_start + price - price / 10);
-    assert_eq!(xlm_client.balance(&context.fee_wallet), fee_start + price / 10);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &creator),
+        seller_start + price - price / 10
+    );
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
+        fee_start + price / 10
+    );
 }
 
 #[test]
@@ -995,10 +1093,13 @@ fn test_set_fee_percentage_and_update_platform_fee_emit_same_event_shape() {
     let client = PromptHashContractClient::new(&env, &context.contract);
 
     client.set_fee_percentage(&600u32);
+    assert_eq!(client.get_pending_governance_proposals().len(), 1);
+    execute_latest_governance(&env, &client);
     assert_eq!(client.get_fee_percentage(), 600u32);
     assert_eq!(client.get_platform_fee(), 600u32);
 
     client.update_platform_fee(&context.admin, &700u32);
+    execute_latest_governance(&env, &client);
     assert_eq!(client.get_fee_percentage(), 700u32);
     assert_eq!(client.get_platform_fee(), 700u32);
 }
@@ -1120,8 +1221,14 @@ fn test_massive_price_does_not_overflow() {
     let expected_fee = massive_price * fee_bps / 10_000;
     let expected_seller = massive_price - expected_fee;
 
-    assert_eq!(xlm_client.balance(&creator), expected_seller);
-    assert_eq!(xlm_client.balance(&context.fee_wallet), expected_fee);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &creator),
+        expected_seller
+    );
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
+        expected_fee
+    );
 }
 
 #[test]
@@ -1151,6 +1258,10 @@ fn test_global_pause_blocks_mutations_but_not_reads() {
             splits: Vec::new(&env),
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
     match create_res {
@@ -1219,6 +1330,7 @@ fn test_buy_prompt_with_referrer_splits_payment_correctly() {
 
     // Set referral to 5% (500 BPS)
     client.set_referral_percentage(&500);
+    execute_latest_governance(&env, &client);
 
     let creator = Address::generate(&env);
     let buyer = Address::generate(&env);
@@ -1235,9 +1347,9 @@ fn test_buy_prompt_with_referrer_splits_payment_correctly() {
 
     fund_buyer(&xlm_client, &buyer, &context.contract, price);
 
-    let creator_start = xlm_client.balance(&creator);
-    let fee_start = xlm_client.balance(&context.fee_wallet);
-    let referrer_start = xlm_client.balance(&referrer);
+    let creator_start = paid_balance(&client, &xlm_client, &creator);
+    let fee_start = paid_balance(&client, &xlm_client, &context.fee_wallet);
+    let referrer_start = paid_balance(&client, &xlm_client, &referrer);
 
     client.buy_prompt(
         &buyer,
@@ -1257,15 +1369,15 @@ fn test_buy_prompt_with_referrer_splits_payment_correctly() {
     let expected_creator = price - expected_fee - expected_referral;
 
     assert_eq!(
-        xlm_client.balance(&creator),
+        paid_balance(&client, &xlm_client, &creator),
         creator_start + expected_creator
     );
     assert_eq!(
-        xlm_client.balance(&context.fee_wallet),
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
         fee_start + expected_fee
     );
     assert_eq!(
-        xlm_client.balance(&referrer),
+        paid_balance(&client, &xlm_client, &referrer),
         referrer_start + expected_referral
     );
 }
@@ -1278,6 +1390,7 @@ fn test_referrer_cannot_be_buyer() {
     let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
 
     client.set_referral_percentage(&500);
+    execute_latest_governance(&env, &client);
 
     let creator = Address::generate(&env);
     let buyer = Address::generate(&env);
@@ -1315,6 +1428,7 @@ fn test_referrer_cannot_be_creator() {
     let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
 
     client.set_referral_percentage(&500);
+    execute_latest_governance(&env, &client);
 
     let creator = Address::generate(&env);
     let buyer = Address::generate(&env);
@@ -1352,6 +1466,7 @@ fn test_buy_without_referrer_no_referral_amount_paid() {
     let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
 
     client.set_referral_percentage(&500);
+    execute_latest_governance(&env, &client);
 
     let creator = Address::generate(&env);
     let buyer = Address::generate(&env);
@@ -1367,8 +1482,8 @@ fn test_buy_without_referrer_no_referral_amount_paid() {
 
     fund_buyer(&xlm_client, &buyer, &context.contract, price);
 
-    let creator_start = xlm_client.balance(&creator);
-    let fee_start = xlm_client.balance(&context.fee_wallet);
+    let creator_start = paid_balance(&client, &xlm_client, &creator);
+    let fee_start = paid_balance(&client, &xlm_client, &context.fee_wallet);
 
     client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
 
@@ -1379,11 +1494,11 @@ fn test_buy_without_referrer_no_referral_amount_paid() {
     let expected_creator = price - expected_fee;
 
     assert_eq!(
-        xlm_client.balance(&creator),
+        paid_balance(&client, &xlm_client, &creator),
         creator_start + expected_creator
     );
     assert_eq!(
-        xlm_client.balance(&context.fee_wallet),
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
         fee_start + expected_fee
     );
 }
@@ -1396,6 +1511,7 @@ fn test_set_referral_percentage_only_owner() {
 
     // Owner can set referral percentage
     client.set_referral_percentage(&300);
+    execute_latest_governance(&env, &client);
     assert_eq!(client.get_referral_percentage(), 300);
 
     // Non-owner cannot set referral percentage
@@ -1434,6 +1550,10 @@ fn test_create_prompt_blocked_when_paused() {
             splits: Vec::new(&env),
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
     match result {
@@ -1648,8 +1768,8 @@ fn test_tip_above_price_succeeds_and_creator_receives_full_tip() {
 
     fund_buyer(&xlm_client, &buyer, &context.contract, total_payment);
 
-    let creator_start = xlm_client.balance(&creator);
-    let fee_start = xlm_client.balance(&context.fee_wallet);
+    let creator_start = paid_balance(&client, &xlm_client, &creator);
+    let fee_start = paid_balance(&client, &xlm_client, &context.fee_wallet);
 
     client.buy_prompt(
         &buyer,
@@ -1666,11 +1786,11 @@ fn test_tip_above_price_succeeds_and_creator_receives_full_tip() {
     let expected_creator = total_payment - expected_fee;
 
     assert_eq!(
-        xlm_client.balance(&creator),
+        paid_balance(&client, &xlm_client, &creator),
         creator_start + expected_creator
     );
     assert_eq!(
-        xlm_client.balance(&context.fee_wallet),
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
         fee_start + expected_fee
     );
 }
@@ -1767,8 +1887,8 @@ fn test_voucher_applies_discount_on_purchase() {
     let discounted_price: i128 = 8_000;
     fund_buyer(&xlm_client, &buyer, &context.contract, discounted_price);
 
-    let creator_start = xlm_client.balance(&creator);
-    let fee_start = xlm_client.balance(&context.fee_wallet);
+    let creator_start = paid_balance(&client, &xlm_client, &creator);
+    let fee_start = paid_balance(&client, &xlm_client, &context.fee_wallet);
 
     client.buy_prompt(
         &buyer,
@@ -1784,11 +1904,11 @@ fn test_voucher_applies_discount_on_purchase() {
     let expected_creator = discounted_price - expected_fee;
 
     assert_eq!(
-        xlm_client.balance(&creator),
+        paid_balance(&client, &xlm_client, &creator),
         creator_start + expected_creator
     );
     assert_eq!(
-        xlm_client.balance(&context.fee_wallet),
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
         fee_start + expected_fee
     );
     assert!(client.has_access(&buyer, &prompt_id));
@@ -1961,6 +2081,7 @@ fn test_voucher_with_referrer_combined() {
     let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
 
     client.set_referral_percentage(&500); // 5%
+    execute_latest_governance(&env, &client);
 
     let creator = Address::generate(&env);
     let buyer = Address::generate(&env);
@@ -1985,9 +2106,9 @@ fn test_voucher_with_referrer_combined() {
     let discounted_price: i128 = 9_000;
     fund_buyer(&xlm_client, &buyer, &context.contract, discounted_price);
 
-    let creator_start = xlm_client.balance(&creator);
-    let fee_start = xlm_client.balance(&context.fee_wallet);
-    let referrer_start = xlm_client.balance(&referrer);
+    let creator_start = paid_balance(&client, &xlm_client, &creator);
+    let fee_start = paid_balance(&client, &xlm_client, &context.fee_wallet);
+    let referrer_start = paid_balance(&client, &xlm_client, &referrer);
 
     client.buy_prompt(
         &buyer,
@@ -2007,15 +2128,15 @@ fn test_voucher_with_referrer_combined() {
     let expected_creator = discounted_price - expected_fee - expected_referral;
 
     assert_eq!(
-        xlm_client.balance(&creator),
+        paid_balance(&client, &xlm_client, &creator),
         creator_start + expected_creator
     );
     assert_eq!(
-        xlm_client.balance(&context.fee_wallet),
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
         fee_start + expected_fee
     );
     assert_eq!(
-        xlm_client.balance(&referrer),
+        paid_balance(&client, &xlm_client, &referrer),
         referrer_start + expected_referral
     );
     assert!(client.has_access(&buyer, &prompt_id));
@@ -2042,8 +2163,8 @@ fn test_buy_prompt_with_non_xlm_asset() {
     usdc_client.mint(&buyer, &price);
     usdc_client.approve(&buyer, &context.contract, &price, &1_000);
 
-    let creator_start = usdc_client.balance(&creator);
-    let fee_start = usdc_client.balance(&context.fee_wallet);
+    let creator_start = paid_balance(&client, &usdc_client, &creator);
+    let fee_start = paid_balance(&client, &usdc_client, &context.fee_wallet);
 
     client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
 
@@ -2053,11 +2174,11 @@ fn test_buy_prompt_with_non_xlm_asset() {
     let expected_creator = price - expected_fee;
 
     assert_eq!(
-        usdc_client.balance(&creator),
+        paid_balance(&client, &usdc_client, &creator),
         creator_start + expected_creator
     );
     assert_eq!(
-        usdc_client.balance(&context.fee_wallet),
+        paid_balance(&client, &usdc_client, &context.fee_wallet),
         fee_start + expected_fee
     );
     assert!(client.has_access(&buyer, &prompt_id));
@@ -2096,7 +2217,7 @@ fn test_create_and_buy_different_assets() {
     usdc_client.approve(&buyer, &context.contract, &usdc_price, &1_000);
 
     // Buy the XLM prompt - XLM balances should change, USDC should not
-    let creator_xlm_before = xlm_client.balance(&creator);
+    let creator_xlm_before = paid_balance(&client, &xlm_client, &creator);
     let creator_usdc_before = usdc_client.balance(&creator);
 
     client.buy_prompt(
@@ -2111,13 +2232,13 @@ fn test_create_and_buy_different_assets() {
 
     let xlm_fee = xlm_price * 500 / 10_000;
     assert_eq!(
-        xlm_client.balance(&creator),
+        paid_balance(&client, &xlm_client, &creator),
         creator_xlm_before + xlm_price - xlm_fee
     );
     assert_eq!(usdc_client.balance(&creator), creator_usdc_before);
 
     // Buy the USDC prompt - USDC balances should change
-    let creator_usdc_before = usdc_client.balance(&creator);
+    let creator_usdc_before = paid_balance(&client, &usdc_client, &creator);
     client.buy_prompt(
         &buyer,
         &prompt_usdc,
@@ -2130,7 +2251,7 @@ fn test_create_and_buy_different_assets() {
 
     let usdc_fee = usdc_price * 500 / 10_000;
     assert_eq!(
-        usdc_client.balance(&creator),
+        paid_balance(&client, &usdc_client, &creator),
         creator_usdc_before + usdc_price - usdc_fee
     );
 
@@ -2162,14 +2283,14 @@ fn test_lease_prompt_with_non_xlm_asset() {
     usdc_client.mint(&buyer, &lease_price);
     usdc_client.approve(&buyer, &context.contract, &lease_price, &1_000);
 
-    let creator_start = usdc_client.balance(&creator);
+    let creator_start = paid_balance(&client, &usdc_client, &creator);
 
     client.lease_prompt(&buyer, &prompt_id, &600);
 
     let expected_fee = lease_price * 500 / 10_000;
     let expected_seller = lease_price - expected_fee;
     assert_eq!(
-        usdc_client.balance(&creator),
+        paid_balance(&client, &usdc_client, &creator),
         creator_start + expected_seller
     );
     assert!(client.has_access(&buyer, &prompt_id));
@@ -2211,6 +2332,10 @@ fn test_create_prompt_with_expiry_stores_expires_at() {
             splits: Vec::new(&env),
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
 
@@ -2246,6 +2371,10 @@ fn test_expired_listing_excluded_from_get_all_prompts() {
             splits: Vec::new(&env),
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
     let persistent = create_prompt(&env, &client, &creator, "Persistent", 5_000, &context.xlm);
@@ -2290,6 +2419,10 @@ fn test_buy_expired_listing_fails() {
             splits: Vec::new(&env),
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
 
@@ -2352,6 +2485,10 @@ fn test_extend_listing_pushes_expiry_and_allows_purchase() {
             splits: Vec::new(&env),
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
 
@@ -2430,6 +2567,10 @@ fn test_create_prompt_with_splits_stores_split_data() {
             splits,
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
 
@@ -2475,14 +2616,18 @@ fn test_buy_prompt_with_splits_distributes_correctly() {
             splits,
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
 
     fund_buyer(&xlm_client, &buyer, &context.contract, price);
 
-    let creator_start = xlm_client.balance(&creator);
-    let co_creator_start = xlm_client.balance(&co_creator);
-    let fee_start = xlm_client.balance(&context.fee_wallet);
+    let creator_start = paid_balance(&client, &xlm_client, &creator);
+    let co_creator_start = paid_balance(&client, &xlm_client, &co_creator);
+    let fee_start = paid_balance(&client, &xlm_client, &context.fee_wallet);
 
     client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
 
@@ -2493,15 +2638,15 @@ fn test_buy_prompt_with_splits_distributes_correctly() {
     let expected_creator = price - expected_fee - expected_split; // 7_500
 
     assert_eq!(
-        xlm_client.balance(&creator),
+        paid_balance(&client, &xlm_client, &creator),
         creator_start + expected_creator
     );
     assert_eq!(
-        xlm_client.balance(&co_creator),
+        paid_balance(&client, &xlm_client, &co_creator),
         co_creator_start + expected_split
     );
     assert_eq!(
-        xlm_client.balance(&context.fee_wallet),
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
         fee_start + expected_fee
     );
     assert!(client.has_access(&buyer, &prompt_id));
@@ -2540,6 +2685,10 @@ fn test_splits_exceeding_max_bps_minus_fee_rejected() {
             splits,
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
     match result {
@@ -2592,25 +2741,35 @@ fn test_multiple_splits_distribute_all_recipients() {
             splits,
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
 
     fund_buyer(&xlm_client, &buyer, &context.contract, price);
 
-    let creator_start = xlm_client.balance(&creator);
-    let co1_start = xlm_client.balance(&co1);
-    let co2_start = xlm_client.balance(&co2);
+    let creator_start = paid_balance(&client, &xlm_client, &creator);
+    let co1_start = paid_balance(&client, &xlm_client, &co1);
+    let co2_start = paid_balance(&client, &xlm_client, &co2);
 
     client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
 
     client.settle_purchase(&context.admin, &prompt_id, &buyer);
 
     assert_eq!(
-        xlm_client.balance(&creator),
+        paid_balance(&client, &xlm_client, &creator),
         creator_start + price * (10_000 - 500 - 1_000 - 1_500) / 10_000
     );
-    assert_eq!(xlm_client.balance(&co1), co1_start + price * 1_000 / 10_000);
-    assert_eq!(xlm_client.balance(&co2), co2_start + price * 1_500 / 10_000);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &co1),
+        co1_start + price * 1_000 / 10_000
+    );
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &co2),
+        co2_start + price * 1_500 / 10_000
+    );
 }
 
 // ─── Issue #51: Bulk Purchase ─────────────────────────────────────────────────
@@ -2654,8 +2813,14 @@ fn test_buy_prompts_bulk_purchases_all_and_grants_access() {
     let expected_creator =
         (price_a - price_a * fee_bps / 10_000) + (price_b - price_b * fee_bps / 10_000);
     let expected_fee = price_a * fee_bps / 10_000 + price_b * fee_bps / 10_000;
-    assert_eq!(xlm_client.balance(&creator), expected_creator);
-    assert_eq!(xlm_client.balance(&context.fee_wallet), expected_fee);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &creator),
+        expected_creator
+    );
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
+        expected_fee
+    );
 }
 
 #[test]
@@ -2728,6 +2893,7 @@ fn test_buy_prompts_bulk_with_referrer() {
     let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
 
     client.set_referral_percentage(&500); // 5%
+    execute_latest_governance(&env, &client);
 
     let creator = Address::generate(&env);
     let buyer = Address::generate(&env);
@@ -2747,7 +2913,7 @@ fn test_buy_prompts_bulk_with_referrer() {
     amounts.push_back(price);
     amounts.push_back(price);
 
-    let referrer_start = xlm_client.balance(&referrer);
+    let referrer_start = paid_balance(&client, &xlm_client, &referrer);
     client.buy_prompts_bulk(&buyer, &ids, &amounts, &Some(referrer.clone()));
 
     client.settle_purchase(&context.admin, &prompt_a, &buyer);
@@ -2756,7 +2922,7 @@ fn test_buy_prompts_bulk_with_referrer() {
     // referral = 10_000 * 500 / 10_000 = 500 per prompt × 2
     let expected_referral = price * 500 / 10_000 * 2;
     assert_eq!(
-        xlm_client.balance(&referrer),
+        paid_balance(&client, &xlm_client, &referrer),
         referrer_start + expected_referral
     );
     assert!(client.has_access(&buyer, &prompt_a));
@@ -3132,9 +3298,9 @@ fn test_update_splits_replaces_existing_splits() {
     assert_eq!(prompt.splits.get(1).unwrap().bps, 1_500);
 
     fund_buyer(&xlm_client, &buyer, &context.contract, price);
-    let co1_start = xlm_client.balance(&co1);
-    let co2_start = xlm_client.balance(&co2);
-    let creator_start = xlm_client.balance(&creator);
+    let co1_start = paid_balance(&client, &xlm_client, &co1);
+    let co2_start = paid_balance(&client, &xlm_client, &co2);
+    let creator_start = paid_balance(&client, &xlm_client, &creator);
 
     client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
 
@@ -3145,10 +3311,16 @@ fn test_update_splits_replaces_existing_splits() {
     let expected_co2 = price * 1_500 / 10_000;
     let expected_creator = price - expected_fee - expected_co1 - expected_co2;
 
-    assert_eq!(xlm_client.balance(&co1), co1_start + expected_co1);
-    assert_eq!(xlm_client.balance(&co2), co2_start + expected_co2);
     assert_eq!(
-        xlm_client.balance(&creator),
+        paid_balance(&client, &xlm_client, &co1),
+        co1_start + expected_co1
+    );
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &co2),
+        co2_start + expected_co2
+    );
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &creator),
         creator_start + expected_creator
     );
 }
@@ -3293,6 +3465,10 @@ fn test_create_prompt_rejects_duplicate_split_recipients() {
             splits: dup_splits,
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
     match result {
@@ -3358,6 +3534,10 @@ fn test_create_prompt_tags_and_category_filters() {
                 ],
             ),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
 
@@ -3403,11 +3583,14 @@ fn test_buyer_can_open_and_admin_can_resolve_refund_dispute() {
     let open = client.get_dispute(&prompt_id, &buyer);
     assert_eq!(open.status, crate::types::DisputeStatus::Open);
 
-    let buyer_before = xlm_client.balance(&buyer);
-    client.resolve_dispute(&context.admin, &prompt_id, &buyer, &true);
+    let buyer_before = paid_balance(&client, &xlm_client, &buyer);
+    client.resolve_dispute(&context.admin, &prompt_id, &buyer, &price);
     let resolved = client.get_dispute(&prompt_id, &buyer);
     assert_eq!(resolved.status, crate::types::DisputeStatus::Refunded);
-    assert_eq!(xlm_client.balance(&buyer), buyer_before + price);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &buyer),
+        buyer_before + price
+    );
     assert!(!client.has_access(&buyer, &prompt_id));
 }
 
@@ -3449,9 +3632,9 @@ fn test_resolved_dispute_cannot_be_resolved_twice() {
         &prompt_id,
         &crate::types::DisputeReason::InvalidEncryptedPayload,
     );
-    client.resolve_dispute(&context.admin, &prompt_id, &buyer, &false);
+    client.resolve_dispute(&context.admin, &prompt_id, &buyer, &0);
 
-    let res = client.try_resolve_dispute(&context.admin, &prompt_id, &buyer, &false);
+    let res = client.try_resolve_dispute(&context.admin, &prompt_id, &buyer, &0);
     match res {
         Err(Ok(Error::DisputeResolved)) => {}
         other => panic!("expected DisputeResolved, got {:?}", other),
@@ -3558,6 +3741,10 @@ fn test_create_prompt_with_max_supply_stores_correctly() {
             splits: Vec::new(&env),
             tags: Vec::new(&env),
             max_supply: 3,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
 
@@ -3596,7 +3783,7 @@ fn test_limited_edition_exhausts_after_max_supply_sales() {
     );
 
     // Admin rejects the dispute (refund = false)
-    client.resolve_dispute(&context.admin, &prompt_id, &buyer, &false);
+    client.resolve_dispute(&context.admin, &prompt_id, &buyer, &0);
 
     // Buyer should NOT receive a refund
     let balance_after = xlm_client.balance(&buyer);
@@ -3663,6 +3850,10 @@ fn test_lease_price_is_40_percent_of_listing() {
             splits: Vec::new(&env),
             tags: Vec::new(&env),
             max_supply: 2,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
 
@@ -3727,14 +3918,14 @@ fn test_unlimited_supply_allows_many_purchases() {
     let lease_price = price * 4_000 / 10_000; // 40_000
     fund_buyer(&xlm_client, &buyer, &context.contract, lease_price);
 
-    let creator_balance_before = xlm_client.balance(&creator);
+    let creator_balance_before = paid_balance(&client, &xlm_client, &creator);
 
     client.lease_prompt(&buyer, &prompt_id, &3600); // 1 hour lease
 
     // Creator should receive lease_price minus fee
     let fee_pct = client.get_fee_percentage() as i128;
     let expected_creator_amount = lease_price - (lease_price * fee_pct / 10_000);
-    let creator_balance_after = xlm_client.balance(&creator);
+    let creator_balance_after = paid_balance(&client, &xlm_client, &creator);
     assert_eq!(
         creator_balance_after - creator_balance_before,
         expected_creator_amount
@@ -3818,6 +4009,10 @@ fn test_get_prompts_by_ids_empty_list() {
             splits: Vec::new(&env),
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
 
@@ -3868,7 +4063,7 @@ fn test_non_owner_cannot_set_referral_percentage() {
 }
 
 #[test]
-fn test_zero_price_prompt_rejected() {
+fn test_negative_price_prompt_rejected() {
     let env: Env = Default::default();
     let context = setup(&env);
     let client = PromptHashContractClient::new(&env, &context.contract);
@@ -3885,25 +4080,29 @@ fn test_zero_price_prompt_rejected() {
         &String::from_str(&env, "wrapped-key"),
         &hash(&env, 99),
         &ListingConfig {
-            price: 0,
+            price: -1,
             asset: context.xlm.clone(),
             expires_at: 0,
             splits: Vec::new(&env),
             tags: Vec::new(&env),
             max_supply: 0,
+            royalty_bps: 500,
+            royalty_splits: Vec::new(&env),
+            resale_allowed: true,
+            pricing: Vec::new(&env),
         },
     );
     match result {
         Err(Ok(Error::InvalidPrice)) => {}
         other => panic!(
-            "expected InvalidPrice for zero price prompt, got {:?}",
+            "expected InvalidPrice for negative price prompt, got {:?}",
             other
         ),
     }
 }
 
 #[test]
-fn test_update_price_to_zero_rejected() {
+fn test_update_price_to_negative_rejected() {
     let env: Env = Default::default();
     let context = setup(&env);
     let client = PromptHashContractClient::new(&env, &context.contract);
@@ -3919,11 +4118,11 @@ fn test_update_price_to_zero_rejected() {
         &context.xlm,
     );
 
-    let result = client.try_update_prompt_price(&creator, &prompt_id, &0i128);
+    let result = client.try_update_prompt_price(&creator, &prompt_id, &-1i128);
     match result {
         Err(Ok(Error::InvalidPrice)) => {}
         other => panic!(
-            "expected InvalidPrice for zero price update, got {:?}",
+            "expected InvalidPrice for negative price update, got {:?}",
             other
         ),
     }
@@ -4042,8 +4241,8 @@ fn test_payout_invariant_fee_plus_creator_equals_payment() {
     );
     fund_buyer(&xlm_client, &buyer, &context.contract, payment);
 
-    let creator_balance_before = xlm_client.balance(&creator);
-    let fee_wallet_balance_before = xlm_client.balance(&context.fee_wallet);
+    let creator_balance_before = paid_balance(&client, &xlm_client, &creator);
+    let fee_wallet_balance_before = paid_balance(&client, &xlm_client, &context.fee_wallet);
 
     client.buy_prompt(
         &buyer,
@@ -4060,8 +4259,9 @@ fn test_payout_invariant_fee_plus_creator_equals_payment() {
     let expected_fee = payment * fee_bps / 10_000;
     let expected_creator = payment - expected_fee;
 
-    let creator_received = xlm_client.balance(&creator) - creator_balance_before;
-    let fee_received = xlm_client.balance(&context.fee_wallet) - fee_wallet_balance_before;
+    let creator_received = paid_balance(&client, &xlm_client, &creator) - creator_balance_before;
+    let fee_received =
+        paid_balance(&client, &xlm_client, &context.fee_wallet) - fee_wallet_balance_before;
 
     assert_eq!(creator_received, expected_creator);
     assert_eq!(fee_received, expected_fee);
@@ -4091,9 +4291,9 @@ fn test_payout_invariant_with_referral() {
     );
     fund_buyer(&xlm_client, &buyer, &context.contract, payment);
 
-    let creator_balance_before = xlm_client.balance(&creator);
-    let fee_wallet_balance_before = xlm_client.balance(&context.fee_wallet);
-    let referrer_balance_before = xlm_client.balance(&referrer);
+    let creator_balance_before = paid_balance(&client, &xlm_client, &creator);
+    let fee_wallet_balance_before = paid_balance(&client, &xlm_client, &context.fee_wallet);
+    let referrer_balance_before = paid_balance(&client, &xlm_client, &referrer);
 
     client.buy_prompt(
         &buyer,
@@ -4112,9 +4312,10 @@ fn test_payout_invariant_with_referral() {
     let expected_referral = payment * referral_bps / 10_000;
     let expected_creator = payment - expected_fee - expected_referral;
 
-    let creator_received = xlm_client.balance(&creator) - creator_balance_before;
-    let fee_received = xlm_client.balance(&context.fee_wallet) - fee_wallet_balance_before;
-    let referrer_received = xlm_client.balance(&referrer) - referrer_balance_before;
+    let creator_received = paid_balance(&client, &xlm_client, &creator) - creator_balance_before;
+    let fee_received =
+        paid_balance(&client, &xlm_client, &context.fee_wallet) - fee_wallet_balance_before;
+    let referrer_received = paid_balance(&client, &xlm_client, &referrer) - referrer_balance_before;
 
     assert_eq!(creator_received + fee_received + referrer_received, payment);
     assert_eq!(expected_fee + expected_referral + expected_creator, payment);
@@ -4155,10 +4356,10 @@ fn test_payout_invariant_with_splits() {
     );
     fund_buyer(&xlm_client, &buyer, &context.contract, payment);
 
-    let creator_balance_before = xlm_client.balance(&creator);
-    let fee_wallet_balance_before = xlm_client.balance(&context.fee_wallet);
-    let split_1_balance_before = xlm_client.balance(&split_recipient_1);
-    let split_2_balance_before = xlm_client.balance(&split_recipient_2);
+    let creator_balance_before = paid_balance(&client, &xlm_client, &creator);
+    let fee_wallet_balance_before = paid_balance(&client, &xlm_client, &context.fee_wallet);
+    let split_1_balance_before = paid_balance(&client, &xlm_client, &split_recipient_1);
+    let split_2_balance_before = paid_balance(&client, &xlm_client, &split_recipient_2);
 
     client.buy_prompt(
         &buyer,
@@ -4177,10 +4378,13 @@ fn test_payout_invariant_with_splits() {
     let expected_split_2 = payment * 500 / 10_000;
     let expected_creator = payment - expected_fee - expected_split_1 - expected_split_2;
 
-    let creator_received = xlm_client.balance(&creator) - creator_balance_before;
-    let fee_received = xlm_client.balance(&context.fee_wallet) - fee_wallet_balance_before;
-    let split_1_received = xlm_client.balance(&split_recipient_1) - split_1_balance_before;
-    let split_2_received = xlm_client.balance(&split_recipient_2) - split_2_balance_before;
+    let creator_received = paid_balance(&client, &xlm_client, &creator) - creator_balance_before;
+    let fee_received =
+        paid_balance(&client, &xlm_client, &context.fee_wallet) - fee_wallet_balance_before;
+    let split_1_received =
+        paid_balance(&client, &xlm_client, &split_recipient_1) - split_1_balance_before;
+    let split_2_received =
+        paid_balance(&client, &xlm_client, &split_recipient_2) - split_2_balance_before;
 
     assert_eq!(
         creator_received + fee_received + split_1_received + split_2_received,
@@ -4209,8 +4413,8 @@ fn test_payout_invariant_with_tip() {
     let prompt_id = create_prompt(&env, &client, &creator, "Tip Test", price, &context.xlm);
     fund_buyer(&xlm_client, &buyer, &context.contract, payment);
 
-    let creator_balance_before = xlm_client.balance(&creator);
-    let fee_wallet_balance_before = xlm_client.balance(&context.fee_wallet);
+    let creator_balance_before = paid_balance(&client, &xlm_client, &creator);
+    let fee_wallet_balance_before = paid_balance(&client, &xlm_client, &context.fee_wallet);
 
     client.buy_prompt(
         &buyer,
@@ -4227,8 +4431,9 @@ fn test_payout_invariant_with_tip() {
     let expected_fee = payment * fee_bps / 10_000;
     let expected_creator = payment - expected_fee;
 
-    let creator_received = xlm_client.balance(&creator) - creator_balance_before;
-    let fee_received = xlm_client.balance(&context.fee_wallet) - fee_wallet_balance_before;
+    let creator_received = paid_balance(&client, &xlm_client, &creator) - creator_balance_before;
+    let fee_received =
+        paid_balance(&client, &xlm_client, &context.fee_wallet) - fee_wallet_balance_before;
 
     // Creator should receive their share of the full payment (including tip)
     assert_eq!(creator_received, expected_creator);
@@ -4348,7 +4553,7 @@ fn test_dispute_refund_releases_supply_for_resale() {
         &prompt_id,
         &crate::types::DisputeReason::FailedIntegrityVerification,
     );
-    client.resolve_dispute(&context.admin, &prompt_id, &buyer_one, &true);
+    client.resolve_dispute(&context.admin, &prompt_id, &buyer_one, &price);
     assert_eq!(client.get_prompt(&prompt_id).sales_count, 0);
 
     // Now the second buyer can acquire the freed unit.
@@ -4384,6 +4589,7 @@ fn test_transfer_license_does_not_consume_or_free_supply() {
         &price,
         &None::<Bytes>,
     );
+    client.settle_purchase(&context.admin, &prompt_id, &seller);
     assert_eq!(client.get_prompt(&prompt_id).sales_count, 1);
 
     let resale_price = 3_000;
@@ -4775,7 +4981,7 @@ fn test_permissionless_settlement_after_window_by_any_caller() {
     fund_buyer(&xlm_client, &buyer, &context.contract, price);
     client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
 
-    let creator_before = xlm_client.balance(&creator);
+    let creator_before = paid_balance(&client, &xlm_client, &creator);
     env.ledger()
         .with_mut(|ledger| ledger.timestamp = 3 * 24 * 60 * 60 + 1);
 
@@ -4785,7 +4991,7 @@ fn test_permissionless_settlement_after_window_by_any_caller() {
 
     let escrow = client.get_purchase_escrow(&prompt_id, &buyer).unwrap();
     assert_eq!(escrow.status, crate::types::SettlementStatus::Settled);
-    assert!(xlm_client.balance(&creator) > creator_before);
+    assert!(paid_balance(&client, &xlm_client, &creator) > creator_before);
 }
 
 #[test]
@@ -4919,7 +5125,7 @@ fn test_asset_liability_moves_back_to_pending_on_dispute_rejected() {
         &prompt_id,
         &crate::types::DisputeReason::InvalidEncryptedPayload,
     );
-    client.resolve_dispute(&context.admin, &prompt_id, &buyer, &false);
+    client.resolve_dispute(&context.admin, &prompt_id, &buyer, &0);
 
     let liability = client.get_asset_liability(&context.xlm);
     assert_eq!(liability.pending, price);
@@ -4949,7 +5155,7 @@ fn test_asset_liability_clears_on_dispute_refunded() {
         &prompt_id,
         &crate::types::DisputeReason::InvalidEncryptedPayload,
     );
-    client.resolve_dispute(&context.admin, &prompt_id, &buyer, &true);
+    client.resolve_dispute(&context.admin, &prompt_id, &buyer, &price);
 
     let liability = client.get_asset_liability(&context.xlm);
     assert_eq!(liability.pending, 0);
@@ -5060,3 +5266,4443 @@ fn test_migrate_asset_liability_is_idempotent() {
     client.migrate_asset_liability(&context.admin, &prompt_id, &buyer);
     assert_eq!(client.get_asset_liability(&context.xlm).pending, price);
 }
+
+// ─── Issue #565: Quote-bound checkout for every acquisition path ──────────────
+
+fn quote_for(
+    env: &Env,
+    context: &PromptHashContext,
+    client: &PromptHashContractClient,
+    buyer: &Address,
+    kind: AcquisitionKind,
+    acquisition_id: u128,
+    max_charge: i128,
+    nonce_byte: u8,
+) -> QuoteCommitment {
+    QuoteCommitment {
+        network_id: env.ledger().network_id(),
+        contract_id: env
+            .crypto()
+            .sha256(&context.contract.to_string().to_bytes())
+            .to_bytes(),
+        buyer: buyer.clone(),
+        kind: kind.clone(),
+        acquisition_id,
+        asset: context.xlm.clone(),
+        terms_hash: client.get_quote_terms_hash(&kind, &acquisition_id),
+        max_charge,
+        tip_amount: 0,
+        not_before_ledger: env.ledger().sequence(),
+        expiry_ledger: env.ledger().sequence() + 100,
+        nonce: hash(env, nonce_byte),
+    }
+}
+
+#[test]
+fn test_buy_with_quote_grants_access_and_rejects_replay() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Quoted", price, &context.xlm);
+
+    let mut quote = quote_for(
+        &env,
+        &context,
+        &client,
+        &buyer,
+        AcquisitionKind::DirectPurchase,
+        prompt_id as u128,
+        price,
+        1,
+    );
+    quote.tip_amount = 250;
+    fund_buyer(&xlm_client, &buyer, &context.contract, price * 2);
+
+    client.buy_with_quote(&quote, &None::<Address>);
+    assert!(client.has_access(&buyer, &prompt_id));
+    // Only the quoted price plus the explicit tip is debited.
+    assert_eq!(xlm_client.balance(&buyer), price * 2 - price - 250);
+
+    let res = client.try_buy_with_quote(&quote, &None::<Address>);
+    match res {
+        Err(Ok(Error::QuoteNonceConsumed)) => {}
+        other => panic!("expected QuoteNonceConsumed, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_buy_with_quote_fails_closed_when_terms_change() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Moving", price, &context.xlm);
+
+    let quote = quote_for(
+        &env,
+        &context,
+        &client,
+        &buyer,
+        AcquisitionKind::DirectPurchase,
+        prompt_id as u128,
+        price * 2,
+        2,
+    );
+    fund_buyer(&xlm_client, &buyer, &context.contract, price * 2);
+
+    // A price change between simulation and submission invalidates the
+    // quote even though the new price is still under `max_charge`.
+    client.update_prompt_price(&creator, &prompt_id, &(price + 1));
+    let res = client.try_buy_with_quote(&quote, &None::<Address>);
+    match res {
+        Err(Ok(Error::QuoteTermsChanged)) => {}
+        other => panic!("expected QuoteTermsChanged, got {:?}", other),
+    }
+    assert!(!client.has_access(&buyer, &prompt_id));
+
+    // A fee-schedule change invalidates it as well.
+    let quote = quote_for(
+        &env,
+        &context,
+        &client,
+        &buyer,
+        AcquisitionKind::DirectPurchase,
+        prompt_id as u128,
+        price * 2,
+        3,
+    );
+    env.as_contract(&context.contract, || {
+        crate::storage::InstanceStorage::set_fee_percentage(&env, &100);
+    });
+    let res = client.try_buy_with_quote(&quote, &None::<Address>);
+    match res {
+        Err(Ok(Error::QuoteTermsChanged)) => {}
+        other => panic!("expected QuoteTermsChanged, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_quote_rejects_charge_above_cap_expiry_and_wrong_domain() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Capped", price, &context.xlm);
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+
+    let quote = quote_for(
+        &env,
+        &context,
+        &client,
+        &buyer,
+        AcquisitionKind::DirectPurchase,
+        prompt_id as u128,
+        price - 1,
+        4,
+    );
+    let res = client.try_buy_with_quote(&quote, &None::<Address>);
+    match res {
+        Err(Ok(Error::QuoteChargeExceeded)) => {}
+        other => panic!("expected QuoteChargeExceeded, got {:?}", other),
+    }
+
+    let mut quote = quote_for(
+        &env,
+        &context,
+        &client,
+        &buyer,
+        AcquisitionKind::DirectPurchase,
+        prompt_id as u128,
+        price,
+        5,
+    );
+    quote.contract_id = hash(&env, 9);
+    let res = client.try_buy_with_quote(&quote, &None::<Address>);
+    match res {
+        Err(Ok(Error::QuoteDomainMismatch)) => {}
+        other => panic!("expected QuoteDomainMismatch, got {:?}", other),
+    }
+
+    let quote = quote_for(
+        &env,
+        &context,
+        &client,
+        &buyer,
+        AcquisitionKind::DirectPurchase,
+        prompt_id as u128,
+        price,
+        6,
+    );
+    env.ledger()
+        .with_mut(|ledger| ledger.sequence_number = quote.expiry_ledger + 1);
+    let res = client.try_buy_with_quote(&quote, &None::<Address>);
+    match res {
+        Err(Ok(Error::QuoteExpired)) => {}
+        other => panic!("expected QuoteExpired, got {:?}", other),
+    }
+    assert!(!client.has_access(&buyer, &prompt_id));
+}
+
+#[test]
+fn test_lease_quote_fails_once_lease_terms_change() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Lease", 10_000, &context.xlm);
+    client.set_lease_terms(&creator, &prompt_id, &Some(daily_lease_terms(100)));
+    fund_buyer(&xlm_client, &buyer, &context.contract, 10_000);
+
+    let mut quote = quote_for(
+        &env,
+        &context,
+        &client,
+        &buyer,
+        AcquisitionKind::DirectPurchase,
+        prompt_id as u128,
+        1_000,
+        12,
+    );
+    quote.kind = AcquisitionKind::Lease;
+    quote.terms_hash = client.get_lease_quote_terms_hash(&prompt_id, &(3 * DAY));
+
+    // Tripling the daily price keeps the charge under the quote's cap, but
+    // the buyer never agreed to it.
+    client.set_lease_terms(&creator, &prompt_id, &Some(daily_lease_terms(300)));
+    match client.try_lease_with_quote(&quote, &(3 * DAY)) {
+        Err(Ok(Error::QuoteTermsChanged)) => {}
+        other => panic!("expected QuoteTermsChanged, got {:?}", other),
+    }
+    assert!(!client.has_access(&buyer, &prompt_id));
+
+    client.set_lease_terms(&creator, &prompt_id, &Some(daily_lease_terms(100)));
+    client.lease_with_quote(&quote, &(3 * DAY));
+    assert!(client.has_access(&buyer, &prompt_id));
+}
+
+#[test]
+fn test_lease_bundle_and_pass_with_quote() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let lease_prompt = create_prompt(&env, &client, &creator, "Lease", 10_000, &context.xlm);
+    let bundled = create_prompt(&env, &client, &creator, "Bundled", 3_000, &context.xlm);
+    fund_buyer(&xlm_client, &buyer, &context.contract, 50_000);
+
+    let mut quote = quote_for(
+        &env,
+        &context,
+        &client,
+        &buyer,
+        AcquisitionKind::DirectPurchase,
+        lease_prompt as u128,
+        4_000,
+        10,
+    );
+    quote.kind = AcquisitionKind::Lease;
+    quote.terms_hash = client.get_lease_quote_terms_hash(&lease_prompt, &1_000u64);
+    // The quote is bound to the duration it was signed for.
+    match client.try_lease_with_quote(&quote, &500u64) {
+        Err(Ok(Error::QuoteTermsChanged)) => {}
+        other => panic!("expected QuoteTermsChanged, got {:?}", other),
+    }
+    client.lease_with_quote(&quote, &1_000u64);
+    assert!(client.has_access(&buyer, &lease_prompt));
+
+    let mut prompt_ids = Vec::new(&env);
+    prompt_ids.push_back(bundled);
+    let bundle_id = client.create_bundle(
+        &creator,
+        &String::from_str(&env, "Quoted Bundle"),
+        &prompt_ids,
+        &2_500,
+        &context.xlm,
+        &0u64,
+    );
+    let mut quote = quote_for(
+        &env,
+        &context,
+        &client,
+        &buyer,
+        AcquisitionKind::Bundle,
+        bundle_id,
+        2_500,
+        11,
+    );
+    // Tips are only meaningful on direct purchases.
+    quote.tip_amount = 1;
+    let res = client.try_buy_bundle_with_quote(&quote);
+    match res {
+        Err(Ok(Error::UnauthorizedTip)) => {}
+        other => panic!("expected UnauthorizedTip, got {:?}", other),
+    }
+    quote.tip_amount = 0;
+    client.buy_bundle_with_quote(&quote);
+    assert!(client.has_access(&buyer, &bundled));
+
+    let pass_id = client.create_access_pass(
+        &creator,
+        &String::from_str(&env, "Quoted Pass"),
+        &1_000u64,
+        &6_000,
+        &context.xlm,
+        &0u32,
+    );
+    let quote = quote_for(
+        &env,
+        &context,
+        &client,
+        &buyer,
+        AcquisitionKind::AccessPass,
+        pass_id,
+        6_000,
+        12,
+    );
+    let balance_before = xlm_client.balance(&buyer);
+    client.buy_pass_with_quote(&quote);
+    assert_eq!(xlm_client.balance(&buyer), balance_before - 6_000);
+}
+
+#[test]
+fn test_bulk_checkout_with_quote_caps_total_charge() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let first = create_prompt(&env, &client, &creator, "First", 2_000, &context.xlm);
+    let second = create_prompt(&env, &client, &creator, "Second", 3_000, &context.xlm);
+    fund_buyer(&xlm_client, &buyer, &context.contract, 10_000);
+
+    let mut prompt_ids = Vec::new(&env);
+    prompt_ids.push_back(first);
+    prompt_ids.push_back(second);
+
+    let mut quote = quote_for(
+        &env,
+        &context,
+        &client,
+        &buyer,
+        AcquisitionKind::DirectPurchase,
+        0,
+        4_999,
+        20,
+    );
+    quote.kind = AcquisitionKind::BulkCheckout;
+    quote.terms_hash = client.get_bulk_quote_terms_hash(&prompt_ids);
+    let res = client.try_buy_bulk_with_quote(&quote, &prompt_ids, &None::<Address>);
+    match res {
+        Err(Ok(Error::QuoteChargeExceeded)) => {}
+        other => panic!("expected QuoteChargeExceeded, got {:?}", other),
+    }
+
+    quote.max_charge = 5_000;
+    client.buy_bulk_with_quote(&quote, &prompt_ids, &None::<Address>);
+    assert!(client.has_access(&buyer, &first));
+    assert!(client.has_access(&buyer, &second));
+    assert_eq!(xlm_client.balance(&buyer), 5_000);
+}
+
+// ─── Issue #567: Append-only settlement records ──────────────────────────────
+
+#[test]
+fn test_lease_then_repurchase_keeps_both_settlements() {
+    let env: Env = Default::default();
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1_000);
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 10_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Ledger", price, &context.xlm);
+    fund_buyer(&xlm_client, &buyer, &context.contract, 100_000);
+
+    client.lease_prompt(&buyer, &prompt_id, &600);
+    let lease_id = client
+        .get_entitlement_settlement(&prompt_id, &buyer)
+        .unwrap();
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = 2_000);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+    let purchase_id = client
+        .get_entitlement_settlement(&prompt_id, &buyer)
+        .unwrap();
+    assert_ne!(lease_id, purchase_id);
+
+    // The lease record is untouched by the repurchase.
+    let lease = client.get_settlement(&lease_id);
+    assert_eq!(lease.kind, AcquisitionKind::Lease);
+    assert_eq!(lease.amount, 4_000);
+    assert_eq!(lease.status, crate::types::SettlementStatus::Settled);
+    assert_eq!(
+        client.get_settlement_escrow(&lease_id).unwrap().amount,
+        4_000
+    );
+
+    let purchase = client.get_settlement(&purchase_id);
+    assert_eq!(purchase.kind, AcquisitionKind::DirectPurchase);
+    assert_eq!(purchase.supersedes, Some(lease_id));
+    assert_eq!(purchase.status, crate::types::SettlementStatus::Pending);
+
+    let history = client.get_prompt_settlements(&prompt_id, &buyer);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().settlement_id, lease_id);
+    assert_eq!(history.get(1).unwrap().settlement_id, purchase_id);
+
+    // Settling by ID updates the record as well as the escrow.
+    client.settle_settlement(&context.admin, &purchase_id);
+    assert_eq!(
+        client.get_settlement(&purchase_id).status,
+        crate::types::SettlementStatus::Settled
+    );
+    let res = client.try_settle_settlement(&context.admin, &purchase_id);
+    match res {
+        Err(Ok(Error::SettlementAlreadyFinalized)) => {}
+        other => panic!("expected SettlementAlreadyFinalized, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_bundle_and_pass_settlements_do_not_collide() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let first = create_prompt(&env, &client, &creator, "First", 3_000, &context.xlm);
+    let second = create_prompt(&env, &client, &creator, "Second", 3_000, &context.xlm);
+    fund_buyer(&xlm_client, &buyer, &context.contract, 100_000);
+
+    let mut prompt_ids = Vec::new(&env);
+    prompt_ids.push_back(first);
+    prompt_ids.push_back(second);
+    let bundle_id = client.create_bundle(
+        &creator,
+        &String::from_str(&env, "Pair"),
+        &prompt_ids,
+        &5_000,
+        &context.xlm,
+        &0u64,
+    );
+    let pass_id = client.create_access_pass(
+        &creator,
+        &String::from_str(&env, "Pass"),
+        &1_000u64,
+        &7_000,
+        &context.xlm,
+        &0u32,
+    );
+    client.buy_bundle(&buyer, &bundle_id, &5_000);
+    client.buy_access_pass(&buyer, &pass_id, &7_000);
+
+    // Both bundled prompts point at the single bundle settlement.
+    let bundle_settlement = client.get_entitlement_settlement(&first, &buyer).unwrap();
+    assert_eq!(
+        client.get_entitlement_settlement(&second, &buyer),
+        Some(bundle_settlement)
+    );
+
+    let (page, cursor) = client.get_buyer_settlements(&buyer, &None::<u128>, &1);
+    assert_eq!(page.len(), 1);
+    let bundle = page.get(0).unwrap();
+    assert_eq!(bundle.settlement_id, bundle_settlement);
+    assert_eq!(bundle.kind, AcquisitionKind::Bundle);
+    assert_eq!(bundle.acquisition_id, bundle_id);
+    assert_eq!(bundle.amount, 5_000);
+
+    let (page, cursor) = client.get_buyer_settlements(&buyer, &cursor, &10);
+    assert_eq!(page.len(), 1);
+    let pass = page.get(0).unwrap();
+    assert_eq!(pass.kind, AcquisitionKind::AccessPass);
+    assert_eq!(pass.acquisition_id, pass_id);
+    assert_eq!(pass.amount, 7_000);
+    assert_eq!(
+        client
+            .get_settlement_escrow(&bundle_settlement)
+            .unwrap()
+            .amount,
+        5_000
+    );
+
+    let (page, _) = client.get_buyer_settlements(&buyer, &cursor, &10);
+    assert!(page.is_empty());
+}
+
+#[test]
+fn test_legacy_escrow_is_migrated_on_first_state_change() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Legacy", price, &context.xlm);
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+
+    // Rewrite the escrow into the pre-#567 buyer-keyed layout.
+    env.as_contract(&context.contract, || {
+        let settlement_id =
+            crate::storage::Storage::get_entitlement_settlement(&env, prompt_id, &buyer).unwrap();
+        let escrow = crate::storage::Storage::get_settlement_escrow(&env, settlement_id).unwrap();
+        let storage = env.storage().persistent();
+        storage.set(
+            &crate::types::DataKey::PurchaseEscrow(prompt_id, buyer.clone()),
+            &escrow,
+        );
+        storage.remove(&crate::types::DataKey::EntitlementPointer(
+            prompt_id,
+            buyer.clone(),
+        ));
+    });
+
+    // Reads see the legacy escrow without migrating it.
+    let escrow = client.get_purchase_escrow(&prompt_id, &buyer).unwrap();
+    assert_eq!(escrow.amount, price);
+    assert_eq!(client.get_entitlement_settlement(&prompt_id, &buyer), None);
+
+    // The first state change migrates it, and it settles normally.
+    client.settle_purchase(&context.admin, &prompt_id, &buyer);
+    let migrated = client
+        .get_entitlement_settlement(&prompt_id, &buyer)
+        .unwrap();
+    assert_eq!(client.get_settlement(&migrated).amount, price);
+    assert_eq!(
+        client.get_settlement(&migrated).status,
+        crate::types::SettlementStatus::Settled
+    );
+}
+
+#[test]
+fn test_pre_upgrade_layouts_decode_with_defaults() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Legacy", price, &context.xlm);
+    client.revise_listing(
+        &creator,
+        &prompt_id,
+        &String::from_str(&env, "Legacy v2"),
+        &String::from_str(&env, "Software Development"),
+        &String::from_str(&env, "preview"),
+        &String::from_str(&env, "https://example.com/image.png"),
+        &price,
+    );
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+
+    // Rewrite every record in the layout the contract stored before the
+    // upgrade, with the escrow back under its buyer key.
+    env.as_contract(&context.contract, || {
+        use crate::types::{
+            DataKey, LegacyAssetLiability, LegacyListingRevisionRecord, LegacyPayoutPlan,
+            LegacyPrompt, LegacyPurchase, LegacyPurchaseEscrow,
+        };
+        let storage = env.storage().persistent();
+        let prompt = crate::storage::Storage::require_prompt(&env, prompt_id).unwrap();
+        storage.set(
+            &DataKey::Prompt(prompt_id),
+            &LegacyPrompt {
+                id: prompt.id,
+                creator: prompt.creator,
+                image_url: prompt.image_url,
+                title: prompt.title,
+                category: prompt.category,
+                preview_text: prompt.preview_text,
+                encrypted_payload: prompt.encrypted_payload,
+                encryption_iv: prompt.encryption_iv,
+                wrapped_key: prompt.wrapped_key,
+                content_hash: prompt.content_hash,
+                price_stroops: prompt.price_stroops,
+                asset: prompt.asset,
+                status: prompt.status,
+                sales_count: prompt.sales_count,
+                max_supply: prompt.max_supply,
+                expires_at: prompt.expires_at,
+                splits: prompt.splits,
+                revision: prompt.revision,
+                tags: prompt.tags,
+            },
+        );
+        let record = crate::storage::Storage::get_listing_revision(&env, prompt_id, 0).unwrap();
+        storage.set(
+            &DataKey::ListingRevision(prompt_id, 0),
+            &LegacyListingRevisionRecord {
+                prompt_id,
+                revision: 0,
+                title: record.title,
+                category: record.category,
+                preview_text: record.preview_text,
+                image_url: record.image_url,
+                price_stroops: record.price_stroops,
+                revised_at: record.revised_at,
+            },
+        );
+        let purchase = crate::storage::Storage::get_purchase(&env, prompt_id, &buyer).unwrap();
+        storage.set(
+            &DataKey::Purchase(prompt_id, buyer.clone()),
+            &LegacyPurchase {
+                prompt_id,
+                original_creator: purchase.original_creator,
+                owner: purchase.owner,
+                original_price: purchase.original_price,
+                last_transfer_price: purchase.last_transfer_price,
+                transfer_count: purchase.transfer_count,
+                last_transferred_at: purchase.last_transferred_at,
+                expires_at: purchase.expires_at,
+            },
+        );
+        let settlement_id =
+            crate::storage::Storage::get_entitlement_settlement(&env, prompt_id, &buyer).unwrap();
+        let escrow = crate::storage::Storage::get_settlement_escrow(&env, settlement_id).unwrap();
+        let plan = escrow.payout_plan;
+        storage.set(
+            &DataKey::PurchaseEscrow(prompt_id, buyer.clone()),
+            &LegacyPurchaseEscrow {
+                prompt_id,
+                buyer: buyer.clone(),
+                amount: escrow.amount,
+                status: escrow.status,
+                created_at: escrow.created_at,
+                settled_at: escrow.settled_at,
+                dispute_deadline: escrow.dispute_deadline,
+                asset: escrow.asset,
+                referrer: escrow.referrer,
+                creator_amount: escrow.creator_amount,
+                fee_amount: escrow.fee_amount,
+                referral_amount: escrow.referral_amount,
+                payout_plan: LegacyPayoutPlan {
+                    creator: plan.creator,
+                    fee_wallet: plan.fee_wallet,
+                    fee_amount: plan.fee_amount,
+                    referrer: plan.referrer,
+                    referral_amount: plan.referral_amount,
+                    splits: plan.splits,
+                    creator_amount: plan.creator_amount,
+                },
+            },
+        );
+        storage.remove(&DataKey::EntitlementPointer(prompt_id, buyer.clone()));
+        storage.set(
+            &DataKey::AssetLiability(context.xlm.clone()),
+            &LegacyAssetLiability {
+                pending: price,
+                disputed: 0,
+            },
+        );
+    });
+
+    let prompt = client.get_prompt(&prompt_id);
+    assert_eq!(prompt.royalty_bps, 0);
+    assert!(prompt.resale_allowed);
+    assert_eq!(prompt.pricing.len(), 0);
+    let record = client.get_listing_revision(&prompt_id, &0);
+    assert_eq!(record.title, String::from_str(&env, "Legacy"));
+    assert_eq!(record.content_hash, BytesN::from_array(&env, &[0; 32]));
+
+    // A legacy license is unpinned and reads the latest revision.
+    client.set_content_access_policy(
+        &creator,
+        &prompt_id,
+        &ContentAccessPolicy::PurchasedRevision,
+    );
+    assert_eq!(client.get_licensed_content(&prompt_id, &buyer).revision, 1);
+
+    let escrow = client.get_purchase_escrow(&prompt_id, &buyer).unwrap();
+    assert_eq!(escrow.refunded_amount, 0);
+    assert_eq!(escrow.payout_plan.conversion, PriceConversion::Listed);
+    let liability = client.get_asset_liability(&context.xlm);
+    assert_eq!(liability.pending, price);
+    assert_eq!(liability.claimable, 0);
+
+    // State changes rewrite the records in the current layout.
+    client.settle_purchase(&context.admin, &prompt_id, &buyer);
+    let liability = client.get_asset_liability(&context.xlm);
+    assert_eq!(liability.pending, 0);
+    assert!(liability.claimable > 0);
+    client.update_prompt_price(&creator, &prompt_id, &6_000);
+    assert_eq!(client.get_prompt(&prompt_id).price_stroops, 6_000);
+}
+
+// ─── Issue #568: Seller-signed resale orders ─────────────────────────────────
+
+fn resale_order_for(
+    env: &Env,
+    context: &PromptHashContext,
+    client: &PromptHashContractClient,
+    seller: &Address,
+    prompt_id: u64,
+    price: i128,
+    buyer: Option<Address>,
+    nonce_byte: u8,
+) -> ResaleOrder {
+    ResaleOrder {
+        network_id: env.ledger().network_id(),
+        contract_id: env
+            .crypto()
+            .sha256(&context.contract.to_string().to_bytes())
+            .to_bytes(),
+        seller: seller.clone(),
+        prompt_id,
+        settlement_id: client
+            .get_entitlement_settlement(&prompt_id, seller)
+            .unwrap(),
+        asset: context.xlm.clone(),
+        price,
+        min_proceeds: 0,
+        buyer,
+        royalty_bps: 500,
+        expiry_ledger: env.ledger().sequence() + 100,
+        nonce: hash(env, nonce_byte),
+        status: ResaleOrderStatus::Open,
+    }
+}
+
+#[test]
+fn test_open_resale_order_fills_without_seller_and_routes_royalty() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Resale", 10_000, &context.xlm);
+
+    fund_buyer(&xlm_client, &seller, &context.contract, 10_000);
+    client.buy_prompt(
+        &seller,
+        &prompt_id,
+        &None::<Address>,
+        &10_000,
+        &None::<Bytes>,
+    );
+    client.settle_purchase(&context.admin, &prompt_id, &seller);
+
+    let mut order = resale_order_for(&env, &context, &client, &seller, prompt_id, 20_000, None, 1);
+    order.royalty_bps = 1_000;
+    order.min_proceeds = 18_000;
+    let order_hash = client.post_resale_order(&order);
+    assert_eq!(client.get_resale_order(&order_hash), order);
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, 20_000);
+    let creator_before = paid_balance(&client, &xlm_client, &creator);
+    let seller_before = paid_balance(&client, &xlm_client, &seller);
+    client.fill_resale_order(&buyer, &order);
+
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &creator),
+        creator_before + 2_000
+    );
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &seller),
+        seller_before + 18_000
+    );
+    assert!(client.has_access(&buyer, &prompt_id));
+    assert!(!client.has_access(&seller, &prompt_id));
+    assert_eq!(
+        client.get_resale_order(&order_hash).status,
+        ResaleOrderStatus::Filled
+    );
+    let settlement_id = client
+        .get_entitlement_settlement(&prompt_id, &buyer)
+        .unwrap();
+    assert_eq!(
+        client.get_settlement(&settlement_id).kind,
+        AcquisitionKind::ResaleFill
+    );
+
+    let other = Address::generate(&env);
+    fund_buyer(&xlm_client, &other, &context.contract, 20_000);
+    let res = client.try_fill_resale_order(&other, &order);
+    match res {
+        Err(Ok(Error::ResaleOrderFilled)) => {}
+        other => panic!("expected ResaleOrderFilled, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_restricted_resale_order_rejects_other_buyers_and_cancel_blocks_fill() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let named = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Restricted", 10_000, &context.xlm);
+
+    fund_buyer(&xlm_client, &seller, &context.contract, 10_000);
+    client.buy_prompt(
+        &seller,
+        &prompt_id,
+        &None::<Address>,
+        &10_000,
+        &None::<Bytes>,
+    );
+
+    let order = resale_order_for(
+        &env,
+        &context,
+        &client,
+        &seller,
+        prompt_id,
+        15_000,
+        Some(named.clone()),
+        2,
+    );
+    let order_hash = client.post_resale_order(&order);
+
+    fund_buyer(&xlm_client, &stranger, &context.contract, 15_000);
+    let res = client.try_fill_resale_order(&stranger, &order);
+    match res {
+        Err(Ok(Error::ResaleOrderBuyerMismatch)) => {}
+        other => panic!("expected ResaleOrderBuyerMismatch, got {:?}", other),
+    }
+
+    // Tampering with the terms does not match the posted order.
+    let mut cheaper = order.clone();
+    cheaper.price = 1;
+    let res = client.try_fill_resale_order(&named, &cheaper);
+    match res {
+        Err(Ok(Error::ResaleOrderNotFound)) => {}
+        other => panic!("expected ResaleOrderNotFound, got {:?}", other),
+    }
+
+    client.cancel_resale_order(&seller, &order_hash);
+    fund_buyer(&xlm_client, &named, &context.contract, 15_000);
+    let res = client.try_fill_resale_order(&named, &order);
+    match res {
+        Err(Ok(Error::ResaleOrderCancelled)) => {}
+        other => panic!("expected ResaleOrderCancelled, got {:?}", other),
+    }
+
+    // A nonce is single-use even after cancellation.
+    let res = client.try_post_resale_order(&order);
+    match res {
+        Err(Ok(Error::ResaleOrderNonceConsumed)) => {}
+        other => panic!("expected ResaleOrderNonceConsumed, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_resale_order_rechecks_ownership_and_terms() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let first_buyer = Address::generate(&env);
+    let second_buyer = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Double", 10_000, &context.xlm);
+
+    fund_buyer(&xlm_client, &seller, &context.contract, 10_000);
+    client.buy_prompt(
+        &seller,
+        &prompt_id,
+        &None::<Address>,
+        &10_000,
+        &None::<Bytes>,
+    );
+    client.settle_purchase(&context.admin, &prompt_id, &seller);
+
+    let mut below_floor =
+        resale_order_for(&env, &context, &client, &seller, prompt_id, 10_000, None, 3);
+    below_floor.royalty_bps = 100;
+    let res = client.try_post_resale_order(&below_floor);
+    match res {
+        Err(Ok(Error::InvalidRoyaltyBps)) => {}
+        other => panic!("expected InvalidRoyaltyBps, got {:?}", other),
+    }
+
+    let mut prefilled =
+        resale_order_for(&env, &context, &client, &seller, prompt_id, 10_000, None, 7);
+    prefilled.status = ResaleOrderStatus::Filled;
+    let res = client.try_post_resale_order(&prefilled);
+    match res {
+        Err(Ok(Error::ResaleOrderNotOpen)) => {}
+        other => panic!("expected ResaleOrderNotOpen, got {:?}", other),
+    }
+
+    let mut greedy = resale_order_for(&env, &context, &client, &seller, prompt_id, 10_000, None, 4);
+    greedy.min_proceeds = 10_000;
+    let res = client.try_post_resale_order(&greedy);
+    match res {
+        Err(Ok(Error::ResaleProceedsBelowMinimum)) => {}
+        other => panic!("expected ResaleProceedsBelowMinimum, got {:?}", other),
+    }
+
+    // Two open orders against the same license: once one fills, the seller
+    // no longer owns it and the other must fail.
+    let first = resale_order_for(&env, &context, &client, &seller, prompt_id, 12_000, None, 5);
+    let second = resale_order_for(&env, &context, &client, &seller, prompt_id, 11_000, None, 6);
+    client.post_resale_order(&first);
+    client.post_resale_order(&second);
+
+    fund_buyer(&xlm_client, &first_buyer, &context.contract, 12_000);
+    client.fill_resale_order(&first_buyer, &first);
+
+    fund_buyer(&xlm_client, &second_buyer, &context.contract, 11_000);
+    let res = client.try_fill_resale_order(&second_buyer, &second);
+    match res {
+        Err(Ok(Error::ResaleOrderOwnershipChanged)) => {}
+        other => panic!("expected ResaleOrderOwnershipChanged, got {:?}", other),
+    }
+    assert!(!client.has_access(&second_buyer, &prompt_id));
+}
+
+// ─── Issue #569: Two-phase timelocked governance ─────────────────────────────
+
+#[test]
+fn test_governance_proposal_cannot_execute_before_delay() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+
+    let before = client.get_fee_percentage();
+    let proposal_hash = client.propose_governance(
+        &context.admin,
+        &GovernanceAction::SetFeePercentage(250),
+        &hash(&env, 1),
+    );
+    let proposal = client.get_governance_proposal(&proposal_hash);
+    assert_eq!(
+        proposal.executable_at_ledger,
+        proposal.proposed_at_ledger + client.get_governance_delay()
+    );
+    assert_eq!(
+        proposal.expected_state_hash,
+        client.get_governance_state_hash()
+    );
+
+    env.ledger()
+        .with_mut(|li| li.sequence_number = proposal.executable_at_ledger - 1);
+    let res = client.try_execute_governance(&context.admin, &proposal_hash);
+    match res {
+        Err(Ok(Error::GovernanceDelayNotElapsed)) => {}
+        other => panic!("expected GovernanceDelayNotElapsed, got {:?}", other),
+    }
+    assert_eq!(client.get_fee_percentage(), before);
+
+    env.ledger()
+        .with_mut(|li| li.sequence_number = proposal.executable_at_ledger);
+    client.execute_governance(&context.admin, &proposal_hash);
+    assert_eq!(client.get_fee_percentage(), 250);
+    assert_eq!(client.get_pending_governance_proposals().len(), 0);
+
+    // Executed proposals are consumed.
+    let res = client.try_execute_governance(&context.admin, &proposal_hash);
+    match res {
+        Err(Ok(Error::GovernanceProposalNotFound)) => {}
+        other => panic!("expected GovernanceProposalNotFound, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_governance_proposal_expires_after_execution_window() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+
+    let new_wallet = Address::generate(&env);
+    let proposal_hash = client.propose_governance(
+        &context.admin,
+        &GovernanceAction::SetFeeWallet(new_wallet.clone()),
+        &hash(&env, 2),
+    );
+    let proposal = client.get_governance_proposal(&proposal_hash);
+
+    env.ledger()
+        .with_mut(|li| li.sequence_number = proposal.expiry_ledger + 1);
+    let res = client.try_execute_governance(&context.admin, &proposal_hash);
+    match res {
+        Err(Ok(Error::GovernanceProposalExpired)) => {}
+        other => panic!("expected GovernanceProposalExpired, got {:?}", other),
+    }
+    assert_eq!(client.get_fee_wallet(), Some(context.fee_wallet.clone()));
+}
+
+#[test]
+fn test_governance_state_drift_invalidates_sibling_proposal() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+
+    let first = client.propose_governance(
+        &context.admin,
+        &GovernanceAction::SetFeePercentage(300),
+        &hash(&env, 3),
+    );
+    let second = client.propose_governance(
+        &context.admin,
+        &GovernanceAction::SetReferralPercentage(400),
+        &hash(&env, 4),
+    );
+    assert_eq!(client.get_pending_governance_proposals().len(), 2);
+
+    let proposal = client.get_governance_proposal(&first);
+    env.ledger()
+        .with_mut(|li| li.sequence_number = proposal.executable_at_ledger);
+    client.execute_governance(&context.admin, &first);
+
+    // The second proposal was written against the pre-change fee.
+    let res = client.try_execute_governance(&context.admin, &second);
+    match res {
+        Err(Ok(Error::GovernanceStateMismatch)) => {}
+        other => panic!("expected GovernanceStateMismatch, got {:?}", other),
+    }
+    assert_eq!(client.get_referral_percentage(), 0);
+}
+
+#[test]
+fn test_governance_cancel_and_nonce_reuse() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+
+    let action = GovernanceAction::SetGovernanceDelay(1_000);
+    let proposal_hash = client.propose_governance(&context.admin, &action, &hash(&env, 5));
+    client.cancel_governance(&context.admin, &proposal_hash);
+    assert_eq!(client.get_pending_governance_proposals().len(), 0);
+
+    let res = client.try_execute_governance(&context.admin, &proposal_hash);
+    match res {
+        Err(Ok(Error::GovernanceProposalNotFound)) => {}
+        other => panic!("expected GovernanceProposalNotFound, got {:?}", other),
+    }
+
+    // A cancelled proposal's nonce cannot be replayed.
+    let res = client.try_propose_governance(&context.admin, &action, &hash(&env, 5));
+    match res {
+        Err(Ok(Error::GovernanceNonceConsumed)) => {}
+        other => panic!("expected GovernanceNonceConsumed, got {:?}", other),
+    }
+
+    let res = client.try_propose_governance(
+        &context.admin,
+        &GovernanceAction::SetGovernanceDelay(1),
+        &hash(&env, 6),
+    );
+    match res {
+        Err(Ok(Error::InvalidGovernanceDelay)) => {}
+        other => panic!("expected InvalidGovernanceDelay, got {:?}", other),
+    }
+
+    let stranger = Address::generate(&env);
+    let res = client.try_propose_governance(&stranger, &action, &hash(&env, 7));
+    match res {
+        Err(Ok(Error::Unauthorized)) => {}
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_governance_delay_change_applies_to_later_proposals() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+
+    client.propose_governance(
+        &context.admin,
+        &GovernanceAction::SetGovernanceDelay(2_000),
+        &hash(&env, 8),
+    );
+    execute_latest_governance(&env, &client);
+    assert_eq!(client.get_governance_delay(), 2_000);
+
+    let proposal_hash = client.propose_governance(
+        &context.admin,
+        &GovernanceAction::SetReferralPercentage(100),
+        &hash(&env, 9),
+    );
+    let proposal = client.get_governance_proposal(&proposal_hash);
+    assert_eq!(
+        proposal.executable_at_ledger,
+        proposal.proposed_at_ledger + 2_000
+    );
+}
+
+#[test]
+fn test_legacy_setter_queues_distinct_proposals_in_same_ledger() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+
+    let before = client.get_pending_governance_proposals().len();
+    client.set_fee_percentage(&300);
+    client.set_fee_percentage(&300);
+    let pending = client.get_pending_governance_proposals();
+    assert_eq!(pending.len(), before + 2);
+    assert_ne!(
+        pending.get(before).unwrap(),
+        pending.get(before + 1).unwrap()
+    );
+}
+
+// ─── License tokens ──────────────────────────────────────────────────────────
+
+fn create_token_prompt(
+    env: &Env,
+    context: &PromptHashContext,
+    client: &PromptHashContractClient,
+    creator: &Address,
+) -> u64 {
+    let prompt_id = create_prompt(env, client, creator, "Tokenized", 10_000, &context.xlm);
+    client.set_license_token_mode(creator, &prompt_id, &true);
+    prompt_id
+}
+
+#[test]
+fn test_license_token_minted_on_purchase_and_access_follows_owner() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let prompt_id = create_token_prompt(&env, &context, &client, &creator);
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, 10_000);
+    client.buy_prompt(
+        &buyer,
+        &prompt_id,
+        &None::<Address>,
+        &10_000,
+        &None::<Bytes>,
+    );
+    client.settle_purchase(&context.admin, &prompt_id, &buyer);
+
+    let token_id = client
+        .get_purchase_license_token(&prompt_id, &buyer)
+        .unwrap();
+    assert_eq!(client.owner_of(&token_id), buyer);
+    assert_eq!(client.balance(&buyer), 1);
+    assert_eq!(client.get_license_token(&token_id).prompt_id, prompt_id);
+    assert_eq!(
+        client.royalty_info(&token_id, &20_000),
+        (creator.clone(), 1_000)
+    );
+
+    // An unpriced token transfer would skip the creator's royalty.
+    let res = client.try_transfer(&buyer, &holder, &token_id);
+    match res {
+        Err(Ok(Error::UnpricedLicenseTransfer)) => {}
+        other => panic!("expected UnpricedLicenseTransfer, got {:?}", other),
+    }
+    assert_eq!(client.owner_of(&token_id), buyer);
+
+    // A filled resale order moves the token and pays the royalty.
+    let order = resale_order_for(&env, &context, &client, &buyer, prompt_id, 20_000, None, 1);
+    client.post_resale_order(&order);
+    fund_buyer(&xlm_client, &holder, &context.contract, 20_000);
+    let creator_before = paid_balance(&client, &xlm_client, &creator);
+    client.fill_resale_order(&holder, &order);
+
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &creator),
+        creator_before + 1_000
+    );
+    assert_eq!(client.owner_of(&token_id), holder);
+    assert_eq!(client.balance(&buyer), 0);
+    assert_eq!(client.balance(&holder), 1);
+    assert!(client.has_access(&holder, &prompt_id));
+    assert!(!client.has_access(&buyer, &prompt_id));
+    // The royalty still routes to the original creator after a resale.
+    assert_eq!(client.royalty_info(&token_id, &20_000).0, creator);
+
+    let settlement_id = client
+        .get_entitlement_settlement(&prompt_id, &holder)
+        .unwrap();
+    let record = client.get_settlement(&settlement_id);
+    assert_eq!(record.kind, AcquisitionKind::ResaleFill);
+    assert_eq!(record.amount, 20_000);
+    assert_eq!(client.get_entitlement_settlement(&prompt_id, &buyer), None);
+}
+
+#[test]
+fn test_license_token_transfer_from_checks_approval_but_needs_a_resale() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let prompt_id = create_token_prompt(&env, &context, &client, &creator);
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, 10_000);
+    client.buy_prompt(
+        &buyer,
+        &prompt_id,
+        &None::<Address>,
+        &10_000,
+        &None::<Bytes>,
+    );
+    client.settle_purchase(&context.admin, &prompt_id, &buyer);
+    let token_id = client
+        .get_purchase_license_token(&prompt_id, &buyer)
+        .unwrap();
+
+    let res = client.try_transfer_from(&spender, &buyer, &holder, &token_id);
+    match res {
+        Err(Ok(Error::LicenseTokenNotApproved)) => {}
+        other => panic!("expected LicenseTokenNotApproved, got {:?}", other),
+    }
+
+    // Approved spenders and operators pass the approval check, but the
+    // transfer is still unpriced.
+    let live_until = env.ledger().sequence() + 100;
+    client.approve(&buyer, &spender, &token_id, &live_until);
+    assert_eq!(client.get_approved(&token_id), Some(spender.clone()));
+    let res = client.try_transfer_from(&spender, &buyer, &holder, &token_id);
+    match res {
+        Err(Ok(Error::UnpricedLicenseTransfer)) => {}
+        other => panic!("expected UnpricedLicenseTransfer, got {:?}", other),
+    }
+    client.approve_for_all(&buyer, &operator, &live_until);
+    assert!(client.is_approved_for_all(&buyer, &operator));
+    let res = client.try_transfer_from(&operator, &buyer, &holder, &token_id);
+    match res {
+        Err(Ok(Error::UnpricedLicenseTransfer)) => {}
+        other => panic!("expected UnpricedLicenseTransfer, got {:?}", other),
+    }
+    assert_eq!(client.owner_of(&token_id), buyer);
+
+    // Single-token approvals do not survive a resale.
+    fund_buyer(&xlm_client, &holder, &context.contract, 20_000);
+    client.transfer_license(&buyer, &prompt_id, &holder, &20_000);
+    assert_eq!(client.owner_of(&token_id), holder);
+    assert_eq!(client.get_approved(&token_id), None);
+
+    env.ledger().with_mut(|li| li.sequence_number = 10);
+    let res = client.try_approve(&holder, &spender, &token_id, &9);
+    match res {
+        Err(Ok(Error::InvalidLicenseApproval)) => {}
+        other => panic!("expected InvalidLicenseApproval, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_license_token_mode_locks_after_sale_and_refund_burns_token() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let untokenized = create_prompt(&env, &client, &creator, "Plain", 10_000, &context.xlm);
+    let prompt_id = create_token_prompt(&env, &context, &client, &creator);
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, 20_000);
+    client.buy_prompt(
+        &buyer,
+        &untokenized,
+        &None::<Address>,
+        &10_000,
+        &None::<Bytes>,
+    );
+    assert_eq!(
+        client.get_purchase_license_token(&untokenized, &buyer),
+        None
+    );
+    assert_eq!(client.balance(&buyer), 0);
+
+    let res = client.try_set_license_token_mode(&creator, &untokenized, &true);
+    match res {
+        Err(Ok(Error::LicenseTokenModeLocked)) => {}
+        other => panic!("expected LicenseTokenModeLocked, got {:?}", other),
+    }
+
+    client.buy_prompt(
+        &buyer,
+        &prompt_id,
+        &None::<Address>,
+        &10_000,
+        &None::<Bytes>,
+    );
+    let token_id = client
+        .get_purchase_license_token(&prompt_id, &buyer)
+        .unwrap();
+    client.open_dispute(
+        &buyer,
+        &prompt_id,
+        &crate::types::DisputeReason::FailedIntegrityVerification,
+    );
+    let res = client.try_transfer_license(&buyer, &prompt_id, &creator, &20_000);
+    match res {
+        Err(Ok(Error::DisputeAlreadyOpen)) => {}
+        other => panic!("expected DisputeAlreadyOpen, got {:?}", other),
+    }
+
+    client.resolve_dispute(&context.admin, &prompt_id, &buyer, &10_000);
+    assert!(!client.has_access(&buyer, &prompt_id));
+    assert_eq!(client.balance(&buyer), 0);
+    let res = client.try_owner_of(&token_id);
+    match res {
+        Err(Ok(Error::LicenseTokenNotFound)) => {}
+        other => panic!("expected LicenseTokenNotFound, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_priced_license_transfer_moves_token() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let prompt_id = create_token_prompt(&env, &context, &client, &creator);
+
+    fund_buyer(&xlm_client, &seller, &context.contract, 10_000);
+    client.buy_prompt(
+        &seller,
+        &prompt_id,
+        &None::<Address>,
+        &10_000,
+        &None::<Bytes>,
+    );
+    client.settle_purchase(&context.admin, &prompt_id, &seller);
+    let token_id = client
+        .get_purchase_license_token(&prompt_id, &seller)
+        .unwrap();
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, 20_000);
+    client.transfer_license(&seller, &prompt_id, &buyer, &20_000);
+
+    assert_eq!(client.owner_of(&token_id), buyer);
+    assert_eq!(
+        client.get_purchase_license_token(&prompt_id, &buyer),
+        Some(token_id)
+    );
+    assert_eq!(client.get_purchase_license_token(&prompt_id, &seller), None);
+}
+
+#[test]
+fn test_leased_and_expired_licenses_cannot_move() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let lessee = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let prompt_id = create_token_prompt(&env, &context, &client, &creator);
+    client.set_lease_terms(&creator, &prompt_id, &Some(daily_lease_terms(100)));
+
+    fund_buyer(&xlm_client, &lessee, &context.contract, 300);
+    client.lease_prompt(&lessee, &prompt_id, &(3 * DAY));
+    let token_id = client
+        .get_purchase_license_token(&prompt_id, &lessee)
+        .unwrap();
+
+    let res = client.try_transfer_license(&lessee, &prompt_id, &holder, &1_000);
+    match res {
+        Err(Ok(Error::LicenseNotTransferable)) => {}
+        other => panic!("expected LicenseNotTransferable, got {:?}", other),
+    }
+
+    env.ledger().with_mut(|li| li.timestamp = 3 * DAY + 1);
+    let res = client.try_transfer_license(&lessee, &prompt_id, &holder, &1_000);
+    match res {
+        Err(Ok(Error::LicenseNotTransferable)) => {}
+        other => panic!("expected LicenseNotTransferable, got {:?}", other),
+    }
+    assert_eq!(client.owner_of(&token_id), lessee);
+}
+
+// ─── Auction sale modes ──────────────────────────────────────────────────────
+
+fn create_auction_prompt(
+    env: &Env,
+    context: &PromptHashContext,
+    client: &PromptHashContractClient,
+    creator: &Address,
+    max_supply: u64,
+) -> u64 {
+    let prompt_id = create_prompt(env, client, creator, "Auction Prompt", 10_000, &context.xlm);
+    client.set_prompt_max_supply(creator, &prompt_id, &max_supply);
+    prompt_id
+}
+
+#[test]
+fn test_english_auction_refunds_outbid_bidder_and_settles_to_winner() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let prompt_id = create_auction_prompt(&env, &context, &client, &creator, 1);
+
+    let ends_at = env.ledger().timestamp() + 3_600;
+    client.start_english_auction(&creator, &prompt_id, &5_000, &ends_at);
+
+    // Fixed-price checkout is closed while the listing is at auction.
+    fund_buyer(&xlm_client, &first, &context.contract, 20_000);
+    let res = client.try_buy_prompt(
+        &first,
+        &prompt_id,
+        &None::<Address>,
+        &10_000,
+        &None::<Bytes>,
+    );
+    match res {
+        Err(Ok(Error::ListingInAuction)) => {}
+        other => panic!("expected ListingInAuction, got {:?}", other),
+    }
+
+    let res = client.try_place_bid(&first, &prompt_id, &4_999);
+    match res {
+        Err(Ok(Error::BidTooLow)) => {}
+        other => panic!("expected BidTooLow, got {:?}", other),
+    }
+    client.place_bid(&first, &prompt_id, &6_000);
+    assert_eq!(xlm_client.balance(&first), 14_000);
+    assert_eq!(client.get_asset_liability(&context.xlm).pending, 6_000);
+
+    // The next bid must clear the 5% minimum increment.
+    fund_buyer(&xlm_client, &second, &context.contract, 20_000);
+    let res = client.try_place_bid(&second, &prompt_id, &6_200);
+    match res {
+        Err(Ok(Error::BidTooLow)) => {}
+        other => panic!("expected BidTooLow, got {:?}", other),
+    }
+    client.place_bid(&second, &prompt_id, &6_300);
+    assert_eq!(xlm_client.balance(&second), 13_700);
+    assert_eq!(client.get_asset_liability(&context.xlm).pending, 6_300);
+    // The outbid bid waits as a claimable balance.
+    assert_eq!(xlm_client.balance(&first), 14_000);
+    assert_eq!(client.get_claimable_balance(&first, &context.xlm), 6_000);
+    assert_eq!(client.get_asset_liability(&context.xlm).claimable, 6_000);
+    client.withdraw(&first, &context.xlm);
+    assert_eq!(xlm_client.balance(&first), 20_000);
+
+    let res = client.try_cancel_auction(&creator, &prompt_id);
+    match res {
+        Err(Ok(Error::AuctionHasBids)) => {}
+        other => panic!("expected AuctionHasBids, got {:?}", other),
+    }
+    let res = client.try_settle_auction(&prompt_id);
+    match res {
+        Err(Ok(Error::AuctionNotEnded)) => {}
+        other => panic!("expected AuctionNotEnded, got {:?}", other),
+    }
+
+    env.ledger().with_mut(|li| li.timestamp = ends_at + 1);
+    let res = client.try_place_bid(&first, &prompt_id, &10_000);
+    match res {
+        Err(Ok(Error::AuctionEnded)) => {}
+        other => panic!("expected AuctionEnded, got {:?}", other),
+    }
+    client.settle_auction(&prompt_id);
+
+    let auction = client.get_auction(&prompt_id);
+    assert_eq!(auction.status, AuctionStatus::Sold);
+    assert!(client.has_access(&second, &prompt_id));
+    assert!(!client.has_access(&first, &prompt_id));
+    assert_eq!(client.get_prompt(&prompt_id).sales_count, 1);
+
+    // The winning bid settles exactly like a direct purchase.
+    let escrow = client.get_purchase_escrow(&prompt_id, &second).unwrap();
+    assert_eq!(escrow.amount, 6_300);
+    assert_eq!(escrow.status, crate::types::SettlementStatus::Pending);
+    assert_eq!(escrow.payout_plan.creator, creator);
+    let settlement_id = client
+        .get_entitlement_settlement(&prompt_id, &second)
+        .unwrap();
+    assert_eq!(
+        client.get_settlement(&settlement_id).kind,
+        AcquisitionKind::Auction
+    );
+    assert_eq!(client.get_asset_liability(&context.xlm).pending, 6_300);
+}
+
+#[test]
+fn test_dutch_auction_price_decays_linearly_and_sells_out() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let first = Address::generate(&env);
+    let second = Address::generate(&env);
+    let prompt_id = create_auction_prompt(&env, &context, &client, &creator, 2);
+
+    let start = env.ledger().sequence();
+    client.start_dutch_auction(&creator, &prompt_id, &10_000, &2_000, &100);
+    assert_eq!(client.get_dutch_auction_price(&prompt_id), 10_000);
+
+    env.ledger().with_mut(|li| li.sequence_number = start + 25);
+    assert_eq!(client.get_dutch_auction_price(&prompt_id), 8_000);
+
+    fund_buyer(&xlm_client, &first, &context.contract, 10_000);
+    let res = client.try_buy_dutch_auction(&first, &prompt_id, &7_999);
+    match res {
+        Err(Ok(Error::AuctionPriceAboveMax)) => {}
+        other => panic!("expected AuctionPriceAboveMax, got {:?}", other),
+    }
+    client.buy_dutch_auction(&first, &prompt_id, &8_000);
+    assert_eq!(xlm_client.balance(&first), 2_000);
+    assert!(client.has_access(&first, &prompt_id));
+    assert_eq!(client.get_auction(&prompt_id).status, AuctionStatus::Open);
+
+    // Past the end ledger the price holds at the floor.
+    env.ledger().with_mut(|li| li.sequence_number = start + 500);
+    assert_eq!(client.get_dutch_auction_price(&prompt_id), 2_000);
+    fund_buyer(&xlm_client, &second, &context.contract, 2_000);
+    client.buy_dutch_auction(&second, &prompt_id, &2_000);
+
+    assert_eq!(client.get_auction(&prompt_id).status, AuctionStatus::Sold);
+    assert_eq!(client.get_asset_liability(&context.xlm).pending, 10_000);
+    assert_eq!(
+        client
+            .get_purchase_escrow(&prompt_id, &second)
+            .unwrap()
+            .amount,
+        2_000
+    );
+}
+
+#[test]
+fn test_auction_requires_limited_supply_and_unsold_auction_closes() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let unlimited = create_prompt(&env, &client, &creator, "Open", 10_000, &context.xlm);
+    let ends_at = env.ledger().timestamp() + 100;
+    let res = client.try_start_english_auction(&creator, &unlimited, &1_000, &ends_at);
+    match res {
+        Err(Ok(Error::AuctionRequiresLimitedSupply)) => {}
+        other => panic!("expected AuctionRequiresLimitedSupply, got {:?}", other),
+    }
+
+    let prompt_id = create_auction_prompt(&env, &context, &client, &creator, 1);
+    let res = client.try_start_dutch_auction(&creator, &prompt_id, &1_000, &1_000, &10);
+    match res {
+        Err(Ok(Error::InvalidAuctionConfig)) => {}
+        other => panic!("expected InvalidAuctionConfig, got {:?}", other),
+    }
+
+    client.start_english_auction(&creator, &prompt_id, &1_000, &ends_at);
+    let res = client.try_start_dutch_auction(&creator, &prompt_id, &5_000, &1_000, &10);
+    match res {
+        Err(Ok(Error::AuctionAlreadyOpen)) => {}
+        other => panic!("expected AuctionAlreadyOpen, got {:?}", other),
+    }
+
+    env.ledger().with_mut(|li| li.timestamp = ends_at + 1);
+    client.settle_auction(&prompt_id);
+    assert_eq!(client.get_auction(&prompt_id).status, AuctionStatus::Closed);
+
+    // A closed auction returns the listing to fixed-price sale.
+    fund_buyer(&xlm_client, &buyer, &context.contract, 10_000);
+    client.buy_prompt(
+        &buyer,
+        &prompt_id,
+        &None::<Address>,
+        &10_000,
+        &None::<Bytes>,
+    );
+    assert!(client.has_access(&buyer, &prompt_id));
+}
+
+#[test]
+fn test_dutch_auction_rejects_expired_listing() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let prompt_id = create_auction_prompt(&env, &context, &client, &creator, 1);
+
+    let expires_at = env.ledger().timestamp() + 100;
+    client.extend_listing(&creator, &prompt_id, &expires_at);
+    client.start_dutch_auction(&creator, &prompt_id, &10_000, &2_000, &100);
+
+    env.ledger().with_mut(|li| li.timestamp = expires_at + 1);
+    fund_buyer(&xlm_client, &buyer, &context.contract, 10_000);
+    let res = client.try_buy_dutch_auction(&buyer, &prompt_id, &10_000);
+    match res {
+        Err(Ok(Error::ListingExpired)) => {}
+        other => panic!("expected ListingExpired, got {:?}", other),
+    }
+}
+
+// ─── Access pass auto-renewal ────────────────────────────────────────────────
+
+#[test]
+fn test_pass_subscription_renews_within_window_until_periods_exhausted() {
+    let env: Env = Default::default();
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1_000);
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Catalog", 1_000, &context.xlm);
+    let price = 5_000;
+    let period = 30 * 24 * 60 * 60;
+
+    let pass_id = client.create_access_pass(
+        &creator,
+        &String::from_str(&env, "Monthly"),
+        &period,
+        &price,
+        &context.xlm,
+        &0u32,
+    );
+    fund_buyer(&xlm_client, &buyer, &context.contract, price * 3);
+    client.buy_access_pass(&buyer, &pass_id, &price);
+    client.subscribe_pass(&buyer, &pass_id, &price, &2);
+
+    // Too early: the current period is not yet in its renewal window.
+    let res = client.try_renew_pass(&buyer, &pass_id);
+    match res {
+        Err(Ok(Error::RenewalWindowNotOpen)) => {}
+        other => panic!("expected RenewalWindowNotOpen, got {:?}", other),
+    }
+
+    let first_expiry = 1_000 + period;
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = first_expiry - 3_600);
+    assert!(client.renew_pass(&buyer, &pass_id));
+    assert_eq!(
+        client
+            .get_pass_subscription(&buyer, &pass_id)
+            .periods_remaining,
+        1
+    );
+    assert_eq!(xlm_client.balance(&buyer), price);
+
+    // The renewal extends from the existing expiry, like a manual re-buy.
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = first_expiry + period - 1);
+    assert!(client.has_access(&buyer, &prompt_id));
+
+    assert!(client.renew_pass(&buyer, &pass_id));
+    assert_eq!(xlm_client.balance(&buyer), 0);
+    let res = client.try_get_pass_subscription(&buyer, &pass_id);
+    match res {
+        Err(Ok(Error::PassSubscriptionNotFound)) => {}
+        other => panic!("expected PassSubscriptionNotFound, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_pass_subscription_ends_cleanly_on_missing_allowance_or_price_rise() {
+    let env: Env = Default::default();
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1_000);
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+
+    let pass_id = client.create_access_pass(
+        &creator,
+        &String::from_str(&env, "Monthly"),
+        &1_000u64,
+        &price,
+        &context.xlm,
+        &0u32,
+    );
+
+    // No allowance was ever granted: the renewal ends the subscription
+    // without charging or reverting.
+    xlm_client.mint(&buyer, &price);
+    client.subscribe_pass(&buyer, &pass_id, &price, &12);
+    assert!(!client.renew_pass(&buyer, &pass_id));
+    assert_eq!(xlm_client.balance(&buyer), price);
+    assert!(client.try_get_pass_subscription(&buyer, &pass_id).is_err());
+
+    // A price above the buyer's ceiling ends it too.
+    xlm_client.approve(&buyer, &context.contract, &(price * 2), &1_000);
+    client.subscribe_pass(&buyer, &pass_id, &price, &12);
+    client.update_access_pass_price(&creator, &pass_id, &(price + 1));
+    assert!(!client.renew_pass(&buyer, &pass_id));
+    assert_eq!(xlm_client.balance(&buyer), price);
+    assert!(client.try_get_pass_subscription(&buyer, &pass_id).is_err());
+
+    client.subscribe_pass(&buyer, &pass_id, &(price * 2), &12);
+    client.cancel_pass_subscription(&buyer, &pass_id);
+    let res = client.try_renew_pass(&buyer, &pass_id);
+    match res {
+        Err(Ok(Error::PassSubscriptionNotFound)) => {}
+        other => panic!("expected PassSubscriptionNotFound, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_short_pass_renews_once_per_period() {
+    let env: Env = Default::default();
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1_000);
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 500;
+    let hour = 3_600u64;
+
+    let pass_id = client.create_access_pass(
+        &creator,
+        &String::from_str(&env, "Hourly"),
+        &hour,
+        &price,
+        &context.xlm,
+        &0u32,
+    );
+    fund_buyer(&xlm_client, &buyer, &context.contract, price * 5);
+    client.buy_access_pass(&buyer, &pass_id, &price);
+    client.subscribe_pass(&buyer, &pass_id, &price, &4);
+
+    // The 24-hour window is capped at the one-hour duration.
+    let res = client.try_renew_pass(&buyer, &pass_id);
+    match res {
+        Err(Ok(Error::RenewalWindowNotOpen)) => {}
+        other => panic!("expected RenewalWindowNotOpen, got {:?}", other),
+    }
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = 2_000);
+    assert!(client.renew_pass(&buyer, &pass_id));
+    let res = client.try_renew_pass(&buyer, &pass_id);
+    match res {
+        Err(Ok(Error::RenewalWindowNotOpen)) => {}
+        other => panic!("expected RenewalWindowNotOpen, got {:?}", other),
+    }
+    assert_eq!(xlm_client.balance(&buyer), price * 3);
+    assert_eq!(
+        client
+            .get_pass_subscription(&buyer, &pass_id)
+            .periods_remaining,
+        3
+    );
+}
+
+// ─── Per-buyer key delivery ──────────────────────────────────────────────────
+
+#[test]
+fn test_registered_key_blocks_settlement_until_wrapped_key_delivered() {
+    let env: Env = Default::default();
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1_000);
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let agent = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Wrapped", price, &context.xlm);
+
+    client.register_encryption_key(&buyer, &hash(&env, 3));
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+    assert_eq!(
+        client.get_key_delivery_deadline(&prompt_id, &buyer),
+        Some(1_000 + 24 * 60 * 60)
+    );
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = 1_000 + 3 * 24 * 60 * 60 + 1);
+    let res = client.try_settle_purchase(&creator, &prompt_id, &buyer);
+    match res {
+        Err(Ok(Error::KeyDeliveryPending)) => {}
+        other => panic!("expected KeyDeliveryPending, got {:?}", other),
+    }
+
+    let wrapped = String::from_str(&env, "wrapped-for-buyer");
+    let res = client.try_deliver_wrapped_key(&stranger, &prompt_id, &buyer, &wrapped);
+    match res {
+        Err(Ok(Error::Unauthorized)) => {}
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+
+    client.set_key_delivery_agent(&creator, &prompt_id, &Some(agent.clone()));
+    client.deliver_wrapped_key(&agent, &prompt_id, &buyer, &wrapped);
+    let delivered = client.get_buyer_wrapped_key(&prompt_id, &buyer);
+    assert_eq!(delivered.wrapped_key, wrapped);
+    assert_eq!(delivered.recipient_key, hash(&env, 3));
+    assert_eq!(delivered.delivered_by, agent);
+    assert_eq!(delivered.revision, 0);
+    assert_eq!(client.get_key_delivery_deadline(&prompt_id, &buyer), None);
+
+    client.settle_purchase(&creator, &prompt_id, &buyer);
+    let escrow = client.get_purchase_escrow(&prompt_id, &buyer).unwrap();
+    assert_eq!(escrow.status, crate::types::SettlementStatus::Settled);
+}
+
+#[test]
+fn test_overdue_key_delivery_stays_disputable_as_invalid_payload() {
+    let env: Env = Default::default();
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1_000);
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Overdue", price, &context.xlm);
+
+    client.register_encryption_key(&buyer, &hash(&env, 4));
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = 1_000 + 3 * 24 * 60 * 60 + 1);
+    let res = client.try_open_dispute(
+        &buyer,
+        &prompt_id,
+        &crate::types::DisputeReason::MissingMetadata,
+    );
+    match res {
+        Err(Ok(Error::DisputeWindowClosed)) => {}
+        other => panic!("expected DisputeWindowClosed, got {:?}", other),
+    }
+
+    client.open_dispute(
+        &buyer,
+        &prompt_id,
+        &crate::types::DisputeReason::InvalidEncryptedPayload,
+    );
+    let dispute = client.get_dispute(&prompt_id, &buyer);
+    assert_eq!(dispute.status, crate::types::DisputeStatus::Open);
+}
+
+#[test]
+fn test_key_registered_after_purchase_does_not_block_settlement() {
+    let env: Env = Default::default();
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1_000);
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Legacy", price, &context.xlm);
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = 2_000);
+    client.register_encryption_key(&buyer, &hash(&env, 5));
+    assert_eq!(client.get_key_delivery_deadline(&prompt_id, &buyer), None);
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = 1_000 + 3 * 24 * 60 * 60 + 1);
+    client.settle_purchase(&creator, &prompt_id, &buyer);
+
+    let res = client.try_get_buyer_wrapped_key(&prompt_id, &buyer);
+    match res {
+        Err(Ok(Error::WrappedKeyNotDelivered)) => {}
+        other => panic!("expected WrappedKeyNotDelivered, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_content_revision_requires_fresh_wrapped_key() {
+    let env: Env = Default::default();
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1_000);
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Rewrapped", price, &context.xlm);
+
+    client.register_encryption_key(&buyer, &hash(&env, 3));
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+    let wrapped = String::from_str(&env, "wrapped-for-buyer");
+    client.deliver_wrapped_key(&creator, &prompt_id, &buyer, &wrapped);
+    assert_eq!(
+        client.get_licensed_content(&prompt_id, &buyer).wrapped_key,
+        wrapped
+    );
+
+    // The buyer now reads revision 1, which the delivered key cannot open.
+    client.revise_content(
+        &creator,
+        &prompt_id,
+        &String::from_str(&env, "encrypted-v1"),
+        &String::from_str(&env, "iv-v1"),
+        &String::from_str(&env, "wrapped-key-v1"),
+        &hash(&env, 8),
+    );
+    assert_eq!(
+        client.get_key_delivery_deadline(&prompt_id, &buyer),
+        Some(1_000 + 24 * 60 * 60)
+    );
+    let content = client.get_licensed_content(&prompt_id, &buyer);
+    assert_eq!(content.revision, 1);
+    assert_eq!(
+        content.wrapped_key,
+        String::from_str(&env, "wrapped-key-v1")
+    );
+
+    let rewrapped = String::from_str(&env, "wrapped-v1-for-buyer");
+    client.deliver_wrapped_key(&creator, &prompt_id, &buyer, &rewrapped);
+    assert_eq!(client.get_buyer_wrapped_key(&prompt_id, &buyer).revision, 1);
+    assert_eq!(client.get_key_delivery_deadline(&prompt_id, &buyer), None);
+    assert_eq!(
+        client.get_licensed_content(&prompt_id, &buyer).wrapped_key,
+        rewrapped
+    );
+}
+
+// ─── Content revisions ───────────────────────────────────────────────────────
+
+#[test]
+fn test_revise_content_preserves_prior_content_hash_and_bumps_revision() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Versioned", 1_000, &context.xlm);
+
+    let new_revision = client.revise_content(
+        &creator,
+        &prompt_id,
+        &String::from_str(&env, "encrypted-v1"),
+        &String::from_str(&env, "iv-v1"),
+        &String::from_str(&env, "wrapped-key-v1"),
+        &hash(&env, 8),
+    );
+    assert_eq!(new_revision, 1);
+
+    let prompt = client.get_prompt(&prompt_id);
+    assert_eq!(prompt.revision, 1);
+    assert_eq!(prompt.content_hash, hash(&env, 8));
+    assert_eq!(prompt.title, String::from_str(&env, "Versioned"));
+
+    let snapshot = client.get_listing_revision(&prompt_id, &0);
+    assert_eq!(snapshot.content_hash, hash(&env, 7));
+    assert_eq!(
+        snapshot.encrypted_payload,
+        String::from_str(&env, "encrypted")
+    );
+
+    let stranger = Address::generate(&env);
+    let res = client.try_revise_content(
+        &stranger,
+        &prompt_id,
+        &String::from_str(&env, "encrypted-v2"),
+        &String::from_str(&env, "iv-v2"),
+        &String::from_str(&env, "wrapped-key-v2"),
+        &hash(&env, 9),
+    );
+    match res {
+        Err(Ok(Error::Unauthorized)) => {}
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_content_access_policy_pins_or_upgrades_existing_buyers() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let early_buyer = Address::generate(&env);
+    let late_buyer = Address::generate(&env);
+    let price = 1_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Versioned", price, &context.xlm);
+
+    fund_buyer(&xlm_client, &early_buyer, &context.contract, price);
+    client.buy_prompt(
+        &early_buyer,
+        &prompt_id,
+        &None::<Address>,
+        &price,
+        &None::<Bytes>,
+    );
+
+    // A metadata revision and a content revision both advance the counter.
+    client.revise_listing(
+        &creator,
+        &prompt_id,
+        &String::from_str(&env, "Versioned v2"),
+        &String::from_str(&env, "Software Development"),
+        &String::from_str(&env, "preview"),
+        &String::from_str(&env, "https://example.com/image.png"),
+        &price,
+    );
+    client.revise_content(
+        &creator,
+        &prompt_id,
+        &String::from_str(&env, "encrypted-v2"),
+        &String::from_str(&env, "iv-v2"),
+        &String::from_str(&env, "wrapped-key-v2"),
+        &hash(&env, 8),
+    );
+
+    fund_buyer(&xlm_client, &late_buyer, &context.contract, price);
+    client.buy_prompt(
+        &late_buyer,
+        &prompt_id,
+        &None::<Address>,
+        &price,
+        &None::<Bytes>,
+    );
+
+    // Default policy: every buyer reads the latest content.
+    assert_eq!(
+        client.get_content_access_policy(&prompt_id),
+        ContentAccessPolicy::LatestRevision
+    );
+    let content = client.get_licensed_content(&prompt_id, &early_buyer);
+    assert_eq!(content.revision, 2);
+    assert_eq!(content.content_hash, hash(&env, 8));
+
+    client.set_content_access_policy(
+        &creator,
+        &prompt_id,
+        &ContentAccessPolicy::PurchasedRevision,
+    );
+    let content = client.get_licensed_content(&prompt_id, &early_buyer);
+    assert_eq!(content.revision, 0);
+    assert_eq!(content.content_hash, hash(&env, 7));
+    assert_eq!(
+        content.encrypted_payload,
+        String::from_str(&env, "encrypted")
+    );
+    let content = client.get_licensed_content(&prompt_id, &late_buyer);
+    assert_eq!(content.revision, 2);
+    assert_eq!(content.content_hash, hash(&env, 8));
+
+    let res = client.try_get_licensed_content(&prompt_id, &Address::generate(&env));
+    match res {
+        Err(Ok(Error::LicenseNotFound)) => {}
+        other => panic!("expected LicenseNotFound, got {:?}", other),
+    }
+}
+
+// ─── Per-listing lease terms ─────────────────────────────────────────────────
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn daily_lease_terms(price_per_day: i128) -> LeaseTerms {
+    LeaseTerms {
+        enabled: true,
+        price_per_period: price_per_day,
+        period_secs: DAY,
+        min_duration_secs: DAY,
+        max_duration_secs: 30 * DAY,
+    }
+}
+
+#[test]
+fn test_lease_terms_price_by_period_and_bound_duration() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let lessee = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Leased", 10_000, &context.xlm);
+
+    // Without terms, a lease costs the flat 40% of the price.
+    assert_eq!(client.get_lease_price(&prompt_id, &(90 * DAY)), 4_000);
+
+    client.set_lease_terms(&creator, &prompt_id, &Some(daily_lease_terms(100)));
+    // Every started day is charged.
+    assert_eq!(client.get_lease_price(&prompt_id, &(3 * DAY)), 300);
+    assert_eq!(client.get_lease_price(&prompt_id, &(3 * DAY + 1)), 400);
+    let res = client.try_get_lease_price(&prompt_id, &(31 * DAY));
+    match res {
+        Err(Ok(Error::InvalidLeaseDuration)) => {}
+        other => panic!("expected InvalidLeaseDuration, got {:?}", other),
+    }
+
+    fund_buyer(&xlm_client, &lessee, &context.contract, 300);
+    client.lease_prompt(&lessee, &prompt_id, &(3 * DAY));
+    assert_eq!(xlm_client.balance(&lessee), 0);
+    let lease = client.get_lease(&prompt_id, &lessee);
+    assert_eq!(lease.paid_total, 300);
+    assert_eq!(lease.expires_at, 3 * DAY);
+
+    let mut disabled = daily_lease_terms(100);
+    disabled.enabled = false;
+    client.set_lease_terms(&creator, &prompt_id, &Some(disabled));
+    let res = client.try_lease_prompt(&Address::generate(&env), &prompt_id, &DAY);
+    match res {
+        Err(Ok(Error::LeaseDisabled)) => {}
+        other => panic!("expected LeaseDisabled, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_renew_lease_extends_from_current_expiry() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let lessee = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Leased", 10_000, &context.xlm);
+    client.set_lease_terms(&creator, &prompt_id, &Some(daily_lease_terms(100)));
+
+    fund_buyer(&xlm_client, &lessee, &context.contract, 1_000);
+    client.lease_prompt(&lessee, &prompt_id, &(2 * DAY));
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = DAY);
+    client.renew_lease(&lessee, &prompt_id, &(2 * DAY));
+    let lease = client.get_lease(&prompt_id, &lessee);
+    assert_eq!(lease.expires_at, 4 * DAY);
+    assert_eq!(lease.started_at, 0);
+    assert_eq!(lease.paid_total, 400);
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = 4 * DAY - 1);
+    assert!(client.has_access(&lessee, &prompt_id));
+
+    // A lapsed lease restarts from now.
+    env.ledger().with_mut(|ledger| ledger.timestamp = 10 * DAY);
+    assert!(!client.has_access(&lessee, &prompt_id));
+    client.renew_lease(&lessee, &prompt_id, &DAY);
+    let lease = client.get_lease(&prompt_id, &lessee);
+    assert_eq!(lease.started_at, 10 * DAY);
+    assert_eq!(lease.expires_at, 11 * DAY);
+    assert_eq!(lease.paid_total, 100);
+    assert!(client.has_access(&lessee, &prompt_id));
+
+    let res = client.try_renew_lease(&Address::generate(&env), &prompt_id, &DAY);
+    match res {
+        Err(Ok(Error::LeaseNotFound)) => {}
+        other => panic!("expected LeaseNotFound, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_convert_lease_credits_unused_value() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let lessee = Address::generate(&env);
+    let price = 10_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Leased", price, &context.xlm);
+    client.set_lease_terms(&creator, &prompt_id, &Some(daily_lease_terms(100)));
+
+    fund_buyer(&xlm_client, &lessee, &context.contract, 1_000 + price);
+    client.lease_prompt(&lessee, &prompt_id, &(10 * DAY));
+
+    // Six of ten days remain: 600 of the 1,000 paid is credited.
+    env.ledger().with_mut(|ledger| ledger.timestamp = 4 * DAY);
+    assert_eq!(client.get_lease_conversion_credit(&prompt_id, &lessee), 600);
+    let res = client.try_convert_lease(&lessee, &prompt_id, &(price - 601));
+    match res {
+        Err(Ok(Error::InvalidPaymentAmount)) => {}
+        other => panic!("expected InvalidPaymentAmount, got {:?}", other),
+    }
+
+    let balance_before = xlm_client.balance(&lessee);
+    client.convert_lease(&lessee, &prompt_id, &(price - 600));
+    assert_eq!(xlm_client.balance(&lessee), balance_before - (price - 600));
+
+    let escrow = client.get_purchase_escrow(&prompt_id, &lessee).unwrap();
+    assert_eq!(escrow.amount, price - 600);
+    assert_eq!(escrow.status, crate::types::SettlementStatus::Pending);
+
+    // The license is now permanent and the lease is gone.
+    env.ledger().with_mut(|ledger| ledger.timestamp = 100 * DAY);
+    assert!(client.has_access(&lessee, &prompt_id));
+    let res = client.try_get_lease(&prompt_id, &lessee);
+    match res {
+        Err(Ok(Error::LeaseNotFound)) => {}
+        other => panic!("expected LeaseNotFound, got {:?}", other),
+    }
+    assert_eq!(client.get_prompt(&prompt_id).sales_count, 1);
+}
+
+#[test]
+fn test_refunded_lease_renewal_rolls_back_only_the_extension() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let lessee = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Leased", 10_000, &context.xlm);
+    client.set_lease_terms(&creator, &prompt_id, &Some(daily_lease_terms(100)));
+
+    fund_buyer(&xlm_client, &lessee, &context.contract, 500);
+    client.lease_prompt(&lessee, &prompt_id, &(3 * DAY));
+    let lease_settlement = client
+        .get_entitlement_settlement(&prompt_id, &lessee)
+        .unwrap();
+    client.renew_lease(&lessee, &prompt_id, &(2 * DAY));
+    assert_eq!(client.get_lease(&prompt_id, &lessee).expires_at, 5 * DAY);
+
+    // Lease sales settle immediately, so the creator refunds from their own
+    // balance.
+    xlm_client.mint(&creator, &200);
+    client.creator_refund(&creator, &prompt_id, &lessee, &200);
+
+    let lease = client.get_lease(&prompt_id, &lessee);
+    assert_eq!(lease.expires_at, 3 * DAY);
+    assert_eq!(lease.paid_total, 300);
+    assert_eq!(client.get_prompt(&prompt_id).sales_count, 1);
+    assert_eq!(
+        client.get_entitlement_settlement(&prompt_id, &lessee),
+        Some(lease_settlement)
+    );
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = 3 * DAY - 1);
+    assert!(client.has_access(&lessee, &prompt_id));
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = 3 * DAY + 1);
+    assert!(!client.has_access(&lessee, &prompt_id));
+}
+
+#[test]
+fn test_convert_lease_charges_the_listing_price() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let lessee = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Leased", 10_000, &context.xlm);
+    client.set_lease_terms(&creator, &prompt_id, &Some(daily_lease_terms(100)));
+
+    fund_buyer(&xlm_client, &lessee, &context.contract, 1_000 + 5_000);
+    client.lease_prompt(&lessee, &prompt_id, &(10 * DAY));
+    client.set_pay_what_you_want(&creator, &prompt_id, &Some(5_000));
+
+    env.ledger().with_mut(|ledger| ledger.timestamp = 4 * DAY);
+    client.convert_lease(&lessee, &prompt_id, &4_400);
+    let escrow = client.get_purchase_escrow(&prompt_id, &lessee).unwrap();
+    assert_eq!(escrow.amount, 4_400);
+}
+
+#[test]
+fn test_partial_lease_refund_reduces_conversion_credit() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let lessee = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Leased", 10_000, &context.xlm);
+    client.set_lease_terms(&creator, &prompt_id, &Some(daily_lease_terms(100)));
+
+    fund_buyer(&xlm_client, &lessee, &context.contract, 1_000 + 9_640);
+    client.lease_prompt(&lessee, &prompt_id, &(10 * DAY));
+
+    // The lease settled at purchase, so the refund comes from the creator.
+    xlm_client.mint(&creator, &400);
+    client.creator_refund(&creator, &prompt_id, &lessee, &400);
+    assert!(client.has_access(&lessee, &prompt_id));
+
+    // Six of ten days remain on the 600 still paid: a credit of 360.
+    env.ledger().with_mut(|ledger| ledger.timestamp = 4 * DAY);
+    client.convert_lease(&lessee, &prompt_id, &9_640);
+    let escrow = client.get_purchase_escrow(&prompt_id, &lessee).unwrap();
+    assert_eq!(escrow.amount, 9_640);
+}
+
+// ─── Per-listing resale royalty ──────────────────────────────────────────────
+
+fn create_resale_prompt(
+    env: &Env,
+    client: &PromptHashContractClient,
+    creator: &Address,
+    asset: &Address,
+    royalty_bps: u32,
+    royalty_splits: Vec<Split>,
+    resale_allowed: bool,
+) -> Result<u64, Error> {
+    let config = ListingConfig {
+        royalty_bps,
+        royalty_splits,
+        resale_allowed,
+        ..listing_config(env, 10_000, asset)
+    };
+    try_create_prompt_with_config(env, client, creator, "Resale Terms", &config)
+}
+
+#[test]
+fn test_resale_routes_listing_royalty_through_royalty_splits() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let collaborator = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let new_buyer = Address::generate(&env);
+
+    let res = create_resale_prompt(
+        &env,
+        &client,
+        &creator,
+        &context.xlm,
+        2_501,
+        Vec::new(&env),
+        true,
+    );
+    assert_eq!(res, Err(Error::InvalidRoyaltyBps));
+
+    let mut royalty_splits = Vec::new(&env);
+    royalty_splits.push_back(Split {
+        recipient: collaborator.clone(),
+        bps: 2_500,
+    });
+    let prompt_id = create_resale_prompt(
+        &env,
+        &client,
+        &creator,
+        &context.xlm,
+        1_000,
+        royalty_splits,
+        true,
+    )
+    .unwrap();
+
+    fund_buyer(&xlm_client, &seller, &context.contract, 10_000);
+    client.buy_prompt(
+        &seller,
+        &prompt_id,
+        &None::<Address>,
+        &10_000,
+        &None::<Bytes>,
+    );
+    client.settle_purchase(&context.admin, &prompt_id, &seller);
+
+    let creator_before = paid_balance(&client, &xlm_client, &creator);
+    fund_buyer(&xlm_client, &new_buyer, &context.contract, 20_000);
+    client.transfer_license(&seller, &prompt_id, &new_buyer, &20_000);
+
+    // A 10% royalty of 2,000, a quarter of it to the collaborator.
+    assert_eq!(paid_balance(&client, &xlm_client, &collaborator), 500);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &creator) - creator_before,
+        1_500
+    );
+    assert_eq!(paid_balance(&client, &xlm_client, &seller), 18_000);
+    assert!(client.has_access(&new_buyer, &prompt_id));
+}
+
+#[test]
+fn test_listing_can_forbid_resale() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let new_buyer = Address::generate(&env);
+
+    let prompt_id = create_resale_prompt(
+        &env,
+        &client,
+        &creator,
+        &context.xlm,
+        500,
+        Vec::new(&env),
+        false,
+    )
+    .unwrap();
+    fund_buyer(&xlm_client, &seller, &context.contract, 10_000);
+    client.buy_prompt(
+        &seller,
+        &prompt_id,
+        &None::<Address>,
+        &10_000,
+        &None::<Bytes>,
+    );
+
+    fund_buyer(&xlm_client, &new_buyer, &context.contract, 20_000);
+    let res = client.try_transfer_license(&seller, &prompt_id, &new_buyer, &20_000);
+    match res {
+        Err(Ok(Error::ResaleNotAllowed)) => {}
+        other => panic!("expected ResaleNotAllowed, got {:?}", other),
+    }
+    assert!(client.has_access(&seller, &prompt_id));
+    assert!(!client.has_access(&new_buyer, &prompt_id));
+}
+
+// ─── Dispute arbitration ─────────────────────────────────────────────────────
+
+/// Registers `count` arbiters and returns them in registry order.
+fn register_arbiters(
+    env: &Env,
+    client: &PromptHashContractClient,
+    admin: &Address,
+    count: u32,
+) -> Vec<Address> {
+    let mut arbiters = Vec::new(env);
+    for _ in 0..count {
+        let arbiter = Address::generate(env);
+        client.add_arbiter(admin, &arbiter);
+        arbiters.push_back(arbiter);
+    }
+    arbiters
+}
+
+/// Buys a prompt and opens a dispute on it, returning the settlement ID.
+fn open_arbitrated_dispute(
+    env: &Env,
+    context: &PromptHashContext,
+    client: &PromptHashContractClient,
+    creator: &Address,
+    buyer: &Address,
+    price: i128,
+) -> (u64, u128) {
+    let xlm_client = token::StellarAssetClient::new(env, &context.xlm);
+    let prompt_id = create_prompt(env, client, creator, "Arbitrated", price, &context.xlm);
+    fund_buyer(&xlm_client, buyer, &context.contract, price);
+    client.buy_prompt(buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+    client.open_dispute(
+        buyer,
+        &prompt_id,
+        &crate::types::DisputeReason::InvalidEncryptedPayload,
+    );
+    let settlement_id = client
+        .get_entitlement_settlement(&prompt_id, buyer)
+        .unwrap();
+    (prompt_id, settlement_id)
+}
+
+#[test]
+fn test_arbitration_panel_decides_by_quorum_after_appeal_window() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    let arbiters = register_arbiters(&env, &client, &context.admin, 4);
+
+    let (prompt_id, settlement_id) =
+        open_arbitrated_dispute(&env, &context, &client, &creator, &buyer, price);
+
+    // Round-robin from the start of the registry.
+    let arbitration = client.get_arbitration(&settlement_id);
+    assert_eq!(arbitration.round, 0);
+    assert_eq!(arbitration.quorum, 2);
+    assert_eq!(arbitration.panel.len(), 3);
+    assert_eq!(arbitration.panel.get(0).unwrap(), arbiters.get(0).unwrap());
+
+    // The owner can no longer decide alone.
+    let res = client.try_resolve_dispute(&context.admin, &prompt_id, &buyer, &price);
+    match res {
+        Err(Ok(Error::DisputeUnderArbitration)) => {}
+        other => panic!("expected DisputeUnderArbitration, got {:?}", other),
+    }
+
+    client.submit_dispute_evidence(&buyer, &settlement_id, &hash(&env, 1));
+    client.submit_dispute_evidence(&creator, &settlement_id, &hash(&env, 2));
+    let res = client.try_submit_dispute_evidence(
+        &arbiters.get(3).unwrap(),
+        &settlement_id,
+        &hash(&env, 3),
+    );
+    match res {
+        Err(Ok(Error::Unauthorized)) => {}
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+    assert_eq!(client.get_dispute_evidence(&settlement_id).len(), 2);
+
+    let res = client.try_cast_arbitration_vote(&arbiters.get(3).unwrap(), &settlement_id, &true);
+    match res {
+        Err(Ok(Error::NotOnArbitrationPanel)) => {}
+        other => panic!("expected NotOnArbitrationPanel, got {:?}", other),
+    }
+    client.cast_arbitration_vote(&arbitration.panel.get(0).unwrap(), &settlement_id, &true);
+    let res =
+        client.try_cast_arbitration_vote(&arbitration.panel.get(0).unwrap(), &settlement_id, &true);
+    match res {
+        Err(Ok(Error::ArbiterAlreadyVoted)) => {}
+        other => panic!("expected ArbiterAlreadyVoted, got {:?}", other),
+    }
+    client.cast_arbitration_vote(&arbitration.panel.get(1).unwrap(), &settlement_id, &true);
+    assert_eq!(client.get_arbitration(&settlement_id).outcome, Some(true));
+
+    // The outcome waits out the appeal window before funds move.
+    let res = client.try_finalize_arbitration(&settlement_id);
+    match res {
+        Err(Ok(Error::AppealWindowOpen)) => {}
+        other => panic!("expected AppealWindowOpen, got {:?}", other),
+    }
+    assert_eq!(client.get_asset_liability(&context.xlm).disputed, price);
+
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = 2 * 24 * 60 * 60 + 1);
+    client.finalize_arbitration(&settlement_id);
+
+    assert_eq!(paid_balance(&client, &xlm_client, &buyer), price);
+    assert!(!client.has_access(&buyer, &prompt_id));
+    let liability = client.get_asset_liability(&context.xlm);
+    assert_eq!(liability.pending, 0);
+    assert_eq!(liability.disputed, 0);
+    assert_eq!(
+        client.get_dispute(&prompt_id, &buyer).status,
+        crate::types::DisputeStatus::Refunded
+    );
+}
+
+#[test]
+fn test_arbitration_appeal_goes_to_fresh_larger_panel_once() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    register_arbiters(&env, &client, &context.admin, 8);
+
+    let (prompt_id, settlement_id) =
+        open_arbitrated_dispute(&env, &context, &client, &creator, &buyer, price);
+    let first = client.get_arbitration(&settlement_id);
+    client.cast_arbitration_vote(&first.panel.get(0).unwrap(), &settlement_id, &false);
+    client.cast_arbitration_vote(&first.panel.get(1).unwrap(), &settlement_id, &false);
+
+    let res = client.try_appeal_arbitration(&Address::generate(&env), &settlement_id);
+    match res {
+        Err(Ok(Error::Unauthorized)) => {}
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+    client.appeal_arbitration(&buyer, &settlement_id);
+
+    let appeal = client.get_arbitration(&settlement_id);
+    assert_eq!(appeal.round, 1);
+    assert_eq!(appeal.quorum, 3);
+    assert_eq!(appeal.panel.len(), 5);
+    for arbiter in first.panel.iter() {
+        assert!(!appeal.panel.contains(&arbiter));
+    }
+    let res = client.try_appeal_arbitration(&creator, &settlement_id);
+    match res {
+        Err(Ok(Error::AppealAlreadyUsed)) => {}
+        other => panic!("expected AppealAlreadyUsed, got {:?}", other),
+    }
+
+    // The appeal panel's decision applies immediately: the rejection moves
+    // the escrow back to pending.
+    for i in 0..3 {
+        client.cast_arbitration_vote(&appeal.panel.get(i).unwrap(), &settlement_id, &false);
+    }
+    assert_eq!(
+        client.get_dispute(&prompt_id, &buyer).status,
+        crate::types::DisputeStatus::Rejected
+    );
+    let liability = client.get_asset_liability(&context.xlm);
+    assert_eq!(liability.pending, price);
+    assert_eq!(liability.disputed, 0);
+    assert!(client.has_access(&buyer, &prompt_id));
+}
+
+#[test]
+fn test_appeal_panel_sized_to_eligible_arbiters_and_reopen_keeps_history() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    register_arbiters(&env, &client, &context.admin, 6);
+
+    let (prompt_id, settlement_id) =
+        open_arbitrated_dispute(&env, &context, &client, &creator, &buyer, price);
+    let first = client.get_arbitration(&settlement_id);
+    client.cast_arbitration_vote(&first.panel.get(0).unwrap(), &settlement_id, &false);
+    client.cast_arbitration_vote(&first.panel.get(1).unwrap(), &settlement_id, &false);
+
+    // Only three arbiters remain eligible, so the appeal seats all of them.
+    client.appeal_arbitration(&buyer, &settlement_id);
+    let appeal = client.get_arbitration(&settlement_id);
+    assert_eq!(appeal.round, 1);
+    assert_eq!(appeal.panel.len(), 3);
+    assert_eq!(appeal.quorum, 2);
+    for arbiter in first.panel.iter() {
+        assert!(!appeal.panel.contains(&arbiter));
+    }
+    client.cast_arbitration_vote(&appeal.panel.get(0).unwrap(), &settlement_id, &false);
+    client.cast_arbitration_vote(&appeal.panel.get(1).unwrap(), &settlement_id, &false);
+    assert_eq!(
+        client.get_dispute(&prompt_id, &buyer).status,
+        crate::types::DisputeStatus::Rejected
+    );
+
+    // Reopening seats a new panel without losing the earlier ones.
+    client.open_dispute(
+        &buyer,
+        &prompt_id,
+        &crate::types::DisputeReason::MissingMetadata,
+    );
+    let reopened = client.get_arbitration(&settlement_id);
+    assert_eq!(reopened.round, 0);
+    assert!(reopened.outcome.is_none());
+    let history = client.get_arbitration_history(&settlement_id);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().panel, first.panel);
+    assert_eq!(history.get(1).unwrap().round, 1);
+    assert_eq!(history.get(1).unwrap().outcome, Some(false));
+}
+
+#[test]
+fn test_appeal_requires_a_full_first_size_fresh_panel() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 5_000;
+    register_arbiters(&env, &client, &context.admin, 5);
+
+    let (_, settlement_id) =
+        open_arbitrated_dispute(&env, &context, &client, &creator, &buyer, price);
+    let first = client.get_arbitration(&settlement_id);
+    client.cast_arbitration_vote(&first.panel.get(0).unwrap(), &settlement_id, &true);
+    client.cast_arbitration_vote(&first.panel.get(1).unwrap(), &settlement_id, &true);
+
+    let res = client.try_appeal_arbitration(&creator, &settlement_id);
+    match res {
+        Err(Ok(Error::NotEnoughArbiters)) => {}
+        other => panic!("expected NotEnoughArbiters, got {:?}", other),
+    }
+    assert_eq!(client.get_arbitration(&settlement_id).round, 0);
+}
+
+// ─── Partial and creator refunds ─────────────────────────────────────────────
+
+#[test]
+fn test_partial_dispute_refund_scales_payout_plan() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 10_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Partial", price, &context.xlm);
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+    client.open_dispute(
+        &buyer,
+        &prompt_id,
+        &crate::types::DisputeReason::MissingMetadata,
+    );
+
+    let res = client.try_resolve_dispute(&context.admin, &prompt_id, &buyer, &(price + 1));
+    match res {
+        Err(Ok(Error::InvalidRefundAmount)) => {}
+        other => panic!("expected InvalidRefundAmount, got {:?}", other),
+    }
+
+    let buyer_before = paid_balance(&client, &xlm_client, &buyer);
+    client.resolve_dispute(&context.admin, &prompt_id, &buyer, &4_000);
+    assert_eq!(
+        client.get_dispute(&prompt_id, &buyer).status,
+        crate::types::DisputeStatus::PartiallyRefunded
+    );
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &buyer),
+        buyer_before + 4_000
+    );
+    assert!(client.has_access(&buyer, &prompt_id));
+    let res = client.try_open_dispute(
+        &buyer,
+        &prompt_id,
+        &crate::types::DisputeReason::MissingMetadata,
+    );
+    match res {
+        Err(Ok(Error::DisputeResolved)) => {}
+        other => panic!("expected DisputeResolved, got {:?}", other),
+    }
+
+    let liability = client.get_asset_liability(&context.xlm);
+    assert_eq!(liability.pending, 6_000);
+    assert_eq!(liability.disputed, 0);
+
+    let settlement_id = client
+        .get_entitlement_settlement(&prompt_id, &buyer)
+        .unwrap();
+    let escrow = client.get_settlement_escrow(&settlement_id).unwrap();
+    assert_eq!(escrow.amount, 6_000);
+    assert_eq!(escrow.refunded_amount, 4_000);
+    let plan = escrow.payout_plan;
+    assert_eq!(
+        plan.fee_amount + plan.referral_amount + plan.creator_amount,
+        6_000
+    );
+
+    let creator_before = paid_balance(&client, &xlm_client, &creator);
+    let fee_before = paid_balance(&client, &xlm_client, &context.fee_wallet);
+    client.settle_purchase(&context.admin, &prompt_id, &buyer);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &creator),
+        creator_before + plan.creator_amount
+    );
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &context.fee_wallet),
+        fee_before + plan.fee_amount
+    );
+
+    // The settled shares and the refund are owed until withdrawn.
+    let solvency = client.get_asset_solvency(&context.xlm);
+    assert_eq!(solvency.tracked_liability, price);
+    assert_eq!(solvency.surplus, 0);
+}
+
+#[test]
+fn test_creator_refund_before_settlement() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 10_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Goodwill", price, &context.xlm);
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+
+    let stranger = Address::generate(&env);
+    let res = client.try_creator_refund(&stranger, &prompt_id, &buyer, &1_000);
+    match res {
+        Err(Ok(Error::Unauthorized)) => {}
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+
+    let buyer_before = paid_balance(&client, &xlm_client, &buyer);
+    client.creator_refund(&creator, &prompt_id, &buyer, &2_500);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &buyer),
+        buyer_before + 2_500
+    );
+    assert!(client.has_access(&buyer, &prompt_id));
+    assert_eq!(client.get_asset_liability(&context.xlm).pending, 7_500);
+
+    client.creator_refund(&creator, &prompt_id, &buyer, &7_500);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &buyer),
+        buyer_before + price
+    );
+    assert!(!client.has_access(&buyer, &prompt_id));
+    assert_eq!(client.get_prompt(&prompt_id).sales_count, 0);
+    assert_eq!(client.get_asset_liability(&context.xlm).pending, 0);
+
+    let settlement_id = client
+        .get_entitlement_settlement(&prompt_id, &buyer)
+        .unwrap();
+    let escrow = client.get_settlement_escrow(&settlement_id).unwrap();
+    assert_eq!(escrow.status, crate::types::SettlementStatus::Refunded);
+    assert_eq!(escrow.refunded_amount, price);
+    assert_eq!(client.get_asset_solvency(&context.xlm).surplus, 0);
+}
+
+#[test]
+fn test_creator_refund_after_settlement_uses_creator_balance() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 10_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Settled", price, &context.xlm);
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+    client.settle_purchase(&context.admin, &prompt_id, &buyer);
+    client.withdraw(&creator, &context.xlm);
+
+    let contract_before = xlm_client.balance(&context.contract);
+    let creator_before = xlm_client.balance(&creator);
+    let buyer_before = xlm_client.balance(&buyer);
+    client.creator_refund(&creator, &prompt_id, &buyer, &3_000);
+    assert_eq!(xlm_client.balance(&creator), creator_before - 3_000);
+    assert_eq!(xlm_client.balance(&buyer), buyer_before + 3_000);
+    assert_eq!(xlm_client.balance(&context.contract), contract_before);
+    assert!(client.has_access(&buyer, &prompt_id));
+
+    let liability = client.get_asset_liability(&context.xlm);
+    assert_eq!(liability.pending, 0);
+    assert_eq!(liability.disputed, 0);
+
+    let res = client.try_creator_refund(&creator, &prompt_id, &buyer, &(price - 2_999));
+    match res {
+        Err(Ok(Error::InvalidRefundAmount)) => {}
+        other => panic!("expected InvalidRefundAmount, got {:?}", other),
+    }
+}
+
+// ─── Product dispute windows ─────────────────────────────────────────────────
+
+#[test]
+fn test_dispute_window_policy_changes_through_governance_within_bounds() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let kind = crate::types::AcquisitionKind::Bundle;
+    assert_eq!(client.get_dispute_window_policy(&kind), 0);
+
+    let stranger = Address::generate(&env);
+    match client.try_set_dispute_window_policy(&stranger, &kind, &DAY) {
+        Err(Ok(Error::Unauthorized)) => {}
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+    match client.try_set_dispute_window_policy(
+        &context.admin,
+        &crate::types::AcquisitionKind::DirectPurchase,
+        &DAY,
+    ) {
+        Err(Ok(Error::InvalidDisputeWindow)) => {}
+        other => panic!("expected InvalidDisputeWindow, got {:?}", other),
+    }
+    // Outside the governed 1 hour to 14 day bounds.
+    for window_secs in [60, 15 * DAY] {
+        match client.try_set_dispute_window_policy(&context.admin, &kind, &window_secs) {
+            Err(Ok(Error::InvalidDisputeWindow)) => {}
+            other => panic!("expected InvalidDisputeWindow, got {:?}", other),
+        }
+    }
+
+    // The change is queued, not applied.
+    client.set_dispute_window_policy(&context.admin, &kind, &(10 * DAY));
+    assert_eq!(client.get_dispute_window_policy(&kind), 0);
+    let pending = client.get_pending_governance_proposals();
+    let proposal = client.get_governance_proposal(&pending.get(pending.len() - 1).unwrap());
+    assert_eq!(
+        proposal.action,
+        GovernanceAction::SetDisputeWindowPolicy(kind.clone(), 10 * DAY)
+    );
+    execute_latest_governance(&env, &client);
+    assert_eq!(client.get_dispute_window_policy(&kind), 10 * DAY);
+
+    // Narrowing the bounds holds the policy to them.
+    client.propose_governance(
+        &context.admin,
+        &GovernanceAction::SetDisputeWindow(crate::types::DisputeWindowConfig {
+            default_secs: DAY,
+            min_secs: 60 * 60,
+            max_secs: 7 * DAY,
+        }),
+        &hash(&env, 1),
+    );
+    execute_latest_governance(&env, &client);
+    assert_eq!(client.get_dispute_window_policy(&kind), 7 * DAY);
+
+    client.set_dispute_window_policy(&context.admin, &kind, &0);
+    execute_latest_governance(&env, &client);
+    assert_eq!(client.get_dispute_window_policy(&kind), 0);
+}
+
+#[test]
+fn test_bundle_dispute_refund_revokes_bundled_prompts() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    client.set_dispute_window_policy(
+        &context.admin,
+        &crate::types::AcquisitionKind::Bundle,
+        &(2 * DAY),
+    );
+    execute_latest_governance(&env, &client);
+
+    let prompt_a = create_prompt(&env, &client, &creator, "Bundle A", 7_000, &context.xlm);
+    let prompt_b = create_prompt(&env, &client, &creator, "Bundle B", 9_000, &context.xlm);
+    let bundle_price: i128 = 12_000;
+    let bundle_id = client.create_bundle(
+        &creator,
+        &String::from_str(&env, "Broken Bundle"),
+        &Vec::from_array(&env, [prompt_a, prompt_b]),
+        &bundle_price,
+        &context.xlm,
+        &0,
+    );
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, bundle_price);
+    let creator_before = xlm_client.balance(&creator);
+    client.buy_bundle(&buyer, &bundle_id, &bundle_price);
+    assert_eq!(xlm_client.balance(&creator), creator_before);
+    assert_eq!(
+        client.get_asset_liability(&context.xlm).pending,
+        bundle_price
+    );
+
+    let settlement_id = client
+        .get_entitlement_settlement(&prompt_a, &buyer)
+        .unwrap();
+    let escrow = client.get_settlement_escrow(&settlement_id).unwrap();
+    assert_eq!(escrow.status, crate::types::SettlementStatus::Pending);
+    assert_eq!(escrow.dispute_deadline, escrow.created_at + 2 * DAY);
+
+    client.open_settlement_dispute(
+        &buyer,
+        &settlement_id,
+        &crate::types::DisputeReason::InvalidEncryptedPayload,
+    );
+    assert_eq!(
+        client.get_asset_liability(&context.xlm).disputed,
+        bundle_price
+    );
+
+    let buyer_before = paid_balance(&client, &xlm_client, &buyer);
+    client.resolve_settlement_dispute(&context.admin, &settlement_id, &bundle_price);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &buyer),
+        buyer_before + bundle_price
+    );
+    assert!(!client.has_access(&buyer, &prompt_a));
+    assert!(!client.has_access(&buyer, &prompt_b));
+    assert_eq!(client.get_prompt(&prompt_a).sales_count, 0);
+    assert_eq!(client.get_prompt(&prompt_b).sales_count, 0);
+    assert_eq!(client.get_bundle(&bundle_id).sales_count, 0);
+
+    let liability = client.get_asset_liability(&context.xlm);
+    assert_eq!(liability.pending, 0);
+    assert_eq!(liability.disputed, 0);
+}
+
+#[test]
+fn test_bundled_license_moves_only_once_the_bundle_settles() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+    client.set_dispute_window_policy(
+        &context.admin,
+        &crate::types::AcquisitionKind::Bundle,
+        &(2 * DAY),
+    );
+    execute_latest_governance(&env, &client);
+
+    let prompt_a = create_prompt(&env, &client, &creator, "Bundle A", 7_000, &context.xlm);
+    let prompt_b = create_prompt(&env, &client, &creator, "Bundle B", 9_000, &context.xlm);
+    let bundle_price: i128 = 12_000;
+    let bundle_id = client.create_bundle(
+        &creator,
+        &String::from_str(&env, "Resold Bundle"),
+        &Vec::from_array(&env, [prompt_a, prompt_b]),
+        &bundle_price,
+        &context.xlm,
+        &0,
+    );
+    fund_buyer(&xlm_client, &buyer, &context.contract, bundle_price);
+    client.buy_bundle(&buyer, &bundle_id, &bundle_price);
+    let settlement_id = client
+        .get_entitlement_settlement(&prompt_a, &buyer)
+        .unwrap();
+
+    // While the bundle can still be disputed, none of its licenses move.
+    fund_buyer(&xlm_client, &new_owner, &context.contract, 8_000);
+    let res = client.try_transfer_license(&buyer, &prompt_a, &new_owner, &8_000);
+    match res {
+        Err(Ok(Error::LicenseNotTransferable)) => {}
+        other => panic!("expected LicenseNotTransferable, got {:?}", other),
+    }
+
+    // Once settled, a bundled license resells and the bundle can no longer
+    // be disputed out from under its new owner.
+    client.settle_settlement(&context.admin, &settlement_id);
+    client.transfer_license(&buyer, &prompt_a, &new_owner, &8_000);
+    let res = client.try_open_settlement_dispute(
+        &buyer,
+        &settlement_id,
+        &crate::types::DisputeReason::InvalidEncryptedPayload,
+    );
+    match res {
+        Err(Ok(Error::DisputeWindowClosed)) => {}
+        other => panic!("expected DisputeWindowClosed, got {:?}", other),
+    }
+    assert!(client.has_access(&new_owner, &prompt_a));
+    assert!(!client.has_access(&buyer, &prompt_a));
+    assert!(client.has_access(&buyer, &prompt_b));
+}
+
+#[test]
+fn test_lease_and_pass_follow_their_dispute_window_policy() {
+    let env: Env = Default::default();
+    env.ledger().with_mut(|ledger| ledger.timestamp = 1_000);
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let lessee = Address::generate(&env);
+    let pass_buyer = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Leased", 10_000, &context.xlm);
+
+    // Without a policy a lease still pays out at purchase and cannot be
+    // disputed.
+    fund_buyer(&xlm_client, &lessee, &context.contract, 10_000);
+    client.lease_prompt(&lessee, &prompt_id, &DAY);
+    let first = client
+        .get_entitlement_settlement(&prompt_id, &lessee)
+        .unwrap();
+    assert_eq!(
+        client.get_settlement_escrow(&first).unwrap().status,
+        crate::types::SettlementStatus::Settled
+    );
+    match client.try_open_settlement_dispute(
+        &lessee,
+        &first,
+        &crate::types::DisputeReason::MissingMetadata,
+    ) {
+        Err(Ok(Error::DisputeWindowClosed)) => {}
+        other => panic!("expected DisputeWindowClosed, got {:?}", other),
+    }
+
+    client.set_dispute_window_policy(&context.admin, &crate::types::AcquisitionKind::Lease, &DAY);
+
+    execute_latest_governance(&env, &client);
+    client.set_dispute_window_policy(
+        &context.admin,
+        &crate::types::AcquisitionKind::AccessPass,
+        &DAY,
+    );
+    execute_latest_governance(&env, &client);
+
+    env.ledger().with_mut(|ledger| ledger.timestamp += 2 * DAY);
+    client.lease_prompt(&lessee, &prompt_id, &DAY);
+    let reserved = client.get_prompt(&prompt_id).sales_count;
+    let second = client
+        .get_entitlement_settlement(&prompt_id, &lessee)
+        .unwrap();
+    let lease_escrow = client.get_settlement_escrow(&second).unwrap();
+    assert_eq!(lease_escrow.status, crate::types::SettlementStatus::Pending);
+    client.open_settlement_dispute(
+        &lessee,
+        &second,
+        &crate::types::DisputeReason::MissingMetadata,
+    );
+    client.resolve_settlement_dispute(&context.admin, &second, &lease_escrow.amount);
+    assert!(!client.has_access(&lessee, &prompt_id));
+    assert_eq!(client.get_prompt(&prompt_id).sales_count, reserved - 1);
+
+    let pass_price: i128 = 15_000;
+    let pass_id = client.create_access_pass(
+        &creator,
+        &String::from_str(&env, "Weekly Catalog"),
+        &(7 * DAY),
+        &pass_price,
+        &context.xlm,
+        &0u32,
+    );
+    fund_buyer(&xlm_client, &pass_buyer, &context.contract, pass_price);
+    client.buy_access_pass(&pass_buyer, &pass_id, &pass_price);
+    assert!(client.has_access(&pass_buyer, &prompt_id));
+    let pass_settlement = second + 1;
+    assert_eq!(
+        client
+            .get_settlement_escrow(&pass_settlement)
+            .unwrap()
+            .status,
+        crate::types::SettlementStatus::Pending
+    );
+
+    // Once the window closes anyone may settle the pass sale.
+    env.ledger().with_mut(|ledger| ledger.timestamp += DAY + 1);
+    let creator_before = paid_balance(&client, &xlm_client, &creator);
+    client.settle_settlement(&pass_buyer, &pass_settlement);
+    assert!(paid_balance(&client, &xlm_client, &creator) > creator_before);
+    assert_eq!(client.get_access_pass(&pass_id).sales_count, 1);
+    assert_eq!(client.get_asset_liability(&context.xlm).pending, 0);
+}
+
+// ─── Dispute bonds ───────────────────────────────────────────────────────────
+
+#[test]
+fn test_dispute_bond_returned_on_refund_and_forfeited_on_rejection() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let honest = Address::generate(&env);
+    let abuser = Address::generate(&env);
+    let price = 10_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Bonded", price, &context.xlm);
+
+    match client.try_set_dispute_bond(&context.admin, &10_001) {
+        Err(Ok(Error::InvalidDisputeBond)) => {}
+        other => panic!("expected InvalidDisputeBond, got {:?}", other),
+    }
+    client.set_dispute_bond(&context.admin, &1_000);
+    assert_eq!(client.get_dispute_bond(&honest), 1_000);
+
+    for buyer in [&honest, &abuser] {
+        fund_buyer(&xlm_client, buyer, &context.contract, price);
+        client.buy_prompt(buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+        fund_buyer(&xlm_client, buyer, &context.contract, 1_000);
+        client.open_dispute(
+            buyer,
+            &prompt_id,
+            &crate::types::DisputeReason::MissingMetadata,
+        );
+        assert_eq!(client.get_dispute(&prompt_id, buyer).bond_amount, 1_000);
+    }
+    let liability = client.get_asset_liability(&context.xlm);
+    assert_eq!(liability.disputed, 2 * price);
+    assert_eq!(liability.bonds, 2_000);
+    let solvency = client.get_asset_solvency(&context.xlm);
+    assert_eq!(solvency.tracked_liability, 2 * price + 2_000);
+    assert_eq!(solvency.surplus, 0);
+
+    client.resolve_dispute(&context.admin, &prompt_id, &honest, &price);
+    assert_eq!(paid_balance(&client, &xlm_client, &honest), price + 1_000);
+
+    let creator_before = paid_balance(&client, &xlm_client, &creator);
+    client.resolve_dispute(&context.admin, &prompt_id, &abuser, &0);
+    assert_eq!(xlm_client.balance(&abuser), 0);
+    assert_eq!(
+        paid_balance(&client, &xlm_client, &creator),
+        creator_before + 1_000
+    );
+
+    let liability = client.get_asset_liability(&context.xlm);
+    assert_eq!(liability.bonds, 0);
+    assert_eq!(liability.pending, price);
+    assert_eq!(client.get_asset_solvency(&context.xlm).surplus, 0);
+
+    let stats = client.get_buyer_dispute_stats(&abuser);
+    assert_eq!(stats.opened, 1);
+    assert_eq!(stats.rejected, 1);
+    assert_eq!(stats.refunded, 0);
+    assert_eq!(client.get_buyer_dispute_stats(&honest).refunded, 1);
+}
+
+#[test]
+fn test_admin_can_raise_dispute_bond_for_one_buyer() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 10_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Repeat", price, &context.xlm);
+
+    // No bond by default.
+    assert_eq!(client.get_dispute_bond(&buyer), 0);
+    client.set_buyer_dispute_bond(&context.admin, &buyer, &Some(5_000));
+    assert_eq!(client.get_dispute_bond(&buyer), 5_000);
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+
+    // Without an allowance for the bond the dispute cannot be opened.
+    assert!(client
+        .try_open_dispute(
+            &buyer,
+            &prompt_id,
+            &crate::types::DisputeReason::MissingMetadata,
+        )
+        .is_err());
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, 5_000);
+    client.open_dispute(
+        &buyer,
+        &prompt_id,
+        &crate::types::DisputeReason::MissingMetadata,
+    );
+    assert_eq!(client.get_asset_liability(&context.xlm).bonds, 5_000);
+
+    client.set_buyer_dispute_bond(&context.admin, &buyer, &None);
+    assert_eq!(client.get_dispute_bond(&buyer), 0);
+}
+
+// ─── Configurable dispute window ─────────────────────────────────────────────
+
+#[test]
+fn test_listing_dispute_window_is_snapshotted_within_bounds() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let late_buyer = Address::generate(&env);
+    let price = 10_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Enterprise", price, &context.xlm);
+    assert_eq!(client.get_listing_dispute_window(&prompt_id), 3 * DAY);
+
+    let config = client.get_dispute_window_config();
+    match client.try_set_listing_dispute_window(&creator, &prompt_id, &Some(config.max_secs + 1)) {
+        Err(Ok(Error::InvalidDisputeWindow)) => {}
+        other => panic!("expected InvalidDisputeWindow, got {:?}", other),
+    }
+    let stranger = Address::generate(&env);
+    match client.try_set_listing_dispute_window(&stranger, &prompt_id, &Some(DAY)) {
+        Err(Ok(Error::Unauthorized)) => {}
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+
+    client.set_listing_dispute_window(&creator, &prompt_id, &Some(14 * DAY));
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+    let escrow = client.get_purchase_escrow(&prompt_id, &buyer).unwrap();
+    assert_eq!(escrow.dispute_deadline, escrow.created_at + 14 * DAY);
+
+    // Later changes only affect later sales.
+    client.set_listing_dispute_window(&creator, &prompt_id, &None);
+    fund_buyer(&xlm_client, &late_buyer, &context.contract, price);
+    client.buy_prompt(
+        &late_buyer,
+        &prompt_id,
+        &None::<Address>,
+        &price,
+        &None::<Bytes>,
+    );
+    let late = client.get_purchase_escrow(&prompt_id, &late_buyer).unwrap();
+    assert_eq!(late.dispute_deadline, late.created_at + 3 * DAY);
+    assert_eq!(
+        client
+            .get_purchase_escrow(&prompt_id, &buyer)
+            .unwrap()
+            .dispute_deadline,
+        escrow.dispute_deadline
+    );
+}
+
+#[test]
+fn test_dispute_window_config_changes_through_governance() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Instant", 10_000, &context.xlm);
+    client.set_listing_dispute_window(&creator, &prompt_id, &Some(10 * DAY));
+
+    let invalid = crate::types::DisputeWindowConfig {
+        default_secs: DAY,
+        min_secs: 2 * DAY,
+        max_secs: 7 * DAY,
+    };
+    match client.try_propose_governance(
+        &context.admin,
+        &GovernanceAction::SetDisputeWindow(invalid),
+        &hash(&env, 1),
+    ) {
+        Err(Ok(Error::InvalidDisputeWindow)) => {}
+        other => panic!("expected InvalidDisputeWindow, got {:?}", other),
+    }
+
+    let config = crate::types::DisputeWindowConfig {
+        default_secs: DAY,
+        min_secs: 60 * 60,
+        max_secs: 7 * DAY,
+    };
+    let proposal_hash = client.propose_governance(
+        &context.admin,
+        &GovernanceAction::SetDisputeWindow(config.clone()),
+        &hash(&env, 2),
+    );
+    let proposal = client.get_governance_proposal(&proposal_hash);
+    env.ledger()
+        .with_mut(|li| li.sequence_number = proposal.executable_at_ledger);
+    client.execute_governance(&context.admin, &proposal_hash);
+
+    assert_eq!(client.get_dispute_window_config(), config);
+    // A window chosen under the old bounds is held to the new ones.
+    assert_eq!(client.get_listing_dispute_window(&prompt_id), 7 * DAY);
+    let other = create_prompt(&env, &client, &creator, "Default", 10_000, &context.xlm);
+    assert_eq!(client.get_listing_dispute_window(&other), DAY);
+}
+
+// ─── Pull-payment withdrawals ────────────────────────────────────────────────
+
+#[test]
+fn test_settlement_credits_claimable_balances_until_withdrawn() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let price = 10_000;
+    let prompt_id = create_prompt(&env, &client, &creator, "Pull", price, &context.xlm);
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, price);
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &price, &None::<Bytes>);
+    client.settle_purchase(&context.admin, &prompt_id, &buyer);
+
+    let creator_share = client.get_claimable_balance(&creator, &context.xlm);
+    let fee_share = client.get_claimable_balance(&context.fee_wallet, &context.xlm);
+    assert_eq!(creator_share + fee_share, price);
+    assert_eq!(xlm_client.balance(&creator), 0);
+
+    let liability = client.get_asset_liability(&context.xlm);
+    assert_eq!(liability.pending, 0);
+    assert_eq!(liability.claimable, price);
+    assert_eq!(client.get_asset_solvency(&context.xlm).surplus, 0);
+
+    assert_eq!(client.withdraw(&creator, &context.xlm), creator_share);
+    assert_eq!(xlm_client.balance(&creator), creator_share);
+    assert_eq!(client.get_claimable_balance(&creator, &context.xlm), 0);
+    assert_eq!(
+        client.get_asset_liability(&context.xlm).claimable,
+        fee_share
+    );
+
+    match client.try_withdraw(&creator, &context.xlm) {
+        Err(Ok(Error::NothingToWithdraw)) => {}
+        other => panic!("expected NothingToWithdraw, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_deauthorized_recipient_does_not_block_bundle_sale() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let sac = env.register_stellar_asset_contract_v2(context.admin.clone());
+    sac.issuer()
+        .set_flag(soroban_sdk::testutils::IssuerFlags::RevocableFlag);
+    let usdc = sac.address();
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let prompt_a = create_prompt(&env, &client, &creator, "Frozen A", 7_000, &usdc);
+    let prompt_b = create_prompt(&env, &client, &creator, "Frozen B", 9_000, &usdc);
+    let bundle_price: i128 = 12_000;
+    let bundle_id = client.create_bundle(
+        &creator,
+        &String::from_str(&env, "Frozen Bundle"),
+        &Vec::from_array(&env, [prompt_a, prompt_b]),
+        &bundle_price,
+        &usdc,
+        &0,
+    );
+
+    // The creator's trustline is frozen; the sale still goes through.
+    usdc_client.set_authorized(&creator, &false);
+    fund_buyer(&usdc_client, &buyer, &context.contract, bundle_price);
+    client.buy_bundle(&buyer, &bundle_id, &bundle_price);
+    assert!(client.has_access(&buyer, &prompt_a));
+    assert!(client.get_claimable_balance(&creator, &usdc) > 0);
+
+    // Only the creator's own withdrawal waits on the trustline.
+    assert!(client.try_withdraw(&creator, &usdc).is_err());
+    usdc_client.set_authorized(&creator, &true);
+    let owed = client.get_claimable_balance(&creator, &usdc);
+    assert_eq!(client.withdraw(&creator, &usdc), owed);
+    assert_eq!(usdc_client.balance(&creator), owed);
+}
+
+// ─── Multi-asset pricing ─────────────────────────────────────────────────────
+
+#[test]
+fn test_buy_prompt_in_accepted_asset_escrows_that_asset() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let usdc = env.register(FungibleTokenContract, (context.admin.clone(),));
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Multi", 10_000, &context.xlm);
+
+    let other = env.register(FungibleTokenContract, (context.admin.clone(),));
+    match client.try_buy_prompt_in_asset(
+        &buyer,
+        &prompt_id,
+        &usdc,
+        &None::<Address>,
+        &2_000,
+        &None::<Bytes>,
+    ) {
+        Err(Ok(Error::InvalidAsset)) => {}
+        other => panic!("expected InvalidAsset, got {:?}", other),
+    }
+
+    client.set_listing_pricing(
+        &creator,
+        &prompt_id,
+        &Vec::from_array(
+            &env,
+            [PricingConfig {
+                price: 2_000,
+                asset: usdc.clone(),
+            }],
+        ),
+    );
+    assert_eq!(client.get_listing_price(&prompt_id, &usdc), 2_000);
+    assert_eq!(client.get_listing_price(&prompt_id, &context.xlm), 10_000);
+    assert!(client.try_get_listing_price(&prompt_id, &other).is_err());
+
+    fund_buyer(&usdc_client, &buyer, &context.contract, 2_000);
+    client.buy_prompt_in_asset(
+        &buyer,
+        &prompt_id,
+        &usdc,
+        &None::<Address>,
+        &2_000,
+        &None::<Bytes>,
+    );
+    assert!(client.has_access(&buyer, &prompt_id));
+    assert_eq!(usdc_client.balance(&context.contract), 2_000);
+
+    let escrow = client.get_purchase_escrow(&prompt_id, &buyer).unwrap();
+    assert_eq!(escrow.asset, usdc);
+    assert_eq!(escrow.amount, 2_000);
+    assert_eq!(client.get_asset_liability(&usdc).pending, 2_000);
+    assert_eq!(client.get_asset_liability(&context.xlm).pending, 0);
+
+    client.settle_purchase(&context.admin, &prompt_id, &buyer);
+    assert_eq!(
+        client.get_claimable_balance(&creator, &usdc)
+            + client.get_claimable_balance(&context.fee_wallet, &usdc),
+        2_000
+    );
+}
+
+#[test]
+fn test_listing_pricing_rejects_invalid_entries() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let usdc = env.register(FungibleTokenContract, (context.admin.clone(),));
+    let creator = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Multi", 10_000, &context.xlm);
+
+    let entry = |asset: &Address, price: i128| PricingConfig {
+        price,
+        asset: asset.clone(),
+    };
+    for pricing in [
+        Vec::from_array(&env, [entry(&context.xlm, 5_000)]),
+        Vec::from_array(&env, [entry(&usdc, 0)]),
+        Vec::from_array(&env, [entry(&usdc, 1_000), entry(&usdc, 2_000)]),
+    ] {
+        match client.try_set_listing_pricing(&creator, &prompt_id, &pricing) {
+            Err(Ok(Error::InvalidPricing)) => {}
+            other => panic!("expected InvalidPricing, got {:?}", other),
+        }
+    }
+
+    let stranger = Address::generate(&env);
+    match client.try_set_listing_pricing(
+        &stranger,
+        &prompt_id,
+        &Vec::from_array(&env, [entry(&usdc, 1_000)]),
+    ) {
+        Err(Ok(Error::Unauthorized)) => {}
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_bundle_accepts_prompts_priced_in_its_asset() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let usdc = env.register(FungibleTokenContract, (context.admin.clone(),));
+    let usdc_client = token::StellarAssetClient::new(&env, &usdc);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let xlm_prompt = create_prompt(&env, &client, &creator, "XLM", 10_000, &context.xlm);
+    let usdc_prompt = create_prompt(&env, &client, &creator, "USDC", 3_000, &usdc);
+    let prompt_ids = Vec::from_array(&env, [xlm_prompt, usdc_prompt]);
+    let title = String::from_str(&env, "Mixed Bundle");
+
+    match client.try_create_bundle(&creator, &title, &prompt_ids, &4_000, &usdc, &0) {
+        Err(Ok(Error::InvalidAsset)) => {}
+        other => panic!("expected InvalidAsset, got {:?}", other),
+    }
+
+    client.set_listing_pricing(
+        &creator,
+        &xlm_prompt,
+        &Vec::from_array(
+            &env,
+            [PricingConfig {
+                price: 2_000,
+                asset: usdc.clone(),
+            }],
+        ),
+    );
+    let bundle_id = client.create_bundle(&creator, &title, &prompt_ids, &4_000, &usdc, &0);
+    fund_buyer(&usdc_client, &buyer, &context.contract, 4_000);
+    client.buy_bundle(&buyer, &bundle_id, &4_000);
+    assert!(client.has_access(&buyer, &xlm_prompt));
+    assert!(client.has_access(&buyer, &usdc_prompt));
+    assert_eq!(usdc_client.balance(&context.contract), 4_000);
+}
+
+// ─── Oracle pricing ──────────────────────────────────────────────────────────
+
+/// $0.10 per token, in the mock oracle's 14 decimals.
+const TEN_CENTS: i128 = 10_000_000_000_000;
+
+fn setup_oracle<'a>(
+    env: &'a Env,
+    context: &PromptHashContext,
+    client: &PromptHashContractClient,
+) -> MockPriceOracleClient<'a> {
+    let oracle = env.register(MockPriceOracle, ());
+    let oracle_client = MockPriceOracleClient::new(env, &oracle);
+    oracle_client.set_price(&context.xlm, &TEN_CENTS, &env.ledger().timestamp());
+    client.set_price_oracle(&context.admin, &oracle, &(10 * 60));
+    oracle_client
+}
+
+#[test]
+fn test_reference_priced_listing_converts_at_oracle_rate() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "USD", 10_000, &context.xlm);
+
+    match client.try_set_reference_price(&creator, &prompt_id, &Some(500)) {
+        Err(Ok(Error::OracleNotConfigured)) => {}
+        other => panic!("expected OracleNotConfigured, got {:?}", other),
+    }
+    let oracle_client = setup_oracle(&env, &context, &client);
+    client.set_reference_price(&creator, &prompt_id, &Some(500));
+    assert_eq!(client.get_reference_price(&prompt_id), Some(500));
+
+    // $5.00 at $0.10 per token is 50 tokens of 18 decimals.
+    let amount = 50 * 10i128.pow(18);
+    assert_eq!(client.get_listing_price(&prompt_id, &context.xlm), amount);
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, amount);
+    client.buy_prompt_at_oracle_price(&buyer, &prompt_id, &None::<Address>, &amount, &100);
+    assert!(client.has_access(&buyer, &prompt_id));
+
+    let escrow = client.get_purchase_escrow(&prompt_id, &buyer).unwrap();
+    assert_eq!(escrow.amount, amount);
+    let conversion = match escrow.payout_plan.conversion {
+        PriceConversion::Oracle(conversion) => conversion,
+        other => panic!("expected an oracle conversion, got {:?}", other),
+    };
+    assert_eq!(conversion.reference_amount, 500);
+    assert_eq!(conversion.oracle_price, TEN_CENTS);
+    assert_eq!(conversion.oracle_decimals, 14);
+
+    // The token drops to $0.08: the same $5.00 now costs 62.5 tokens, past
+    // a 1% slippage bound on the earlier quote.
+    let late_buyer = Address::generate(&env);
+    oracle_client.set_price(
+        &context.xlm,
+        &(TEN_CENTS * 8 / 10),
+        &env.ledger().timestamp(),
+    );
+    fund_buyer(&xlm_client, &late_buyer, &context.contract, amount * 2);
+    match client.try_buy_prompt_at_oracle_price(
+        &late_buyer,
+        &prompt_id,
+        &None::<Address>,
+        &amount,
+        &100,
+    ) {
+        Err(Ok(Error::SlippageExceeded)) => {}
+        other => panic!("expected SlippageExceeded, got {:?}", other),
+    }
+    // A plain purchase must also cover the converted price.
+    match client.try_buy_prompt(
+        &late_buyer,
+        &prompt_id,
+        &None::<Address>,
+        &amount,
+        &None::<Bytes>,
+    ) {
+        Err(Ok(Error::InvalidPaymentAmount)) => {}
+        other => panic!("expected InvalidPaymentAmount, got {:?}", other),
+    }
+
+    client.set_reference_price(&creator, &prompt_id, &None);
+    assert_eq!(client.get_listing_price(&prompt_id, &context.xlm), 10_000);
+}
+
+#[test]
+fn test_oracle_pricing_rejects_stale_or_missing_prices() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "USD", 10_000, &context.xlm);
+
+    match client.try_set_price_oracle(&context.admin, &context.contract, &0) {
+        Err(Ok(Error::InvalidOracleConfig)) => {}
+        other => panic!("expected InvalidOracleConfig, got {:?}", other),
+    }
+    let oracle_client = setup_oracle(&env, &context, &client);
+    client.set_reference_price(&creator, &prompt_id, &Some(500));
+
+    env.ledger().with_mut(|ledger| {
+        ledger.timestamp += 11 * 60;
+    });
+    match client.try_buy_prompt_at_oracle_price(
+        &buyer,
+        &prompt_id,
+        &None::<Address>,
+        &i128::MAX,
+        &0,
+    ) {
+        Err(Ok(Error::StaleOraclePrice)) => {}
+        other => panic!("expected StaleOraclePrice, got {:?}", other),
+    }
+
+    // Nor can a price stamped in the future.
+    oracle_client.set_price(&context.xlm, &TEN_CENTS, &(env.ledger().timestamp() + 60));
+    match client.try_get_listing_price(&prompt_id, &context.xlm) {
+        Err(Ok(Error::OraclePriceUnavailable)) => {}
+        other => panic!("expected OraclePriceUnavailable, got {:?}", other),
+    }
+
+    // A listing in an asset the feed does not quote cannot be converted.
+    let usdc = env.register(FungibleTokenContract, (context.admin.clone(),));
+    let usdc_prompt = create_prompt(&env, &client, &creator, "USDC", 10_000, &usdc);
+    client.set_reference_price(&creator, &usdc_prompt, &Some(500));
+    oracle_client.set_price(&context.xlm, &TEN_CENTS, &env.ledger().timestamp());
+    match client.try_get_listing_price(&usdc_prompt, &usdc) {
+        Err(Ok(Error::OraclePriceUnavailable)) => {}
+        other => panic!("expected OraclePriceUnavailable, got {:?}", other),
+    }
+}
+
+// ─── Free and pay-what-you-want listings ─────────────────────────────────────
+
+#[test]
+fn test_free_prompt_claim_records_purchase_within_supply() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let prompt_id = create_prompt_with_supply(&env, &client, &creator, 1, 0);
+
+    match client.try_buy_prompt(&buyer, &prompt_id, &None::<Address>, &0, &None::<Bytes>) {
+        Err(Ok(Error::InvalidPaymentAmount)) => {}
+        other => panic!("expected InvalidPaymentAmount, got {:?}", other),
+    }
+
+    client.claim_free_prompt(&buyer, &prompt_id);
+    assert!(client.has_access(&buyer, &prompt_id));
+    assert_eq!(client.get_prompts_by_buyer(&buyer).len(), 1);
+    let settlement_id = client
+        .get_entitlement_settlement(&prompt_id, &buyer)
+        .unwrap();
+    let record = client.get_settlement(&settlement_id);
+    assert_eq!(record.kind, AcquisitionKind::FreeClaim);
+    assert_eq!(record.amount, 0);
+    assert_eq!(record.status, crate::types::SettlementStatus::Settled);
+    assert_eq!(client.get_asset_liability(&record.asset).pending, 0);
+
+    match client.try_claim_free_prompt(&Address::generate(&env), &prompt_id) {
+        Err(Ok(Error::MaxSupplyReached)) => {}
+        other => panic!("expected MaxSupplyReached, got {:?}", other),
+    }
+
+    let paid_id = create_prompt(&env, &client, &creator, "Paid", 10_000, &context.xlm);
+    match client.try_claim_free_prompt(&buyer, &paid_id) {
+        Err(Ok(Error::NotFreeListing)) => {}
+        other => panic!("expected NotFreeListing, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_pay_what_you_want_counts_overpayment_as_revenue() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Open", 10_000, &context.xlm);
+
+    client.set_pay_what_you_want(&creator, &prompt_id, &Some(2_000));
+    assert_eq!(client.get_pay_what_you_want(&prompt_id), Some(2_000));
+    assert_eq!(client.get_listing_price(&prompt_id, &context.xlm), 2_000);
+    match client.try_set_reference_price(&creator, &prompt_id, &Some(500)) {
+        Err(Ok(Error::InvalidPrice)) => {}
+        other => panic!("expected InvalidPrice, got {:?}", other),
+    }
+
+    fund_buyer(&xlm_client, &buyer, &context.contract, 6_000);
+    match client.try_buy_prompt(&buyer, &prompt_id, &None::<Address>, &1_000, &None::<Bytes>) {
+        Err(Ok(Error::InvalidPaymentAmount)) => {}
+        other => panic!("expected InvalidPaymentAmount, got {:?}", other),
+    }
+    client.buy_prompt(&buyer, &prompt_id, &None::<Address>, &6_000, &None::<Bytes>);
+    let escrow = client.get_purchase_escrow(&prompt_id, &buyer).unwrap();
+    assert_eq!(escrow.amount, 6_000);
+    assert_eq!(escrow.fee_amount + escrow.creator_amount, 6_000);
+
+    // A zero minimum makes the listing claimable for free as well.
+    client.set_pay_what_you_want(&creator, &prompt_id, &Some(0));
+    let claimer = Address::generate(&env);
+    client.claim_free_prompt(&claimer, &prompt_id);
+    assert!(client.has_access(&claimer, &prompt_id));
+
+    client.set_pay_what_you_want(&creator, &prompt_id, &None);
+    assert_eq!(client.get_listing_price(&prompt_id, &context.xlm), 10_000);
+}
+
+// ─── Discovery index maintenance ─────────────────────────────────────────────
+
+fn page_ids(env: &Env, prompts: &Vec<crate::types::Prompt>) -> Vec<u64> {
+    let mut ids = Vec::new(env);
+    for prompt in prompts.iter() {
+        ids.push_back(prompt.id);
+    }
+    ids
+}
+
+#[test]
+fn test_indexes_follow_status_category_and_expiry_changes() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let first = create_prompt(&env, &client, &creator, "First", 1_000, &context.xlm);
+    let second = create_prompt(&env, &client, &creator, "Second", 1_000, &context.xlm);
+    let active = |client: &PromptHashContractClient| {
+        page_ids(&env, &client.get_active_prompts_paginated(&None, &10).0)
+    };
+    assert_eq!(active(&client), Vec::from_array(&env, [first, second]));
+
+    client.set_prompt_sale_status(&creator, &first, &PromptSaleStatus::Paused);
+    assert_eq!(active(&client), Vec::from_array(&env, [second]));
+    client.admin_set_prompt_sale_status(&context.admin, &second, &PromptSaleStatus::Retired);
+    assert!(active(&client).is_empty());
+    client.set_prompt_sale_status(&creator, &first, &PromptSaleStatus::Active);
+    assert_eq!(active(&client), Vec::from_array(&env, [first]));
+
+    let software = String::from_str(&env, "Software Development");
+    let writing = String::from_str(&env, "Writing");
+    client.revise_listing(
+        &creator,
+        &first,
+        &String::from_str(&env, "First"),
+        &writing,
+        &String::from_str(&env, "preview"),
+        &String::from_str(&env, "https://example.com/image.png"),
+        &1_000,
+    );
+    let category = |name: &String| {
+        page_ids(
+            &env,
+            &client.get_prompts_by_category_page(name, &None, &10).0,
+        )
+    };
+    assert_eq!(category(&software), Vec::from_array(&env, [second]));
+    assert_eq!(category(&writing), Vec::from_array(&env, [first]));
+
+    // An expired listing drops out of the active page without a
+    // transaction, and extending it brings it back.
+    let now = env.ledger().timestamp();
+    client.extend_listing(&creator, &first, &(now + 100));
+    env.ledger().with_mut(|ledger| {
+        ledger.timestamp = now + 101;
+    });
+    assert!(active(&client).is_empty());
+    client.extend_listing(&creator, &first, &(now + 1_000));
+    assert_eq!(active(&client), Vec::from_array(&env, [first]));
+}
+
+#[test]
+fn test_rebuild_indexes_repairs_drifted_entries() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let mut ids = Vec::new(&env);
+    for _ in 0..15 {
+        ids.push_back(create_prompt(
+            &env,
+            &client,
+            &creator,
+            "Bulk",
+            1_000,
+            &context.xlm,
+        ));
+    }
+    let retired = ids.get(3).unwrap();
+    client.set_prompt_sale_status(&creator, &retired, &PromptSaleStatus::Retired);
+
+    // Simulate entries written before status and category changes were
+    // maintained: a stale active entry, and a listing still indexed under
+    // the category it was last marked with.
+    let moved = ids.get(5).unwrap();
+    let old_category = String::from_str(&env, "Old Category");
+    env.as_contract(&context.contract, || {
+        Storage::index_add(&env, &PromptIndex::Active, retired);
+        Storage::index_add(&env, &PromptIndex::Category(old_category.clone()), moved);
+        env.storage().persistent().set(
+            &crate::types::DataKey::IndexedCategory(moved),
+            &old_category,
+        );
+    });
+
+    match client.try_rebuild_indexes(&Address::generate(&env), &None) {
+        Err(Ok(Error::Unauthorized)) => {}
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+    let next = client.rebuild_indexes(&context.admin, &None);
+    assert_eq!(next, Some(MAX_INDEX_REBUILD_BATCH));
+    let next = client.rebuild_indexes(&context.admin, &next);
+    assert_eq!(next, Some(2 * MAX_INDEX_REBUILD_BATCH));
+    assert_eq!(client.rebuild_indexes(&context.admin, &next), None);
+
+    let stored = env.as_contract(&context.contract, || {
+        Storage::index_ids(&env, &PromptIndex::Active)
+    });
+    assert_eq!(stored.len(), 14);
+    assert!(!stored.contains(retired));
+    let stale = env.as_contract(&context.contract, || {
+        Storage::index_ids(&env, &PromptIndex::Category(old_category.clone()))
+    });
+    assert_eq!(stale.len(), 0);
+}
+
+#[test]
+fn test_update_tags_moves_listing_between_tag_indexes() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let prompt_id = create_prompt(&env, &client, &creator, "Tagged", 1_000, &context.xlm);
+    let rust = String::from_str(&env, "rust");
+    let wasm = String::from_str(&env, "wasm");
+    let agents = String::from_str(&env, "agents");
+    let tagged = |tag: &String| {
+        page_ids(
+            &env,
+            &client.get_prompts_by_tag_paginated(tag, &None, &10).0,
+        )
+    };
+
+    client.update_tags(
+        &creator,
+        &prompt_id,
+        &Vec::from_array(&env, [rust.clone(), wasm.clone()]),
+    );
+    assert_eq!(tagged(&rust), Vec::from_array(&env, [prompt_id]));
+    assert_eq!(tagged(&wasm), Vec::from_array(&env, [prompt_id]));
+
+    client.update_tags(
+        &creator,
+        &prompt_id,
+        &Vec::from_array(&env, [wasm.clone(), agents.clone()]),
+    );
+    assert!(tagged(&rust).is_empty());
+    assert_eq!(tagged(&wasm), Vec::from_array(&env, [prompt_id]));
+    assert_eq!(tagged(&agents), Vec::from_array(&env, [prompt_id]));
+    assert_eq!(
+        client.get_prompt(&prompt_id).tags,
+        Vec::from_array(&env, [wasm.clone(), agents.clone()])
+    );
+
+    match client.try_update_tags(
+        &creator,
+        &prompt_id,
+        &Vec::from_array(&env, [wasm.clone(), wasm]),
+    ) {
+        Err(Ok(Error::InvalidCategoryLength)) => {}
+        other => panic!("expected InvalidCategoryLength, got {:?}", other),
+    }
+    match client.try_update_tags(&Address::generate(&env), &prompt_id, &Vec::new(&env)) {
+        Err(Ok(Error::Unauthorized)) => {}
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+}
+
+// ─── Sharded indexes ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_creator_pages_span_index_buckets() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let total = INDEX_BUCKET_SIZE + 20;
+    let mut ids = Vec::new(&env);
+    for _ in 0..total {
+        ids.push_back(create_prompt(
+            &env,
+            &client,
+            &creator,
+            "Bulk",
+            1_000,
+            &context.xlm,
+        ));
+    }
+
+    let mut seen = Vec::new(&env);
+    let mut cursor = None;
+    loop {
+        let (page, next) = client.get_prompts_by_creator_paginated(&creator, &cursor, &50);
+        if page.is_empty() {
+            break;
+        }
+        seen.append(&page_ids(&env, &page));
+        cursor = next;
+    }
+    assert_eq!(seen, ids);
+
+    // Removing a member leaves the rest of its bucket and later buckets in
+    // place, so a cursor taken before the removal still resumes correctly.
+    let (first_page, cursor) = client.get_active_prompts_paginated(&None, &50);
+    assert_eq!(first_page.len(), 50);
+    let retired = ids.get(INDEX_BUCKET_SIZE - 1).unwrap();
+    client.set_prompt_sale_status(&creator, &retired, &PromptSaleStatus::Retired);
+    let (second_page, _) = client.get_active_prompts_paginated(&cursor, &50);
+    assert_eq!(second_page.len(), 50);
+    assert_eq!(second_page.get(0).unwrap().id, ids.get(50).unwrap());
+    assert!(!page_ids(&env, &second_page).contains(retired));
+    assert_eq!(
+        second_page.get(49).unwrap().id,
+        ids.get(INDEX_BUCKET_SIZE).unwrap()
+    );
+}
+
+#[test]
+fn test_migrate_index_moves_legacy_vector_into_buckets() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let mut ids = Vec::new(&env);
+    for _ in 0..15 {
+        ids.push_back(create_prompt(
+            &env,
+            &client,
+            &creator,
+            "Legacy",
+            1_000,
+            &context.xlm,
+        ));
+    }
+
+    // A buyer index written under the pre-sharding single-vector layout.
+    let legacy_key = crate::types::DataKey::BuyerPrompts(buyer.clone());
+    env.as_contract(&context.contract, || {
+        env.storage().persistent().set(&legacy_key, &ids);
+    });
+    // Reads see the unmigrated entries.
+    assert_eq!(page_ids(&env, &client.get_prompts_by_buyer(&buyer)), ids);
+
+    let index = PromptIndex::Buyer(buyer.clone());
+    match client.try_migrate_index(&Address::generate(&env), &index) {
+        Err(Ok(Error::Unauthorized)) => {}
+        other => panic!("expected Unauthorized, got {:?}", other),
+    }
+    assert_eq!(
+        client.migrate_index(&context.admin, &index),
+        Some(15 - MAX_INDEX_REBUILD_BATCH as u32)
+    );
+    assert_eq!(page_ids(&env, &client.get_prompts_by_buyer(&buyer)), ids);
+    assert_eq!(
+        client.migrate_index(&context.admin, &index),
+        Some(15 - 2 * MAX_INDEX_REBUILD_BATCH as u32)
+    );
+    assert_eq!(client.migrate_index(&context.admin, &index), None);
+
+    assert_eq!(page_ids(&env, &client.get_prompts_by_buyer(&buyer)), ids);
+    let (page, _) = client.get_prompts_by_buyer_paginated(&buyer, &None, &5);
+    assert_eq!(page_ids(&env, &page), ids.slice(0..5));
+    assert!(!env.as_contract(&context.contract, || {
+        env.storage().persistent().has(&legacy_key)
+    }));
+
+    // Re-running against the removed legacy entry is a no-op.
+    assert_eq!(client.migrate_index(&context.admin, &index), None);
+    assert_eq!(client.get_prompts_by_buyer(&buyer).len(), 15);
+}
+
+#[test]
+fn test_legacy_index_migrates_on_write_and_pages_across_the_remainder() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let mut ids = Vec::new(&env);
+    for _ in 0..6 {
+        ids.push_back(create_prompt(
+            &env,
+            &client,
+            &creator,
+            "Legacy",
+            1_000,
+            &context.xlm,
+        ));
+    }
+    let legacy_key = crate::types::DataKey::BuyerPrompts(buyer.clone());
+    env.as_contract(&context.contract, || {
+        env.storage().persistent().set(&legacy_key, &ids);
+    });
+    let legacy_len = || {
+        env.as_contract(&context.contract, || {
+            env.storage()
+                .persistent()
+                .get::<_, Vec<u64>>(&legacy_key)
+                .map(|legacy| legacy.len())
+        })
+    };
+
+    // A cursor issued inside the legacy vector.
+    let (first, cursor) = client.get_prompts_by_buyer_paginated(&buyer, &None, &2);
+    assert_eq!(page_ids(&env, &first), ids.slice(0..2));
+
+    // Buying writes the buyer index, which migrates a step and appends the
+    // new member behind the entries still waiting.
+    let fresh = create_prompt(&env, &client, &creator, "Fresh", 1_000, &context.xlm);
+    fund_buyer(&xlm_client, &buyer, &context.contract, 1_000);
+    client.buy_prompt(&buyer, &fresh, &None::<Address>, &1_000, &None::<Bytes>);
+    ids.push_back(fresh);
+    assert_eq!(legacy_len(), Some(7 - INDEX_MIGRATION_STEP));
+    assert_eq!(page_ids(&env, &client.get_prompts_by_buyer(&buyer)), ids);
+
+    // The cursor follows its prompt into the buckets.
+    let (second, cursor) = client.get_prompts_by_buyer_paginated(&buyer, &cursor, &3);
+    assert_eq!(page_ids(&env, &second), ids.slice(2..5));
+    let (third, cursor) = client.get_prompts_by_buyer_paginated(&buyer, &cursor, &3);
+    assert_eq!(page_ids(&env, &third), ids.slice(5..7));
+    assert!(client
+        .get_prompts_by_buyer_paginated(&buyer, &cursor, &3)
+        .0
+        .is_empty());
+
+    // Removing a member migrates another step and drops it wherever it is.
+    let index = PromptIndex::Buyer(buyer.clone());
+    env.as_contract(&context.contract, || {
+        Storage::index_remove(&env, &index, ids.get(5).unwrap());
+    });
+    ids.remove(5);
+    assert_eq!(legacy_len(), Some(7 - 2 * INDEX_MIGRATION_STEP - 1));
+    assert_eq!(page_ids(&env, &client.get_prompts_by_buyer(&buyer)), ids);
+
+    assert_eq!(client.migrate_index(&context.admin, &index), None);
+    assert_eq!(legacy_len(), None);
+    assert_eq!(page_ids(&env, &client.get_prompts_by_buyer(&buyer)), ids);
+}
+
+#[test]
+fn test_page_cursor_is_bound_to_its_index_and_survives_removals() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let mut ids = Vec::new(&env);
+    for _ in 0..6 {
+        ids.push_back(create_prompt(
+            &env,
+            &client,
+            &creator,
+            "Paged",
+            1_000,
+            &context.xlm,
+        ));
+    }
+    let software = String::from_str(&env, "Software Development");
+    let writing = String::from_str(&env, "Writing");
+
+    let (first, cursor) = client.get_prompts_by_category_page(&software, &None, &3);
+    assert_eq!(page_ids(&env, &first), ids.slice(0..3));
+
+    // A cursor only resumes the index it was issued for.
+    match client.try_get_prompts_by_category_page(&writing, &cursor, &3) {
+        Err(Ok(Error::InvalidCursor)) => {}
+        other => panic!("expected InvalidCursor, got {:?}", other),
+    }
+    match client.try_get_all_prompts_paginated(&cursor, &3) {
+        Err(Ok(Error::InvalidCursor)) => {}
+        other => panic!("expected InvalidCursor, got {:?}", other),
+    }
+    let other_creator = Address::generate(&env);
+    let (_, creator_cursor) = client.get_prompts_by_creator_paginated(&creator, &None, &1);
+    match client.try_get_prompts_by_creator_paginated(&other_creator, &creator_cursor, &1) {
+        Err(Ok(Error::InvalidCursor)) => {}
+        other => panic!("expected InvalidCursor, got {:?}", other),
+    }
+
+    // Retiring a listing ahead of the cursor shifts the cursor's offset in
+    // the active bucket; the page still resumes right after its prompt.
+    let (active_first, active_cursor) = client.get_active_prompts_paginated(&None, &3);
+    assert_eq!(page_ids(&env, &active_first), ids.slice(0..3));
+    client.set_prompt_sale_status(&creator, &ids.get(0).unwrap(), &PromptSaleStatus::Retired);
+    let (active_rest, _) = client.get_active_prompts_paginated(&active_cursor, &10);
+    assert_eq!(page_ids(&env, &active_rest), ids.slice(3..6));
+
+    let (second, _) = client.get_prompts_by_category_page(&software, &cursor, &10);
+    assert_eq!(page_ids(&env, &second), ids.slice(3..6));
+}
+
+// ─── Catalog queries ─────────────────────────────────────────────────────────
+
+fn any_listing(env: &Env) -> CatalogFilter {
+    CatalogFilter {
+        category: None,
+        tags: Vec::new(env),
+        tag_match: TagMatch::All,
+        asset: None,
+        min_price: None,
+        max_price: None,
+        creator: None,
+        listing: ListingFilter::Any,
+    }
+}
+
+#[test]
+fn test_query_prompts_filters_and_sorts_by_price() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let mut ids = Vec::new(&env);
+    for price in [500, 3_000, 1_000, 2_000] {
+        ids.push_back(create_prompt(
+            &env,
+            &client,
+            &creator,
+            "Query",
+            price,
+            &context.xlm,
+        ));
+    }
+    let id = |i: u32| ids.get(i).unwrap();
+    let rust = String::from_str(&env, "rust");
+    let wasm = String::from_str(&env, "wasm");
+    client.update_tags(&creator, &id(0), &Vec::from_array(&env, [rust.clone()]));
+    client.update_tags(
+        &creator,
+        &id(2),
+        &Vec::from_array(&env, [rust.clone(), wasm.clone()]),
+    );
+    client.update_tags(&creator, &id(3), &Vec::from_array(&env, [wasm.clone()]));
+
+    // Repricing moves the listing in the price index.
+    client.update_prompt_price(&creator, &id(1), &100);
+
+    let query = |filter: &CatalogFilter, sort: CatalogSort| {
+        let (page, next) = client.query_prompts(filter, &sort, &None, &10);
+        assert_eq!(next, None);
+        page_ids(&env, &page)
+    };
+    let all = any_listing(&env);
+    assert_eq!(
+        query(&all, CatalogSort::PriceAsc),
+        Vec::from_array(&env, [id(1), id(0), id(2), id(3)])
+    );
+    assert_eq!(
+        query(&all, CatalogSort::PriceDesc),
+        Vec::from_array(&env, [id(3), id(2), id(0), id(1)])
+    );
+    assert_eq!(
+        query(&all, CatalogSort::Newest),
+        Vec::from_array(&env, [id(3), id(2), id(1), id(0)])
+    );
+
+    let mut in_range = any_listing(&env);
+    in_range.min_price = Some(600);
+    in_range.max_price = Some(2_000);
+    assert_eq!(
+        query(&in_range, CatalogSort::PriceAsc),
+        Vec::from_array(&env, [id(2), id(3)])
+    );
+
+    let mut both_tags = any_listing(&env);
+    both_tags.tags = Vec::from_array(&env, [rust.clone(), wasm.clone()]);
+    assert_eq!(
+        query(&both_tags, CatalogSort::Newest),
+        Vec::from_array(&env, [id(2)])
+    );
+    let mut either_tag = both_tags.clone();
+    either_tag.tag_match = TagMatch::Any;
+    assert_eq!(
+        query(&either_tag, CatalogSort::Newest),
+        Vec::from_array(&env, [id(3), id(2), id(0)])
+    );
+
+    let mut other_creator = any_listing(&env);
+    other_creator.creator = Some(Address::generate(&env));
+    assert!(query(&other_creator, CatalogSort::PriceAsc).is_empty());
+
+    let mut inverted = any_listing(&env);
+    inverted.min_price = Some(2_000);
+    inverted.max_price = Some(1_000);
+    match client.try_query_prompts(&inverted, &CatalogSort::Newest, &None, &10) {
+        Err(Ok(Error::InvalidCatalogFilter)) => {}
+        other => panic!("expected InvalidCatalogFilter, got {:?}", other),
+    }
+
+    // A cursor only resumes the query it was issued for.
+    let (_, cursor) = client.query_prompts(&all, &CatalogSort::PriceAsc, &None, &1);
+    match client.try_query_prompts(&all, &CatalogSort::PriceDesc, &cursor, &1) {
+        Err(Ok(Error::InvalidCursor)) => {}
+        other => panic!("expected InvalidCursor, got {:?}", other),
+    }
+    match client.try_query_prompts(&in_range, &CatalogSort::PriceAsc, &cursor, &1) {
+        Err(Ok(Error::InvalidCursor)) => {}
+        other => panic!("expected InvalidCursor, got {:?}", other),
+    }
+
+    // Price sorts only order primary-asset prices.
+    let mut in_xlm = any_listing(&env);
+    in_xlm.asset = Some(context.xlm.clone());
+    assert_eq!(query(&in_xlm, CatalogSort::Newest).len(), 4);
+    for sort in [CatalogSort::PriceAsc, CatalogSort::PriceDesc] {
+        match client.try_query_prompts(&in_xlm, &sort, &None, &10) {
+            Err(Ok(Error::InvalidCatalogFilter)) => {}
+            other => panic!("expected InvalidCatalogFilter, got {:?}", other),
+        }
+    }
+
+    // A pay-what-you-want listing has no fixed price to sort by.
+    client.set_pay_what_you_want(&creator, &id(0), &Some(200));
+    assert_eq!(
+        query(&all, CatalogSort::PriceAsc),
+        Vec::from_array(&env, [id(1), id(2), id(3)])
+    );
+    assert_eq!(query(&all, CatalogSort::Newest).len(), 4);
+    client.set_pay_what_you_want(&creator, &id(0), &None);
+    assert_eq!(
+        query(&all, CatalogSort::PriceAsc),
+        Vec::from_array(&env, [id(1), id(0), id(2), id(3)])
+    );
+}
+
+#[test]
+fn test_query_prompts_best_selling_and_expiry_filters() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let mut ids = Vec::new(&env);
+    for _ in 0..3 {
+        ids.push_back(create_prompt(
+            &env,
+            &client,
+            &creator,
+            "Seller",
+            1_000,
+            &context.xlm,
+        ));
+    }
+    let id = |i: u32| ids.get(i).unwrap();
+    for (buyer_count, prompt) in [(2, id(1)), (1, id(2))] {
+        for _ in 0..buyer_count {
+            let buyer = Address::generate(&env);
+            fund_buyer(&xlm_client, &buyer, &context.contract, 1_000);
+            client.buy_prompt(
+                &buyer,
+                &prompt,
+                &None::<Address>,
+                &1_000i128,
+                &None::<Bytes>,
+            );
+        }
+    }
+
+    // Page one prompt at a time through the sales index.
+    let all = any_listing(&env);
+    let mut seen = Vec::new(&env);
+    let mut cursor = None;
+    loop {
+        let (page, next) = client.query_prompts(&all, &CatalogSort::BestSelling, &cursor, &1);
+        seen.append(&page_ids(&env, &page));
+        if next.is_none() {
+            break;
+        }
+        cursor = next;
+    }
+    assert_eq!(seen, Vec::from_array(&env, [id(1), id(2), id(0)]));
+
+    let now = env.ledger().timestamp();
+    client.extend_listing(&creator, &id(0), &(now + 100));
+    env.ledger().with_mut(|ledger| {
+        ledger.timestamp = now + 101;
+    });
+    let mut expired = any_listing(&env);
+    expired.listing = ListingFilter::Expired;
+    let (page, _) = client.query_prompts(&expired, &CatalogSort::Newest, &None, &10);
+    assert_eq!(page_ids(&env, &page), Vec::from_array(&env, [id(0)]));
+    let mut listed = any_listing(&env);
+    listed.listing = ListingFilter::Listed;
+    let (page, _) = client.query_prompts(&listed, &CatalogSort::BestSelling, &None, &10);
+    assert_eq!(page_ids(&env, &page), Vec::from_array(&env, [id(1), id(2)]));
+}
+
+#[test]
+fn test_query_cursor_survives_its_prompt_being_refiled() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let mut ids = Vec::new(&env);
+    for price in [10, 500, 900, 2_000] {
+        ids.push_back(create_prompt(
+            &env,
+            &client,
+            &creator,
+            "Refiled",
+            price,
+            &context.xlm,
+        ));
+    }
+    let id = |i: u32| ids.get(i).unwrap();
+    let all = any_listing(&env);
+    let (first, cursor) = client.query_prompts(&all, &CatalogSort::PriceAsc, &None, &1);
+    assert_eq!(page_ids(&env, &first), Vec::from_array(&env, [id(0)]));
+
+    // Repricing the cursor's own prompt past the rest must not skip the
+    // listings priced in between; it is met again at its new price.
+    client.update_prompt_price(&creator, &id(0), &1_000);
+    let (rest, next) = client.query_prompts(&all, &CatalogSort::PriceAsc, &cursor, &10);
+    assert_eq!(next, None);
+    assert_eq!(
+        page_ids(&env, &rest),
+        Vec::from_array(&env, [id(1), id(2), id(0), id(3)])
+    );
+
+    // Newest orders by prompt ID, even for a listing relisted after its
+    // successors.
+    client.set_prompt_sale_status(&creator, &id(1), &PromptSaleStatus::Paused);
+    client.set_prompt_sale_status(&creator, &id(1), &PromptSaleStatus::Active);
+    let mut listed = any_listing(&env);
+    listed.listing = ListingFilter::Listed;
+    let mut seen = Vec::new(&env);
+    let mut cursor = None;
+    loop {
+        let (page, next) = client.query_prompts(&listed, &CatalogSort::Newest, &cursor, &1);
+        seen.append(&page_ids(&env, &page));
+        if next.is_none() {
+            break;
+        }
+        cursor = next;
+    }
+    assert_eq!(seen, Vec::from_array(&env, [id(3), id(2), id(1), id(0)]));
+}
+
+#[test]
+fn test_query_prompts_pages_across_split_sort_buckets() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let creator = Address::generate(&env);
+    let mut ids = Vec::new(&env);
+    for _ in 0..INDEX_BUCKET_SIZE + 20 {
+        ids.push_back(create_prompt(
+            &env,
+            &client,
+            &creator,
+            "Bulk",
+            1_000,
+            &context.xlm,
+        ));
+    }
+    // Reprice one listing already paged past; it must not reappear.
+    let all = any_listing(&env);
+    let (first, cursor) = client.query_prompts(&all, &CatalogSort::PriceAsc, &None, &50);
+    assert_eq!(page_ids(&env, &first), ids.slice(0..50));
+    client.update_prompt_price(&creator, &ids.get(0).unwrap(), &900);
+
+    let mut seen = page_ids(&env, &first);
+    let mut cursor = cursor;
+    while let Some(next) = cursor {
+        let (page, after) = client.query_prompts(&all, &CatalogSort::PriceAsc, &Some(next), &50);
+        seen.append(&page_ids(&env, &page));
+        cursor = after;
+    }
+    assert_eq!(seen, ids);
+}
+
+#[test]
+fn test_sale_rewrites_sort_directory_only_when_a_head_moves() {
+    let env: Env = Default::default();
+    let context = setup(&env);
+    let client = PromptHashContractClient::new(&env, &context.contract);
+    let xlm_client = token::StellarAssetClient::new(&env, &context.xlm);
+    let creator = Address::generate(&env);
+    let mut ids = Vec::new(&env);
+    for _ in 0..INDEX_BUCKET_SIZE + 20 {
+        ids.push_back(create_prompt(
+            &env,
+            &client,
+            &creator,
+            "Bulk",
+            1_000,
+            &context.xlm,
+        ));
+    }
+    let buy = |prompt_id: u64| {
+        let buyer = Address::generate(&env);
+        fund_buyer(&xlm_client, &buyer, &context.contract, 1_000);
+        client.buy_prompt(
+            &buyer,
+            &prompt_id,
+            &None::<Address>,
+            &1_000i128,
+            &None::<Bytes>,
+        );
+        env.cost_estimate().resources().write_entries
+    };
+
+    // A listing inside its sales bucket moves without touching the bucket
+    // heads; the first listing of the index moves its bucket's head, which
+    // is also its page's first entry.
+    let inside = buy(ids.get(5).unwrap());
+    let head = buy(ids.get(0).unwrap());
+    assert_eq!(head, inside + 2);
+
+    let all = any_listing(&env);
+    let (page, _) = client.query_prompts(&all, &CatalogSort::BestSelling, &None, &2);
+    assert_eq!(
+        page_ids(&env, &page),
+        Vec::from_array(&env, [ids.get(5).unwrap(), ids.get(0).unwrap()])
+    );
+}
@@ -53,6 +53,13 @@ pub fn get_ttl_for_key(key: &DataKey) -> u32 {
         DataKey::TagPrompts(_) => ONE_YEAR,
         DataKey::ActivePrompts => ONE_YEAR,
         DataKey::AllPrompts => ONE_YEAR,
+        DataKey::IndexBucketCount(_) => ONE_YEAR,
+        DataKey::IndexBucket(_, _) => ONE_YEAR,
+        DataKey::IndexEntry(_, _) => ONE_YEAR,
+        DataKey::SortIndex(_) => ONE_YEAR,
+        DataKey::SortPage(_, _) => ONE_YEAR,
+        DataKey::SortBucket(_, _) => ONE_YEAR,
+        DataKey::SortSlot(_, _) => ONE_YEAR,
 
         // Buyer tracking
         DataKey::BuyerPrompts(_) => ONE_YEAR,
@@ -109,6 +109,115 @@ pub enum Error {
     MaxSupplyBelowCommitted = 84,
     DisputeWindowClosed = 85,
     DisputeWindowNotElapsed = 86,
+
+    // Signed resale orders, continued (#568).
+    ResaleOrderFilled = 87,
+    /// A resale royalty below the listing's royalty or above 100%, or a
+    /// listing royalty above the platform maximum.
+    InvalidRoyaltyBps = 88,
+
+    // License tokens.
+    LicenseTokenNotFound = 89,
+    /// Token mode can only change before a listing has any licenses.
+    LicenseTokenModeLocked = 90,
+    /// Caller is neither the token owner, its approved spender, nor an operator.
+    LicenseTokenNotApproved = 91,
+    InvalidLicenseApproval = 92,
+
+    // Auction sale modes.
+    AuctionNotFound = 93,
+    AuctionAlreadyOpen = 94,
+    AuctionNotOpen = 95,
+    AuctionEnded = 96,
+    AuctionNotEnded = 97,
+    /// Bid is below the reserve or the minimum increment over the high bid.
+    BidTooLow = 98,
+    AuctionHasBids = 99,
+    /// Fixed-price acquisition attempted while the listing is at auction.
+    ListingInAuction = 100,
+    InvalidAuctionConfig = 101,
+    /// Auctions are only for listings with a bounded `max_supply`.
+    AuctionRequiresLimitedSupply = 102,
+    /// Current Dutch auction price exceeds the buyer's stated maximum.
+    AuctionPriceAboveMax = 103,
+    AuctionKindMismatch = 104,
+
+    // Access pass auto-renewal.
+    PassSubscriptionNotFound = 105,
+    /// The current pass period has not yet entered its renewal window.
+    RenewalWindowNotOpen = 106,
+    InvalidSubscription = 107,
+
+    // Per-buyer key delivery.
+    EncryptionKeyNotRegistered = 108,
+    WrappedKeyNotDelivered = 109,
+    /// The buyer registered a key before purchase and the creator has not
+    /// yet delivered a wrapped key to it, so the escrow cannot be released.
+    KeyDeliveryPending = 110,
+    // Per-listing lease terms.
+    LeaseDisabled = 111,
+    InvalidLeaseTerms = 112,
+    /// The requested duration is outside the listing's lease bounds.
+    InvalidLeaseDuration = 113,
+    LeaseNotFound = 114,
+    /// The listing forbids resale and transfer of its licenses.
+    ResaleNotAllowed = 115,
+    // Dispute arbitration.
+    ArbiterAlreadyRegistered = 116,
+    ArbiterNotFound = 117,
+    /// Too few eligible arbiters are registered to seat a panel.
+    NotEnoughArbiters = 118,
+    /// The dispute is before an arbitration panel and cannot be resolved
+    /// by the owner until the panel's voting deadline has passed.
+    DisputeUnderArbitration = 119,
+    NotOnArbitrationPanel = 120,
+    ArbiterAlreadyVoted = 121,
+    ArbitrationNotFound = 122,
+    ArbitrationNotDecided = 123,
+    AppealWindowClosed = 124,
+    AppealWindowOpen = 125,
+    AppealAlreadyUsed = 126,
+    TooMuchEvidence = 127,
+    ArbiterRegistryFull = 128,
+    /// A refund must be positive (zero rejects a dispute) and at most the
+    /// escrow's amount net of earlier refunds.
+    InvalidRefundAmount = 129,
+    /// A dispute window is out of bounds: product policies cover leases,
+    /// bundles and passes only, product and listing windows must sit inside
+    /// the governed bounds, and nothing may exceed `MAX_DISPUTE_WINDOW_SECS`.
+    InvalidDisputeWindow = 130,
+    /// A dispute bond is a share of the escrow in basis points, at most
+    /// `MAX_BPS`.
+    InvalidDisputeBond = 131,
+    /// The caller has no claimable balance in the requested asset.
+    NothingToWithdraw = 132,
+    /// Extra listing prices must be positive, name each asset at most once
+    /// (never the primary asset) and stay within `MAX_PRICING_ASSETS`.
+    InvalidPricing = 133,
+    /// The oracle's maximum price age must be positive and at most
+    /// `MAX_ORACLE_PRICE_AGE_SECS`.
+    InvalidOracleConfig = 134,
+    /// A reference-priced listing needs a configured price oracle.
+    OracleNotConfigured = 135,
+    /// The oracle has no usable (positive) price for the payment asset.
+    OraclePriceUnavailable = 136,
+    /// The oracle's latest price is older than the configured maximum age.
+    StaleOraclePrice = 137,
+    /// The converted price moved past the buyer's slippage bound.
+    SlippageExceeded = 138,
+    /// Only listings whose price (or pay-what-you-want minimum) is zero can
+    /// be claimed without payment.
+    NotFreeListing = 139,
+    /// A catalog query filter is malformed, e.g. a price range whose
+    /// minimum exceeds its maximum, or an asset filter on a price sort.
+    InvalidCatalogFilter = 140,
+    /// Leased and expired licenses cannot be resold or transferred.
+    LicenseNotTransferable = 141,
+    /// A resale order can only be posted with `ResaleOrderStatus::Open`.
+    ResaleOrderNotOpen = 142,
+    /// License tokens only move through a priced resale, which pays the
+    /// creator's royalty; plain token transfers are refused.
+    UnpricedLicenseTransfer = 143,
 }
 
 #[contracttype]
@@ -137,6 +246,20 @@ pub enum InstanceDataKey {
     /// Ledger delay that must elapse between proposing and executing a
     /// high-risk governance action (#569).
     GovernanceDelayLedgers,
+    /// Monotonic counter backing license token IDs.
+    LicenseTokenCounter,
+    /// Registry position where the next arbitration panel starts.
+    ArbiterCursor,
+    /// Dispute window, in seconds, applied to one kind of product sale.
+    DisputeWindowPolicy(AcquisitionKind),
+    /// Default dispute bond, in basis points of the disputed escrow.
+    DisputeBondBps,
+    /// Governed default and bounds for per-listing dispute windows.
+    DisputeWindowConfig,
+    /// SEP-40 price feed used to convert reference-priced listings.
+    PriceOracle,
+    /// Monotonic counter seeding nonces for legacy governance setters.
+    LegacyGovernanceCounter,
 }
 
 /// Persistent storage keys — per-item records stored in
@@ -146,6 +269,8 @@ pub enum InstanceDataKey {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum DataKey {
     Prompt(u64),
+    // Legacy single-vector indexes, superseded by the sharded
+    // `IndexBucket` layout and drained into it as each index is written.
     CreatorPrompts(Address),
     BuyerPrompts(Address),
     CategoryPrompts(String), // Index: category → Vec<prompt_ids>
@@ -153,7 +278,9 @@ pub enum DataKey {
     ActivePrompts,           // Index: all active → Vec<prompt_ids>
     AllPrompts,              // Index: all → Vec<prompt_ids>
     Purchase(u64, Address),
-    PurchaseEscrow(u64, Address), // Settlement tracking for refunds (#420)
+    /// Legacy escrow keyed by buyer (#420). Superseded by `SettlementEscrow`
+    /// (#567) and migrated lazily on first lookup.
+    PurchaseEscrow(u64, Address),
     VoucherKey(u64, BytesN<32>),
     /// Nonce consumed for a signed discount authorization.
     /// Key: (prompt_id, nonce_hash) where nonce_hash = sha256(nonce_bytes)
@@ -161,6 +288,7 @@ pub enum DataKey {
     /// Snapshot of a listing taken before a revision (#226).
     /// Key: (prompt_id, revision_number_before_change)
     ListingRevision(u64, u32),
+    /// Legacy dispute keyed by buyer; migrated with its escrow (#567).
     PurchaseDispute(u64, Address),
     Bundle(u128),
     BundleCounter,
@@ -186,6 +314,12 @@ pub enum DataKey {
     /// so settling or refunding one acquisition cannot mutate another.
     SettlementEscrow(u128),
     SettlementDispute(u128),
+    /// Every settlement ID allocated to a buyer, across all acquisition
+    /// kinds, for receipt history (#567): (buyer, page number). Pages hold
+    /// up to `INDEX_BUCKET_SIZE` IDs in allocation order.
+    BuyerSettlementPage(Address, u32),
+    /// Number of settlement history pages allocated to a buyer.
+    BuyerSettlementPageCount(Address),
 
     /// A seller-signed resale order, keyed by its order hash (#568).
     ResaleOrder(BytesN<32>),
@@ -194,12 +328,210 @@ pub enum DataKey {
 
     /// A pending two-phase governance action, keyed by proposal hash (#569).
     GovernanceProposal(BytesN<32>),
+    /// Hashes of every proposal still pending, for watchers to enumerate.
+    PendingGovernanceProposals,
+    /// Nonce consumed by a governance proposal.
+    GovernanceNonce(BytesN<32>),
 
     /// Aggregate per-asset escrow liability, keyed by SAC asset address (#570).
     AssetLiability(Address),
     /// Marks that `migrate_asset_liability` has already backfilled the given
     /// escrow into `AssetLiability`, so a repeat call is a safe no-op (#570).
     EscrowLiabilityMigrated(u64, Address),
+
+    /// Listings whose grants mint a transferable license token.
+    LicenseTokenMode(u64),
+    /// A minted license token, keyed by token ID.
+    LicenseToken(u32),
+    /// Token backing a purchase: (prompt_id, owner) -> token_id.
+    PurchaseLicenseToken(u64, Address),
+    /// Number of license tokens held by an account.
+    LicenseTokenBalance(Address),
+    /// Single-token spender approval.
+    LicenseTokenApproval(u32),
+    /// Operator approval over all of an owner's tokens: (owner, operator).
+    LicenseTokenOperator(Address, Address),
+
+    /// The current (or most recent) auction for a listing.
+    Auction(u64),
+
+    /// Auto-renewal mandate for an access pass: (buyer, pass_id).
+    PassSubscription(Address, u128),
+
+    /// Public key a buyer registered for per-buyer key delivery.
+    BuyerEncryptionKey(Address),
+    /// Account allowed to deliver wrapped keys on a creator's behalf.
+    KeyDeliveryAgent(u64),
+    /// Content key wrapped to one buyer's registered public key:
+    /// (prompt_id, buyer).
+    BuyerWrappedKey(u64, Address),
+    /// Which content revision buyers of a listing may read. Absent means
+    /// `ContentAccessPolicy::LatestRevision`.
+    ContentAccessPolicy(u64),
+    /// Creator-configured lease terms. Absent means the legacy flat lease
+    /// price of `LEASE_PRICE_BPS` of the purchase price, for any duration.
+    LeaseTerms(u64),
+    /// The lessee's current lease term: (prompt_id, lessee).
+    Lease(u64, Address),
+    /// Seconds a lease renewal settlement added to its lease, keyed by
+    /// settlement ID, so refunding it takes back only that extension.
+    LeaseRenewal(u128),
+    /// Registered dispute arbiters, in assignment order.
+    Arbiters,
+    /// The arbitration panel seated for a disputed settlement.
+    Arbitration(u128),
+    /// Earlier panels on a settlement, oldest first: the first-round panel
+    /// once appealed, and every panel of a dispute since reopened.
+    ArbitrationHistory(u128),
+    /// Evidence hashes submitted by either side of a disputed settlement.
+    DisputeEvidence(u128),
+    /// Owner-set dispute bond for one buyer, overriding the default.
+    BuyerDisputeBond(Address),
+    /// Running dispute outcomes for one buyer.
+    BuyerDisputeStats(Address),
+    /// Creator-selected dispute window for a listing, in seconds.
+    ListingDisputeWindow(u64),
+    /// Payouts credited to a recipient and not yet withdrawn:
+    /// (recipient, asset).
+    ClaimableBalance(Address, Address),
+    /// Listing price in reference units (USD cents), converted into the
+    /// listing's asset through the price oracle at purchase.
+    ReferencePrice(u64),
+    /// Minimum payment for a pay-what-you-want listing.
+    PayWhatYouWant(u64),
+    /// Number of buckets allocated to a prompt index.
+    IndexBucketCount(PromptIndex),
+    /// One fixed-size bucket of a prompt index: (index, bucket number).
+    IndexBucket(PromptIndex, u32),
+    /// Bucket holding a prompt in an index: (index, prompt ID).
+    IndexEntry(PromptIndex, u64),
+    /// Category a prompt is currently indexed under, so a rebuild can drop
+    /// it from a category it has since left.
+    IndexedCategory(u64),
+    /// Root directory of a sorted secondary index, listing its pages.
+    SortIndex(SortField),
+    /// One page of bucket heads of a sorted index: (field, page ID).
+    SortPage(SortField, u32),
+    /// One bucket of a sorted secondary index: (field, bucket ID).
+    SortBucket(SortField, u32),
+    /// Key a prompt is filed under in a sorted index: (field, prompt ID).
+    SortSlot(SortField, u64),
+}
+
+/// A prompt ID index, stored in fixed-size buckets.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PromptIndex {
+    All,
+    /// Listed prompts: active and not expired.
+    Active,
+    Category(String),
+    Tag(String),
+    Creator(Address),
+    Buyer(Address),
+}
+
+/// A mutable listing field kept in a sorted secondary index.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SortField {
+    /// Listed price in the listing's primary asset.
+    Price,
+    /// `sales_count`. Refiled on every purchase, which rewrites the
+    /// listing's bucket and, only when a bucket head moves or a bucket
+    /// splits, the page or root above it.
+    Sales,
+}
+
+/// One member of a sorted index. Entries are ordered by key, then ID.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SortEntry {
+    pub key: i128,
+    pub prompt_id: u64,
+}
+
+/// A bucket of a sorted index and its smallest entry.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SortBucketHead {
+    pub bucket: u32,
+    pub first: SortEntry,
+}
+
+/// A page of bucket heads of a sorted index and its smallest entry.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SortPageHead {
+    pub page: u32,
+    pub first: SortEntry,
+}
+
+/// Pages of bucket heads of a sorted index in key order. Buckets and pages
+/// split in two when full and are dropped when emptied, so their IDs are
+/// not positions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SortIndexDirectory {
+    pub next_bucket: u32,
+    pub next_page: u32,
+    pub pages: Vec<SortPageHead>,
+}
+
+/// How a catalog query combines its tags.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TagMatch {
+    /// Listings carrying every tag.
+    All,
+    /// Listings carrying at least one tag.
+    Any,
+}
+
+/// Which listings a catalog query returns by expiry and status.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ListingFilter {
+    Any,
+    /// Active and not expired.
+    Listed,
+    /// Past their expiry, whatever their status.
+    Expired,
+}
+
+/// Criteria for `query_prompts`. Unset options and an empty tag list do
+/// not constrain the results.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CatalogFilter {
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub tag_match: TagMatch,
+    /// Listings that accept this asset, as primary or additional pricing.
+    /// Not allowed with the price sorts.
+    pub asset: Option<Address>,
+    /// Bounds on the price in `asset`, or in the primary asset when no
+    /// asset is given. Both are inclusive.
+    pub min_price: Option<i128>,
+    pub max_price: Option<i128>,
+    pub creator: Option<Address>,
+    pub listing: ListingFilter,
+}
+
+/// Result order for `query_prompts`.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CatalogSort {
+    /// By prompt ID, newest first.
+    Newest,
+    /// By `price_stroops`, the price in the primary asset. Price sorts
+    /// reject an `asset` filter, and leave out listings priced in reference
+    /// units (`set_reference_price`) or pay-what-you-want, whose charge is
+    /// not `price_stroops`.
+    PriceAsc,
+    PriceDesc,
+    /// By `sales_count`, highest first.
+    BestSelling,
 }
 
 #[contracttype]
@@ -208,6 +540,9 @@ pub enum DisputeStatus {
     Open,
     Refunded,
     Rejected,
+    /// Part of the escrow was refunded; the buyer keeps access and the
+    /// remainder settles to the scaled-down payout plan.
+    PartiallyRefunded,
 }
 
 #[contracttype]
@@ -231,7 +566,10 @@ pub enum SettlementStatus {
 pub struct PurchaseEscrow {
     pub prompt_id: u64,
     pub buyer: Address,
+    /// Amount paid, net of any refunds since.
     pub amount: i128,
+    /// Total refunded to the buyer so far.
+    pub refunded_amount: i128,
     pub status: SettlementStatus,
     pub created_at: u64,
     pub settled_at: u64, // 0 if not yet settled
@@ -267,6 +605,41 @@ pub struct PayoutPlan {
     pub splits: Vec<PayoutSplit>,
     /// Creator's share after all deductions.
     pub creator_amount: i128,
+    /// How the payment amount was priced, including the oracle rate for
+    /// reference-priced listings.
+    pub conversion: PriceConversion,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PriceConversion {
+    /// Priced directly in the payment asset.
+    Listed,
+    /// Converted from a reference price at the recorded oracle rate.
+    Oracle(OracleConversion),
+}
+
+/// Conversion of a reference price into the payment asset, as quoted by
+/// the price oracle at purchase time.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleConversion {
+    /// Listing price in reference units (USD cents).
+    pub reference_amount: i128,
+    /// Oracle price of one whole payment-asset unit.
+    pub oracle_price: i128,
+    pub oracle_decimals: u32,
+    /// When the oracle recorded `oracle_price`.
+    pub price_timestamp: u64,
+}
+
+/// Price feed used for reference-priced listings.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceOracleConfig {
+    pub oracle: Address,
+    /// Oldest oracle price, in seconds, a purchase will accept.
+    pub max_age_secs: u64,
 }
 
 /// A single collaborator split entry in the payout snapshot.
@@ -283,13 +656,17 @@ pub struct PayoutSplit {
 /// refund. `disputed` is the subset currently under an open dispute — moved
 /// out of `pending` while the dispute is open so operators can see disputed
 /// exposure separately, and moved back on settle/refund/reject. The two
-/// buckets never overlap; `pending + disputed` is the asset's total
-/// customer liability the contract's SAC balance must cover.
+/// buckets never overlap; `pending + disputed + bonds + claimable` is the
+/// asset's total customer liability the contract's SAC balance must cover.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct AssetLiability {
     pub pending: i128,
     pub disputed: i128,
+    /// Dispute bonds held until their dispute resolves.
+    pub bonds: i128,
+    /// Payouts credited to recipients and awaiting `withdraw`.
+    pub claimable: i128,
 }
 
 /// Read-only solvency snapshot for one asset (#570). `surplus` is
@@ -313,6 +690,48 @@ pub struct PurchaseDispute {
     pub opened_at: u64,
     pub resolved_at: u64,
     pub status: DisputeStatus,
+    /// Bond the buyer posted to open the dispute, in the escrow's asset.
+    /// Returned on any refund, forfeited to the creator on rejection.
+    pub bond_amount: i128,
+}
+
+/// How a buyer's disputes have gone, for spotting repeat abuse.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BuyerDisputeStats {
+    pub opened: u32,
+    pub refunded: u32,
+    pub rejected: u32,
+}
+
+/// An arbitration panel deciding one disputed settlement. `round` is 0 for
+/// the first panel and 1 for the single appeal panel.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Arbitration {
+    pub settlement_id: u128,
+    pub round: u32,
+    pub panel: Vec<Address>,
+    /// Matching votes needed to decide the round.
+    pub quorum: u32,
+    pub voted: Vec<Address>,
+    pub refund_votes: u32,
+    pub reject_votes: u32,
+    pub assigned_at: u64,
+    /// After this the owner may resolve an undecided dispute directly.
+    pub voting_deadline: u64,
+    /// `Some(refund)` once the round reached quorum.
+    pub outcome: Option<bool>,
+    /// Last moment the first-round outcome can be appealed.
+    pub appeal_deadline: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeEvidence {
+    pub submitter: Address,
+    pub evidence_hash: BytesN<32>,
+    pub submitted_at: u64,
 }
 
 #[contracttype]
@@ -326,6 +745,11 @@ pub struct Purchase {
     pub transfer_count: u32,
     pub last_transferred_at: u64,
     pub expires_at: u64,
+    /// Listing revision in effect when the license was granted. Under
+    /// `ContentAccessPolicy::PurchasedRevision` this pins the content served.
+    /// Licenses granted before revisions were tracked carry `u32::MAX` and
+    /// always read the latest revision.
+    pub revision: u32,
 }
 
 #[contracttype]
@@ -362,6 +786,17 @@ pub struct ListingConfig {
     pub tags: Vec<String>,
     /// Maximum number of licenses that can be sold (0 = unlimited).
     pub max_supply: u64,
+    /// Royalty owed to the creator on every resale, in basis points of the
+    /// resale price. Bounded by the platform maximum.
+    pub royalty_bps: u32,
+    /// Collaborator shares of the resale royalty, in basis points of the
+    /// royalty. The creator keeps the remainder.
+    pub royalty_splits: Vec<Split>,
+    /// `false` forbids reselling or transferring licenses for this listing.
+    pub resale_allowed: bool,
+    /// Further assets the listing accepts, one price each. `price`/`asset`
+    /// above remain the primary price and are always accepted.
+    pub pricing: Vec<PricingConfig>,
 }
 
 #[contracttype]
@@ -388,10 +823,19 @@ pub struct Prompt {
     /// Optional co-creator revenue splits applied against the full payment.
     pub splits: Vec<Split>,
     /// Monotonically increasing revision counter. Starts at 0 on creation and
-    /// increments by 1 on each successful `revise_listing` call (#226).
+    /// increments by 1 on each successful `revise_listing` or `revise_content`
+    /// call (#226).
     pub revision: u32,
     /// Search tags used for marketplace discovery. Tags should be lowercase kebab-case.
     pub tags: Vec<String>,
+    /// Resale royalty in basis points of the resale price.
+    pub royalty_bps: u32,
+    /// Collaborator shares of the resale royalty (bps of the royalty).
+    pub royalty_splits: Vec<Split>,
+    pub resale_allowed: bool,
+    /// Prices in assets other than `asset`, accepted by direct purchases
+    /// and bundles.
+    pub pricing: Vec<PricingConfig>,
 }
 
 #[contracttype]
@@ -433,9 +877,87 @@ pub struct CatalogPassPurchase {
     pub expires_at: u64,
 }
 
+/// Per-listing lease pricing. A lease is charged `price_per_period` for every
+/// started `period_secs` of its duration.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeaseTerms {
+    pub enabled: bool,
+    pub price_per_period: i128,
+    pub period_secs: u64,
+    pub min_duration_secs: u64,
+    pub max_duration_secs: u64,
+}
+
+/// A lessee's current lease term. Renewals while the lease is active
+/// accumulate into it, so `paid_total` always covers `started_at..expires_at`
+/// and the unused share can be credited on conversion to a purchase.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lease {
+    pub prompt_id: u64,
+    pub lessee: Address,
+    pub paid_total: i128,
+    pub started_at: u64,
+    pub expires_at: u64,
+}
+
+/// A buyer's opt-in mandate to renew an access pass from their token
+/// allowance. Anyone may trigger a renewal once the renewal window opens;
+/// the buyer's limits below are the only authority it carries.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PassSubscription {
+    pub buyer: Address,
+    pub pass_id: u128,
+    /// Highest per-period price the buyer agreed to be charged.
+    pub max_price: i128,
+    /// Renewals left before the subscription ends on its own.
+    pub periods_remaining: u32,
+    pub renewals: u32,
+}
+
+/// A buyer's registered public key for per-buyer key delivery (e.g. an
+/// X25519 key). `registered_at` decides which purchases it applies to.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BuyerEncryptionKey {
+    pub public_key: BytesN<32>,
+    pub registered_at: u64,
+}
+
+/// The listing's content key wrapped to one buyer, replacing the shared
+/// `Prompt.wrapped_key` for that buyer. `recipient_key` records which
+/// registered key it was wrapped to, so a later key rotation is detectable,
+/// and `revision` the content revision it unlocks, so a later revision the
+/// buyer is served needs a fresh delivery.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BuyerWrappedKey {
+    pub wrapped_key: String,
+    pub recipient_key: BytesN<32>,
+    pub delivered_by: Address,
+    pub delivered_at: u64,
+    pub revision: u32,
+}
+
+/// Why an access pass subscription stopped renewing.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SubscriptionEndReason {
+    CancelledByBuyer,
+    PeriodsExhausted,
+    PriceAboveCeiling,
+    /// The buyer's allowance or balance no longer covers the price.
+    InsufficientAllowance,
+    /// The pass was deactivated or sold out.
+    PassUnavailable,
+}
+
 /// Snapshot of the mutable listing fields captured before a revision (#226).
 /// Stored under `DataKey::ListingRevision(prompt_id, old_revision)` so
-/// buyers can verify what metadata was in effect when they purchased.
+/// buyers can verify what metadata and content was in effect when they
+/// purchased.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ListingRevisionRecord {
@@ -446,9 +968,36 @@ pub struct ListingRevisionRecord {
     pub preview_text: String,
     pub image_url: String,
     pub price_stroops: i128,
+    pub encrypted_payload: String,
+    pub encryption_iv: String,
+    pub wrapped_key: String,
+    pub content_hash: BytesN<32>,
     pub revised_at: u64,
 }
 
+/// Which content revision a listing's buyers are entitled to read.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ContentAccessPolicy {
+    /// Every buyer reads the current content, including later revisions.
+    LatestRevision,
+    /// Each buyer keeps the content that was live when their license was
+    /// granted; later revisions only reach new buyers.
+    PurchasedRevision,
+}
+
+/// The encrypted content a given license holder is entitled to read.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LicensedContent {
+    pub prompt_id: u64,
+    pub revision: u32,
+    pub encrypted_payload: String,
+    pub encryption_iv: String,
+    pub wrapped_key: String,
+    pub content_hash: BytesN<32>,
+}
+
 /// A creator-signed discount authorization.
 ///
 /// Replaces raw voucher preimages with a signed payload that binds the
@@ -496,6 +1045,13 @@ pub enum AcquisitionKind {
     AccessPass,
     BulkCheckout,
     ResaleFill,
+    /// Unpriced transfer of a license token. No longer recorded: tokens
+    /// only move through priced resales.
+    LicenseTokenTransfer,
+    /// Winning English auction bid or Dutch auction purchase.
+    Auction,
+    /// Zero-price claim of a free listing, recorded already settled.
+    FreeClaim,
 }
 
 /// A bounded quote commitment covering every checkout path (#565).
@@ -539,7 +1095,10 @@ pub struct QuoteCommitment {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SettlementRecord {
     pub settlement_id: u128,
+    /// The prompt this settlement grants, or `0` for bundles and passes.
     pub prompt_id: u64,
+    /// prompt_id, bundle_id or pass_id depending on `kind`.
+    pub acquisition_id: u128,
     pub buyer: Address,
     pub kind: AcquisitionKind,
     pub amount: i128,
@@ -560,6 +1119,66 @@ pub enum ResaleOrderStatus {
     Cancelled,
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AuctionKind {
+    /// Ascending escrowed bids; the highest bid at `ends_at` wins one unit.
+    English,
+    /// Price decays linearly from `start_price` to `reserve_price` between
+    /// `start_ledger` and `end_ledger`; each purchase takes one unit.
+    Dutch,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AuctionStatus {
+    Open,
+    Sold,
+    /// Cancelled by the creator, or ended without a sale.
+    Closed,
+}
+
+/// A per-listing auction sale mode. While an auction is `Open` the listing
+/// cannot be acquired at its fixed price.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Auction {
+    pub prompt_id: u64,
+    pub creator: Address,
+    pub kind: AuctionKind,
+    pub asset: Address,
+    /// English: minimum winning bid. Dutch: floor price reached at `end_ledger`.
+    pub reserve_price: i128,
+    /// Dutch: price at `start_ledger`. Equal to `reserve_price` for English.
+    pub start_price: i128,
+    pub start_ledger: u32,
+    /// Dutch: ledger at which the price reaches the floor.
+    pub end_ledger: u32,
+    /// English: Unix timestamp after which no bids are accepted.
+    pub ends_at: u64,
+    /// English: current high bidder, whose bid is escrowed in the contract.
+    pub highest_bidder: Option<Address>,
+    pub highest_bid: i128,
+    pub status: AuctionStatus,
+}
+
+/// A transferable license token. The `Purchase` it represents is keyed by
+/// `owner` and moves with the token, so access follows token ownership.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LicenseToken {
+    pub token_id: u32,
+    pub prompt_id: u64,
+    pub owner: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LicenseTokenApproval {
+    pub approved: Address,
+    pub live_until_ledger: u32,
+}
+
 /// A seller-signed resale order (#568).
 ///
 /// Replaces dual-authorization `transfer_license`, which needed both parties in
@@ -589,6 +1208,31 @@ pub struct ResaleOrder {
     pub status: ResaleOrderStatus,
 }
 
+impl ResaleOrder {
+    /// Domain-separated hash of the order terms, used as its storage key.
+    /// `status` is excluded so the hash is stable across the order lifecycle.
+    pub fn hash(&self, env: &Env) -> BytesN<32> {
+        let mut buf = Vec::new(env);
+        // Domain separator: network_id || contract_id
+        buf.push_back(self.network_id.to_val());
+        buf.push_back(self.contract_id.to_val());
+        // Payload: seller || prompt_id || settlement_id || asset || price ||
+        // min_proceeds || buyer || royalty_bps || expiry_ledger || nonce
+        buf.push_back(self.seller.to_val());
+        buf.push_back((self.prompt_id as u128).into_val(env));
+        buf.push_back(self.settlement_id.into_val(env));
+        buf.push_back(self.asset.to_val());
+        buf.push_back(self.price.into_val(env));
+        buf.push_back(self.min_proceeds.into_val(env));
+        buf.push_back(self.buyer.into_val(env));
+        buf.push_back((self.royalty_bps as u128).into_val(env));
+        buf.push_back((self.expiry_ledger as u128).into_val(env));
+        buf.push_back(self.nonce.to_val());
+        let raw = env.crypto().sha256(&buf.to_xdr(env));
+        BytesN::from_array(env, &raw.to_array())
+    }
+}
+
 /// The high-risk changes that must go through the delay (#569).
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -599,6 +1243,23 @@ pub enum GovernanceAction {
     SetFeeWallet(Address),
     SetFeePercentage(u32),
     SetReferralPercentage(u32),
+    /// Change the delay itself; bounded so the window can never be removed.
+    SetGovernanceDelay(u32),
+    /// Change the default dispute window and the bounds creators pick from.
+    SetDisputeWindow(DisputeWindowConfig),
+    /// Set the dispute window for lease, bundle or access-pass sales; zero
+    /// pays them out at purchase.
+    SetDisputeWindowPolicy(AcquisitionKind, u64),
+}
+
+/// Dispute window applied to direct sales: `default_secs` unless the
+/// creator picked a window between `min_secs` and `max_secs`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeWindowConfig {
+    pub default_secs: u64,
+    pub min_secs: u64,
+    pub max_secs: u64,
 }
 
 /// A proposed governance action awaiting its observation window (#569).
@@ -620,6 +1281,280 @@ pub struct GovernanceProposal {
     pub nonce: BytesN<32>,
 }
 
+impl GovernanceProposal {
+    /// Hash identifying the proposal: the action together with who proposed
+    /// it, when, and under which nonce.
+    pub fn hash(&self, env: &Env) -> BytesN<32> {
+        let mut buf = Vec::new(env);
+        buf.push_back(self.action.clone().into_val(env));
+        buf.push_back(self.proposer.to_val());
+        buf.push_back((self.proposed_at_ledger as u128).into_val(env));
+        buf.push_back(self.nonce.to_val());
+        let raw = env.crypto().sha256(&buf.to_xdr(env));
+        BytesN::from_array(env, &raw.to_array())
+    }
+}
+
+/// A storage layout written before its current type gained fields. Reads
+/// recognise the legacy layout by its field count, since decoding a struct
+/// of the wrong size traps, and upgrade it in memory; the entry is rewritten
+/// in the current layout the next time it is saved.
+pub trait LegacyLayout<T> {
+    const FIELDS: u32;
+
+    fn upgrade(self, env: &Env) -> T;
+}
+
+/// `Prompt` before resale royalties and multi-asset pricing.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyPrompt {
+    pub id: u64,
+    pub creator: Address,
+    pub image_url: String,
+    pub title: String,
+    pub category: String,
+    pub preview_text: String,
+    pub encrypted_payload: String,
+    pub encryption_iv: String,
+    pub wrapped_key: String,
+    pub content_hash: BytesN<32>,
+    pub price_stroops: i128,
+    pub asset: Address,
+    pub status: PromptSaleStatus,
+    pub sales_count: u64,
+    pub max_supply: u64,
+    pub expires_at: u64,
+    pub splits: Vec<Split>,
+    pub revision: u32,
+    pub tags: Vec<String>,
+}
+
+impl LegacyLayout<Prompt> for LegacyPrompt {
+    const FIELDS: u32 = 19;
+
+    /// Legacy listings carried no royalty and could always be resold.
+    fn upgrade(self, env: &Env) -> Prompt {
+        Prompt {
+            id: self.id,
+            creator: self.creator,
+            image_url: self.image_url,
+            title: self.title,
+            category: self.category,
+            preview_text: self.preview_text,
+            encrypted_payload: self.encrypted_payload,
+            encryption_iv: self.encryption_iv,
+            wrapped_key: self.wrapped_key,
+            content_hash: self.content_hash,
+            price_stroops: self.price_stroops,
+            asset: self.asset,
+            status: self.status,
+            sales_count: self.sales_count,
+            max_supply: self.max_supply,
+            expires_at: self.expires_at,
+            splits: self.splits,
+            revision: self.revision,
+            tags: self.tags,
+            royalty_bps: 0,
+            royalty_splits: Vec::new(env),
+            resale_allowed: true,
+            pricing: Vec::new(env),
+        }
+    }
+}
+
+/// `ListingRevisionRecord` before content revisions, when a revision
+/// snapshot held listing metadata only.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyListingRevisionRecord {
+    pub prompt_id: u64,
+    pub revision: u32,
+    pub title: String,
+    pub category: String,
+    pub preview_text: String,
+    pub image_url: String,
+    pub price_stroops: i128,
+    pub revised_at: u64,
+}
+
+impl LegacyLayout<ListingRevisionRecord> for LegacyListingRevisionRecord {
+    const FIELDS: u32 = 8;
+
+    /// No content was captured, so the content fields come back empty. No
+    /// license pins a legacy revision: legacy purchases read the latest.
+    fn upgrade(self, env: &Env) -> ListingRevisionRecord {
+        ListingRevisionRecord {
+            prompt_id: self.prompt_id,
+            revision: self.revision,
+            title: self.title,
+            category: self.category,
+            preview_text: self.preview_text,
+            image_url: self.image_url,
+            price_stroops: self.price_stroops,
+            encrypted_payload: String::from_str(env, ""),
+            encryption_iv: String::from_str(env, ""),
+            wrapped_key: String::from_str(env, ""),
+            content_hash: BytesN::from_array(env, &[0; 32]),
+            revised_at: self.revised_at,
+        }
+    }
+}
+
+/// `Purchase` before content revisions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyPurchase {
+    pub prompt_id: u64,
+    pub original_creator: Address,
+    pub owner: Address,
+    pub original_price: i128,
+    pub last_transfer_price: i128,
+    pub transfer_count: u32,
+    pub last_transferred_at: u64,
+    pub expires_at: u64,
+}
+
+impl LegacyLayout<Purchase> for LegacyPurchase {
+    const FIELDS: u32 = 8;
+
+    fn upgrade(self, _env: &Env) -> Purchase {
+        Purchase {
+            prompt_id: self.prompt_id,
+            original_creator: self.original_creator,
+            owner: self.owner,
+            original_price: self.original_price,
+            last_transfer_price: self.last_transfer_price,
+            transfer_count: self.transfer_count,
+            last_transferred_at: self.last_transferred_at,
+            expires_at: self.expires_at,
+            revision: u32::MAX,
+        }
+    }
+}
+
+/// `PayoutPlan` before oracle-converted prices.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyPayoutPlan {
+    pub creator: Address,
+    pub fee_wallet: Address,
+    pub fee_amount: i128,
+    pub referrer: Option<Address>,
+    pub referral_amount: i128,
+    pub splits: Vec<PayoutSplit>,
+    pub creator_amount: i128,
+}
+
+impl LegacyLayout<PayoutPlan> for LegacyPayoutPlan {
+    const FIELDS: u32 = 7;
+
+    fn upgrade(self, _env: &Env) -> PayoutPlan {
+        PayoutPlan {
+            creator: self.creator,
+            fee_wallet: self.fee_wallet,
+            fee_amount: self.fee_amount,
+            referrer: self.referrer,
+            referral_amount: self.referral_amount,
+            splits: self.splits,
+            creator_amount: self.creator_amount,
+            conversion: PriceConversion::Listed,
+        }
+    }
+}
+
+/// `PurchaseEscrow` before partial refunds, as stored under the
+/// buyer-keyed `DataKey::PurchaseEscrow`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyPurchaseEscrow {
+    pub prompt_id: u64,
+    pub buyer: Address,
+    pub amount: i128,
+    pub status: SettlementStatus,
+    pub created_at: u64,
+    pub settled_at: u64,
+    pub dispute_deadline: u64,
+    pub asset: Address,
+    pub referrer: Option<Address>,
+    pub creator_amount: i128,
+    pub fee_amount: i128,
+    pub referral_amount: i128,
+    pub payout_plan: LegacyPayoutPlan,
+}
+
+impl LegacyLayout<PurchaseEscrow> for LegacyPurchaseEscrow {
+    const FIELDS: u32 = 13;
+
+    fn upgrade(self, env: &Env) -> PurchaseEscrow {
+        PurchaseEscrow {
+            prompt_id: self.prompt_id,
+            buyer: self.buyer,
+            amount: self.amount,
+            refunded_amount: 0,
+            status: self.status,
+            created_at: self.created_at,
+            settled_at: self.settled_at,
+            dispute_deadline: self.dispute_deadline,
+            asset: self.asset,
+            referrer: self.referrer,
+            creator_amount: self.creator_amount,
+            fee_amount: self.fee_amount,
+            referral_amount: self.referral_amount,
+            payout_plan: self.payout_plan.upgrade(env),
+        }
+    }
+}
+
+/// `PurchaseDispute` before dispute bonds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyPurchaseDispute {
+    pub prompt_id: u64,
+    pub buyer: Address,
+    pub reason: DisputeReason,
+    pub opened_at: u64,
+    pub resolved_at: u64,
+    pub status: DisputeStatus,
+}
+
+impl LegacyLayout<PurchaseDispute> for LegacyPurchaseDispute {
+    const FIELDS: u32 = 6;
+
+    fn upgrade(self, _env: &Env) -> PurchaseDispute {
+        PurchaseDispute {
+            prompt_id: self.prompt_id,
+            buyer: self.buyer,
+            reason: self.reason,
+            opened_at: self.opened_at,
+            resolved_at: self.resolved_at,
+            status: self.status,
+            bond_amount: 0,
+        }
+    }
+}
+
+/// `AssetLiability` before dispute bonds and claimable balances.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegacyAssetLiability {
+    pub pending: i128,
+    pub disputed: i128,
+}
+
+impl LegacyLayout<AssetLiability> for LegacyAssetLiability {
+    const FIELDS: u32 = 2;
+
+    fn upgrade(self, _env: &Env) -> AssetLiability {
+        AssetLiability {
+            pending: self.pending,
+            disputed: self.disputed,
+            bonds: 0,
+            claimable: 0,
+        }
+    }
+}
+
 pub trait PromptHashTrait {
     fn __constructor(
         env: Env,
@@ -680,6 +1615,45 @@ pub trait PromptHashTrait {
         voucher: Option<Bytes>,
     ) -> Result<(), Error>;
 
+    /// Claim a license to a free listing: no payment or escrow, but the
+    /// purchase is recorded and counts toward `max_supply`.
+    fn claim_free_prompt(env: Env, buyer: Address, prompt_id: u64) -> Result<(), Error>;
+
+    /// Let buyers pay any amount of at least `minimum` in the listing's
+    /// primary asset (or clear the mode with `None`). Payments above the
+    /// minimum are revenue, not tips.
+    fn set_pay_what_you_want(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        minimum: Option<i128>,
+    ) -> Result<(), Error>;
+    fn get_pay_what_you_want(env: Env, prompt_id: u64) -> Option<i128>;
+
+    /// Buy a listing in any asset it accepts, at that asset's price.
+    fn buy_prompt_in_asset(
+        env: Env,
+        buyer: Address,
+        prompt_id: u64,
+        asset: Address,
+        referrer: Option<Address>,
+        payment_amount_stroops: i128,
+        voucher: Option<Bytes>,
+    ) -> Result<(), Error>;
+
+    /// Replace the extra accepted assets of a listing. The primary price is
+    /// unchanged. Leases, auctions and resales are only ever priced and paid
+    /// in the primary asset.
+    fn set_listing_pricing(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        pricing: Vec<PricingConfig>,
+    ) -> Result<(), Error>;
+
+    /// Price of a listing in `asset`, or `InvalidAsset` when not accepted.
+    fn get_listing_price(env: Env, prompt_id: u64, asset: Address) -> Result<i128, Error>;
+
     fn buy_prompt_with_auth(
         env: Env,
         buyer: Address,
@@ -715,6 +1689,56 @@ pub trait PromptHashTrait {
         lease_duration_secs: u64,
     ) -> Result<(), Error>;
 
+    /// Set or clear (`None`) the listing's lease terms. Clearing restores
+    /// the flat `LEASE_PRICE_BPS` lease price.
+    fn set_lease_terms(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        terms: Option<LeaseTerms>,
+    ) -> Result<(), Error>;
+
+    fn get_lease_terms(env: Env, prompt_id: u64) -> Option<LeaseTerms>;
+
+    /// Price of a lease of `lease_duration_secs` under the current terms.
+    fn get_lease_price(env: Env, prompt_id: u64, lease_duration_secs: u64) -> Result<i128, Error>;
+
+    fn get_lease(env: Env, prompt_id: u64, lessee: Address) -> Result<Lease, Error>;
+
+    /// Extend a lease by `lease_duration_secs`. An active lease is extended
+    /// from its current expiry; a lapsed one restarts from now.
+    fn renew_lease(
+        env: Env,
+        lessee: Address,
+        prompt_id: u64,
+        lease_duration_secs: u64,
+    ) -> Result<(), Error>;
+
+    /// Convert an active lease into a permanent purchase. The unused share
+    /// of the lease payments is credited against the purchase price, and
+    /// the remainder (at most `max_charge`) goes through purchase escrow.
+    fn convert_lease(
+        env: Env,
+        lessee: Address,
+        prompt_id: u64,
+        max_charge: i128,
+    ) -> Result<(), Error>;
+
+    /// Credit `lessee` would receive if they converted their lease now.
+    fn get_lease_conversion_credit(
+        env: Env,
+        prompt_id: u64,
+        lessee: Address,
+    ) -> Result<i128, Error>;
+
+    /// Replace a listing's search tags, moving it between tag indexes.
+    fn update_tags(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        tags: Vec<String>,
+    ) -> Result<(), Error>;
+
     /// Push the expiry date of a listing forward. `new_expires_at` must be
     /// strictly greater than the current ledger timestamp.
     fn extend_listing(
@@ -736,6 +1760,55 @@ pub trait PromptHashTrait {
         referrer: Option<Address>,
     ) -> Result<(), Error>;
 
+    // Quote-bound checkout (#565). The buyer is `quote.buyer`, whose auth
+    // covers the full quote. Each entrypoint re-derives the live terms hash,
+    // rejects the call if it no longer matches `quote.terms_hash`, and never
+    // debits more than `quote.max_charge` (plus an explicit `tip_amount` on
+    // direct purchases).
+    fn buy_with_quote(
+        env: Env,
+        quote: QuoteCommitment,
+        referrer: Option<Address>,
+    ) -> Result<(), Error>;
+
+    fn lease_with_quote(
+        env: Env,
+        quote: QuoteCommitment,
+        lease_duration_secs: u64,
+    ) -> Result<(), Error>;
+
+    fn buy_bundle_with_quote(env: Env, quote: QuoteCommitment) -> Result<(), Error>;
+
+    fn buy_pass_with_quote(env: Env, quote: QuoteCommitment) -> Result<(), Error>;
+
+    /// `quote.acquisition_id` is unused for bulk checkout; `terms_hash`
+    /// covers every prompt in `prompt_ids` and `max_charge` caps their total.
+    fn buy_bulk_with_quote(
+        env: Env,
+        quote: QuoteCommitment,
+        prompt_ids: Vec<u64>,
+        referrer: Option<Address>,
+    ) -> Result<(), Error>;
+
+    /// The terms hash a quote for `kind`/`acquisition_id` must commit to,
+    /// for clients to read during simulation.
+    fn get_quote_terms_hash(
+        env: Env,
+        kind: AcquisitionKind,
+        acquisition_id: u128,
+    ) -> Result<BytesN<32>, Error>;
+
+    fn get_bulk_quote_terms_hash(env: Env, prompt_ids: Vec<u64>) -> Result<BytesN<32>, Error>;
+
+    /// Lease quotes commit to the duration and the listing's `LeaseTerms`
+    /// as well, so they are hashed here rather than by
+    /// `get_quote_terms_hash`.
+    fn get_lease_quote_terms_hash(
+        env: Env,
+        prompt_id: u64,
+        lease_duration_secs: u64,
+    ) -> Result<BytesN<32>, Error>;
+
     fn create_bundle(
         env: Env,
         creator: Address,
@@ -797,6 +1870,35 @@ pub trait PromptHashTrait {
 
     fn get_access_passes_by_creator(env: Env, creator: Address) -> Result<Vec<AccessPass>, Error>;
 
+    /// Opt into auto-renewal of `pass_id` for up to `max_periods` periods at
+    /// no more than `max_price` each. The buyer must separately approve this
+    /// contract on the pass asset for the renewals to be charged.
+    fn subscribe_pass(
+        env: Env,
+        buyer: Address,
+        pass_id: u128,
+        max_price: i128,
+        max_periods: u32,
+    ) -> Result<(), Error>;
+    fn cancel_pass_subscription(env: Env, buyer: Address, pass_id: u128) -> Result<(), Error>;
+    /// Permissionless renewal once the buyer's current pass period is within
+    /// the renewal window (24 hours, or the pass duration if shorter), at
+    /// most once per period. Charges the pass's current price. Returns `false`
+    /// — and ends the subscription — if the price exceeds the buyer's
+    /// ceiling, the allowance is missing, or the pass is unavailable.
+    fn renew_pass(env: Env, buyer: Address, pass_id: u128) -> Result<bool, Error>;
+    fn get_pass_subscription(
+        env: Env,
+        buyer: Address,
+        pass_id: u128,
+    ) -> Result<PassSubscription, Error>;
+
+    /// Deprecated: needs both the seller's and the new owner's auth in one
+    /// invocation, so the two must coordinate off-chain. Use
+    /// `post_resale_order` and `fill_resale_order` instead, which let the
+    /// seller list once and any buyer fill later. Kept for existing
+    /// integrations; it applies the listing's royalty and the same transfer
+    /// checks as a filled order.
     fn transfer_license(
         env: Env,
         seller: Address,
@@ -805,6 +1907,19 @@ pub trait PromptHashTrait {
         resale_price: i128,
     ) -> Result<(), Error>;
 
+    // Signed resale orders (#568). The seller posts an order once; any buyer
+    // (or the named buyer, for a restricted order) fills it later without the
+    // seller being online. Returns the order hash used to cancel or look it up.
+    fn post_resale_order(env: Env, order: ResaleOrder) -> Result<BytesN<32>, Error>;
+
+    fn cancel_resale_order(env: Env, seller: Address, order_hash: BytesN<32>) -> Result<(), Error>;
+
+    /// `order` must match the posted terms exactly, so the buyer's auth
+    /// commits to the price, royalty and asset they are paying.
+    fn fill_resale_order(env: Env, buyer: Address, order: ResaleOrder) -> Result<(), Error>;
+
+    fn get_resale_order(env: Env, order_hash: BytesN<32>) -> Result<ResaleOrder, Error>;
+
     /// Update the mutable metadata fields of an existing listing.
     ///
     /// The old title, category, preview_text, image_url, and price_stroops are
@@ -829,6 +1944,38 @@ pub trait PromptHashTrait {
         revision: u32,
     ) -> Result<ListingRevisionRecord, Error>;
 
+    /// Replace the encrypted payload, IV, wrapped key and content hash of a
+    /// listing. The prior content is preserved in the `ListingRevisionRecord`
+    /// for the pre-change revision, and the revision counter is bumped.
+    fn revise_content(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        encrypted_payload: String,
+        encryption_iv: String,
+        wrapped_key: String,
+        content_hash: BytesN<32>,
+    ) -> Result<u32, Error>;
+
+    fn set_content_access_policy(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        policy: ContentAccessPolicy,
+    ) -> Result<(), Error>;
+
+    fn get_content_access_policy(env: Env, prompt_id: u64) -> Result<ContentAccessPolicy, Error>;
+
+    /// The content revision `user` may read under the listing's access
+    /// policy. Pass holders and the creator always read the latest revision.
+    /// A buyer with a wrapped key delivered for that revision gets it in
+    /// place of the listing-wide key.
+    fn get_licensed_content(
+        env: Env,
+        prompt_id: u64,
+        user: Address,
+    ) -> Result<LicensedContent, Error>;
+
     fn update_splits(
         env: Env,
         creator: Address,
@@ -860,15 +2007,47 @@ pub trait PromptHashTrait {
         cursor: Option<String>,
         limit: u64,
     ) -> Result<(Vec<Prompt>, Option<String>), Error>;
+    fn get_prompts_by_creator_paginated(
+        env: Env,
+        creator: Address,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> Result<(Vec<Prompt>, Option<String>), Error>;
+    fn get_prompts_by_buyer_paginated(
+        env: Env,
+        buyer: Address,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> Result<(Vec<Prompt>, Option<String>), Error>;
     fn get_active_prompts_paginated(
         env: Env,
         cursor: Option<String>,
         limit: u64,
     ) -> Result<(Vec<Prompt>, Option<String>), Error>;
+    /// Filtered, sorted catalog page. A page may come back short when the
+    /// scan bound is reached first; keep following `next_cursor` until it
+    /// is `None`.
+    fn query_prompts(
+        env: Env,
+        filter: CatalogFilter,
+        sort: CatalogSort,
+        cursor: Option<String>,
+        limit: u64,
+    ) -> Result<(Vec<Prompt>, Option<String>), Error>;
 
     // TTL maintenance (operator utilities).
     fn renew_critical_keys(env: Env, cursor: Option<u64>) -> Result<(u32, Option<u64>), Error>;
     fn get_expiry_risk_metrics(env: Env) -> Result<Vec<(String, String)>, Error>;
+    /// Repair the category, tag and active-listing indexes for a bounded
+    /// batch of prompts, for deployments whose indexes drifted before they
+    /// were maintained on every listing change. Returns the next cursor.
+    fn rebuild_indexes(env: Env, admin: Address, cursor: Option<u64>)
+        -> Result<Option<u64>, Error>;
+    /// Move a bounded batch of a pre-sharding single-vector index into
+    /// buckets; the legacy entry is removed once empty. Writes to the index
+    /// migrate it too, a few entries at a time. Returns the number of
+    /// entries still to migrate.
+    fn migrate_index(env: Env, admin: Address, index: PromptIndex) -> Result<Option<u32>, Error>;
 
     fn open_dispute(
         env: Env,
@@ -876,14 +2055,64 @@ pub trait PromptHashTrait {
         prompt_id: u64,
         reason: DisputeReason,
     ) -> Result<(), Error>;
+    /// Resolve an open dispute by refunding `refund_amount` of the escrow.
+    /// Zero rejects the dispute and the full amount refunds it outright;
+    /// anything between is taken pro rata from every share of the payout
+    /// plan, and the buyer keeps access.
     fn resolve_dispute(
         env: Env,
         admin: Address,
         prompt_id: u64,
         buyer: Address,
-        refund: bool,
+        refund_amount: i128,
+    ) -> Result<(), Error>;
+
+    /// Voluntarily refund `amount` to `buyer`. Before settlement it comes
+    /// out of escrow, pro rata over the payout plan; afterwards the creator
+    /// pays it from their own balance. Refunding everything revokes access.
+    fn creator_refund(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        buyer: Address,
+        amount: i128,
     ) -> Result<(), Error>;
     fn get_dispute(env: Env, prompt_id: u64, buyer: Address) -> Result<PurchaseDispute, Error>;
+
+    // Per-buyer key delivery. A buyer who registers a public key before
+    // purchasing is owed a wrapped key for each Pending purchase: the escrow
+    // cannot be released until it is delivered, and once the delivery
+    // deadline passes the buyer may dispute with `InvalidEncryptedPayload`
+    // even after the normal dispute window.
+    fn register_encryption_key(
+        env: Env,
+        buyer: Address,
+        public_key: BytesN<32>,
+    ) -> Result<(), Error>;
+    fn get_encryption_key(env: Env, buyer: Address) -> Result<BuyerEncryptionKey, Error>;
+    /// `None` removes the agent, leaving delivery to the creator alone.
+    fn set_key_delivery_agent(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        agent: Option<Address>,
+    ) -> Result<(), Error>;
+    fn get_key_delivery_agent(env: Env, prompt_id: u64) -> Option<Address>;
+    fn deliver_wrapped_key(
+        env: Env,
+        sender: Address,
+        prompt_id: u64,
+        buyer: Address,
+        wrapped_key: String,
+    ) -> Result<(), Error>;
+    fn get_buyer_wrapped_key(
+        env: Env,
+        prompt_id: u64,
+        buyer: Address,
+    ) -> Result<BuyerWrappedKey, Error>;
+    /// Ledger timestamp by which the buyer's current purchase must receive
+    /// its wrapped key, or `None` if no delivery is owed.
+    fn get_key_delivery_deadline(env: Env, prompt_id: u64, buyer: Address) -> Option<u64>;
     fn settle_purchase(
         env: Env,
         caller: Address,
@@ -891,6 +2120,115 @@ pub trait PromptHashTrait {
         buyer: Address,
     ) -> Result<(), Error>;
     fn get_purchase_escrow(env: Env, prompt_id: u64, buyer: Address) -> Option<PurchaseEscrow>;
+
+    // Settlement-ID addressed variants and receipt queries (#567). The
+    // `(prompt_id, buyer)` entrypoints above act on the settlement the
+    // buyer's entitlement currently points at.
+    fn open_settlement_dispute(
+        env: Env,
+        buyer: Address,
+        settlement_id: u128,
+        reason: DisputeReason,
+    ) -> Result<(), Error>;
+    fn resolve_settlement_dispute(
+        env: Env,
+        admin: Address,
+        settlement_id: u128,
+        refund_amount: i128,
+    ) -> Result<(), Error>;
+    fn settle_settlement(env: Env, caller: Address, settlement_id: u128) -> Result<(), Error>;
+    fn get_settlement(env: Env, settlement_id: u128) -> Result<SettlementRecord, Error>;
+    fn get_settlement_escrow(env: Env, settlement_id: u128) -> Option<PurchaseEscrow>;
+    fn get_settlement_dispute(env: Env, settlement_id: u128) -> Result<PurchaseDispute, Error>;
+
+    /// Owner proposes how long lease, bundle and access-pass sales stay
+    /// pending and disputable. Queues a
+    /// `GovernanceAction::SetDisputeWindowPolicy` proposal rather than
+    /// applying it. The window must be zero, the default, which pays them
+    /// out at purchase, or within the `get_dispute_window_config` bounds.
+    fn set_dispute_window_policy(
+        env: Env,
+        admin: Address,
+        kind: AcquisitionKind,
+        window_secs: u64,
+    ) -> Result<(), Error>;
+    fn get_dispute_window_policy(env: Env, kind: AcquisitionKind) -> u64;
+    /// Default and bounds for direct-sale dispute windows. Changed only
+    /// through `GovernanceAction::SetDisputeWindow`.
+    fn get_dispute_window_config(env: Env) -> DisputeWindowConfig;
+    /// Creator picks the dispute window for future sales of a listing, or
+    /// clears it (`None`) to follow the default. Each sale snapshots the
+    /// window into its escrow's `dispute_deadline`.
+    fn set_listing_dispute_window(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        window_secs: Option<u64>,
+    ) -> Result<(), Error>;
+    /// Window the next sale of the listing will get.
+    fn get_listing_dispute_window(env: Env, prompt_id: u64) -> Result<u64, Error>;
+
+    // Dispute bonds. Opening a dispute can require a bond, a share of the
+    // disputed escrow posted in its asset: it is returned with any refund
+    // and forfeited to the creator when the dispute is rejected.
+    fn set_dispute_bond(env: Env, admin: Address, bond_bps: u32) -> Result<(), Error>;
+    /// Override (or clear, with `None`) the bond for one buyer, typically
+    /// to raise it for a buyer whose disputes keep being rejected.
+    fn set_buyer_dispute_bond(
+        env: Env,
+        admin: Address,
+        buyer: Address,
+        bond_bps: Option<u32>,
+    ) -> Result<(), Error>;
+    /// Bond, in basis points of the escrow, `buyer` must post to dispute.
+    fn get_dispute_bond(env: Env, buyer: Address) -> u32;
+    fn get_buyer_dispute_stats(env: Env, buyer: Address) -> BuyerDisputeStats;
+
+    // Dispute arbitration. While enough arbiters are registered, each new
+    // dispute is seated before a panel chosen round-robin from the registry
+    // instead of waiting on the owner. The panel decides by majority; either
+    // side may appeal once to a fresh panel.
+    fn add_arbiter(env: Env, admin: Address, arbiter: Address) -> Result<(), Error>;
+    fn remove_arbiter(env: Env, admin: Address, arbiter: Address) -> Result<(), Error>;
+    fn get_arbiters(env: Env) -> Vec<Address>;
+    fn get_arbitration(env: Env, settlement_id: u128) -> Result<Arbitration, Error>;
+    /// Panels previously seated on a settlement, oldest first.
+    fn get_arbitration_history(env: Env, settlement_id: u128) -> Vec<Arbitration>;
+    fn cast_arbitration_vote(
+        env: Env,
+        arbiter: Address,
+        settlement_id: u128,
+        refund: bool,
+    ) -> Result<(), Error>;
+    /// Appeal a first-round outcome to a larger, fresh panel. Available to
+    /// the buyer or the creator, once per dispute, within the appeal window.
+    fn appeal_arbitration(env: Env, appellant: Address, settlement_id: u128) -> Result<(), Error>;
+    /// Apply an unappealed first-round outcome once the appeal window has
+    /// closed. Permissionless.
+    fn finalize_arbitration(env: Env, settlement_id: u128) -> Result<(), Error>;
+    fn submit_dispute_evidence(
+        env: Env,
+        submitter: Address,
+        settlement_id: u128,
+        evidence_hash: BytesN<32>,
+    ) -> Result<(), Error>;
+    fn get_dispute_evidence(env: Env, settlement_id: u128) -> Vec<DisputeEvidence>;
+    fn get_entitlement_settlement(env: Env, prompt_id: u64, buyer: Address) -> Option<u128>;
+    /// Every settlement `buyer` has recorded against `prompt_id`, oldest first.
+    fn get_prompt_settlements(
+        env: Env,
+        prompt_id: u64,
+        buyer: Address,
+    ) -> Result<Vec<SettlementRecord>, Error>;
+    /// A page of `buyer`'s settlements across all acquisition kinds, in
+    /// allocation order. `cursor` is the last settlement ID of the previous
+    /// page; `limit` is capped at `MAX_PAGE_SIZE`.
+    fn get_buyer_settlements(
+        env: Env,
+        buyer: Address,
+        cursor: Option<u128>,
+        limit: u64,
+    ) -> Result<(Vec<SettlementRecord>, Option<u128>), Error>;
     fn get_prompts_by_creator(env: Env, creator: Address) -> Result<Vec<Prompt>, Error>;
     fn get_prompts_by_buyer(env: Env, buyer: Address) -> Result<Vec<Prompt>, Error>;
     fn set_fee_wallet(env: Env, new_fee_wallet: Address) -> Result<(), Error>;
@@ -907,6 +2245,34 @@ pub trait PromptHashTrait {
     /// ceiling. Clamps it down; a no-op if already within bound (#566).
     fn migrate_platform_fee_bound(env: Env, admin: Address) -> Result<(), Error>;
 
+    // Two-phase governance (#569). Fee wallet, fee percentage, referral
+    // percentage, upgrades and the delay itself are proposed first and only
+    // executable once `get_governance_delay` ledgers have passed, giving
+    // users an observation window. The legacy setters above (and `upgrade`)
+    // no longer apply immediately — they validate and queue a proposal.
+    fn propose_governance(
+        env: Env,
+        proposer: Address,
+        action: GovernanceAction,
+        nonce: BytesN<32>,
+    ) -> Result<BytesN<32>, Error>;
+    /// Fails if the delay has not elapsed, the execution window has passed,
+    /// or the configuration drifted from the proposal's `expected_state_hash`.
+    fn execute_governance(
+        env: Env,
+        executor: Address,
+        proposal_hash: BytesN<32>,
+    ) -> Result<(), Error>;
+    fn cancel_governance(env: Env, admin: Address, proposal_hash: BytesN<32>) -> Result<(), Error>;
+    fn get_governance_proposal(
+        env: Env,
+        proposal_hash: BytesN<32>,
+    ) -> Result<GovernanceProposal, Error>;
+    fn get_pending_governance_proposals(env: Env) -> Vec<BytesN<32>>;
+    /// Hash of the governed configuration a new proposal would pin.
+    fn get_governance_state_hash(env: Env) -> BytesN<32>;
+    fn get_governance_delay(env: Env) -> u32;
+
     // Per-asset escrow liability and solvency reconciliation (#570).
     fn get_asset_liability(env: Env, asset: Address) -> AssetLiability;
     fn get_asset_solvency(env: Env, asset: Address) -> AssetSolvency;
@@ -924,6 +2290,137 @@ pub trait PromptHashTrait {
         prompt_id: u64,
         buyer: Address,
     ) -> Result<(), Error>;
+
+    // Pull payments. Payouts to creators, the fee wallet, referrers and
+    // split recipients are credited to a per-(recipient, asset) balance
+    // rather than pushed, so one unusable trustline cannot revert a sale.
+    fn get_claimable_balance(env: Env, recipient: Address, asset: Address) -> i128;
+    /// Pay out the caller's whole claimable balance in `asset`.
+    fn withdraw(env: Env, recipient: Address, asset: Address) -> Result<i128, Error>;
+
+    // Oracle pricing. A listing may be priced in USD cents instead of its
+    // asset; every direct purchase then converts the reference price into
+    // the listing's asset at the SEP-40 oracle's latest rate.
+    fn set_price_oracle(
+        env: Env,
+        admin: Address,
+        oracle: Address,
+        max_age_secs: u64,
+    ) -> Result<(), Error>;
+    fn get_price_oracle(env: Env) -> Option<PriceOracleConfig>;
+    /// Price a listing in USD cents (or clear it, with `None`, to fall back
+    /// to `price_stroops`).
+    fn set_reference_price(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        amount: Option<i128>,
+    ) -> Result<(), Error>;
+    fn get_reference_price(env: Env, prompt_id: u64) -> Option<i128>;
+    /// Buy at the live converted price. Fails with `SlippageExceeded` if it
+    /// is more than `max_slippage_bps` above `expected_amount`.
+    fn buy_prompt_at_oracle_price(
+        env: Env,
+        buyer: Address,
+        prompt_id: u64,
+        referrer: Option<Address>,
+        expected_amount: i128,
+        max_slippage_bps: u32,
+    ) -> Result<(), Error>;
+
+    // License tokens. Listings in token mode mint one non-fungible token per
+    // granted license; the surface below follows SEP-50 so wallets and
+    // external marketplaces can display and trade licenses. It is
+    // implemented here rather than on `stellar-non-fungible`, whose releases
+    // require an older soroban-sdk than this workspace, and token IDs are
+    // the SEP-50 `u32`.
+    /// Creator opts a listing into (or out of) token mode. Only allowed
+    /// before the listing has sold any license.
+    fn set_license_token_mode(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        enabled: bool,
+    ) -> Result<(), Error>;
+    fn is_license_token_mode(env: Env, prompt_id: u64) -> bool;
+    fn get_license_token(env: Env, token_id: u32) -> Result<LicenseToken, Error>;
+    fn get_purchase_license_token(env: Env, prompt_id: u64, owner: Address) -> Option<u32>;
+    fn balance(env: Env, account: Address) -> u32;
+    fn owner_of(env: Env, token_id: u32) -> Result<Address, Error>;
+    /// Always fails with `UnpricedLicenseTransfer` once the caller's right
+    /// to move the token is checked. A license token moves with its license
+    /// when a resale order is filled, so the creator's royalty is paid.
+    fn transfer(env: Env, from: Address, to: Address, token_id: u32) -> Result<(), Error>;
+    /// As `transfer`, for an approved spender or operator.
+    fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: u32,
+    ) -> Result<(), Error>;
+    /// `live_until_ledger == 0` revokes the approval.
+    fn approve(
+        env: Env,
+        approver: Address,
+        approved: Address,
+        token_id: u32,
+        live_until_ledger: u32,
+    ) -> Result<(), Error>;
+    /// `live_until_ledger == 0` revokes the operator.
+    fn approve_for_all(
+        env: Env,
+        owner: Address,
+        operator: Address,
+        live_until_ledger: u32,
+    ) -> Result<(), Error>;
+    fn get_approved(env: Env, token_id: u32) -> Option<Address>;
+    fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool;
+    fn name(env: Env) -> String;
+    fn symbol(env: Env) -> String;
+    /// The listing image, which wallets render as the token artwork.
+    fn token_uri(env: Env, token_id: u32) -> Result<String, Error>;
+    /// Royalty owed to the license's original creator on a sale at
+    /// `sale_price`. Every resale through this contract, the only way a
+    /// token changes hands, pays it.
+    fn royalty_info(env: Env, token_id: u32, sale_price: i128) -> Result<(Address, i128), Error>;
+
+    // Auction sale modes for limited-supply listings.
+    fn start_english_auction(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        reserve_price: i128,
+        ends_at: u64,
+    ) -> Result<(), Error>;
+    fn start_dutch_auction(
+        env: Env,
+        creator: Address,
+        prompt_id: u64,
+        start_price: i128,
+        floor_price: i128,
+        duration_ledgers: u32,
+    ) -> Result<(), Error>;
+    /// Escrow a bid on an English auction. The previous high bidder is
+    /// refunded in the same call.
+    fn place_bid(env: Env, bidder: Address, prompt_id: u64, amount: i128) -> Result<(), Error>;
+    /// Permissionlessly close an English auction after `ends_at`, selling a
+    /// unit to the high bidder if there is one.
+    fn settle_auction(env: Env, prompt_id: u64) -> Result<(), Error>;
+    /// Buy one unit at the current Dutch auction price, provided it does not
+    /// exceed `max_price`.
+    fn buy_dutch_auction(
+        env: Env,
+        buyer: Address,
+        prompt_id: u64,
+        max_price: i128,
+    ) -> Result<(), Error>;
+    /// Creator closes an auction. English auctions can only be cancelled
+    /// before the first bid.
+    fn cancel_auction(env: Env, creator: Address, prompt_id: u64) -> Result<(), Error>;
+    fn get_auction(env: Env, prompt_id: u64) -> Result<Auction, Error>;
+    fn get_dutch_auction_price(env: Env, prompt_id: u64) -> Result<i128, Error>;
+
     fn set_pause_status(env: Env, paused: bool) -> Result<(), Error>;
     fn is_paused(env: Env) -> bool;
     fn add_voucher(